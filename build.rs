@@ -0,0 +1,14 @@
+use std::error::Error;
+use vergen::EmitBuilder;
+
+/// Embed the commit, the build time and the enabled features, see `src/version.rs`.
+///
+/// Outside a git checkout, e.g. when building from a source tarball, the commit is left as `VERGEN_IDEMPOTENT_OUTPUT`.
+fn main() -> Result<(), Box<dyn Error>> {
+    EmitBuilder::builder()
+        .build_timestamp()
+        .cargo_features()
+        .git_sha(true)
+        .emit()?;
+    Ok(())
+}
@@ -0,0 +1,14 @@
+digest-quiet = A quiet week here, nobody asked me anything. Try /doggo!
+digest-title = This week in this chat:
+digest-dogs = { $count ->
+    [one] { $count } dog sent
+   *[other] { $count } dogs sent
+}
+digest-top-breed = Most requested breed: { $breed } ({ $count ->
+    [one] { $count } time
+   *[other] { $count } times
+})
+digest-commands = { $count ->
+    [one] { $count } command used
+   *[other] { $count } commands used
+}
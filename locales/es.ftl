@@ -0,0 +1,14 @@
+digest-quiet = Una semana tranquila, nadie me ha pedido nada. ¡Prueba /doggo!
+digest-title = Esta semana en este chat:
+digest-dogs = { $count ->
+    [one] { $count } perro enviado
+   *[other] { $count } perros enviados
+}
+digest-top-breed = Raza más pedida: { $breed } ({ $count ->
+    [one] { $count } vez
+   *[other] { $count } veces
+})
+digest-commands = { $count ->
+    [one] { $count } comando usado
+   *[other] { $count } comandos usados
+}
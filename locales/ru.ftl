@@ -0,0 +1,17 @@
+digest-quiet = Тихая неделя, меня ни о чём не спрашивали. Попробуйте /doggo!
+digest-title = На этой неделе в этом чате:
+digest-dogs = { $count ->
+    [one] { $count } собака отправлена
+    [few] { $count } собаки отправлены
+   *[many] { $count } собак отправлено
+}
+digest-top-breed = Самая популярная порода: { $breed } ({ $count ->
+    [one] { $count } раз
+    [few] { $count } раза
+   *[many] { $count } раз
+})
+digest-commands = { $count ->
+    [one] { $count } команда использована
+    [few] { $count } команды использованы
+   *[many] { $count } команд использовано
+}
@@ -0,0 +1,66 @@
+CREATE TABLE IF NOT EXISTS users (
+    id BIGINT PRIMARY KEY,
+    username TEXT,
+    first_seen TIMESTAMPTZ NOT NULL,
+    last_seen TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS preferences (
+    user_id BIGINT PRIMARY KEY,
+    favourite_breed TEXT,
+    currency TEXT,
+    language TEXT,
+    quiz_difficulty TEXT
+);
+
+CREATE TABLE IF NOT EXISTS chat_settings (
+    chat_id BIGINT PRIMARY KEY,
+    language TEXT,
+    timezone TEXT
+);
+
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    kind TEXT NOT NULL,
+    schedule TEXT NOT NULL,
+    options TEXT NOT NULL DEFAULT '{}',
+    UNIQUE (chat_id, kind)
+);
+
+CREATE TABLE IF NOT EXISTS alerts (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    chat_id BIGINT NOT NULL,
+    symbol TEXT NOT NULL,
+    target DOUBLE PRECISION NOT NULL,
+    above BOOLEAN NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS favourites (
+    user_id BIGINT NOT NULL,
+    breed TEXT NOT NULL,
+    PRIMARY KEY (user_id, breed)
+);
+
+CREATE TABLE IF NOT EXISTS command_log (
+    id BIGSERIAL PRIMARY KEY,
+    command TEXT NOT NULL,
+    user_id BIGINT,
+    chat_id BIGINT NOT NULL,
+    chat_type TEXT NOT NULL,
+    at TIMESTAMPTZ NOT NULL,
+    latency_ms BIGINT NOT NULL,
+    success BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS command_log_at ON command_log (at);
+
+CREATE TABLE IF NOT EXISTS breed_requests (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    breed TEXT NOT NULL,
+    at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS breed_requests_chat ON breed_requests (chat_id);
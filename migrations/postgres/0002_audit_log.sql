@@ -0,0 +1,6 @@
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    action TEXT NOT NULL,
+    user_id BIGINT NOT NULL,
+    at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE job_runs (
+    name TEXT PRIMARY KEY,
+    last_run TIMESTAMPTZ NOT NULL,
+    last_error TEXT,
+    runs BIGINT NOT NULL,
+    failures BIGINT NOT NULL
+);
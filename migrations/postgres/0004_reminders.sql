@@ -0,0 +1,7 @@
+CREATE TABLE reminders (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    chat_id BIGINT NOT NULL,
+    at TIMESTAMPTZ NOT NULL,
+    text TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE trivia_scores (
+    chat_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    points BIGINT NOT NULL,
+    PRIMARY KEY (chat_id, user_id)
+);
@@ -0,0 +1,4 @@
+-- Alerts on a move of the price within a window, instead of a target
+ALTER TABLE alerts ADD COLUMN move_percent DOUBLE PRECISION;
+ALTER TABLE alerts ADD COLUMN window_secs BIGINT;
+ALTER TABLE alerts ADD COLUMN created_at TIMESTAMPTZ;
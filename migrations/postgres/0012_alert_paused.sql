@@ -0,0 +1,2 @@
+-- Paused alerts aren't checked until they are resumed
+ALTER TABLE alerts ADD COLUMN paused BOOLEAN NOT NULL DEFAULT FALSE;
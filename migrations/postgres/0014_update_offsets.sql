@@ -0,0 +1,5 @@
+-- Last update handled by each bot, the ones Telegram sends again after a restart are skipped
+CREATE TABLE update_offsets (
+    bot TEXT PRIMARY KEY,
+    update_id BIGINT NOT NULL
+);
@@ -0,0 +1,3 @@
+-- Seconds between two commands of a user in the chat, by how heavy they are; the configured ones when NULL
+ALTER TABLE chat_settings ADD COLUMN cheap_cooldown_secs BIGINT;
+ALTER TABLE chat_settings ADD COLUMN expensive_cooldown_secs BIGINT;
@@ -0,0 +1,66 @@
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY,
+    username TEXT,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS preferences (
+    user_id INTEGER PRIMARY KEY,
+    favourite_breed TEXT,
+    currency TEXT,
+    language TEXT,
+    quiz_difficulty TEXT
+);
+
+CREATE TABLE IF NOT EXISTS chat_settings (
+    chat_id INTEGER PRIMARY KEY,
+    language TEXT,
+    timezone TEXT
+);
+
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    chat_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    schedule TEXT NOT NULL,
+    options TEXT NOT NULL DEFAULT '{}',
+    UNIQUE (chat_id, kind)
+);
+
+CREATE TABLE IF NOT EXISTS alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    chat_id INTEGER NOT NULL,
+    symbol TEXT NOT NULL,
+    target REAL NOT NULL,
+    above BOOLEAN NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS favourites (
+    user_id INTEGER NOT NULL,
+    breed TEXT NOT NULL,
+    PRIMARY KEY (user_id, breed)
+);
+
+CREATE TABLE IF NOT EXISTS command_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command TEXT NOT NULL,
+    user_id INTEGER,
+    chat_id INTEGER NOT NULL,
+    chat_type TEXT NOT NULL,
+    at TEXT NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    success BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS command_log_at ON command_log (at);
+
+CREATE TABLE IF NOT EXISTS breed_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    chat_id INTEGER NOT NULL,
+    breed TEXT NOT NULL,
+    at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS breed_requests_chat ON breed_requests (chat_id);
@@ -0,0 +1,6 @@
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    at TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE job_runs (
+    name TEXT PRIMARY KEY,
+    last_run TEXT NOT NULL,
+    last_error TEXT,
+    runs INTEGER NOT NULL,
+    failures INTEGER NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE reminders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    chat_id INTEGER NOT NULL,
+    at TEXT NOT NULL,
+    text TEXT NOT NULL
+);
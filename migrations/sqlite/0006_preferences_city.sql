@@ -0,0 +1 @@
+ALTER TABLE preferences ADD COLUMN city TEXT;
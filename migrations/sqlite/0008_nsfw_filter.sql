@@ -0,0 +1 @@
+ALTER TABLE chat_settings ADD COLUMN nsfw_filter BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,7 @@
+CREATE TABLE trivia_scores (
+    chat_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    points INTEGER NOT NULL,
+    PRIMARY KEY (chat_id, user_id)
+);
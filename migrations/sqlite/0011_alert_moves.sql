@@ -0,0 +1,4 @@
+-- Alerts on a move of the price within a window, instead of a target
+ALTER TABLE alerts ADD COLUMN move_percent REAL;
+ALTER TABLE alerts ADD COLUMN window_secs INTEGER;
+ALTER TABLE alerts ADD COLUMN created_at TEXT;
@@ -0,0 +1,6 @@
+-- Chats the bot can't write to anymore, e.g. blocked by the user, until they write to it again
+CREATE TABLE inactive_chats (
+    chat_id INTEGER PRIMARY KEY,
+    reason TEXT NOT NULL,
+    since TEXT NOT NULL
+);
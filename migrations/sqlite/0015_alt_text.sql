@@ -0,0 +1 @@
+ALTER TABLE chat_settings ADD COLUMN alt_text BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,166 @@
+//! REST API for the operators, under `/admin` of the embedded server, e.g. to script announcements.
+//!
+//! Every request needs the `Authorization: Bearer <token>` header with [`crate::server::ServerConfig::admin_token`].
+
+use crate::{
+    health::HealthReport,
+    server::{self, ApiError},
+    state::AppState,
+    storage::{CommandStats, Subscription},
+    subscriptions::{self, chat_is_gone, deactivate_chat},
+};
+use axum::{
+    extract::{Query, Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+use teloxide::prelude::*;
+use tracing::{info, warn};
+
+/// Longest period of the stats, in days.
+const MAX_STATS_DAYS: i64 = 365;
+
+/// Routes of the API, rejecting the requests without the token.
+pub fn router(state: Arc<AppState>, token: String) -> Router {
+    let token = Arc::new(token);
+    Router::new()
+        .route("/admin/status", get(status))
+        .route("/admin/stats", get(stats))
+        .route("/admin/subscriptions", get(subscriptions))
+        .route("/admin/announcements", post(announce))
+        .layer(middleware::from_fn(move |request: Request, next: Next| {
+            authorize(token.clone(), request, next)
+        }))
+        .with_state(state)
+}
+
+async fn authorize(token: Arc<String>, request: Request, next: Next) -> Response {
+    match server::bearer(request.headers()) {
+        Some(given) if server::same_token(given, &token) => next.run(request).await,
+        _ => ApiError::Unauthorized.into_response(),
+    }
+}
+
+#[derive(Serialize)]
+struct Status {
+    health: HealthReport,
+    subscriptions: usize,
+    alerts: usize,
+    reminders: usize,
+}
+
+async fn status(State(state): State<Arc<AppState>>) -> Result<Json<Status>, ApiError> {
+    let (subscriptions, alerts, reminders) = tokio::try_join!(
+        state.storage.subscriptions(),
+        state.storage.alerts(None),
+        state.storage.reminders(None),
+    )?;
+    Ok(Json(Status {
+        health: state.health.report(),
+        subscriptions: subscriptions.len(),
+        alerts: alerts.len(),
+        reminders: reminders.len(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    days: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct Stats {
+    days: i64,
+    active_users: i64,
+    commands: Vec<CommandStats>,
+    /// Chats the bot can't write to anymore, by reason, e.g. `blocked`.
+    inactive_chats: BTreeMap<String, usize>,
+}
+
+/// Active users and the use of each command over the last days, 7 by default.
+async fn stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<Stats>, ApiError> {
+    let days = query.days.unwrap_or(7).clamp(1, MAX_STATS_DAYS);
+    let since = Utc::now() - chrono::Duration::days(days);
+    let (active_users, commands, inactive_chats) = tokio::try_join!(
+        state.storage.active_users(since),
+        state.storage.command_stats(since),
+        state.storage.inactive_chats(),
+    )?;
+    Ok(Json(Stats {
+        days,
+        active_users,
+        commands,
+        inactive_chats: subscriptions::count_by_reason(&inactive_chats),
+    }))
+}
+
+#[derive(Deserialize)]
+struct SubscriptionsQuery {
+    chat_id: Option<i64>,
+}
+
+/// The subscriptions of every chat, or of the given one.
+async fn subscriptions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SubscriptionsQuery>,
+) -> Result<Json<Vec<Subscription>>, ApiError> {
+    let subscriptions = match query.chat_id {
+        Some(chat_id) => state.storage.chat_subscriptions(chat_id).await?,
+        None => state.storage.subscriptions().await?,
+    };
+    Ok(Json(subscriptions))
+}
+
+#[derive(Deserialize)]
+struct Announcement {
+    chat_id: i64,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct Announced {
+    message_id: i32,
+}
+
+/// Send the text to the chat, in turn with the scheduled messages.
+async fn announce(
+    State(state): State<Arc<AppState>>,
+    Json(announcement): Json<Announcement>,
+) -> Result<Json<Announced>, ApiError> {
+    let subscriptions = &state.subscriptions;
+    let sent = subscriptions
+        .queue
+        .send(|| {
+            subscriptions
+                .bot
+                .send_message(ChatId(announcement.chat_id), announcement.text.clone())
+        })
+        .await;
+    match sent {
+        Ok(message) => {
+            info!("Announcement sent to the chat {}", announcement.chat_id);
+            Ok(Json(Announced {
+                message_id: message.id,
+            }))
+        }
+        Err(e) if chat_is_gone(&e) => {
+            deactivate_chat(&state, ChatId(announcement.chat_id), &e).await?;
+            Err(ApiError::ChatGone)
+        }
+        Err(e) => {
+            warn!(
+                "Could not send the announcement to the chat {} -> {}",
+                announcement.chat_id, e
+            );
+            Err(ApiError::Telegram(e))
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use crate::{
+    cache::{self, Cache},
+    commands::PRICES,
+    error::CommandError,
+    money, quiet,
+    scheduler::{JobError, Schedule},
+    state::AppState,
+    storage::Alert,
+    subscriptions::{chat_is_gone, deactivate_chat, migrate_chat, migrated_to},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+use tracing::{info, warn};
+
+/// How the price alerts are checked.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// Time between two checks of every alert.
+    pub poll_interval_secs: u64,
+    /// Coins asked for in a single request to the price API.
+    pub batch_size: usize,
+    /// Pause between two requests of the same check, to stay under the rate limits.
+    pub batch_delay_ms: u64,
+    /// Window of `/alert btc 5%` when none is given, up to a day.
+    pub move_window_secs: u64,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 60,
+            batch_size: 50,
+            batch_delay_ms: 1500,
+            move_window_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+/// Prefixes of the callback data of the buttons of `/alerts`, followed by `:<user id>:<alert id>`.
+pub const PAUSE: &str = "alert-pause";
+pub const EDIT: &str = "alert-edit";
+pub const DELETE: &str = "alert-delete";
+
+/// How long the new threshold of an alert is waited for once its edit button is pressed.
+const EDIT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Longest window of the alerts on the moves of the price, and how long the prices are kept for them.
+pub const MAX_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Prices seen by the checks, kept in the cache for the alerts on the moves of the price.
+pub struct PriceHistory {
+    cache: Arc<dyn Cache>,
+    /// Window of the alerts that don't give one.
+    pub default_window: Duration,
+}
+
+impl PriceHistory {
+    pub fn new(cache: Arc<dyn Cache>, config: &AlertsConfig) -> Self {
+        Self {
+            cache,
+            default_window: Duration::from_secs(config.move_window_secs).clamp(HOUR, MAX_WINDOW),
+        }
+    }
+
+    /// Add the price to the history of the coin, returning the prices of the last [`MAX_WINDOW`], oldest first.
+    pub async fn record(
+        &self,
+        symbol: &str,
+        at: DateTime<Utc>,
+        price: f64,
+    ) -> Vec<(DateTime<Utc>, f64)> {
+        let key = format!("price_history:{}", symbol);
+        let mut history: Vec<(DateTime<Utc>, f64)> = cache::get_json(self.cache.as_ref(), &key)
+            .await
+            .unwrap_or_default();
+        let oldest = at
+            - chrono::Duration::from_std(MAX_WINDOW).unwrap_or_else(|_| chrono::Duration::zero());
+        history.retain(|(seen, _)| *seen >= oldest);
+        history.push((at, price));
+        cache::set_json(self.cache.as_ref(), &key, &history, MAX_WINDOW).await;
+        history
+    }
+}
+
+/// Alerts whose threshold is being edited, waiting for the next message of the user in the chat, they don't survive a
+/// restart.
+#[derive(Default)]
+pub struct Edits {
+    by_chat_and_user: Mutex<HashMap<(i64, i64), (i64, Instant)>>,
+}
+
+impl Edits {
+    /// Wait for the new threshold of the alert, replacing any other edit of the user in the chat.
+    pub fn start(&self, chat_id: ChatId, user_id: i64, alert_id: i64) {
+        self.by_chat_and_user
+            .lock()
+            .unwrap()
+            .insert((chat_id.0, user_id), (alert_id, Instant::now()));
+    }
+
+    /// The alert being edited by the user in the chat, which stops waiting for them.
+    pub fn take(&self, chat_id: ChatId, user_id: i64) -> Option<i64> {
+        let (alert_id, started) = self
+            .by_chat_and_user
+            .lock()
+            .unwrap()
+            .remove(&(chat_id.0, user_id))?;
+        (started.elapsed() < EDIT_TIMEOUT).then_some(alert_id)
+    }
+}
+
+/// Check the alerts on a schedule.
+pub fn start(state: &Arc<AppState>, config: &AlertsConfig) {
+    let interval = Duration::from_secs(config.poll_interval_secs.max(1));
+    let job = {
+        let state = state.clone();
+        let config = config.clone();
+        move || poll(state.clone(), config.clone())
+    };
+    state
+        .scheduler
+        .add("alerts", Schedule::Every(interval), job);
+}
+
+/// The price crossed the target of the alert, never for the alerts on the moves.
+pub fn triggered(alert: &Alert, price: f64) -> bool {
+    if alert.move_percent.is_some() {
+        false
+    } else if alert.above {
+        price >= alert.target
+    } else {
+        price <= alert.target
+    }
+}
+
+/// A window of whole hours, e.g. `1h` or `24h`, up to [`MAX_WINDOW`].
+pub fn parse_window(text: &str) -> Option<Duration> {
+    let hours = text
+        .trim()
+        .to_lowercase()
+        .strip_suffix('h')?
+        .parse::<u32>()
+        .ok()?;
+    let window = HOUR * hours;
+    (hours > 0 && window <= MAX_WINDOW).then_some(window)
+}
+
+/// The biggest move of the price past the percent of the alert, in percent, from the prices seen in its window.
+///
+/// Only the prices seen since the alert was set count, the moves before it don't trigger it.
+pub fn moved(
+    alert: &Alert,
+    history: &[(DateTime<Utc>, f64)],
+    price: f64,
+    now: DateTime<Utc>,
+) -> Option<f64> {
+    let percent = alert.move_percent?;
+    let window = chrono::Duration::seconds(alert.window_secs.unwrap_or_default());
+    let since = match alert.created_at {
+        Some(created_at) => created_at.max(now - window),
+        None => now - window,
+    };
+    history
+        .iter()
+        .filter(|(seen, past)| *seen >= since && *past > 0.0)
+        .map(|(_, past)| (price - past) / past * 100.0)
+        .filter(|change| change.abs() >= percent)
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+}
+
+/// Store an alert for when the price reaches `target`, from below or above depending on the current price.
+///
+/// `None` if the price of the symbol is unknown.
+pub async fn add(
+    state: &AppState,
+    user_id: i64,
+    chat_id: ChatId,
+    symbol: &str,
+    target: f64,
+) -> Result<Option<(Alert, f64)>, CommandError> {
+    let symbol = symbol.to_lowercase();
+    let price = match price(state, &symbol).await? {
+        Some(price) => price,
+        None => return Ok(None),
+    };
+
+    let mut alert = Alert {
+        id: 0,
+        user_id,
+        chat_id: chat_id.0,
+        symbol,
+        target,
+        above: target > price,
+        move_percent: None,
+        window_secs: None,
+        created_at: Some(Utc::now()),
+        paused: false,
+    };
+    alert.id = state.storage.add_alert(&alert).await?;
+    Ok(Some((alert, price)))
+}
+
+/// Store an alert for when the price moves by `percent` either way within the window, up to [`MAX_WINDOW`].
+///
+/// `None` if the price of the symbol is unknown.
+pub async fn add_move(
+    state: &AppState,
+    user_id: i64,
+    chat_id: ChatId,
+    symbol: &str,
+    percent: f64,
+    window: Duration,
+) -> Result<Option<(Alert, f64)>, CommandError> {
+    let symbol = symbol.to_lowercase();
+    let price = match price(state, &symbol).await? {
+        Some(price) => price,
+        None => return Ok(None),
+    };
+
+    let mut alert = Alert {
+        id: 0,
+        user_id,
+        chat_id: chat_id.0,
+        symbol,
+        target: price,
+        above: false,
+        move_percent: Some(percent),
+        window_secs: Some(window.min(MAX_WINDOW).as_secs() as i64),
+        created_at: Some(Utc::now()),
+        paused: false,
+    };
+    alert.id = state.storage.add_alert(&alert).await?;
+    // The price now is where the moves are measured from
+    state
+        .price_history
+        .record(&alert.symbol, Utc::now(), price)
+        .await;
+    Ok(Some((alert, price)))
+}
+
+/// What the alert waits for, e.g. `BTC above $70000` or `BTC moves 5% within 24h`.
+pub fn describe(alert: &Alert) -> String {
+    let symbol = alert.symbol.to_uppercase();
+    let text = match alert.move_percent {
+        Some(percent) => format!(
+            "{} moves {}% within {}h",
+            symbol,
+            percent,
+            alert.window_secs.unwrap_or_default() / 3600
+        ),
+        None => format!(
+            "{} {} ${}",
+            symbol,
+            if alert.above { "above" } else { "below" },
+            alert.target
+        ),
+    };
+    if alert.paused {
+        format!("{} (paused)", text)
+    } else {
+        text
+    }
+}
+
+/// The alerts of `/alerts`, each with the buttons to pause or resume, edit and delete it.
+pub fn list(alerts: &[Alert], user_id: UserId) -> (String, Option<InlineKeyboardMarkup>) {
+    if alerts.is_empty() {
+        return ("You have no alerts, add one with /alert".to_string(), None);
+    }
+
+    let mut text = String::from("Your alerts:\n");
+    let mut buttons = Vec::new();
+    for (position, alert) in alerts.iter().enumerate() {
+        let number = position + 1;
+        writeln!(text, "{}. {}", number, describe(alert)).ok();
+        let button = |label: String, action: &str| {
+            InlineKeyboardButton::callback(label, format!("{}:{}:{}", action, user_id, alert.id))
+        };
+        buttons.push([
+            if alert.paused {
+                button(format!("▶️ {}", number), PAUSE)
+            } else {
+                button(format!("⏸ {}", number), PAUSE)
+            },
+            button(format!("✏️ {}", number), EDIT),
+            button(format!("🗑 {}", number), DELETE),
+        ]);
+    }
+    (text, Some(InlineKeyboardMarkup::new(buttons)))
+}
+
+/// The alert of the user with the id, `None` if it was deleted, notified or isn't theirs.
+pub async fn of_user(
+    state: &AppState,
+    user_id: i64,
+    id: i64,
+) -> Result<Option<Alert>, CommandError> {
+    Ok(state
+        .storage
+        .alerts(Some(user_id))
+        .await?
+        .into_iter()
+        .find(|alert| alert.id == id))
+}
+
+/// Set the threshold of the alert from the text the user sent, the price, or the percent of the alerts on the moves.
+///
+/// `None` if the text isn't a positive number.
+pub async fn edit(
+    state: &AppState,
+    alert: &Alert,
+    text: &str,
+) -> Result<Option<Alert>, CommandError> {
+    let value = match text.trim().trim_end_matches('%').trim().parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => value,
+        _ => return Ok(None),
+    };
+
+    let mut alert = alert.clone();
+    if alert.move_percent.is_some() {
+        alert.move_percent = Some(value);
+    } else {
+        // The direction follows the current price, as when the alert is added
+        if let Some(price) = price(state, &alert.symbol).await? {
+            alert.above = value > price;
+        }
+        alert.target = value;
+    }
+    state.storage.update_alert(&alert).await?;
+    Ok(Some(alert))
+}
+
+async fn price(state: &AppState, symbol: &str) -> Result<Option<f64>, CommandError> {
+    state
+        .price_api
+        .usd_price(symbol)
+        .await
+        .map_err(|error| CommandError::Upstream {
+            upstream: PRICES,
+            error,
+        })
+}
+
+/// Fetch the price of every watched coin, in as few requests as possible, and notify the triggered alerts.
+///
+/// Alerts are one-shot, they are removed once notified. Chats in their quiet hours are notified by the first check
+/// after them, if the price is still past the target. The alerts on the moves compare the price with the ones seen by
+/// the previous checks within their window. Paused alerts are skipped.
+pub async fn poll(state: Arc<AppState>, config: AlertsConfig) -> Result<(), JobError> {
+    let mut alerts = state.storage.alerts(None).await?;
+    alerts.retain(|alert| !alert.paused);
+    if alerts.is_empty() {
+        return Ok(());
+    }
+
+    let symbols = alerts
+        .iter()
+        .map(|alert| alert.symbol.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let mut prices = HashMap::new();
+    for (position, batch) in symbols.chunks(config.batch_size.max(1)).enumerate() {
+        if position > 0 {
+            tokio::time::sleep(Duration::from_millis(config.batch_delay_ms)).await;
+        }
+        match state.price_api.usd_prices(batch).await {
+            Ok(batch) => {
+                state.reporter.success(PRICES);
+                prices.extend(batch);
+            }
+            Err(e) => {
+                warn!("Could not fetch the prices of {:?} -> {}", batch, e);
+                state.reporter.failure(PRICES, e).await;
+            }
+        }
+    }
+
+    let now = Utc::now();
+    // Only the coins watched for their moves are kept
+    let mut histories = HashMap::new();
+    for alert in alerts.iter().filter(|alert| alert.move_percent.is_some()) {
+        if histories.contains_key(&alert.symbol) {
+            continue;
+        }
+        if let Some(price) = prices.get(&alert.symbol) {
+            let history = state.price_history.record(&alert.symbol, now, *price).await;
+            histories.insert(alert.symbol.clone(), history);
+        }
+    }
+
+    for alert in alerts {
+        let price = match prices.get(&alert.symbol) {
+            Some(price) => *price,
+            None => continue,
+        };
+        let history = histories.get(&alert.symbol).map_or(&[][..], Vec::as_slice);
+        let change = moved(&alert, history, price, now);
+        if !(triggered(&alert, price) || change.is_some())
+            || quiet::ends_after(state.storage.as_ref(), alert.chat_id, now)
+                .await
+                .is_some()
+        {
+            continue;
+        }
+        // One chat failing doesn't hold back the others
+        if let Err(e) = notify(&state, &alert, price, change).await {
+            warn!("Could not notify the alert {} -> {}", alert.id, e);
+        }
+    }
+    Ok(())
+}
+
+async fn notify(
+    state: &Arc<AppState>,
+    alert: &Alert,
+    price: f64,
+    change: Option<f64>,
+) -> Result<(), JobError> {
+    let text = match change {
+        Some(change) => format!(
+            "{} moved {} in the last {}h, it's now ${}",
+            alert.symbol.to_uppercase(),
+            money::change(change),
+            alert.window_secs.unwrap_or_default() / 3600,
+            price
+        ),
+        None => format!(
+            "{} is now ${}, {} your target of ${}",
+            alert.symbol.to_uppercase(),
+            price,
+            if alert.above { "above" } else { "below" },
+            alert.target
+        ),
+    };
+    let subscriptions = &state.subscriptions;
+    let text = &text;
+    let send = move |chat_id| {
+        subscriptions
+            .queue
+            .send(move || subscriptions.bot.send_message(chat_id, text.clone()))
+    };
+    let mut chat_id = ChatId(alert.chat_id);
+    let sent = match send(chat_id).await {
+        Err(e) => match migrated_to(&e) {
+            Some(to) => {
+                migrate_chat(state, chat_id, to).await?;
+                chat_id = to;
+                send(to).await
+            }
+            None => Err(e),
+        },
+        sent => sent,
+    };
+    match sent {
+        Ok(_) => info!("Alert {} notified", alert.id),
+        Err(e) if chat_is_gone(&e) => {
+            info!("Dropping the alert {}, the chat is gone", alert.id);
+            deactivate_chat(state, chat_id, &e).await?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+    state.storage.remove_alert(alert.id).await?;
+    Ok(())
+}
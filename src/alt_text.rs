@@ -0,0 +1,66 @@
+//! Captions describing the dogs sent to the chats that asked for them, read out by screen readers.
+
+use crate::{
+    breed::BreedQuery,
+    storage::{self, Storage},
+};
+use reqwest::Url;
+use std::str::FromStr;
+use tracing::warn;
+
+/// The chat asked for the dogs to be described.
+pub async fn is_enabled(storage: &dyn Storage, chat_id: i64) -> bool {
+    match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings.is_some_and(|settings| settings.alt_text),
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            false
+        }
+    }
+}
+
+pub async fn set(storage: &dyn Storage, chat_id: i64, enabled: bool) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| settings.alt_text = enabled).await
+}
+
+/// Breed of a dog.ceo image, e.g. `golden retriever` for `.../breeds/retriever-golden/n02099601_176.jpg`.
+pub fn breed_of(image: &str) -> Option<BreedQuery> {
+    let url = Url::parse(image).ok()?;
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| *segment == "breeds")?;
+    // The sub-breed follows the breed, e.g. `retriever-golden`
+    let name = segments.next()?;
+    let query = match name.split_once('-') {
+        Some((breed, sub_breed)) => format!("{} {}", sub_breed, breed),
+        None => name.to_string(),
+    };
+    BreedQuery::from_str(&query).ok()
+}
+
+/// What the image shows, e.g. `Photo of a golden retriever`.
+pub fn describe(image: &str) -> String {
+    match breed_of(image) {
+        Some(breed) => {
+            let breed = breed.to_string();
+            let article = if breed.starts_with(['a', 'e', 'i', 'o', 'u']) {
+                "an"
+            } else {
+                "a"
+            };
+            format!("Photo of {} {}", article, breed)
+        }
+        None => "Photo of a dog".to_string(),
+    }
+}
+
+/// The description of the image when the chat asked for them.
+pub async fn caption(storage: &dyn Storage, chat_id: i64, image: &str) -> Option<String> {
+    if is_enabled(storage, chat_id).await {
+        Some(describe(image))
+    } else {
+        None
+    }
+}
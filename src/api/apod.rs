@@ -0,0 +1,70 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+/// Astronomy Picture of the Day.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Apod {
+    pub title: String,
+    pub explanation: String,
+    /// Image, or embeddable video page.
+    pub url: String,
+    pub media_type: String,
+    pub copyright: Option<String>,
+}
+
+impl Apod {
+    pub fn is_image(&self) -> bool {
+        self.media_type == "image"
+    }
+}
+
+/// Source of astronomy pictures.
+#[async_trait]
+pub trait ApodApi: Send + Sync {
+    async fn today(&self) -> Result<Apod, reqwest_middleware::Error>;
+}
+
+/// https://api.nasa.gov
+pub struct Nasa {
+    client: HttpClient,
+    base_url: String,
+    api_key: String,
+}
+
+impl Nasa {
+    pub const BASE_URL: &'static str = "https://api.nasa.gov";
+    /// Rate limited per IP, enough for a small bot.
+    pub const DEMO_KEY: &'static str = "DEMO_KEY";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL, Self::DEMO_KEY)
+    }
+
+    pub fn with_base_url(
+        client: HttpClient,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ApodApi for Nasa {
+    async fn today(&self) -> Result<Apod, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!("{}/planetary/apod", self.base_url))
+            .query(&[("api_key", self.api_key.as_str())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Apod>()
+            .await?)
+    }
+}
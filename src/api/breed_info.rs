@@ -0,0 +1,90 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+/// Both units, e.g. `"35 - 60"` pounds and `"16 - 27"` kilograms.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Measure {
+    pub imperial: Option<String>,
+    pub metric: Option<String>,
+}
+
+/// What a breed is like, as described by the kennel clubs.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct BreedInfo {
+    pub name: String,
+    /// In kilograms.
+    #[serde(default)]
+    pub weight: Measure,
+    /// At the withers, in centimetres.
+    #[serde(default)]
+    pub height: Measure,
+    /// e.g. `"12 - 15 years"`.
+    pub life_span: Option<String>,
+    /// Comma separated, e.g. `"Outgoing, Friendly, Alert"`.
+    pub temperament: Option<String>,
+}
+
+/// Source of the size, temperament and lifespan of the breeds.
+#[async_trait]
+pub trait BreedInfoApi: Send + Sync {
+    /// The breed best matching the name, `None` if there is none.
+    async fn search(&self, name: &str) -> Result<Option<BreedInfo>, reqwest_middleware::Error>;
+}
+
+/// https://thedogapi.com
+pub struct TheDogApi {
+    client: HttpClient,
+    base_url: String,
+    /// Searching works without one.
+    api_key: Option<String>,
+}
+
+impl TheDogApi {
+    pub const BASE_URL: &'static str = "https://api.thedogapi.com/v1";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL, None)
+    }
+
+    pub fn with_base_url(
+        client: HttpClient,
+        base_url: impl Into<String>,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl BreedInfoApi for TheDogApi {
+    async fn search(&self, name: &str) -> Result<Option<BreedInfo>, reqwest_middleware::Error> {
+        let mut request = self
+            .client
+            .get(format!("{}/breeds/search", self.base_url))
+            .query(&[("q", name)]);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        let breeds = request
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<BreedInfo>>()
+            .await?;
+        Ok(best_match(breeds, name))
+    }
+}
+
+/// The breed named exactly like that, e.g. `Pug` over `Puggle`, or else the first one found.
+fn best_match(breeds: Vec<BreedInfo>, name: &str) -> Option<BreedInfo> {
+    let exact = breeds
+        .iter()
+        .position(|breed| breed.name.eq_ignore_ascii_case(name))
+        .unwrap_or_default();
+    breeds.into_iter().nth(exact)
+}
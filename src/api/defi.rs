@@ -0,0 +1,86 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+/// Total value locked in a DeFi protocol.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Protocol {
+    pub name: String,
+    pub slug: String,
+    /// In USD.
+    pub tvl: Option<f64>,
+    /// In percent.
+    pub change_7d: Option<f64>,
+}
+
+/// Total value locked in the protocols of a chain.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub name: String,
+    /// In USD.
+    pub tvl: f64,
+}
+
+/// Source of the value locked in DeFi.
+#[async_trait]
+pub trait DefiApi: Send + Sync {
+    /// The protocol with the slug or name, in any case, `None` if it's unknown.
+    async fn protocol(&self, name: &str) -> Result<Option<Protocol>, reqwest_middleware::Error>;
+
+    /// The chains, the most valuable first.
+    async fn chains(&self) -> Result<Vec<Chain>, reqwest_middleware::Error>;
+}
+
+/// https://defillama.com, which needs no key.
+pub struct DefiLlama {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl DefiLlama {
+    pub const BASE_URL: &'static str = "https://api.llama.fi";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl DefiApi for DefiLlama {
+    async fn protocol(&self, name: &str) -> Result<Option<Protocol>, reqwest_middleware::Error> {
+        // The single protocol endpoint has the whole history without the change, the list has both
+        let protocols = self
+            .client
+            .get(format!("{}/protocols", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<Protocol>>()
+            .await?;
+
+        Ok(protocols.into_iter().find(|protocol| {
+            protocol.slug.eq_ignore_ascii_case(name) || protocol.name.eq_ignore_ascii_case(name)
+        }))
+    }
+
+    async fn chains(&self) -> Result<Vec<Chain>, reqwest_middleware::Error> {
+        let mut chains = self
+            .client
+            .get(format!("{}/v2/chains", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<Chain>>()
+            .await?;
+
+        chains.sort_by(|a, b| b.tvl.total_cmp(&a.tvl));
+        Ok(chains)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// Senses shown at most, the common words have dozens.
+const MAX_SENSES: usize = 5;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub word: String,
+    pub phonetic: Option<String>,
+    #[serde(default)]
+    pub phonetics: Vec<Phonetic>,
+    pub meanings: Vec<Meaning>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Phonetic {
+    pub text: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Meaning {
+    pub part_of_speech: String,
+    pub definitions: Vec<Definition>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub definition: String,
+    pub example: Option<String>,
+}
+
+impl Entry {
+    /// Pronunciation, from any of the phonetics when the main one is missing.
+    pub fn pronunciation(&self) -> Option<&str> {
+        self.phonetic
+            .iter()
+            .chain(
+                self.phonetics
+                    .iter()
+                    .filter_map(|phonetic| phonetic.text.as_ref()),
+            )
+            .map(String::as_str)
+            .find(|text| !text.is_empty())
+    }
+
+    /// The word with a line per sense, with their part of speech and an example if there's one.
+    pub fn render(&self) -> String {
+        let mut text = match self.pronunciation() {
+            Some(pronunciation) => format!("{} {}\n", self.word, pronunciation),
+            None => format!("{}\n", self.word),
+        };
+        let senses = self.meanings.iter().flat_map(|meaning| {
+            meaning
+                .definitions
+                .iter()
+                .map(move |definition| (&meaning.part_of_speech, definition))
+        });
+        for (position, (part_of_speech, definition)) in senses.take(MAX_SENSES).enumerate() {
+            write!(
+                text,
+                "\n{}. ({}) {}",
+                position + 1,
+                part_of_speech,
+                definition.definition
+            )
+            .ok();
+            if let Some(example) = &definition.example {
+                write!(text, "\n   \"{}\"", example).ok();
+            }
+        }
+        text
+    }
+}
+
+/// Source of word definitions.
+#[async_trait]
+pub trait DictionaryApi: Send + Sync {
+    /// First entry of the English word, `None` if it's unknown.
+    async fn define(&self, word: &str) -> Result<Option<Entry>, reqwest_middleware::Error>;
+}
+
+/// https://dictionaryapi.dev
+pub struct FreeDictionary {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl FreeDictionary {
+    pub const BASE_URL: &'static str = "https://api.dictionaryapi.dev/api/v2";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl DictionaryApi for FreeDictionary {
+    async fn define(&self, word: &str) -> Result<Option<Entry>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/entries/en/{}", self.base_url, word))
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let entries = res.error_for_status()?.json::<Vec<Entry>>().await?;
+
+        Ok(entries.into_iter().next())
+    }
+}
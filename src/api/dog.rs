@@ -0,0 +1,295 @@
+use crate::{
+    breed::BreedQuery,
+    cache::{self, Cache},
+    http::HttpClient,
+    quota,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use metrics::gauge;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+#[derive(Deserialize, Serialize)]
+pub struct DogResponse<T> {
+    pub message: T,
+    pub status: String,
+}
+
+pub type BreedsList = HashMap<String, Vec<String>>;
+
+/// Source of dog pictures and breeds.
+#[async_trait]
+pub trait DogApi: Send + Sync {
+    /// Random image of any breed.
+    async fn random(&self) -> Result<DogResponse<String>, reqwest_middleware::Error>;
+
+    /// Random image of the given breed.
+    async fn random_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<String>, reqwest_middleware::Error>;
+
+    /// All the images of the given breed, always in the same order.
+    async fn images_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<Vec<String>>, reqwest_middleware::Error>;
+
+    /// All the breeds with their sub-breeds.
+    async fn breeds(&self) -> Result<DogResponse<BreedsList>, reqwest_middleware::Error>;
+
+    /// When the cached breeds were fetched from the upstream, by any instance sharing the cache; None if they
+    /// aren't cached.
+    async fn breeds_fetched_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+/// https://dog.ceo
+pub struct DogCeo {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl DogCeo {
+    pub const BASE_URL: &'static str = "https://dog.ceo/api";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl DogApi for DogCeo {
+    async fn random(&self) -> Result<DogResponse<String>, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!("{}/breeds/image/random", self.base_url))
+            .send()
+            .await?
+            .json::<DogResponse<String>>()
+            .await?)
+    }
+
+    async fn random_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<String>, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!(
+                "{}/breed/{}/images/random",
+                self.base_url,
+                breed.path()
+            ))
+            .send()
+            .await?
+            .json::<DogResponse<String>>()
+            .await?)
+    }
+
+    async fn images_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<Vec<String>>, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!("{}/breed/{}/images", self.base_url, breed.path()))
+            .send()
+            .await?
+            .json::<DogResponse<Vec<String>>>()
+            .await?)
+    }
+
+    async fn breeds(&self) -> Result<DogResponse<BreedsList>, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!("{}/breeds/list/all", self.base_url))
+            .send()
+            .await?
+            .json::<DogResponse<BreedsList>>()
+            .await?)
+    }
+}
+
+/// Offline [`DogApi`] that always answers with the same image.
+pub struct MockDogApi {
+    pub image: String,
+    pub breeds: BreedsList,
+}
+
+impl Default for MockDogApi {
+    fn default() -> Self {
+        let mut breeds = BreedsList::new();
+        breeds.insert("husky".to_string(), vec![]);
+        breeds.insert(
+            "retriever".to_string(),
+            vec!["golden".to_string(), "curly".to_string()],
+        );
+        Self {
+            image: "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg".to_string(),
+            breeds,
+        }
+    }
+}
+
+impl MockDogApi {
+    fn knows(&self, breed: &BreedQuery) -> bool {
+        match (self.breeds.get(breed.breed.as_str()), &breed.sub_breed) {
+            (Some(_), None) => true,
+            (Some(variants), Some(sub_breed)) => variants.iter().any(|v| v == sub_breed.as_str()),
+            (None, _) => false,
+        }
+    }
+}
+
+#[async_trait]
+impl DogApi for MockDogApi {
+    async fn random(&self) -> Result<DogResponse<String>, reqwest_middleware::Error> {
+        Ok(DogResponse {
+            message: self.image.clone(),
+            status: "success".to_string(),
+        })
+    }
+
+    async fn random_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<String>, reqwest_middleware::Error> {
+        if self.knows(breed) {
+            self.random().await
+        } else {
+            Ok(DogResponse {
+                message: "Breed not found (master breed does not exist)".to_string(),
+                status: "error".to_string(),
+            })
+        }
+    }
+
+    async fn images_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<Vec<String>>, reqwest_middleware::Error> {
+        if self.knows(breed) {
+            Ok(DogResponse {
+                message: vec![self.image.clone()],
+                status: "success".to_string(),
+            })
+        } else {
+            Ok(DogResponse {
+                message: Vec::new(),
+                status: "error".to_string(),
+            })
+        }
+    }
+
+    async fn breeds(&self) -> Result<DogResponse<BreedsList>, reqwest_middleware::Error> {
+        Ok(DogResponse {
+            message: self.breeds.clone(),
+            status: "success".to_string(),
+        })
+    }
+}
+
+/// [`DogApi`] remembering the lists of breeds and of their images, which rarely change, and answering with the
+/// last ones while dog.ceo is over its quota.
+pub struct CachedDogApi {
+    inner: Arc<dyn DogApi>,
+    cache: Arc<dyn Cache>,
+    breeds_ttl: Duration,
+}
+
+impl CachedDogApi {
+    pub fn new(inner: Arc<dyn DogApi>, cache: Arc<dyn Cache>, breeds_ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache,
+            breeds_ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl DogApi for CachedDogApi {
+    async fn random(&self) -> Result<DogResponse<String>, reqwest_middleware::Error> {
+        self.inner.random().await
+    }
+
+    async fn random_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<String>, reqwest_middleware::Error> {
+        self.inner.random_for_breed(breed).await
+    }
+
+    async fn images_for_breed(
+        &self,
+        breed: &BreedQuery,
+    ) -> Result<DogResponse<Vec<String>>, reqwest_middleware::Error> {
+        let key = format!("breed_images:{}", breed.path());
+        if let Some(images) = cache::get_json(self.cache.as_ref(), &key).await {
+            return Ok(images);
+        }
+
+        let images = match self.inner.images_for_breed(breed).await {
+            Ok(images) => images,
+            Err(e) if quota::is_exceeded(&e) => {
+                return cache::get_stale_json(self.cache.as_ref(), &key)
+                    .await
+                    .ok_or(e)
+            }
+            Err(e) => return Err(e),
+        };
+        if images.status == "success" {
+            cache::set_json_with_stale(self.cache.as_ref(), &key, &images, self.breeds_ttl).await;
+        }
+        Ok(images)
+    }
+
+    async fn breeds(&self) -> Result<DogResponse<BreedsList>, reqwest_middleware::Error> {
+        if let Some(breeds) = cache::get_json(self.cache.as_ref(), "breeds").await {
+            return Ok(breeds);
+        }
+
+        let breeds = match self.inner.breeds().await {
+            Ok(breeds) => breeds,
+            Err(e) if quota::is_exceeded(&e) => {
+                return cache::get_stale_json(self.cache.as_ref(), "breeds")
+                    .await
+                    .ok_or(e)
+            }
+            Err(e) => return Err(e),
+        };
+        if breeds.status == "success" {
+            let now = Utc::now();
+            // Kept as long as the stale copy, which is served with the same age
+            cache::set_json(
+                self.cache.as_ref(),
+                "breeds_fetched_at",
+                &now,
+                cache::STALE_TTL,
+            )
+            .await;
+            // Its age is `time() - breed_list_updated_timestamp_seconds`
+            gauge!(
+                "breed_list_updated_timestamp_seconds",
+                now.timestamp() as f64
+            );
+            cache::set_json_with_stale(self.cache.as_ref(), "breeds", &breeds, self.breeds_ttl)
+                .await;
+        }
+        Ok(breeds)
+    }
+
+    async fn breeds_fetched_at(&self) -> Option<DateTime<Utc>> {
+        cache::get_json(self.cache.as_ref(), "breeds_fetched_at").await
+    }
+}
@@ -0,0 +1,81 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Source of the exchange rates between fiat currencies.
+#[async_trait]
+pub trait FxApi: Send + Sync {
+    /// Daily rates of `base` in `quote` between the dates, oldest first, `None` if a currency is unknown.
+    ///
+    /// There are no rates on the days the markets are closed.
+    async fn history(
+        &self,
+        base: &str,
+        quote: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Option<Vec<(NaiveDate, f64)>>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct FrankfurterSeries {
+    rates: BTreeMap<NaiveDate, HashMap<String, f64>>,
+}
+
+/// https://www.frankfurter.app, the reference rates of the European Central Bank, which needs no key.
+pub struct Frankfurter {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl Frankfurter {
+    pub const BASE_URL: &'static str = "https://api.frankfurter.app";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl FxApi for Frankfurter {
+    async fn history(
+        &self,
+        base: &str,
+        quote: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Option<Vec<(NaiveDate, f64)>>, reqwest_middleware::Error> {
+        let (base, quote) = (base.to_uppercase(), quote.to_uppercase());
+        let res = self
+            .client
+            .get(format!("{}/{}..{}", self.base_url, from, to))
+            .query(&[("from", base.as_str()), ("to", quote.as_str())])
+            .send()
+            .await?;
+        // Unknown currencies are answered with 404 or, for some, 422
+        if matches!(
+            res.status(),
+            StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY
+        ) {
+            return Ok(None);
+        }
+        let res = res.error_for_status()?.json::<FrankfurterSeries>().await?;
+
+        Ok(Some(
+            res.rates
+                .into_iter()
+                .filter_map(|(date, mut rates)| Some((date, rates.remove(&quote)?)))
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,64 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Holiday {
+    pub date: NaiveDate,
+    /// In the language of the country.
+    pub local_name: String,
+    /// In English.
+    pub name: String,
+}
+
+/// Source of public holidays.
+#[async_trait]
+pub trait HolidaysApi: Send + Sync {
+    /// Public holidays of the next 365 days in the country, soonest first, `None` if the country is unknown.
+    async fn upcoming(
+        &self,
+        country: &str,
+    ) -> Result<Option<Vec<Holiday>>, reqwest_middleware::Error>;
+}
+
+/// https://date.nager.at
+pub struct NagerDate {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl NagerDate {
+    pub const BASE_URL: &'static str = "https://date.nager.at/api/v3";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl HolidaysApi for NagerDate {
+    async fn upcoming(
+        &self,
+        country: &str,
+    ) -> Result<Option<Vec<Holiday>>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/NextPublicHolidays/{}", self.base_url, country))
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(res.error_for_status()?.json::<Vec<Holiday>>().await?))
+    }
+}
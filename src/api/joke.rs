@@ -0,0 +1,124 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+/// Categories that can be asked for, besides any.
+pub const CATEGORIES: &[&str] = &["programming", "misc", "pun", "spooky", "christmas", "dark"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joke {
+    pub id: u32,
+    /// The whole joke, or its setup when it has a punchline.
+    pub text: String,
+    pub punchline: Option<String>,
+}
+
+/// Source of jokes.
+#[async_trait]
+pub trait JokeApi: Send + Sync {
+    /// Random joke from any of the categories, or from all of them when empty.
+    ///
+    /// `None` if no joke matches.
+    async fn random(
+        &self,
+        categories: &[String],
+    ) -> Result<Option<Joke>, reqwest_middleware::Error>;
+
+    async fn by_id(&self, id: u32) -> Result<Option<Joke>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct JokeResponse {
+    error: bool,
+    id: Option<u32>,
+    joke: Option<String>,
+    setup: Option<String>,
+    delivery: Option<String>,
+}
+
+impl JokeResponse {
+    fn into_joke(self) -> Option<Joke> {
+        if self.error {
+            return None;
+        }
+        let id = self.id?;
+        match (self.joke, self.setup, self.delivery) {
+            (Some(text), _, _) => Some(Joke {
+                id,
+                text,
+                punchline: None,
+            }),
+            (None, Some(text), punchline) => Some(Joke {
+                id,
+                text,
+                punchline,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// https://jokeapi.dev
+pub struct JokeApiDev {
+    client: HttpClient,
+    base_url: String,
+    safe_mode: bool,
+}
+
+impl JokeApiDev {
+    pub const BASE_URL: &'static str = "https://v2.jokeapi.dev";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL, true)
+    }
+
+    /// In safe mode only the jokes suitable for everyone are returned.
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>, safe_mode: bool) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            safe_mode,
+        }
+    }
+
+    async fn fetch(
+        &self,
+        categories: &[String],
+        query: &[(&str, String)],
+    ) -> Result<Option<Joke>, reqwest_middleware::Error> {
+        let categories = if categories.is_empty() {
+            "Any".to_string()
+        } else {
+            categories.join(",")
+        };
+        // `safe-mode` is a flag without value
+        let safe_mode = if self.safe_mode { "?safe-mode" } else { "" };
+        let res = self
+            .client
+            .get(format!(
+                "{}/joke/{}{}",
+                self.base_url, categories, safe_mode
+            ))
+            .query(query)
+            .send()
+            .await?
+            .json::<JokeResponse>()
+            .await?;
+
+        Ok(res.into_joke())
+    }
+}
+
+#[async_trait]
+impl JokeApi for JokeApiDev {
+    async fn random(
+        &self,
+        categories: &[String],
+    ) -> Result<Option<Joke>, reqwest_middleware::Error> {
+        self.fetch(categories, &[]).await
+    }
+
+    async fn by_id(&self, id: u32) -> Result<Option<Joke>, reqwest_middleware::Error> {
+        self.fetch(&[], &[("idRange", id.to_string())]).await
+    }
+}
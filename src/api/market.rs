@@ -0,0 +1,80 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The whole crypto market, in USD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalMarket {
+    pub market_cap: f64,
+    pub volume_24h: f64,
+    /// Change of the market cap in the last 24 hours, in percent.
+    pub market_cap_change_24h: Option<f64>,
+    /// Share of the market cap of each coin, keyed by lowercase symbol, in percent.
+    pub dominance: HashMap<String, f64>,
+}
+
+/// Source of the figures of the whole crypto market.
+#[async_trait]
+pub trait MarketApi: Send + Sync {
+    async fn global(&self) -> Result<GlobalMarket, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoGlobal {
+    data: CoinGeckoGlobalData,
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoGlobalData {
+    total_market_cap: HashMap<String, f64>,
+    total_volume: HashMap<String, f64>,
+    market_cap_percentage: HashMap<String, f64>,
+    market_cap_change_percentage_24h_usd: Option<f64>,
+}
+
+/// The global endpoint of https://www.coingecko.com, which needs no key.
+pub struct CoinGeckoMarket {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl CoinGeckoMarket {
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl MarketApi for CoinGeckoMarket {
+    async fn global(&self) -> Result<GlobalMarket, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/global", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<CoinGeckoGlobal>()
+            .await?;
+
+        Ok(GlobalMarket {
+            market_cap: res
+                .data
+                .total_market_cap
+                .get("usd")
+                .copied()
+                .unwrap_or_default(),
+            volume_24h: res
+                .data
+                .total_volume
+                .get("usd")
+                .copied()
+                .unwrap_or_default(),
+            market_cap_change_24h: res.data.market_cap_change_percentage_24h_usd,
+            dominance: res.data.market_cap_percentage,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+pub mod apod;
+pub mod breed_info;
+pub mod defi;
+pub mod dictionary;
+pub mod dog;
+pub mod fx;
+pub mod holidays;
+pub mod joke;
+pub mod market;
+pub mod news;
+pub mod nft;
+pub mod pokemon;
+pub mod price;
+pub mod quote;
+pub mod shortener;
+pub mod stock;
+pub mod translate;
+pub mod trivia;
+pub mod urban;
+pub mod weather;
+pub mod wiki;
+pub mod xkcd;
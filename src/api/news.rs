@@ -0,0 +1,172 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::Url;
+use rss::Channel;
+use std::{fmt, net::IpAddr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Headline {
+    pub title: String,
+    pub link: String,
+    pub description: Option<String>,
+}
+
+impl Headline {
+    /// The topic is in the title or the description, ignoring case.
+    pub fn is_about(&self, topic: &str) -> bool {
+        let topic = topic.to_lowercase();
+        self.title.to_lowercase().contains(&topic)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|description| description.to_lowercase().contains(&topic))
+    }
+}
+
+#[derive(Debug)]
+pub enum NewsError {
+    Http(reqwest_middleware::Error),
+    /// The source isn't an RSS feed.
+    Feed(rss::Error),
+    /// The feed of a chat isn't on the internet, e.g. `http://localhost` or `http://10.0.0.1`.
+    Private(String),
+}
+
+impl fmt::Display for NewsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{}", e),
+            Self::Feed(e) => write!(f, "not an RSS feed: {}", e),
+            Self::Private(feed) => write!(f, "{} is not a public address", feed),
+        }
+    }
+}
+
+impl std::error::Error for NewsError {}
+
+impl From<reqwest_middleware::Error> for NewsError {
+    fn from(e: reqwest_middleware::Error) -> Self {
+        Self::Http(e)
+    }
+}
+
+impl From<reqwest::Error> for NewsError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e.into())
+    }
+}
+
+/// Source of news headlines.
+#[async_trait]
+pub trait NewsApi: Send + Sync {
+    /// Headlines of the feed, or of the default one, in the order of the feed.
+    async fn headlines(&self, feed: Option<&str>) -> Result<Vec<Headline>, NewsError>;
+}
+
+/// Any RSS 2.0 feed.
+pub struct Rss {
+    client: HttpClient,
+    default_feed: String,
+    private_feeds: bool,
+}
+
+impl Rss {
+    /// BBC News.
+    pub const DEFAULT_FEED: &'static str = "https://feeds.bbci.co.uk/news/rss.xml";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_default_feed(client, Self::DEFAULT_FEED)
+    }
+
+    pub fn with_default_feed(client: HttpClient, default_feed: impl Into<String>) -> Self {
+        Self {
+            client,
+            default_feed: default_feed.into(),
+            private_feeds: false,
+        }
+    }
+
+    /// Also read the feeds of the chats in the bot's own network, e.g. a local test server.
+    pub fn with_private_feeds(self) -> Self {
+        Self {
+            private_feeds: true,
+            ..self
+        }
+    }
+}
+
+#[async_trait]
+impl NewsApi for Rss {
+    async fn headlines(&self, feed: Option<&str>) -> Result<Vec<Headline>, NewsError> {
+        // The chats choose their feed, it mustn't reach what only the bot can
+        if let Some(feed) = feed {
+            if !self.private_feeds && !is_public(feed).await {
+                return Err(NewsError::Private(feed.to_string()));
+            }
+        }
+
+        let body = self
+            .client
+            .get(feed.unwrap_or(&self.default_feed))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let channel = Channel::read_from(&body[..]).map_err(NewsError::Feed)?;
+
+        Ok(channel
+            .items()
+            .iter()
+            .filter_map(|item| {
+                Some(Headline {
+                    title: item.title()?.trim().to_string(),
+                    link: item.link()?.trim().to_string(),
+                    description: item
+                        .description()
+                        .map(|description| description.to_string()),
+                })
+            })
+            .collect())
+    }
+}
+
+/// Every address the host of the link resolves to is on the internet.
+async fn is_public(link: &str) -> bool {
+    let url = match Url::parse(link) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    let address = match (url.host_str(), url.port_or_known_default()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        _ => return false,
+    };
+    match tokio::net::lookup_host(address).await {
+        Ok(addresses) => {
+            let addresses = addresses.collect::<Vec<_>>();
+            !addresses.is_empty() && addresses.iter().all(|address| is_public_ip(address.ip()))
+        }
+        Err(_) => false,
+    }
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
@@ -0,0 +1,148 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// Cheapest price a collection is listed at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Floor {
+    pub name: String,
+    /// In the currency of the chain, e.g. ETH.
+    pub price: f64,
+    /// Symbol of the currency of the chain.
+    pub currency: String,
+    /// `None` when the source doesn't price it in USD.
+    pub usd: Option<f64>,
+    /// Change of the USD floor in the last 24 hours, in percent.
+    pub change_24h: Option<f64>,
+}
+
+/// Source of NFT floor prices.
+#[async_trait]
+pub trait NftApi: Send + Sync {
+    /// Floor of the collection with the slug, e.g. `pudgy-penguins`, `None` if it's unknown.
+    async fn floor(&self, collection: &str) -> Result<Option<Floor>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoCollection {
+    name: String,
+    native_currency_symbol: String,
+    floor_price: CoinGeckoPrice,
+    floor_price_in_usd_24h_percentage_change: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoPrice {
+    native_currency: Option<f64>,
+    usd: Option<f64>,
+}
+
+/// The NFT endpoints of https://www.coingecko.com, which need no key.
+pub struct CoinGeckoNfts {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl CoinGeckoNfts {
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl NftApi for CoinGeckoNfts {
+    async fn floor(&self, collection: &str) -> Result<Option<Floor>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/nfts/{}", self.base_url, collection))
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res = res
+            .error_for_status()?
+            .json::<CoinGeckoCollection>()
+            .await?;
+
+        Ok(res.floor_price.native_currency.map(|price| Floor {
+            name: res.name,
+            price,
+            currency: res.native_currency_symbol.to_uppercase(),
+            usd: res.floor_price.usd,
+            change_24h: res.floor_price_in_usd_24h_percentage_change,
+        }))
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenSeaStats {
+    total: OpenSeaTotal,
+}
+
+#[derive(Deserialize)]
+struct OpenSeaTotal {
+    floor_price: Option<f64>,
+    floor_price_symbol: Option<String>,
+}
+
+/// https://opensea.io, which needs a key.
+///
+/// It only prices the floor in the currency of the chain.
+pub struct OpenSea {
+    client: HttpClient,
+    base_url: String,
+    api_key: String,
+}
+
+impl OpenSea {
+    pub const BASE_URL: &'static str = "https://api.opensea.io/api/v2";
+
+    pub fn with_base_url(
+        client: HttpClient,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl NftApi for OpenSea {
+    async fn floor(&self, collection: &str) -> Result<Option<Floor>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!(
+                "{}/collections/{}/stats",
+                self.base_url, collection
+            ))
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res = res.error_for_status()?.json::<OpenSeaStats>().await?;
+
+        Ok(res.total.floor_price.map(|price| Floor {
+            name: collection.to_string(),
+            price,
+            currency: res
+                .total
+                .floor_price_symbol
+                .filter(|symbol| !symbol.is_empty())
+                .unwrap_or_else(|| "ETH".to_string())
+                .to_uppercase(),
+            usd: None,
+            change_24h: None,
+        }))
+    }
+}
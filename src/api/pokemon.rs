@@ -0,0 +1,159 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pokemon {
+    pub id: u32,
+    pub name: String,
+    /// Official artwork, else the game sprite, if any.
+    pub sprite: Option<String>,
+    /// In slot order, e.g. `grass` then `poison`.
+    pub types: Vec<String>,
+    /// Name and value of the base stats, e.g. `hp` and `45`.
+    pub stats: Vec<(String, u32)>,
+}
+
+impl Pokemon {
+    pub fn render(&self) -> String {
+        let mut text = format!("#{} {}\n", self.id, capitalize(&self.name));
+        let types = self
+            .types
+            .iter()
+            .map(|name| capitalize(name))
+            .collect::<Vec<_>>();
+        writeln!(text, "Type: {}\n", types.join(" / ")).ok();
+        for (name, value) in &self.stats {
+            writeln!(text, "{}: {}", stat_name(name), value).ok();
+        }
+        let total = self.stats.iter().map(|(_, value)| value).sum::<u32>();
+        write!(text, "Total: {}", total).ok();
+        text
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn stat_name(name: &str) -> String {
+    match name {
+        "hp" => "HP".to_string(),
+        "special-attack" => "Sp. Atk".to_string(),
+        "special-defense" => "Sp. Def".to_string(),
+        name => capitalize(name),
+    }
+}
+
+/// Source of Pokémon data.
+#[async_trait]
+pub trait PokemonApi: Send + Sync {
+    /// The Pokémon with the name or Pokédex number, `None` if there's none.
+    async fn pokemon(&self, name: &str) -> Result<Option<Pokemon>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct PokemonResponse {
+    id: u32,
+    name: String,
+    sprites: Sprites,
+    types: Vec<TypeSlot>,
+    stats: Vec<StatValue>,
+}
+
+#[derive(Deserialize)]
+struct Sprites {
+    front_default: Option<String>,
+    #[serde(default)]
+    other: OtherSprites,
+}
+
+#[derive(Deserialize, Default)]
+struct OtherSprites {
+    #[serde(rename = "official-artwork", default)]
+    official_artwork: Artwork,
+}
+
+#[derive(Deserialize, Default)]
+struct Artwork {
+    front_default: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TypeSlot {
+    slot: u8,
+    #[serde(rename = "type")]
+    kind: Named,
+}
+
+#[derive(Deserialize)]
+struct StatValue {
+    base_stat: u32,
+    stat: Named,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+/// https://pokeapi.co
+pub struct PokeApi {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl PokeApi {
+    pub const BASE_URL: &'static str = "https://pokeapi.co/api/v2";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PokemonApi for PokeApi {
+    async fn pokemon(&self, name: &str) -> Result<Option<Pokemon>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/pokemon/{}", self.base_url, name))
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res = res.error_for_status()?.json::<PokemonResponse>().await?;
+
+        let mut types = res.types;
+        types.sort_by_key(|slot| slot.slot);
+        Ok(Some(Pokemon {
+            id: res.id,
+            name: res.name,
+            sprite: res
+                .sprites
+                .other
+                .official_artwork
+                .front_default
+                .or(res.sprites.front_default),
+            types: types.into_iter().map(|slot| slot.kind.name).collect(),
+            stats: res
+                .stats
+                .into_iter()
+                .map(|stat| (stat.stat.name, stat.base_stat))
+                .collect(),
+        }))
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    cache::{self, Cache},
+    http::{HttpClient, MAX_CONCURRENT_REQUESTS},
+    quota,
+};
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, io, path::Path, str::FromStr, sync::Arc, time::Duration};
+use tracing::warn;
+
+/// Source of asset prices in USD.
+#[async_trait]
+pub trait PriceApi: Send + Sync {
+    /// Current USD value of the asset with the given symbol (e.g. `eur`, `btc`).
+    async fn usd_price(&self, symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error>;
+
+    /// USD values of several assets, keyed by their lowercase symbol, the unknown ones are left out.
+    ///
+    /// Asks for a few at once unless the backend can fetch them in a single request.
+    /// The ones failing are left out too, it only fails when all of them do.
+    async fn usd_prices(
+        &self,
+        symbols: &[String],
+    ) -> Result<HashMap<String, f64>, reqwest_middleware::Error> {
+        let mut results = stream::iter(symbols.iter().cloned())
+            .map(|symbol| async move {
+                let result = self.usd_price(&symbol).await;
+                (symbol, result)
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS);
+
+        let mut prices = HashMap::new();
+        let mut first_error = None;
+        let mut succeeded = false;
+        while let Some((symbol, result)) = results.next().await {
+            match result {
+                Ok(price) => {
+                    succeeded = true;
+                    if let Some(price) = price {
+                        prices.insert(symbol.to_lowercase(), price);
+                    }
+                }
+                Err(e) => {
+                    warn!("Could not fetch the price of {} -> {}", symbol, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if !succeeded => Err(e),
+            _ => Ok(prices),
+        }
+    }
+}
+
+/// Which [`PriceApi`] to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceBackend {
+    CoinGecko,
+    Binance,
+    Mock,
+}
+
+impl FromStr for PriceBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "coingecko" => Ok(Self::CoinGecko),
+            "binance" => Ok(Self::Binance),
+            "mock" => Ok(Self::Mock),
+            other => Err(format!("Unknown price backend '{}'", other)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoCoinValue {
+    usd: f64,
+}
+
+/// https://www.coingecko.com
+pub struct CoinGecko {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl CoinGecko {
+    pub const BASE_URL: &'static str = "https://api.coingecko.com/api/v3";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// CoinGecko identifies coins by id instead of symbol.
+    fn coin_id(symbol: &str) -> String {
+        match symbol {
+            "eur" => "tether-eurt".to_string(),
+            "btc" => "bitcoin".to_string(),
+            "eth" => "ethereum".to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceApi for CoinGecko {
+    async fn usd_price(&self, symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error> {
+        let id = Self::coin_id(&symbol.to_lowercase());
+        let mut res = self
+            .client
+            .get(format!("{}/simple/price", self.base_url))
+            .query(&[("ids", id.as_str()), ("vs_currencies", "usd")])
+            .send()
+            .await?
+            .json::<HashMap<String, CoinGeckoCoinValue>>()
+            .await?;
+
+        Ok(res.remove(&id).map(|coin| coin.usd))
+    }
+
+    async fn usd_prices(
+        &self,
+        symbols: &[String],
+    ) -> Result<HashMap<String, f64>, reqwest_middleware::Error> {
+        let ids = symbols
+            .iter()
+            .map(|symbol| {
+                let symbol = symbol.to_lowercase();
+                (Self::coin_id(&symbol), symbol)
+            })
+            .collect::<HashMap<_, _>>();
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let joined = ids.keys().cloned().collect::<Vec<_>>().join(",");
+        let res = self
+            .client
+            .get(format!("{}/simple/price", self.base_url))
+            .query(&[("ids", joined.as_str()), ("vs_currencies", "usd")])
+            .send()
+            .await?
+            .json::<HashMap<String, CoinGeckoCoinValue>>()
+            .await?;
+
+        Ok(res
+            .into_iter()
+            .filter_map(|(id, coin)| Some((ids.get(&id)?.clone(), coin.usd)))
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
+struct BinanceTicker {
+    price: String,
+}
+
+/// https://www.binance.com
+pub struct Binance {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl Binance {
+    pub const BASE_URL: &'static str = "https://api.binance.com/api/v3";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceApi for Binance {
+    async fn usd_price(&self, symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error> {
+        let pair = format!("{}USDT", symbol.to_uppercase());
+        let res = self
+            .client
+            .get(format!("{}/ticker/price", self.base_url))
+            .query(&[("symbol", pair.as_str())])
+            .send()
+            .await?;
+
+        // Unknown pairs are answered with a 400
+        if !res.status().is_success() {
+            return Ok(None);
+        }
+
+        let ticker = res.json::<BinanceTicker>().await?;
+        Ok(ticker.price.parse().ok())
+    }
+}
+
+/// Offline [`PriceApi`] that answers with fixed prices.
+#[derive(Default)]
+pub struct MockPriceApi {
+    pub prices: HashMap<String, f64>,
+}
+
+impl MockPriceApi {
+    pub fn new(prices: HashMap<String, f64>) -> Self {
+        Self { prices }
+    }
+
+    /// Load the prices from a JSON fixture, e.g. `{ "eur": 1.07 }`.
+    pub fn from_fixture(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let prices = serde_json::from_str(&content)?;
+        Ok(Self::new(prices))
+    }
+}
+
+#[async_trait]
+impl PriceApi for MockPriceApi {
+    async fn usd_price(&self, symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error> {
+        Ok(self.prices.get(&symbol.to_lowercase()).copied())
+    }
+}
+
+/// [`PriceApi`] answering from the cache while the prices are fresh enough, or with the last ones while the upstream
+/// is over its quota.
+pub struct CachedPriceApi {
+    inner: Arc<dyn PriceApi>,
+    cache: Arc<dyn Cache>,
+    ttl: Duration,
+}
+
+impl CachedPriceApi {
+    pub fn new(inner: Arc<dyn PriceApi>, cache: Arc<dyn Cache>, ttl: Duration) -> Self {
+        Self { inner, cache, ttl }
+    }
+}
+
+#[async_trait]
+impl PriceApi for CachedPriceApi {
+    async fn usd_price(&self, symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error> {
+        let key = format!("price:{}", symbol.to_lowercase());
+        if let Some(price) = cache::get_json(self.cache.as_ref(), &key).await {
+            return Ok(Some(price));
+        }
+
+        let price = match self.inner.usd_price(symbol).await {
+            Ok(price) => price,
+            Err(e) if quota::is_exceeded(&e) => {
+                return match cache::get_stale_json(self.cache.as_ref(), &key).await {
+                    Some(price) => Ok(Some(price)),
+                    None => Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(price) = price {
+            cache::set_json_with_stale(self.cache.as_ref(), &key, &price, self.ttl).await;
+        }
+        Ok(price)
+    }
+
+    async fn usd_prices(
+        &self,
+        symbols: &[String],
+    ) -> Result<HashMap<String, f64>, reqwest_middleware::Error> {
+        let mut prices = HashMap::new();
+        let mut missing = Vec::new();
+        for symbol in symbols {
+            let symbol = symbol.to_lowercase();
+            let key = format!("price:{}", symbol);
+            match cache::get_json(self.cache.as_ref(), &key).await {
+                Some(price) => {
+                    prices.insert(symbol, price);
+                }
+                None => missing.push(symbol),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(prices);
+        }
+
+        let fetched = match self.inner.usd_prices(&missing).await {
+            Ok(fetched) => fetched,
+            // Only if every missing price has a stale one, a partial answer would look like unknown symbols
+            Err(e) if quota::is_exceeded(&e) => {
+                for symbol in missing {
+                    let key = format!("price:{}", symbol);
+                    match cache::get_stale_json(self.cache.as_ref(), &key).await {
+                        Some(price) => prices.insert(symbol, price),
+                        None => return Err(e),
+                    };
+                }
+                return Ok(prices);
+            }
+            Err(e) => return Err(e),
+        };
+        for (symbol, price) in fetched {
+            let key = format!("price:{}", symbol);
+            cache::set_json_with_stale(self.cache.as_ref(), &key, &price, self.ttl).await;
+            prices.insert(symbol, price);
+        }
+        Ok(prices)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Quote {
+    #[serde(rename = "content")]
+    pub text: String,
+    pub author: String,
+}
+
+/// Source of quotes.
+#[async_trait]
+pub trait QuoteApi: Send + Sync {
+    /// Random quote, of the given author if any, `None` if the author has none.
+    async fn random(
+        &self,
+        author: Option<&str>,
+    ) -> Result<Option<Quote>, reqwest_middleware::Error>;
+}
+
+/// https://github.com/lukePeavey/quotable, which unlike ZenQuotes searches by author without a key.
+pub struct Quotable {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl Quotable {
+    pub const BASE_URL: &'static str = "https://api.quotable.io";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteApi for Quotable {
+    async fn random(
+        &self,
+        author: Option<&str>,
+    ) -> Result<Option<Quote>, reqwest_middleware::Error> {
+        let mut request = self
+            .client
+            .get(format!("{}/quotes/random", self.base_url))
+            .query(&[("limit", "1")]);
+        if let Some(author) = author {
+            // Names and slugs are both accepted
+            request = request.query(&[("author", author)]);
+        }
+        let quotes = request.send().await?.json::<Vec<Quote>>().await?;
+
+        Ok(quotes.into_iter().next())
+    }
+}
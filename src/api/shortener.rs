@@ -0,0 +1,91 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::{header::CONTENT_TYPE, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortUrl {
+    pub short_code: String,
+    pub short_url: String,
+    pub long_url: String,
+    pub visits_summary: Visits,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Visits {
+    pub total: u64,
+    /// Visits that weren't made by crawlers or link previews.
+    pub non_bots: u64,
+}
+
+/// URL shortener keeping track of the visits.
+#[async_trait]
+pub trait ShortenerApi: Send + Sync {
+    async fn shorten(&self, url: &Url) -> Result<ShortUrl, reqwest_middleware::Error>;
+
+    /// The short URL with the given code, `None` if there's none.
+    async fn short_url(&self, code: &str) -> Result<Option<ShortUrl>, reqwest_middleware::Error>;
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShortenRequest<'a> {
+    long_url: &'a str,
+}
+
+/// https://shlink.io, self-hosted.
+pub struct Shlink {
+    client: HttpClient,
+    base_url: String,
+    api_key: String,
+}
+
+impl Shlink {
+    pub fn new(
+        client: HttpClient,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ShortenerApi for Shlink {
+    async fn shorten(&self, url: &Url) -> Result<ShortUrl, reqwest_middleware::Error> {
+        let body = serde_json::to_string(&ShortenRequest {
+            long_url: url.as_str(),
+        })
+        .expect("the request serializes");
+        Ok(self
+            .client
+            .post(format!("{}/rest/v3/short-urls", self.base_url))
+            .header("X-Api-Key", &self.api_key)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ShortUrl>()
+            .await?)
+    }
+
+    async fn short_url(&self, code: &str) -> Result<Option<ShortUrl>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/rest/v3/short-urls/{}", self.base_url, code))
+            .header("X-Api-Key", &self.api_key)
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(res.error_for_status()?.json::<ShortUrl>().await?))
+    }
+}
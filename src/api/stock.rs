@@ -0,0 +1,99 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StockQuote {
+    pub symbol: String,
+    pub price: f64,
+    pub previous_close: Option<f64>,
+    pub currency: String,
+}
+
+impl StockQuote {
+    /// Change since the previous close, in percent.
+    pub fn change(&self) -> Option<f64> {
+        let previous_close = self.previous_close.filter(|close| *close != 0.0)?;
+        Some((self.price - previous_close) / previous_close * 100.0)
+    }
+}
+
+/// Source of equity prices.
+#[async_trait]
+pub trait StockApi: Send + Sync {
+    /// Latest price of the ticker, `None` if it's unknown.
+    async fn quote(&self, ticker: &str) -> Result<Option<StockQuote>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct ChartResponse {
+    chart: Chart,
+}
+
+#[derive(Deserialize)]
+struct Chart {
+    result: Option<Vec<ChartResult>>,
+}
+
+#[derive(Deserialize)]
+struct ChartResult {
+    meta: ChartMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChartMeta {
+    symbol: String,
+    currency: Option<String>,
+    regular_market_price: f64,
+    chart_previous_close: Option<f64>,
+}
+
+/// The unofficial chart endpoint of https://finance.yahoo.com, which needs no key.
+pub struct YahooFinance {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl YahooFinance {
+    pub const BASE_URL: &'static str = "https://query1.finance.yahoo.com";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl StockApi for YahooFinance {
+    async fn quote(&self, ticker: &str) -> Result<Option<StockQuote>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/v8/finance/chart/{}", self.base_url, ticker))
+            .query(&[("range", "1d"), ("interval", "1d")])
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res = res.error_for_status()?.json::<ChartResponse>().await?;
+
+        Ok(res
+            .chart
+            .result
+            .and_then(|results| results.into_iter().next())
+            .map(|result| StockQuote {
+                symbol: result.meta.symbol,
+                price: result.meta.regular_market_price,
+                previous_close: result.meta.chart_previous_close,
+                currency: result.meta.currency.unwrap_or_else(|| "USD".to_string()),
+            }))
+    }
+}
@@ -0,0 +1,94 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    pub text: String,
+    /// Language the text was detected in, if the backend tells.
+    pub source: Option<String>,
+}
+
+/// Machine translation.
+#[async_trait]
+pub trait TranslateApi: Send + Sync {
+    /// Translate the text, in any language, into `target`, `None` if the language isn't supported.
+    async fn translate(
+        &self,
+        text: &str,
+        target: &str,
+    ) -> Result<Option<Translation>, reqwest_middleware::Error>;
+}
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: String,
+    detected_language: Option<DetectedLanguage>,
+}
+
+#[derive(Deserialize)]
+struct DetectedLanguage {
+    language: String,
+}
+
+/// https://libretranslate.com, usually self-hosted.
+pub struct LibreTranslate {
+    client: HttpClient,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl LibreTranslate {
+    /// The key is only needed by the instances that ask for one.
+    pub fn new(client: HttpClient, base_url: impl Into<String>, api_key: Option<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl TranslateApi for LibreTranslate {
+    async fn translate(
+        &self,
+        text: &str,
+        target: &str,
+    ) -> Result<Option<Translation>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .post(format!("{}/translate", self.base_url))
+            .form(&TranslateRequest {
+                q: text,
+                source: "auto",
+                target,
+                format: "text",
+                api_key: self.api_key.as_deref(),
+            })
+            .send()
+            .await?;
+        // Unsupported languages are the only bad requests the bot can make
+        if res.status() == StatusCode::BAD_REQUEST {
+            return Ok(None);
+        }
+        let res = res.error_for_status()?.json::<TranslateResponse>().await?;
+
+        Ok(Some(Translation {
+            text: res.translated_text,
+            source: res.detected_language.map(|detected| detected.language),
+        }))
+    }
+}
@@ -0,0 +1,134 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Category {
+    pub id: u32,
+    pub name: String,
+}
+
+/// A multiple-choice question.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Question {
+    #[serde(deserialize_with = "decode")]
+    pub category: String,
+    /// `easy`, `medium` or `hard`.
+    #[serde(deserialize_with = "decode")]
+    pub difficulty: String,
+    #[serde(deserialize_with = "decode")]
+    pub question: String,
+    #[serde(deserialize_with = "decode")]
+    pub correct_answer: String,
+    #[serde(deserialize_with = "decode_all")]
+    pub incorrect_answers: Vec<String>,
+}
+
+/// Source of trivia questions.
+#[async_trait]
+pub trait TriviaApi: Send + Sync {
+    async fn categories(&self) -> Result<Vec<Category>, reqwest_middleware::Error>;
+
+    /// A random question, `None` if there are none left with that category and difficulty.
+    async fn question(
+        &self,
+        category: Option<u32>,
+        difficulty: Option<&str>,
+    ) -> Result<Option<Question>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct CategoriesResponse {
+    trivia_categories: Vec<Category>,
+}
+
+#[derive(Deserialize)]
+struct QuestionsResponse {
+    /// 0 on success, see https://opentdb.com/api_config.php for the others.
+    response_code: u8,
+    results: Vec<Question>,
+}
+
+/// The texts are asked percent-encoded, the default HTML entities would need a whole decoder.
+fn decode<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    Ok(percent_decode_str(&text).decode_utf8_lossy().into_owned())
+}
+
+fn decode_all<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let texts = Vec::<String>::deserialize(deserializer)?;
+    Ok(texts
+        .iter()
+        .map(|text| percent_decode_str(text).decode_utf8_lossy().into_owned())
+        .collect())
+}
+
+/// https://opentdb.com
+pub struct OpenTriviaDb {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl OpenTriviaDb {
+    pub const BASE_URL: &'static str = "https://opentdb.com";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl TriviaApi for OpenTriviaDb {
+    async fn categories(&self) -> Result<Vec<Category>, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!("{}/api_category.php", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<CategoriesResponse>()
+            .await?
+            .trivia_categories)
+    }
+
+    async fn question(
+        &self,
+        category: Option<u32>,
+        difficulty: Option<&str>,
+    ) -> Result<Option<Question>, reqwest_middleware::Error> {
+        let mut query = vec![
+            ("amount", "1".to_string()),
+            ("type", "multiple".to_string()),
+            ("encode", "url3986".to_string()),
+        ];
+        if let Some(category) = category {
+            query.push(("category", category.to_string()));
+        }
+        if let Some(difficulty) = difficulty {
+            query.push(("difficulty", difficulty.to_string()));
+        }
+        let res = self
+            .client
+            .get(format!("{}/api.php", self.base_url))
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<QuestionsResponse>()
+            .await?;
+
+        // Running out of questions and the rate limit are reported in the body
+        if res.response_code != 0 {
+            return Ok(None);
+        }
+        Ok(res.results.into_iter().next())
+    }
+}
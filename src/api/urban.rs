@@ -0,0 +1,81 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{cmp::Reverse, fmt::Write};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub word: String,
+    pub definition: String,
+    pub example: String,
+    pub thumbs_up: u32,
+    pub thumbs_down: u32,
+}
+
+impl Definition {
+    pub fn render(&self) -> String {
+        let mut text = format!("{}\n\n{}", self.word, unbracket(&self.definition));
+        let example = unbracket(&self.example);
+        if !example.trim().is_empty() {
+            write!(text, "\n\n{}", example.trim()).ok();
+        }
+        write!(text, "\n\n👍 {} 👎 {}", self.thumbs_up, self.thumbs_down).ok();
+        text
+    }
+}
+
+/// Urban Dictionary links the words between brackets.
+fn unbracket(text: &str) -> String {
+    text.replace(['[', ']'], "").replace("\r\n", "\n")
+}
+
+/// Source of slang definitions.
+#[async_trait]
+pub trait UrbanApi: Send + Sync {
+    /// Definitions of the term, the most voted first.
+    async fn define(&self, term: &str) -> Result<Vec<Definition>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct DefineResponse {
+    list: Vec<Definition>,
+}
+
+/// https://www.urbandictionary.com
+pub struct UrbanDictionary {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl UrbanDictionary {
+    pub const BASE_URL: &'static str = "https://api.urbandictionary.com/v0";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl UrbanApi for UrbanDictionary {
+    async fn define(&self, term: &str) -> Result<Vec<Definition>, reqwest_middleware::Error> {
+        let mut definitions = self
+            .client
+            .get(format!("{}/define", self.base_url))
+            .query(&[("term", term)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DefineResponse>()
+            .await?
+            .list;
+        definitions.sort_by_key(|definition| Reverse(definition.thumbs_up));
+        Ok(definitions)
+    }
+}
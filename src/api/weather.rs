@@ -0,0 +1,183 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// A place found by its name.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// IANA name of the timezone of the place, e.g. `Asia/Tokyo`.
+    pub timezone: Option<String>,
+}
+
+/// Conditions right now, temperatures in °C and speeds in km/h.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Current {
+    pub temperature: f64,
+    pub feels_like: f64,
+    pub wind_speed: f64,
+    /// WMO weather interpretation code.
+    pub weather_code: u8,
+}
+
+/// Forecast of a single day, in the local date of the place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub min: f64,
+    pub max: f64,
+    pub weather_code: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    pub current: Current,
+    pub days: Vec<Day>,
+}
+
+/// Source of weather forecasts.
+#[async_trait]
+pub trait WeatherApi: Send + Sync {
+    /// Best match for the name of a city, `None` if nothing matches.
+    async fn locate(&self, name: &str) -> Result<Option<Place>, reqwest_middleware::Error>;
+
+    /// Current conditions and the forecast of the next `days` days, today included.
+    async fn forecast(
+        &self,
+        place: &Place,
+        days: u8,
+    ) -> Result<Forecast, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<Place>,
+}
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    current: CurrentResponse,
+    daily: DailyResponse,
+}
+
+#[derive(Deserialize)]
+struct CurrentResponse {
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    wind_speed_10m: f64,
+    weather_code: u8,
+}
+
+#[derive(Deserialize)]
+struct DailyResponse {
+    time: Vec<NaiveDate>,
+    weather_code: Vec<u8>,
+    temperature_2m_max: Vec<f64>,
+    temperature_2m_min: Vec<f64>,
+}
+
+/// https://open-meteo.com, free and without API key.
+pub struct OpenMeteo {
+    client: HttpClient,
+    base_url: String,
+    geocoding_url: String,
+}
+
+impl OpenMeteo {
+    pub const BASE_URL: &'static str = "https://api.open-meteo.com/v1";
+    pub const GEOCODING_URL: &'static str = "https://geocoding-api.open-meteo.com/v1";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_urls(client, Self::BASE_URL, Self::GEOCODING_URL)
+    }
+
+    pub fn with_base_urls(
+        client: HttpClient,
+        base_url: impl Into<String>,
+        geocoding_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            geocoding_url: geocoding_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherApi for OpenMeteo {
+    async fn locate(&self, name: &str) -> Result<Option<Place>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/search", self.geocoding_url))
+            .query(&[("name", name), ("count", "1"), ("format", "json")])
+            .send()
+            .await?
+            .json::<GeocodingResponse>()
+            .await?;
+
+        Ok(res.results.into_iter().next())
+    }
+
+    async fn forecast(
+        &self,
+        place: &Place,
+        days: u8,
+    ) -> Result<Forecast, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/forecast", self.base_url))
+            .query(&[
+                ("latitude", place.latitude.to_string()),
+                ("longitude", place.longitude.to_string()),
+                (
+                    "current",
+                    "temperature_2m,apparent_temperature,wind_speed_10m,weather_code".to_string(),
+                ),
+                (
+                    "daily",
+                    "weather_code,temperature_2m_max,temperature_2m_min".to_string(),
+                ),
+                ("timezone", "auto".to_string()),
+                ("forecast_days", days.to_string()),
+            ])
+            .send()
+            .await?
+            .json::<ForecastResponse>()
+            .await?;
+
+        let daily = res.daily;
+        let days = daily
+            .time
+            .into_iter()
+            .zip(daily.weather_code)
+            .zip(
+                daily
+                    .temperature_2m_max
+                    .into_iter()
+                    .zip(daily.temperature_2m_min),
+            )
+            .map(|((date, weather_code), (max, min))| Day {
+                date,
+                min,
+                max,
+                weather_code,
+            })
+            .collect();
+
+        Ok(Forecast {
+            current: Current {
+                temperature: res.current.temperature_2m,
+                feels_like: res.current.apparent_temperature,
+                wind_speed: res.current.wind_speed_10m,
+                weather_code: res.current.weather_code,
+            },
+            days,
+        })
+    }
+}
@@ -0,0 +1,127 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// Introduction of an article.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub title: String,
+    /// First paragraph of the article.
+    pub extract: String,
+    pub thumbnail: Option<String>,
+    pub url: String,
+}
+
+/// Source of encyclopedia articles.
+#[async_trait]
+pub trait WikiApi: Send + Sync {
+    /// Summary of the article in the given language, `None` if there's none.
+    async fn summary(
+        &self,
+        language: &str,
+        term: &str,
+    ) -> Result<Option<Summary>, reqwest_middleware::Error>;
+}
+
+#[derive(Deserialize)]
+struct SummaryResponse {
+    title: String,
+    extract: String,
+    thumbnail: Option<Thumbnail>,
+    content_urls: ContentUrls,
+}
+
+#[derive(Deserialize)]
+struct Thumbnail {
+    source: String,
+}
+
+#[derive(Deserialize)]
+struct ContentUrls {
+    desktop: PageUrl,
+}
+
+#[derive(Deserialize)]
+struct PageUrl {
+    page: String,
+}
+
+/// https://en.wikipedia.org/api/rest_v1
+pub struct Wikipedia {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl Wikipedia {
+    /// `{lang}` is replaced by the language of the article.
+    pub const BASE_URL: &'static str = "https://{lang}.wikipedia.org/api/rest_v1";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Articles are named with underscores and a capital first letter, the characters meaningful in a URL are
+    /// escaped.
+    fn title(term: &str) -> String {
+        let title = term
+            .trim()
+            .chars()
+            .map(|c| match c {
+                ' ' => "_".to_string(),
+                '/' => "%2F".to_string(),
+                '?' => "%3F".to_string(),
+                '#' => "%23".to_string(),
+                '%' => "%25".to_string(),
+                c => c.to_string(),
+            })
+            .collect::<String>();
+        let mut chars = title.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => title,
+        }
+    }
+}
+
+#[async_trait]
+impl WikiApi for Wikipedia {
+    async fn summary(
+        &self,
+        language: &str,
+        term: &str,
+    ) -> Result<Option<Summary>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!(
+                "{}/page/summary/{}",
+                self.base_url.replace("{lang}", language),
+                Self::title(term)
+            ))
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res = res.error_for_status()?.json::<SummaryResponse>().await?;
+
+        Ok(Some(Summary {
+            title: res.title,
+            extract: res
+                .extract
+                .split('\n')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            thumbnail: res.thumbnail.map(|thumbnail| thumbnail.source),
+            url: res.content_urls.desktop.page,
+        }))
+    }
+}
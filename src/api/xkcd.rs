@@ -0,0 +1,75 @@
+use crate::http::HttpClient;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Comic {
+    pub num: u32,
+    pub title: String,
+    pub img: String,
+    pub alt: String,
+}
+
+impl Comic {
+    /// Title and alt-text.
+    pub fn caption(&self) -> String {
+        format!("#{} {}\n\n{}", self.num, self.title, self.alt)
+    }
+}
+
+/// Source of xkcd comics.
+#[async_trait]
+pub trait XkcdApi: Send + Sync {
+    async fn latest(&self) -> Result<Comic, reqwest_middleware::Error>;
+
+    /// `None` if there's no comic with that number, like 404.
+    async fn comic(&self, num: u32) -> Result<Option<Comic>, reqwest_middleware::Error>;
+}
+
+/// https://xkcd.com/json.html
+pub struct Xkcd {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl Xkcd {
+    pub const BASE_URL: &'static str = "https://xkcd.com";
+
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl XkcdApi for Xkcd {
+    async fn latest(&self) -> Result<Comic, reqwest_middleware::Error> {
+        Ok(self
+            .client
+            .get(format!("{}/info.0.json", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Comic>()
+            .await?)
+    }
+
+    async fn comic(&self, num: u32) -> Result<Option<Comic>, reqwest_middleware::Error> {
+        let res = self
+            .client
+            .get(format!("{}/{}/info.0.json", self.base_url, num))
+            .send()
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(res.error_for_status()?.json::<Comic>().await?))
+    }
+}
@@ -0,0 +1,78 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::Deserialize;
+use std::{fs, io, path::PathBuf};
+use tracing::{info, warn};
+
+/// Where the media sent as is, e.g. the barks of `/bark`, is read from.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AssetsConfig {
+    /// Holds a `barks/` directory of OGG files encoded with Opus, the only voice messages Telegram plays.
+    pub dir: PathBuf,
+}
+
+impl Default for AssetsConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("assets"),
+        }
+    }
+}
+
+/// A voice message, named after its file.
+#[derive(Clone, Debug)]
+pub struct Sound {
+    /// e.g. `barks/husky.ogg`, also the key of its file id in the cache.
+    pub name: String,
+    pub ogg: Vec<u8>,
+}
+
+/// Media bundled with the bot, read once at startup.
+#[derive(Default)]
+pub struct Assets {
+    barks: Vec<Sound>,
+}
+
+impl Assets {
+    pub fn new(barks: Vec<Sound>) -> Self {
+        Self { barks }
+    }
+
+    /// Without a `barks/` directory there is nothing to bark with, but it's not an error.
+    pub fn load(config: &AssetsConfig) -> io::Result<Self> {
+        let dir = config.dir.join("barks");
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                warn!("There are no barks in {}", dir.display());
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut barks = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("ogg") {
+                continue;
+            }
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            barks.push(Sound {
+                name: format!("barks/{}", file_name),
+                ogg: fs::read(&path)?,
+            });
+        }
+        // The order of the directory isn't stable
+        barks.sort_by(|a, b| a.name.cmp(&b.name));
+        info!("Loaded {} barks from {}", barks.len(), dir.display());
+        Ok(Self { barks })
+    }
+
+    pub fn barks(&self) -> &[Sound] {
+        &self.barks
+    }
+
+    pub fn random_bark(&self, rng: &mut impl Rng) -> Option<&Sound> {
+        self.barks.choose(rng)
+    }
+}
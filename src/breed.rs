@@ -0,0 +1,154 @@
+use std::{fmt, str::FromStr};
+
+/// Common names that don't follow the `sub-breed breed` order used by dog.ceo.
+const ALIASES: &[(&str, &str, Option<&str>)] = &[
+    ("australian shepherd", "australian", Some("shepherd")),
+    ("bernese mountain dog", "mountain", Some("bernese")),
+    ("dobermann", "doberman", None),
+    ("doberman pinscher", "doberman", None),
+    ("german shepherd", "germanshepherd", None),
+    ("lab", "labrador", None),
+    ("labrador retriever", "labrador", None),
+    ("pit bull", "pitbull", None),
+    ("saint bernard", "stbernard", None),
+    ("st bernard", "stbernard", None),
+    ("shiba inu", "shiba", None),
+    ("siberian husky", "husky", None),
+    ("swiss mountain dog", "mountain", Some("swiss")),
+    ("yorkie", "terrier", Some("yorkshire")),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreedError {
+    Empty,
+    InvalidName(String),
+    TooManyWords(String),
+}
+
+impl fmt::Display for BreedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Please specify a breed, e.g. /breed husky"),
+            Self::InvalidName(name) => write!(f, "'{}' is not a valid breed name", name),
+            Self::TooManyWords(name) => write!(
+                f,
+                "'{}' has too many words, use `sub-breed breed`, e.g. golden retriever",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BreedError {}
+
+/// Validate a single name as used by dog.ceo (lowercase ASCII letters).
+fn normalize_name(name: &str) -> Result<String, BreedError> {
+    let name = name.to_lowercase();
+    if name.is_empty() {
+        Err(BreedError::Empty)
+    } else if name.chars().all(|c| c.is_ascii_lowercase()) {
+        Ok(name)
+    } else {
+        Err(BreedError::InvalidName(name))
+    }
+}
+
+/// Master breed, e.g. `retriever`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Breed(String);
+
+impl Breed {
+    pub fn new(name: &str) -> Result<Self, BreedError> {
+        normalize_name(name).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Breed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Variant of a master breed, e.g. `golden` for `retriever`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubBreed(String);
+
+impl SubBreed {
+    pub fn new(name: &str) -> Result<Self, BreedError> {
+        normalize_name(name).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SubBreed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A breed as asked by a user, parsed from `breed` or `sub-breed breed`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BreedQuery {
+    pub breed: Breed,
+    pub sub_breed: Option<SubBreed>,
+}
+
+impl BreedQuery {
+    /// Path used by dog.ceo, e.g. `retriever/golden`.
+    pub fn path(&self) -> String {
+        match &self.sub_breed {
+            Some(sub_breed) => format!("{}/{}", self.breed, sub_breed),
+            None => self.breed.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for BreedQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.sub_breed {
+            Some(sub_breed) => write!(f, "{} {}", sub_breed, self.breed),
+            None => write!(f, "{}", self.breed),
+        }
+    }
+}
+
+impl FromStr for BreedQuery {
+    type Err = BreedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s
+            .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        let normalized = words.join(" ");
+
+        if let Some((_, breed, sub_breed)) = ALIASES.iter().find(|(alias, ..)| *alias == normalized)
+        {
+            return Ok(Self {
+                breed: Breed::new(breed)?,
+                sub_breed: sub_breed.map(SubBreed::new).transpose()?,
+            });
+        }
+
+        match words.as_slice() {
+            [] => Err(BreedError::Empty),
+            [breed] => Ok(Self {
+                breed: Breed::new(breed)?,
+                sub_breed: None,
+            }),
+            [sub_breed, breed] => Ok(Self {
+                breed: Breed::new(breed)?,
+                sub_breed: Some(SubBreed::new(sub_breed)?),
+            }),
+            _ => Err(BreedError::TooManyWords(normalized)),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+pub mod memory;
+pub mod redis;
+
+use async_trait::async_trait;
+use metrics::{gauge, increment_counter};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::warn;
+
+/// What is cached and for how long.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// e.g. `redis://localhost:6379`, shared by every instance of the bot. The cache lives in memory without it.
+    pub redis_url: Option<String>,
+    pub breeds_ttl_secs: u64,
+    pub price_ttl_secs: u64,
+    /// Telegram's id of the photos already sent, so they aren't fetched again.
+    pub file_id_ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: None,
+            breeds_ttl_secs: 24 * 60 * 60,
+            price_ttl_secs: 60,
+            file_id_ttl_secs: 30 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// Key-value cache, failures are logged and treated as misses.
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn set(&self, key: &str, value: &str, ttl: Duration);
+}
+
+/// Redis if configured, memory otherwise.
+pub async fn connect(config: &CacheConfig) -> Result<Arc<dyn Cache>, ::redis::RedisError> {
+    let cache: Arc<dyn Cache> = match &config.redis_url {
+        Some(url) => Arc::new(redis::RedisCache::connect(url).await?),
+        None => Arc::new(memory::MemoryCache::default()),
+    };
+    Ok(Arc::new(MeteredCache::new(cache)))
+}
+
+/// [`Cache`] counting its hits and misses, by the kind of entry, e.g. `file_id` for `file_id:<url>`.
+pub struct MeteredCache {
+    inner: Arc<dyn Cache>,
+    /// Hits and lookups of each kind, since the start.
+    lookups: Mutex<HashMap<String, (u64, u64)>>,
+}
+
+impl MeteredCache {
+    pub fn new(inner: Arc<dyn Cache>) -> Self {
+        Self {
+            inner,
+            lookups: Mutex::default(),
+        }
+    }
+
+    /// Share of the lookups of the kind that were hits, `None` before the first lookup.
+    pub fn hit_ratio(&self, kind: &str) -> Option<f64> {
+        let lookups = self.lookups.lock().unwrap();
+        let (hits, total) = lookups.get(kind)?;
+        Some(*hits as f64 / *total as f64)
+    }
+}
+
+#[async_trait]
+impl Cache for MeteredCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        let value = self.inner.get(key).await;
+        let kind = key.split(':').next().unwrap_or_default().to_string();
+        let result = if value.is_some() { "hit" } else { "miss" };
+        increment_counter!("cache_lookups_total", "cache" => kind.clone(), "result" => result);
+
+        let ratio = {
+            let mut lookups = self.lookups.lock().unwrap();
+            let (hits, total) = lookups.entry(kind.clone()).or_default();
+            *hits += u64::from(value.is_some());
+            *total += 1;
+            *hits as f64 / *total as f64
+        };
+        gauge!("cache_hit_ratio", ratio, "cache" => kind);
+        value
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) {
+        self.inner.set(key, value, ttl).await
+    }
+}
+
+pub async fn get_json<T: DeserializeOwned>(cache: &dyn Cache, key: &str) -> Option<T> {
+    let value = cache.get(key).await?;
+    match serde_json::from_str(&value) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Ignoring the unreadable cache entry {} -> {}", key, e);
+            None
+        }
+    }
+}
+
+pub async fn set_json<T: Serialize>(cache: &dyn Cache, key: &str, value: &T, ttl: Duration) {
+    match serde_json::to_string(value) {
+        Ok(value) => cache.set(key, &value, ttl).await,
+        Err(e) => warn!("Could not cache {} -> {}", key, e),
+    }
+}
+
+/// How long the last value of a key is kept past its own TTL, to answer while its upstream is over its quota.
+pub const STALE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The value cached for the key and, for [`STALE_TTL`], its stale copy.
+pub async fn set_json_with_stale<T: Serialize>(
+    cache: &dyn Cache,
+    key: &str,
+    value: &T,
+    ttl: Duration,
+) {
+    set_json(cache, key, value, ttl).await;
+    set_json(cache, &format!("stale:{}", key), value, STALE_TTL).await;
+}
+
+/// The last value cached for the key, even if expired.
+pub async fn get_stale_json<T: DeserializeOwned>(cache: &dyn Cache, key: &str) -> Option<T> {
+    get_json(cache, &format!("stale:{}", key)).await
+}
+
+/// Telegram's file id of the images already sent, keyed by their URL.
+pub struct FileIds {
+    cache: Arc<dyn Cache>,
+    ttl: Duration,
+}
+
+impl FileIds {
+    pub fn new(cache: Arc<dyn Cache>, config: &CacheConfig) -> Self {
+        Self {
+            cache,
+            ttl: Duration::from_secs(config.file_id_ttl_secs),
+        }
+    }
+
+    pub async fn get(&self, url: &str) -> Option<String> {
+        self.cache.get(&format!("file_id:{}", url)).await
+    }
+
+    pub async fn set(&self, url: &str, file_id: &str) {
+        self.cache
+            .set(&format!("file_id:{}", url), file_id, self.ttl)
+            .await
+    }
+}
@@ -0,0 +1,49 @@
+use super::Cache;
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands, RedisError};
+use std::time::Duration;
+use tracing::warn;
+
+/// Keys are namespaced so the Redis instance can be shared.
+const PREFIX: &str = "dog-bot:";
+
+/// [`Cache`] shared by every instance of the bot, surviving restarts.
+pub struct RedisCache {
+    connection: ConnectionManager,
+}
+
+impl RedisCache {
+    pub async fn connect(url: &str) -> Result<Self, RedisError> {
+        let client = redis::Client::open(url)?;
+        Ok(Self {
+            connection: ConnectionManager::new(client).await?,
+        })
+    }
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        let mut connection = self.connection.clone();
+        match connection
+            .get::<_, Option<String>>(format!("{}{}", PREFIX, key))
+            .await
+        {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Could not read {} from Redis -> {}", key, e);
+                None
+            }
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) {
+        let mut connection = self.connection.clone();
+        let result = connection
+            .set_ex::<_, _, ()>(format!("{}{}", PREFIX, key), value, ttl.as_secs() as usize)
+            .await;
+        if let Err(e) = result {
+            warn!("Could not write {} to Redis -> {}", key, e);
+        }
+    }
+}
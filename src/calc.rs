@@ -0,0 +1,251 @@
+//! Arithmetic for `/calc`, parsed and evaluated here, nothing else is ever run.
+//!
+//! ```text
+//! expr    = term (("+" | "-") term)*
+//! term    = unary (("*" | "/") unary)*
+//! unary   = ("-" | "+") unary | power
+//! power   = percent ("^" unary)?
+//! percent = primary "%"?
+//! primary = number | constant | function primary | "(" expr ")"
+//! ```
+
+use std::{iter::Peekable, str::Chars};
+
+/// Longest expression accepted, so a message can't keep the bot busy.
+const MAX_LENGTH: usize = 200;
+/// Deepest nesting of parentheses, functions and signs.
+const MAX_DEPTH: usize = 32;
+
+/// A function of one argument, e.g. `sqrt`.
+type Function = fn(f64) -> f64;
+
+const FUNCTIONS: &[(&str, Function)] = &[
+    ("sqrt", f64::sqrt),
+    ("abs", f64::abs),
+    ("ln", f64::ln),
+    ("log", f64::log10),
+    ("sin", f64::sin),
+    ("cos", f64::cos),
+    ("tan", f64::tan),
+    ("round", f64::round),
+    ("floor", f64::floor),
+    ("ceil", f64::ceil),
+];
+
+const CONSTANTS: &[(&str, f64)] = &[("pi", std::f64::consts::PI), ("e", std::f64::consts::E)];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+fn tokens(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => tokens.push(Token::Number(number(&mut chars)?)),
+            c if c.is_ascii_alphabetic() => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+                    name.push(c.to_ascii_lowercase());
+                }
+                tokens.push(Token::Name(name));
+            }
+            '+' | '-' | '*' | '/' | '^' | '%' | '(' | ')' => {
+                chars.next();
+                tokens.push(Token::Symbol(c));
+            }
+            '×' | '·' => {
+                chars.next();
+                tokens.push(Token::Symbol('*'));
+            }
+            '÷' => {
+                chars.next();
+                tokens.push(Token::Symbol('/'));
+            }
+            '√' => {
+                chars.next();
+                tokens.push(Token::Name("sqrt".to_string()));
+            }
+            c => return Err(format!("I don't know what '{}' means", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn number(chars: &mut Peekable<Chars>) -> Result<f64, String> {
+    let mut number = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+        number.push(c);
+    }
+    number
+        .parse()
+        .map_err(|_| format!("'{}' isn't a number", number))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("That's nested too deep for me".to_string());
+        }
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// `a + b%` is `a` plus `b` percent of it, as on a pocket calculator.
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        loop {
+            let sign = if self.eat('+') {
+                1.0
+            } else if self.eat('-') {
+                -1.0
+            } else {
+                return Ok(value);
+            };
+            let (term, percent) = self.percent_term()?;
+            value += sign * if percent { value * term } else { term };
+        }
+    }
+
+    /// The term and whether it was just a percentage.
+    fn percent_term(&mut self) -> Result<(f64, bool), String> {
+        let start = self.position;
+        let term = self.term()?;
+        let percent = self.position == start + 2
+            && matches!(self.tokens[start], Token::Number(_))
+            && self.tokens[start + 1] == Token::Symbol('%');
+        Ok((term, percent))
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("I can't divide by zero".to_string());
+                }
+                value /= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.eat('-') {
+            self.nested(|parser| parser.unary().map(|value| -value))
+        } else if self.eat('+') {
+            self.nested(Self::unary)
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.percent()?;
+        if self.eat('^') {
+            let exponent = self.nested(Self::unary)?;
+            Ok(base.powf(exponent))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn percent(&mut self) -> Result<f64, String> {
+        let value = self.primary()?;
+        Ok(if self.eat('%') { value / 100.0 } else { value })
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Symbol('(')) => self.nested(|parser| {
+                let value = parser.expr()?;
+                if parser.eat(')') {
+                    Ok(value)
+                } else {
+                    Err("A parenthesis isn't closed".to_string())
+                }
+            }),
+            Some(Token::Name(name)) => {
+                if let Some((_, value)) = CONSTANTS.iter().find(|(known, _)| *known == name) {
+                    return Ok(*value);
+                }
+                let (_, function) = FUNCTIONS
+                    .iter()
+                    .find(|(known, _)| *known == name)
+                    .ok_or_else(|| format!("I don't know what '{}' means", name))?;
+                // `sqrt 16` works as well as `sqrt(16)`
+                let argument = self.nested(Self::primary)?;
+                Ok(function(argument))
+            }
+            Some(Token::Symbol(symbol)) => Err(format!("I didn't expect '{}' there", symbol)),
+            None => Err("The expression ends too soon".to_string()),
+        }
+    }
+}
+
+/// Value of the expression, e.g. `80` for `(2+3)*4^2`.
+pub fn evaluate(expression: &str) -> Result<f64, String> {
+    if expression.chars().count() > MAX_LENGTH {
+        return Err(format!(
+            "That's too long for me, up to {} characters please",
+            MAX_LENGTH
+        ));
+    }
+    let mut parser = Parser {
+        tokens: tokens(expression)?,
+        position: 0,
+        depth: 0,
+    };
+    let value = parser.expr()?;
+    if let Some(token) = parser.peek() {
+        return Err(match token {
+            Token::Symbol(')') => "A parenthesis is closed but never opened".to_string(),
+            _ => "Put an operator between the numbers, e.g. 2*3".to_string(),
+        });
+    }
+    if !value.is_finite() {
+        return Err("The result isn't a real number".to_string());
+    }
+    Ok(value)
+}
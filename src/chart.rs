@@ -0,0 +1,181 @@
+//! Line charts of a rate over time, e.g. `/chart eurusd 90d`.
+
+use chrono::NaiveDate;
+use image::{DynamicImage, ImageError, ImageOutputFormat, Rgb, RgbImage};
+use imageproc::{
+    drawing::{draw_filled_rect_mut, draw_line_segment_mut, draw_text_mut, text_size},
+    rect::Rect,
+};
+use rusttype::{Font, Scale};
+use std::{io::Cursor, str::FromStr};
+
+/// DejaVu Sans Bold, also used by the memes.
+const FONT: &[u8] = include_bytes!("../assets/DejaVuSans-Bold.ttf");
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 450;
+/// Room around the plot, on the left for the values and at the bottom for the dates.
+const LEFT: f32 = 90.0;
+const RIGHT: f32 = 30.0;
+const TOP: f32 = 60.0;
+const BOTTOM: f32 = 50.0;
+/// Horizontal lines across the plot, labelled with their value.
+const GRID_LINES: u32 = 4;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const TEXT: Rgb<u8> = Rgb([40, 40, 40]);
+const GRID: Rgb<u8> = Rgb([225, 225, 225]);
+const UP: Rgb<u8> = Rgb([22, 163, 74]);
+const DOWN: Rgb<u8> = Rgb([220, 38, 38]);
+
+/// Days charted, e.g. `90d`, `12w`, `6m` or `1y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub days: u32,
+}
+
+impl Period {
+    /// Longest period charted, ten years.
+    pub const MAX_DAYS: u32 = 3650;
+}
+
+impl Default for Period {
+    fn default() -> Self {
+        Self { days: 30 }
+    }
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let unit = s.trim_start_matches(|c: char| c.is_ascii_digit());
+        let count = s[..s.len() - unit.len()].parse::<u32>().ok();
+        let days_per_unit = match unit {
+            "d" => 1,
+            "w" => 7,
+            "m" => 30,
+            "y" => 365,
+            _ => 0,
+        };
+        match count.map(|count| count.saturating_mul(days_per_unit)) {
+            Some(days) if days > 0 && days <= Self::MAX_DAYS => Ok(Self { days }),
+            _ => Err(format!(
+                "'{}' isn't a period, try 90d, 12w, 6m or 1y, up to 10y",
+                s
+            )),
+        }
+    }
+}
+
+/// PNG of the rates, oldest first, green when they went up over the period and red otherwise.
+///
+/// `None` without at least two rates to draw a line between.
+pub fn render(title: &str, rates: &[(NaiveDate, f64)]) -> Option<Result<Vec<u8>, ImageError>> {
+    let (first, last) = match rates {
+        [first, .., last] => (first, last),
+        _ => return None,
+    };
+    let font = Font::try_from_bytes(FONT).expect("the bundled font is valid");
+    let mut image = RgbImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+
+    let (min, max) = rates
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), (_, rate)| {
+            (min.min(*rate), max.max(*rate))
+        });
+    // A flat line is drawn across the middle
+    let (min, max) = if max > min {
+        (min, max)
+    } else {
+        (min - 1.0, max + 1.0)
+    };
+    let (plot_width, plot_height) = (WIDTH as f32 - LEFT - RIGHT, HEIGHT as f32 - TOP - BOTTOM);
+    let y = |rate: f64| TOP + ((max - rate) / (max - min)) as f32 * plot_height;
+
+    draw_text_mut(
+        &mut image,
+        TEXT,
+        LEFT as i32,
+        20,
+        Scale::uniform(24.0),
+        &font,
+        title,
+    );
+
+    let small = Scale::uniform(14.0);
+    for line in 0..=GRID_LINES {
+        let rate = min + (max - min) * line as f64 / GRID_LINES as f64;
+        let at = y(rate);
+        draw_line_segment_mut(&mut image, (LEFT, at), (LEFT + plot_width, at), GRID);
+        let label = format_rate(rate);
+        let (width, height) = text_size(small, &font, &label);
+        draw_text_mut(
+            &mut image,
+            TEXT,
+            LEFT as i32 - width - 10,
+            at as i32 - height / 2,
+            small,
+            &font,
+            &label,
+        );
+    }
+
+    let dates_y = (TOP + plot_height) as i32 + 15;
+    let first_date = first.0.to_string();
+    draw_text_mut(
+        &mut image,
+        TEXT,
+        LEFT as i32,
+        dates_y,
+        small,
+        &font,
+        &first_date,
+    );
+    let last_date = last.0.to_string();
+    let (width, _) = text_size(small, &font, &last_date);
+    draw_text_mut(
+        &mut image,
+        TEXT,
+        (LEFT + plot_width) as i32 - width,
+        dates_y,
+        small,
+        &font,
+        &last_date,
+    );
+
+    // Spaced by date, so the days without rates, e.g. the weekends, don't squeeze the line
+    let span = (last.0 - first.0).num_days().max(1) as f32;
+    let x = |date: NaiveDate| LEFT + (date - first.0).num_days() as f32 / span * plot_width;
+    let color = if last.1 >= first.1 { UP } else { DOWN };
+    for pair in rates.windows(2) {
+        let (from, to) = ((x(pair[0].0), y(pair[0].1)), (x(pair[1].0), y(pair[1].1)));
+        // Two pixels thick
+        draw_line_segment_mut(&mut image, from, to, color);
+        draw_line_segment_mut(
+            &mut image,
+            (from.0, from.1 + 1.0),
+            (to.0, to.1 + 1.0),
+            color,
+        );
+    }
+    let end = Rect::at(x(last.0) as i32 - 3, y(last.1) as i32 - 3).of_size(7, 7);
+    draw_filled_rect_mut(&mut image, end, color);
+
+    let mut png = Vec::new();
+    let written =
+        DynamicImage::ImageRgb8(image).write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png);
+    Some(written.map(|_| png))
+}
+
+/// Four significant digits, enough to tell the rates of most pairs apart.
+pub fn format_rate(rate: f64) -> String {
+    let magnitude = if rate == 0.0 {
+        0
+    } else {
+        rate.abs().log10().floor().clamp(-3.0, 3.0) as i32
+    };
+    let decimals = (3 - magnitude) as usize;
+    format!("{:.*}", decimals, rate)
+}
@@ -0,0 +1,126 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Returned instead of calling an upstream whose circuit is open.
+#[derive(Debug)]
+pub struct CircuitOpen {
+    pub upstream: String,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is failing, not calling it for a while",
+            self.upstream
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Circuit {
+    /// Calls go through, counting the failures in a row.
+    Closed { failures: u32 },
+    /// Calls fail fast until the time.
+    Open { until: Instant },
+    /// A single call is let through to try the upstream again, and another one after the time
+    /// in case the first never ends, e.g. dropped by the timeout of the command.
+    HalfOpen { until: Instant },
+}
+
+/// Stops calling the upstreams that keep failing, so the commands fail fast instead of waiting on them.
+///
+/// After `threshold` failures in a row the circuit of the upstream opens for `cooldown`,
+/// then a single call tries it again, closing the circuit if it succeeds and opening it again if it doesn't.
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl CircuitBreaker {
+    /// Never opens when `threshold` is 0.
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            circuits: Mutex::default(),
+        }
+    }
+
+    /// Whether the upstream can be called now.
+    pub fn allow(&self, upstream: &str) -> Result<(), CircuitOpen> {
+        let mut circuits = self.circuits.lock().unwrap();
+        match circuits.get(upstream) {
+            Some(Circuit::Open { until } | Circuit::HalfOpen { until })
+                if Instant::now() >= *until =>
+            {
+                info!("Trying {} again", upstream);
+                circuits.insert(
+                    upstream.to_string(),
+                    Circuit::HalfOpen {
+                        until: Instant::now() + self.cooldown,
+                    },
+                );
+                Ok(())
+            }
+            Some(Circuit::Open { .. } | Circuit::HalfOpen { .. }) => Err(CircuitOpen {
+                upstream: upstream.to_string(),
+            }),
+            Some(Circuit::Closed { .. }) | None => Ok(()),
+        }
+    }
+
+    pub fn success(&self, upstream: &str) {
+        let previous = self.circuits.lock().unwrap().remove(upstream);
+        if let Some(Circuit::HalfOpen { .. }) = previous {
+            info!("{} is back, closing its circuit", upstream);
+        }
+    }
+
+    /// The call let through wasn't made, e.g. it was over quota, so a half-open circuit lets the next one try.
+    pub fn skipped(&self, upstream: &str) {
+        let mut circuits = self.circuits.lock().unwrap();
+        if let Some(circuit) = circuits.get_mut(upstream) {
+            if let Circuit::HalfOpen { .. } = circuit {
+                *circuit = Circuit::Open {
+                    until: Instant::now(),
+                };
+            }
+        }
+    }
+
+    pub fn failure(&self, upstream: &str) {
+        if self.threshold == 0 {
+            return;
+        }
+
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits
+            .entry(upstream.to_string())
+            .or_insert(Circuit::Closed { failures: 0 });
+        let failures = match *circuit {
+            Circuit::Closed { failures } => failures + 1,
+            // The try failed, or a call made before the circuit opened
+            Circuit::HalfOpen { .. } | Circuit::Open { .. } => self.threshold,
+        };
+        *circuit = if failures >= self.threshold {
+            warn!(
+                "{} failed {} times in a row, not calling it for {:?}",
+                upstream, failures, self.cooldown
+            );
+            Circuit::Open {
+                until: Instant::now() + self.cooldown,
+            }
+        } else {
+            Circuit::Closed { failures }
+        };
+    }
+}
@@ -0,0 +1,3140 @@
+use crate::{
+    alerts, alt_text,
+    api::{
+        breed_info::BreedInfo,
+        joke::{self, Joke},
+        news::NewsError,
+    },
+    assets::Sound,
+    breed::BreedQuery,
+    calc,
+    chart::{self, Period},
+    color::Color,
+    compare,
+    config::Config,
+    error::CommandError,
+    holidays, i18n, images,
+    inline::answer_inline,
+    limits::CommandClass,
+    markdown::{self, EscapedText},
+    meme,
+    messenger::{self, Portable, TelegramChat},
+    money, news, nsfw,
+    prefs::{self, UserPrefs},
+    privacy::{self, UserData},
+    qr,
+    quiet::{self, QuietHours},
+    random::{self, Dice, PasswordOptions},
+    reminders,
+    render::{self, fit, fit_caption, MAX_MESSAGE},
+    settings,
+    state::AppState,
+    status::Status,
+    storage::{schema, ChatSettings, CommandRecord},
+    subscriptions, timezones, trivia,
+    units::{self, Conversion},
+    version::BuildInfo,
+    weather,
+};
+use chrono::Utc;
+use chrono_tz::Tz;
+use futures::future;
+use metrics::{histogram, increment_counter};
+use rand::{rngs::OsRng, Rng};
+use reqwest::Url;
+use sentry::{Hub, SentryFutureExt};
+use serde::Deserialize;
+use std::fmt::Write;
+use std::{
+    error::Error,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use teloxide::{
+    dispatching::UpdateHandler,
+    net::Download,
+    prelude::*,
+    types::{
+        CallbackQuery, Chat, ForceReply, InlineKeyboardButton, InlineKeyboardMarkup, InputFile,
+        InputMedia, InputMediaPhoto, User, UserId,
+    },
+    utils::command::BotCommands,
+};
+use tracing::{error, info, info_span, warn, Instrument};
+
+/// Names of the upstreams as shown in the admin alerts.
+pub const DOG_CEO: &str = "dog.ceo";
+pub const THE_DOG_API: &str = "thedogapi";
+pub const PRICES: &str = "prices";
+pub const STOCKS: &str = "stocks";
+pub const NFTS: &str = "nfts";
+pub const DEFILLAMA: &str = "defillama";
+pub const FX: &str = "frankfurter";
+pub const OPEN_METEO: &str = "open-meteo";
+pub const JOKES: &str = "jokeapi";
+pub const QUOTES: &str = "quotable";
+pub const XKCD: &str = "xkcd";
+pub const NASA: &str = "nasa";
+pub const WIKIPEDIA: &str = "wikipedia";
+pub const TRANSLATE: &str = "libretranslate";
+pub const DICTIONARY: &str = "dictionaryapi";
+pub const NEWS: &str = "news";
+pub const URBAN: &str = "urbandictionary";
+pub const SHLINK: &str = "shlink";
+pub const TRIVIA: &str = "opentdb";
+pub const POKEAPI: &str = "pokeapi";
+pub const NAGER_DATE: &str = "nager.date";
+
+/// Prefix of the callback data of the buttons revealing a punchline, followed by `:<user id>:<joke id>`.
+pub const PUNCHLINE: &str = "joke-punchline";
+
+/// Prefix of the callback data of the buttons paging through definitions, followed by
+/// `:<user id>:<index>:<term>`.
+pub const URBAN_PAGE: &str = "urban-page";
+
+/// Prefix of the callback data of the buttons browsing the images of a breed, followed by
+/// `:<user id>:<index>:<breed>`.
+pub const BREED_PAGE: &str = "breed-page";
+
+/// How the commands are run.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CommandsConfig {
+    /// Time a command has to answer, including every upstream call and retry.
+    pub timeout_secs: u64,
+    /// Commands running at once, the rest wait for a free slot.
+    pub max_concurrent: usize,
+    /// Commands running at once in a single chat, the rest are turned down.
+    pub max_per_chat: usize,
+    /// Commands a user can run within `user_window_secs`, without a limit when 0.
+    pub max_per_user: usize,
+    pub user_window_secs: u64,
+    /// Time between two cheap commands of a user in a chat, e.g. /doggo, until the chat sets its own in `/settings`.
+    pub cheap_cooldown_secs: u64,
+    /// Time between two expensive commands of a user in a chat, e.g. /chart.
+    pub expensive_cooldown_secs: u64,
+}
+
+impl Default for CommandsConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            max_concurrent: 32,
+            max_per_chat: 2,
+            max_per_user: 20,
+            user_window_secs: 60,
+            cheap_cooldown_secs: 0,
+            expensive_cooldown_secs: 10,
+        }
+    }
+}
+
+#[derive(BotCommands, Clone)]
+#[command(rename = "lowercase", description = "These commands are supported:")]
+pub enum Command {
+    #[command(description = "Random dog")]
+    Doggo,
+
+    #[command(description = "Random dog from the specified breed")]
+    Breed(String),
+
+    #[command(
+        description = "Random dog from the specified breed in full quality, as a file, e.g. /breedhd husky"
+    )]
+    BreedHd(String),
+
+    #[command(description = "List the breeds of dogs")]
+    Breeds,
+
+    #[command(
+        description = "Photos of two breeds and how they compare, e.g. /comparebreeds husky vs pug"
+    )]
+    CompareBreeds(String),
+
+    #[command(description = "Random dog bark, as a voice message")]
+    Bark,
+
+    #[command(description = "Get the value of EURO in USD")]
+    Euro,
+
+    #[command(description = "Price of a stock, e.g. /stock aapl")]
+    Stock(String),
+
+    #[command(description = "Floor price of an NFT collection, e.g. /floor pudgy-penguins")]
+    Floor(String),
+
+    #[command(description = "Chart of an exchange rate, e.g. /chart eurusd 90d")]
+    Chart(String),
+
+    #[command(description = "Crypto market cap, volume and BTC/ETH dominance")]
+    Dominance,
+
+    #[command(
+        description = "Value locked in a DeFi protocol, e.g. /tvl lido, or /tvl chains for the top chains"
+    )]
+    Tvl(String),
+
+    #[command(
+        description = "Get notified when a coin reaches a price, e.g. /alert btc 70000, or moves, e.g. /alert btc 5% 1h"
+    )]
+    Alert(String),
+
+    #[command(description = "Your alerts, to pause, edit or delete them")]
+    Alerts,
+
+    #[command(description = "Weather and forecast of a city, e.g. /weather madrid")]
+    Weather(String),
+
+    #[command(description = "Random joke, e.g. /joke or /joke programming pun")]
+    Joke(String),
+
+    #[command(description = "Random quote, e.g. /quote or /quote albert einstein")]
+    Quote(String),
+
+    #[command(description = "xkcd comic, e.g. /xkcd, /xkcd 327 or /xkcd random")]
+    Xkcd(String),
+
+    #[command(description = "NASA's Astronomy Picture of the Day")]
+    Apod,
+
+    #[command(description = "Summary of a Wikipedia article, e.g. /wiki golden retriever")]
+    Wiki(String),
+
+    #[command(
+        description = "Translate the text or the message replied to, e.g. /translate es good boy"
+    )]
+    Translate(String),
+
+    #[command(description = "Definitions of an English word, e.g. /define woof")]
+    Define(String),
+
+    #[command(description = "Heads or tails")]
+    Flip,
+
+    #[command(description = "Roll dice, e.g. /roll 2d20")]
+    Roll(String),
+
+    #[command(description = "Pick one of the options, e.g. /pick pizza | sushi | tacos")]
+    Pick(String),
+
+    #[command(description = "Convert between units, e.g. /conv 10 km mi or /conv 20 c f")]
+    Conv(String),
+
+    #[command(
+        description = "Strong random password, e.g. /password, /password 32 or /password 6 digits"
+    )]
+    Password(String),
+
+    #[command(description = "Random UUID, e.g. /uuid or /uuid v7")]
+    Uuid(String),
+
+    #[command(description = "Work out some arithmetic, e.g. /calc (2+3)*4^2 or /calc 80 + 15%")]
+    Calc(String),
+
+    #[command(description = "Local time in a city, e.g. /time tokyo")]
+    Time(String),
+
+    #[command(
+        description = "Upcoming public holidays, e.g. /holidays es, or /holidays country es to set the chat's default"
+    )]
+    Holidays(String),
+
+    #[command(description = "Short link to the URL, e.g. /shorten https://dog.ceo/dog-api")]
+    Shorten(String),
+
+    #[command(description = "How many times a short link was opened, e.g. /clicks abc12")]
+    Clicks(String),
+
+    #[command(
+        description = "Meme of a random dog or the photo replied to, e.g. /meme such dog | very wow"
+    )]
+    Meme(String),
+
+    #[command(
+        description = "QR code of the text, e.g. /qr https://dog.ceo, or reply with /qr to a photo to read one"
+    )]
+    Qr(String),
+
+    #[command(description = "Swatch of a color, e.g. /color #ff6347 or /color tomato")]
+    Color(String),
+
+    #[command(
+        description = "Trivia question for the chat, e.g. /trivia, /trivia science hard or /trivia scores"
+    )]
+    Trivia(String),
+
+    #[command(description = "Types and base stats of a Pokémon, e.g. /pokemon growlithe")]
+    Pokemon(String),
+
+    #[command(description = "Urban Dictionary definitions, e.g. /urban doggo")]
+    Urban(String),
+
+    #[command(
+        description = "Keep the content not safe for work out of the chat, e.g. /nsfwfilter on"
+    )]
+    NsfwFilter(String),
+
+    #[command(
+        description = "Caption the dogs with their breed for screen readers, e.g. /alttext on"
+    )]
+    AltText(String),
+
+    #[command(
+        description = "Top headlines, e.g. /news, /news football or /news source https://example.com/rss"
+    )]
+    News(String),
+
+    #[command(description = "Most requested breeds, here and everywhere")]
+    PopularBreeds,
+
+    #[command(description = "Usage of the bot")]
+    Stats,
+
+    #[command(
+        description = "How long the bot has been up, and how its upstreams, cache and jobs are doing"
+    )]
+    Uptime,
+
+    #[command(description = "Version, commit, build time and features of the bot")]
+    Version,
+
+    #[command(description = "Show or change your preferences, e.g. /prefs breed husky")]
+    Prefs(String),
+
+    #[command(description = "Get something every day, e.g. /subscribe dailydog 09:00")]
+    Subscribe(String),
+
+    #[command(description = "Stop a subscription, e.g. /unsubscribe dailydog")]
+    Unsubscribe(String),
+
+    #[command(description = "Remind you of something, e.g. /remind in 20m walk the dog")]
+    Remind(String),
+
+    #[command(description = "List and cancel your reminders")]
+    Reminders,
+
+    #[command(
+        description = "Timezone of the chat's subscriptions and reminders, e.g. /settimezone Europe/Madrid"
+    )]
+    SetTimezone(String),
+
+    #[command(
+        description = "Language of the chat, e.g. /language es; until set, the one of your Telegram app"
+    )]
+    Language(String),
+
+    #[command(
+        description = "Hold the scheduled messages at night, e.g. /quiethours 23:00-08:00 or off"
+    )]
+    QuietHours(String),
+
+    #[command(
+        description = "Cooldowns of the chat for cheap and expensive commands, e.g. /settings expensive 60 (chat admins only)"
+    )]
+    Settings(String),
+
+    #[command(description = "What the bot stores about you and the chat, and why")]
+    Privacy,
+
+    #[command(description = "Get everything the bot knows about you, as a JSON file")]
+    MyData,
+
+    #[command(description = "Delete everything the bot knows about you")]
+    ForgetMe,
+
+    #[command(description = "Scheduled jobs and how their last run went (admin chat only)")]
+    Jobs,
+
+    #[command(description = "Reload the configuration (admin chat only)")]
+    ReloadConfig,
+}
+
+impl Command {
+    /// Name used in the logs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Doggo => "doggo",
+            Self::Breed(_) => "breed",
+            Self::BreedHd(_) => "breedhd",
+            Self::Breeds => "breeds",
+            Self::CompareBreeds(_) => "comparebreeds",
+            Self::Bark => "bark",
+            Self::Euro => "euro",
+            Self::Stock(_) => "stock",
+            Self::Floor(_) => "floor",
+            Self::Chart(_) => "chart",
+            Self::Dominance => "dominance",
+            Self::Tvl(_) => "tvl",
+            Self::Alert(_) => "alert",
+            Self::Alerts => "alerts",
+            Self::Weather(_) => "weather",
+            Self::Joke(_) => "joke",
+            Self::Quote(_) => "quote",
+            Self::Xkcd(_) => "xkcd",
+            Self::Apod => "apod",
+            Self::Wiki(_) => "wiki",
+            Self::Translate(_) => "translate",
+            Self::Define(_) => "define",
+            Self::Flip => "flip",
+            Self::Roll(_) => "roll",
+            Self::Pick(_) => "pick",
+            Self::Conv(_) => "conv",
+            Self::Password(_) => "password",
+            Self::Uuid(_) => "uuid",
+            Self::Calc(_) => "calc",
+            Self::Time(_) => "time",
+            Self::Holidays(_) => "holidays",
+            Self::Shorten(_) => "shorten",
+            Self::Clicks(_) => "clicks",
+            Self::Meme(_) => "meme",
+            Self::Qr(_) => "qr",
+            Self::Color(_) => "color",
+            Self::Trivia(_) => "trivia",
+            Self::Pokemon(_) => "pokemon",
+            Self::Urban(_) => "urban",
+            Self::NsfwFilter(_) => "nsfwfilter",
+            Self::AltText(_) => "alttext",
+            Self::News(_) => "news",
+            Self::PopularBreeds => "popularbreeds",
+            Self::Stats => "stats",
+            Self::Uptime => "uptime",
+            Self::Version => "version",
+            Self::Prefs(_) => "prefs",
+            Self::Subscribe(_) => "subscribe",
+            Self::Unsubscribe(_) => "unsubscribe",
+            Self::Remind(_) => "remind",
+            Self::Reminders => "reminders",
+            Self::SetTimezone(_) => "settimezone",
+            Self::Language(_) => "language",
+            Self::QuietHours(_) => "quiethours",
+            Self::Settings(_) => "settings",
+            Self::Privacy => "privacy",
+            Self::MyData => "mydata",
+            Self::ForgetMe => "forgetme",
+            Self::Jobs => "jobs",
+            Self::ReloadConfig => "reloadconfig",
+        }
+    }
+
+    /// How heavy the command is, for the cooldowns of the chat; None if it has no cooldown.
+    pub fn class(&self) -> Option<CommandClass> {
+        match self {
+            Self::Doggo | Self::Breed(_) | Self::Bark => Some(CommandClass::Cheap),
+            Self::BreedHd(_) | Self::CompareBreeds(_) | Self::Chart(_) | Self::Meme(_) => {
+                Some(CommandClass::Expensive)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Handlers of every update, shared by the dispatchers and the replays.
+pub fn handler() -> UpdateHandler<Box<dyn Error + Send + Sync>> {
+    dptree::filter(|state: Arc<AppState>| {
+        // Counted for /uptime, every update goes on
+        state.health.update_received();
+        true
+    })
+    .branch(
+        Update::filter_message()
+            .filter_command::<Command>()
+            .endpoint(answer),
+    )
+    .branch(Update::filter_message().endpoint(answer_message))
+    .branch(Update::filter_callback_query().endpoint(answer_callback))
+    .branch(Update::filter_inline_query().endpoint(answer_inline))
+}
+
+/// Handle a command, logging who asked for it, how long it took and how it went.
+pub async fn answer(
+    bot: AutoSend<Bot>,
+    message: Message,
+    command: Command,
+    state: Arc<AppState>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let span = info_span!(
+        "command",
+        command = command.name(),
+        chat_id = message.chat.id.0,
+        user_id = message.from().map(|user| user.id.0),
+    );
+    let start = Instant::now();
+
+    // Errors and panics reported to Sentry carry the command context
+    let hub = Arc::new(Hub::new_from_top(Hub::current()));
+    hub.configure_scope(|scope| {
+        scope.set_tag("command", command.name());
+        scope.set_tag("chat_id", message.chat.id.0);
+        if let Some(user) = message.from() {
+            scope.set_user(Some(sentry::User {
+                id: Some(user.id.to_string()),
+                username: user.username.clone(),
+                ..Default::default()
+            }));
+        }
+    });
+
+    let chat_id = message.chat.id;
+    if let Some(user) = message.from() {
+        let class = match command.class() {
+            Some(class) => {
+                let config = state.commands.read().unwrap().clone();
+                let cooldown =
+                    settings::cooldown(state.storage.as_ref(), chat_id.0, class, &config).await;
+                Some((class, cooldown))
+            }
+            None => None,
+        };
+        if let Err(cooldown) = state.limiter.hit_command(chat_id, user.id, class) {
+            span.in_scope(|| warn!("Too many commands of the user, rejecting"));
+            // Later commands within the cooldown are ignored silently
+            if cooldown.first {
+                tokio::spawn(cooldown_notice(bot, chat_id, cooldown.remaining));
+            }
+            return Ok(());
+        }
+    }
+    let _permit = match state.limiter.acquire(chat_id).await {
+        Some(permit) => permit,
+        None => {
+            span.in_scope(|| warn!("Too many commands running in the chat, rejecting"));
+            bot.send_message(chat_id, "Please wait for your previous commands to finish.")
+                .await
+                .ok();
+            return Ok(());
+        }
+    };
+
+    remember(&state, &message).await;
+    let mut record = CommandRecord {
+        command: command.name().to_string(),
+        user_id: message.from().map(|user| user.id.0 as i64),
+        chat_id: chat_id.0,
+        chat_type: chat_type(&message.chat).to_string(),
+        at: Utc::now(),
+        latency_ms: 0,
+        success: false,
+    };
+    let prefs = UserPrefs::new(
+        state.storage.clone(),
+        message.from().map(|user| user.id.0 as i64),
+    );
+
+    let deadline = Duration::from_secs(state.commands.read().unwrap().timeout_secs);
+    let handled = tokio::time::timeout(
+        deadline,
+        handle(bot.clone(), message, command, state.clone(), prefs)
+            .instrument(span.clone())
+            .bind_hub(hub.clone()),
+    )
+    .await;
+
+    let (result, outcome) = match handled {
+        Ok(Ok(())) => (Ok(()), "ok"),
+        Ok(Err(e)) => {
+            Hub::run(hub, || span.in_scope(|| error!("Command failed -> {}", e)));
+            if let Some(upstream) = e.upstream() {
+                state.reporter.failure(upstream, &e).await;
+            }
+            if let Some(text) = e.user_message() {
+                bot.send_message(chat_id, text).await.ok();
+            }
+            (Err(e.into()), "error")
+        }
+        Err(_) => {
+            span.in_scope(|| warn!("Command timed out after {:?}", deadline));
+            bot.send_message(
+                chat_id,
+                "Sorry, that took too long, please try again later.",
+            )
+            .await
+            .ok();
+            (Ok(()), "timeout")
+        }
+    };
+    let latency = start.elapsed();
+    let latency_ms = latency.as_millis() as u64;
+    span.in_scope(|| info!(latency_ms, outcome, "Command handled"));
+    histogram!("command_duration_seconds", latency, "command" => record.command.clone(), "outcome" => outcome);
+    increment_counter!("commands_total", "command" => record.command.clone(), "outcome" => outcome);
+
+    record.latency_ms = latency_ms as i64;
+    record.success = outcome == "ok";
+    if let Err(e) = state.storage.record_command(&record).await {
+        warn!("Could not record the command -> {}", e);
+    }
+    // Writing to the bot again means it was unblocked, or added back
+    if let Err(e) = state.storage.reactivate_chat(record.chat_id).await {
+        warn!("Could not reactivate the chat -> {}", e);
+    }
+
+    result
+}
+
+async fn handle(
+    bot: AutoSend<Bot>,
+    message: Message,
+    command: Command,
+    state: Arc<AppState>,
+    prefs: UserPrefs,
+) -> Result<(), CommandError> {
+    match command {
+        Command::Breeds => {
+            info!("Fetching a the list of dogs...");
+
+            let breeds = state
+                .dog_api
+                .breeds()
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+            state.reporter.success(DOG_CEO);
+            if breeds.status != "success" {
+                return Err(CommandError::Malformed {
+                    upstream: DOG_CEO,
+                    reason: format!("the list of breeds has the status '{}'", breeds.status),
+                });
+            }
+
+            let msg = render::breeds(&breeds.message);
+
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            bot.send_message(user.id, msg).await?;
+            info!("Breeds sent with success");
+        }
+        Command::Doggo => {
+            if let Some(image) = state.dogs.pop() {
+                info!("Sending a prefetched dog");
+                send_dog(&bot, &state, message.chat.id, &image, None).await?;
+                return Ok(());
+            }
+
+            info!("Fetching a random dog...");
+
+            let dog = state
+                .dog_api
+                .random()
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+            state.reporter.success(DOG_CEO);
+            if dog.status != "success" {
+                return Err(CommandError::Malformed {
+                    upstream: DOG_CEO,
+                    reason: format!("the random dog has the status '{}'", dog.status),
+                });
+            }
+            send_dog(&bot, &state, message.chat.id, &dog.message, None).await?;
+        }
+        Command::CompareBreeds(text) => {
+            let (a, b) = match compare::split(&text) {
+                Some(breeds) => breeds,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me which breeds, e.g. /comparebreeds husky vs pug",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+            let breeds = match (BreedQuery::from_str(a), BreedQuery::from_str(b)) {
+                (Ok(a), Ok(b)) => [a, b],
+                (Err(e), _) | (_, Err(e)) => {
+                    bot.send_message(message.chat.id, e.to_string()).await?;
+                    return Ok(());
+                }
+            };
+            info!("Comparing {} and {}...", breeds[0], breeds[1]);
+            compare_breeds(&bot, &state, message.chat.id, &breeds).await?;
+        }
+        Command::Bark => {
+            let bark = state.assets.random_bark(&mut OsRng).cloned();
+            match bark {
+                Some(bark) => send_bark(&bot, &state, message.chat.id, bark).await?,
+                None => {
+                    bot.send_message(message.chat.id, "I have no barks to share right now")
+                        .await?;
+                }
+            }
+        }
+        Command::Euro => {
+            let chat = TelegramChat::new(bot, message.chat.id);
+            messenger::run(&state, &chat, Portable::Euro).await?;
+        }
+        Command::Stock(ticker) => {
+            let ticker = ticker.trim().to_uppercase();
+            if ticker.is_empty()
+                || !ticker
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '^' | '='))
+            {
+                bot.send_message(message.chat.id, "Tell me the ticker, e.g. /stock aapl")
+                    .await?;
+                return Ok(());
+            }
+
+            let quote =
+                state
+                    .stock_api
+                    .quote(&ticker)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: STOCKS,
+                        error,
+                    })?;
+            state.reporter.success(STOCKS);
+            let text = match quote {
+                Some(quote) => {
+                    let mut text =
+                        format!("{}: {:.2} {}", quote.symbol, quote.price, quote.currency);
+                    if let Some(change) = quote.change() {
+                        write!(text, " ({} today)", money::change(change)).ok();
+                    }
+                    text
+                }
+                None => format!("I don't know the ticker '{}'", ticker),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Floor(collection) => {
+            // The slug of the collection, e.g. `Pudgy Penguins` is `pudgy-penguins`
+            let slug = collection
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+                .to_lowercase();
+            if slug.is_empty()
+                || !slug
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            {
+                bot.send_message(
+                    message.chat.id,
+                    "Tell me the collection, e.g. /floor pudgy-penguins",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let floor =
+                state
+                    .nft_api
+                    .floor(&slug)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: NFTS,
+                        error,
+                    })?;
+            state.reporter.success(NFTS);
+            let text = match floor {
+                Some(floor) => {
+                    let usd = match floor.usd {
+                        Some(usd) => Some(usd),
+                        // Priced in ETH only, converted at the current price
+                        None if floor.currency == "ETH" => {
+                            match state.price_api.usd_price("eth").await {
+                                Ok(eth) => eth.map(|eth| floor.price * eth),
+                                Err(e) => {
+                                    warn!("Could not fetch the price of ETH -> {}", e);
+                                    None
+                                }
+                            }
+                        }
+                        None => None,
+                    };
+                    let mut text = format!(
+                        "{} floor: {}",
+                        floor.name,
+                        money::amount(floor.price, &floor.currency)
+                    );
+                    if let Some(usd) = usd {
+                        write!(text, " ({})", money::usd(usd)).ok();
+                    }
+                    if let Some(change) = floor.change_24h {
+                        write!(text, "\n24h: {}", money::change(change)).ok();
+                    }
+                    text
+                }
+                None => format!("I don't know the collection '{}'", slug),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Chart(args) => {
+            let mut args = args.split_whitespace();
+            let pair = args
+                .next()
+                .unwrap_or_default()
+                .replace('/', "")
+                .to_uppercase();
+            let period = args
+                .next()
+                .map_or(Ok(Period::default()), str::parse::<Period>);
+            let period = match period {
+                Ok(period) if pair.len() == 6 && pair.chars().all(|c| c.is_ascii_alphabetic()) => {
+                    period
+                }
+                Ok(_) => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me the pair and the period, e.g. /chart eurusd 90d",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+                Err(e) => {
+                    bot.send_message(message.chat.id, e).await?;
+                    return Ok(());
+                }
+            };
+            let (base, quote) = pair.split_at(3);
+
+            let to = Utc::now().date_naive();
+            let from = to - chrono::Duration::days(period.days.into());
+            let rates = state
+                .fx_api
+                .history(base, quote, from, to)
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: FX,
+                    error,
+                })?;
+            state.reporter.success(FX);
+            let rates = match rates {
+                Some(rates) => rates,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        format!("I don't know the pair '{}/{}'", base, quote),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let title = format!("{}/{}, last {} days", base, quote, period.days);
+            let png = match chart::render(&title, &rates) {
+                Some(png) => png,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        format!("There are no rates of {}/{} in that period", base, quote),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+            let png = match png {
+                Ok(png) => png,
+                Err(e) => {
+                    error!("Could not draw the chart -> {}", e);
+                    bot.send_message(message.chat.id, "I couldn't draw that chart")
+                        .await?;
+                    return Ok(());
+                }
+            };
+            let (first, last) = (rates[0].1, rates[rates.len() - 1].1);
+            let caption = format!(
+                "{}: {} → {} ({})",
+                title,
+                chart::format_rate(first),
+                chart::format_rate(last),
+                money::change((last - first) / first * 100.0)
+            );
+            bot.send_photo(
+                message.chat.id,
+                InputFile::memory(png).file_name("chart.png"),
+            )
+            .caption(caption)
+            .await?;
+        }
+        Command::Dominance => {
+            let chat = TelegramChat::new(bot, message.chat.id);
+            messenger::run(&state, &chat, Portable::Dominance).await?;
+        }
+        Command::Tvl(protocol) => {
+            let chat = TelegramChat::new(bot, message.chat.id);
+            messenger::run(&state, &chat, Portable::Tvl(protocol)).await?;
+        }
+        Command::Alert(args) => {
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let mut args = args.split_whitespace();
+            let symbol = args.next();
+            let target = args.next();
+            let window = args.next();
+
+            let usage = "Tell me the coin and the price, e.g. /alert btc 70000, \
+                or how much it moves within up to 24 hours, e.g. /alert btc 5% 1h";
+            let percent = target
+                .and_then(|target| target.strip_suffix('%'))
+                .map(|percent| percent.parse::<f64>().ok());
+            let text = match (symbol, percent) {
+                (Some(symbol), Some(Some(percent))) if percent > 0.0 => {
+                    let window = match window {
+                        Some(window) => alerts::parse_window(window),
+                        None => Some(state.price_history.default_window),
+                    };
+                    match window {
+                        Some(window) => match alerts::add_move(
+                            &state,
+                            user.id.0 as i64,
+                            message.chat.id,
+                            symbol,
+                            percent,
+                            window,
+                        )
+                        .await?
+                        {
+                            Some((alert, price)) => format!(
+                                "I'll tell you when {} moves {}% either way within {}h (now ${})",
+                                alert.symbol.to_uppercase(),
+                                percent,
+                                window.as_secs() / 3600,
+                                price
+                            ),
+                            None => format!("I don't know the price of '{}'", symbol),
+                        },
+                        None => usage.to_string(),
+                    }
+                }
+                (Some(_), Some(_)) => usage.to_string(),
+                (Some(symbol), None) => match target
+                    .and_then(|target| target.parse::<f64>().ok())
+                    .filter(|target| *target > 0.0)
+                {
+                    Some(target) => {
+                        match alerts::add(&state, user.id.0 as i64, message.chat.id, symbol, target)
+                            .await?
+                        {
+                            Some((alert, price)) => format!(
+                                "I'll tell you when {} goes {} ${} (now ${})",
+                                alert.symbol.to_uppercase(),
+                                if alert.above { "above" } else { "below" },
+                                alert.target,
+                                price
+                            ),
+                            None => format!("I don't know the price of '{}'", symbol),
+                        }
+                    }
+                    None => usage.to_string(),
+                },
+                _ => usage.to_string(),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Alerts => {
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let alerts = state.storage.alerts(Some(user.id.0 as i64)).await?;
+            let (text, keyboard) = alerts::list(&alerts, user.id);
+            let request = bot.send_message(message.chat.id, text);
+            match keyboard {
+                Some(keyboard) => request.reply_markup(keyboard).await?,
+                None => request.await?,
+            };
+        }
+        Command::Weather(city) => {
+            let city = match city.trim() {
+                "" => prefs.get().await.city,
+                city => Some(city.to_string()),
+            };
+            let city = match city {
+                Some(city) => city,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me the city, e.g. /weather madrid, or set your default one with /prefs city madrid",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let upstream = |error| CommandError::Upstream {
+                upstream: OPEN_METEO,
+                error,
+            };
+            let text = match state.weather_api.locate(&city).await.map_err(upstream)? {
+                Some(place) => {
+                    let forecast = state
+                        .weather_api
+                        .forecast(&place, weather::FORECAST_DAYS)
+                        .await
+                        .map_err(upstream)?;
+                    weather::render(&place, &forecast)
+                }
+                None => format!("I couldn't find the city '{}'", city),
+            };
+            state.reporter.success(OPEN_METEO);
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Joke(args) => {
+            let categories = args
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+            if let Some(unknown) = categories
+                .iter()
+                .find(|category| !joke::CATEGORIES.contains(&category.as_str()))
+            {
+                bot.send_message(
+                    message.chat.id,
+                    format!(
+                        "Unknown category '{}', pick some of: {}",
+                        unknown,
+                        joke::CATEGORIES.join(", ")
+                    ),
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let found = state.joke_api.random(&categories).await.map_err(|error| {
+                CommandError::Upstream {
+                    upstream: JOKES,
+                    error,
+                }
+            })?;
+            state.reporter.success(JOKES);
+            match found {
+                Some(joke) if joke.punchline.is_some() => {
+                    let user = message.from().ok_or(CommandError::NoSender)?;
+                    let keyboard = InlineKeyboardMarkup::new([[InlineKeyboardButton::callback(
+                        "Punchline",
+                        format!("{}:{}:{}", PUNCHLINE, user.id, joke.id),
+                    )]]);
+                    bot.send_message(message.chat.id, joke.text)
+                        .reply_markup(keyboard)
+                        .await?;
+                }
+                Some(joke) => {
+                    bot.send_message(message.chat.id, joke.text).await?;
+                }
+                None => {
+                    bot.send_message(message.chat.id, "I couldn't find a joke like that")
+                        .await?;
+                }
+            }
+        }
+        Command::Quote(author) => {
+            let author = Some(author.trim()).filter(|author| !author.is_empty());
+            let quote =
+                state
+                    .quote_api
+                    .random(author)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: QUOTES,
+                        error,
+                    })?;
+            state.reporter.success(QUOTES);
+            match quote {
+                Some(quote) => {
+                    let text = EscapedText::escape(&quote.text).italic()
+                        + EscapedText::markup("\n— ")
+                        + EscapedText::escape(&quote.author).bold();
+                    markdown::send_message(&bot, message.chat.id, text).await?;
+                }
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        format!("I don't know any quote of '{}'", author.unwrap_or_default()),
+                    )
+                    .await?;
+                }
+            }
+        }
+        Command::Xkcd(args) => {
+            let upstream = |error| CommandError::Upstream {
+                upstream: XKCD,
+                error,
+            };
+            let args = args.trim().to_lowercase();
+            let comic = match args.as_str() {
+                "" => Some(state.xkcd_api.latest().await.map_err(upstream)?),
+                "random" => {
+                    let latest = state.xkcd_api.latest().await.map_err(upstream)?;
+                    // 404 doesn't exist, on purpose
+                    let num = loop {
+                        let num = rand::thread_rng().gen_range(1..=latest.num);
+                        if num != 404 {
+                            break num;
+                        }
+                    };
+                    state.xkcd_api.comic(num).await.map_err(upstream)?
+                }
+                "404" => {
+                    bot.send_message(message.chat.id, "#404 Not Found, there's no such comic")
+                        .await?;
+                    return Ok(());
+                }
+                number => match number.parse::<u32>() {
+                    Ok(num) => state.xkcd_api.comic(num).await.map_err(upstream)?,
+                    Err(_) => {
+                        bot.send_message(
+                            message.chat.id,
+                            "Tell me a comic number or random, e.g. /xkcd 327",
+                        )
+                        .await?;
+                        return Ok(());
+                    }
+                },
+            };
+            state.reporter.success(XKCD);
+
+            match comic {
+                Some(comic) => {
+                    let image = Url::from_str(&comic.img).map_err(|e| CommandError::Malformed {
+                        upstream: XKCD,
+                        reason: format!("'{}' is not an image URL: {}", comic.img, e),
+                    })?;
+                    send_photo(
+                        &bot,
+                        &state,
+                        message.chat.id,
+                        XKCD,
+                        image,
+                        Some(fit_caption(comic.caption())),
+                        None,
+                    )
+                    .await?;
+                }
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        format!("There's no xkcd number {} yet", args),
+                    )
+                    .await?;
+                }
+            }
+        }
+        Command::Apod => {
+            let apod = state
+                .apod_api
+                .today()
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: NASA,
+                    error,
+                })?;
+            state.reporter.success(NASA);
+
+            let mut text = apod.title.clone();
+            if let Some(copyright) = &apod.copyright {
+                write!(text, " (© {})", copyright.trim()).ok();
+            }
+            write!(text, "\n\n{}", apod.explanation).ok();
+            if apod.is_image() {
+                let image = Url::from_str(&apod.url).map_err(|e| CommandError::Malformed {
+                    upstream: NASA,
+                    reason: format!("'{}' is not an image URL: {}", apod.url, e),
+                })?;
+                send_photo(
+                    &bot,
+                    &state,
+                    message.chat.id,
+                    NASA,
+                    image,
+                    Some(fit_caption(text)),
+                    None,
+                )
+                .await?;
+            } else {
+                // Videos are embedded pages, the link preview plays them
+                bot.send_message(message.chat.id, format!("{}\n\n{}", apod.url, text))
+                    .await?;
+            }
+        }
+        Command::Wiki(term) => {
+            let term = term.trim();
+            if term.is_empty() {
+                bot.send_message(message.chat.id, "Tell me what to look up, e.g. /wiki husky")
+                    .await?;
+                return Ok(());
+            }
+
+            let (language, _) = language(&state, &message, &prefs).await;
+            let summary = state
+                .wiki_api
+                .summary(&language, term)
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: WIKIPEDIA,
+                    error,
+                })?;
+            state.reporter.success(WIKIPEDIA);
+
+            let summary = match summary {
+                Some(summary) => summary,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        format!("There's no article about '{}'", term),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+            let link = Url::from_str(&summary.url).map_err(|e| CommandError::Malformed {
+                upstream: WIKIPEDIA,
+                reason: format!("'{}' is not an article URL: {}", summary.url, e),
+            })?;
+            let keyboard =
+                InlineKeyboardMarkup::new([[InlineKeyboardButton::url("Read more", link)]]);
+            let text = format!("{}\n\n{}", summary.title, summary.extract);
+            match summary
+                .thumbnail
+                .and_then(|thumbnail| Url::from_str(&thumbnail).ok())
+            {
+                Some(thumbnail) => {
+                    send_photo(
+                        &bot,
+                        &state,
+                        message.chat.id,
+                        WIKIPEDIA,
+                        thumbnail,
+                        Some(fit_caption(text)),
+                        Some(keyboard),
+                    )
+                    .await?;
+                }
+                None => {
+                    bot.send_message(message.chat.id, text)
+                        .reply_markup(keyboard)
+                        .await?;
+                }
+            }
+        }
+        Command::Translate(args) => {
+            let translate_api = match &state.translate_api {
+                Some(translate_api) => translate_api,
+                None => {
+                    bot.send_message(message.chat.id, "Translations aren't set up in this bot")
+                        .await?;
+                    return Ok(());
+                }
+            };
+
+            let args = args.trim();
+            let (target, text) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            let target = target.to_lowercase();
+            let text = match text.trim() {
+                "" => message
+                    .reply_to_message()
+                    .and_then(|replied| replied.text().or_else(|| replied.caption()))
+                    .unwrap_or_default(),
+                text => text,
+            };
+            if target.is_empty() || text.is_empty() {
+                bot.send_message(
+                    message.chat.id,
+                    "Tell me the language and the text, e.g. /translate es good boy, \
+                     or reply to a message with /translate es",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let translation = translate_api
+                .translate(text, &target)
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: TRANSLATE,
+                    error,
+                })?;
+            state.reporter.success(TRANSLATE);
+            let text = match translation {
+                Some(translation) => match translation.source {
+                    Some(source) => format!("{} ({} → {})", translation.text, source, target),
+                    None => translation.text,
+                },
+                None => format!("I can't translate into '{}'", target),
+            };
+            bot.send_message(message.chat.id, text)
+                .reply_to_message_id(message.id)
+                .await?;
+        }
+        Command::Define(word) => {
+            let word = word.trim().to_lowercase();
+            if word.is_empty()
+                || !word
+                    .chars()
+                    .all(|c| c.is_alphabetic() || matches!(c, ' ' | '-' | '\''))
+            {
+                bot.send_message(message.chat.id, "Tell me a word, e.g. /define woof")
+                    .await?;
+                return Ok(());
+            }
+
+            let entry = state.dictionary_api.define(&word).await.map_err(|error| {
+                CommandError::Upstream {
+                    upstream: DICTIONARY,
+                    error,
+                }
+            })?;
+            state.reporter.success(DICTIONARY);
+            let text = match entry {
+                Some(entry) => entry.render(),
+                None => format!("I don't know the word '{}'", word),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Flip => {
+            let side = random::flip(&mut OsRng);
+            bot.send_message(message.chat.id, format!("🪙 {}", side))
+                .await?;
+        }
+        Command::Roll(dice) => {
+            let text = match dice.trim() {
+                "" => Ok(Dice::default()),
+                dice => dice.parse::<Dice>(),
+            }
+            .map(|dice| {
+                let rolls = dice.roll(&mut OsRng);
+                match rolls.as_slice() {
+                    [roll] => format!("🎲 {}: {}", dice, roll),
+                    rolls => format!(
+                        "🎲 {}: {} = {}",
+                        dice,
+                        rolls
+                            .iter()
+                            .map(u32::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" + "),
+                        rolls.iter().sum::<u32>()
+                    ),
+                }
+            })
+            .unwrap_or_else(|e| e);
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Pick(options) => {
+            let options = random::options(&options);
+            let text = match options.len() {
+                0 | 1 => "Give me a few options, e.g. /pick pizza | sushi | tacos".to_string(),
+                _ => format!(
+                    "👉 {}",
+                    random::pick(&options, &mut OsRng).unwrap_or_default()
+                ),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Conv(conversion) => {
+            let text = match conversion.trim() {
+                "" => {
+                    "Tell me what to convert, e.g. /conv 10 km mi, /conv 20 c f or /conv 4 GiB MB"
+                        .to_string()
+                }
+                conversion => conversion
+                    .parse::<Conversion>()
+                    .map(|conversion| conversion.to_string())
+                    .unwrap_or_else(|e| e),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Password(options) => match options.parse::<PasswordOptions>() {
+            Ok(options) => {
+                let password = random::password(&options, &mut OsRng);
+                let text = EscapedText::markup("🔑 ") + EscapedText::escape(&password).spoiler();
+                markdown::send_message(&bot, message.chat.id, text).await?;
+            }
+            Err(e) => {
+                bot.send_message(message.chat.id, e).await?;
+            }
+        },
+        Command::Uuid(version) => {
+            let uuid = match version.trim().to_lowercase().as_str() {
+                "" | "v4" | "4" => uuid::Uuid::new_v4(),
+                "v7" | "7" => uuid::Uuid::now_v7(),
+                _ => {
+                    bot.send_message(message.chat.id, "I make UUIDs v4 and v7, e.g. /uuid v7")
+                        .await?;
+                    return Ok(());
+                }
+            };
+            let text = EscapedText::escape(&uuid.to_string()).spoiler();
+            markdown::send_message(&bot, message.chat.id, text).await?;
+        }
+        Command::Calc(expression) => {
+            let text = match expression.trim() {
+                "" => "Tell me what to work out, e.g. /calc (2+3)*4^2, /calc 80 + 15% or /calc sqrt(2)"
+                    .to_string(),
+                expression => calc::evaluate(expression)
+                    .map(|value| format!("{} = {}", expression, units::number(value)))
+                    .unwrap_or_else(|e| e),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Time(city) => {
+            let city = match city.trim() {
+                "" => prefs.get().await.city,
+                city => Some(city.to_string()),
+            };
+            let city = match city {
+                Some(city) => city,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me the city, e.g. /time tokyo, or set your default one with /prefs city tokyo",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let place =
+                state
+                    .weather_api
+                    .locate(&city)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: OPEN_METEO,
+                        error,
+                    })?;
+            state.reporter.success(OPEN_METEO);
+            let timezone = place
+                .as_ref()
+                .and_then(|place| place.timezone.as_deref())
+                .and_then(|name| name.parse::<Tz>().ok());
+            let text = match (place, timezone) {
+                (Some(place), Some(timezone)) => {
+                    let reference =
+                        timezones::of_chat(state.storage.as_ref(), message.chat.id.0).await;
+                    let name = match &place.country {
+                        Some(country) => format!("{}, {}", place.name, country),
+                        None => place.name.clone(),
+                    };
+                    format!(
+                        "{}\n{}",
+                        name,
+                        timezones::compare(timezone, reference, Utc::now())
+                    )
+                }
+                (Some(place), None) => format!("I don't know the timezone of {}", place.name),
+                (None, _) => format!("I couldn't find the city '{}'", city),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Holidays(args) => {
+            let chat_id = message.chat.id.0;
+            let args = args.trim();
+            let (first, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            if first.eq_ignore_ascii_case("country") {
+                let text = match rest.trim() {
+                    "" => match holidays::country_of_chat(state.storage.as_ref(), chat_id).await {
+                        Some(country) => {
+                            format!("The holidays of this chat are the ones of {}", country)
+                        }
+                        None => "This chat has no country, set it with e.g. /holidays country es"
+                            .to_string(),
+                    },
+                    _ if !is_chat_admin(&bot, &message).await? => {
+                        "Only the admins of the chat can change its country".to_string()
+                    }
+                    code => match holidays::country_code(code) {
+                        Some(country) => {
+                            holidays::set_country(
+                                state.storage.as_ref(),
+                                chat_id,
+                                Some(country.clone()),
+                            )
+                            .await?;
+                            format!("The holidays of this chat are now the ones of {}", country)
+                        }
+                        None => format!("'{}' isn't a two letter country code, e.g. es", code),
+                    },
+                };
+                bot.send_message(message.chat.id, text).await?;
+                return Ok(());
+            }
+
+            let country = match args {
+                "" => holidays::country_of_chat(state.storage.as_ref(), chat_id).await,
+                code => holidays::country_code(code),
+            };
+            let country = match country {
+                Some(country) => country,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me the two letter code of the country, e.g. /holidays es, \
+                         or set the one of the chat with /holidays country es",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let upcoming = state
+                .holidays_api
+                .upcoming(&country)
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: NAGER_DATE,
+                    error,
+                })?;
+            state.reporter.success(NAGER_DATE);
+            let text = match upcoming {
+                Some(upcoming) => holidays::render(&country, &upcoming),
+                None => format!("I don't know the holidays of '{}'", country),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Shorten(url) => {
+            let shortener_api = match &state.shortener_api {
+                Some(shortener_api) => shortener_api,
+                None => {
+                    bot.send_message(message.chat.id, "Short links aren't set up in this bot")
+                        .await?;
+                    return Ok(());
+                }
+            };
+            let url = match Url::parse(url.trim()) {
+                Ok(url) if ["http", "https"].contains(&url.scheme()) && url.has_host() => url,
+                _ => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me a link, e.g. /shorten https://dog.ceo/dog-api",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let short_url =
+                shortener_api
+                    .shorten(&url)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: SHLINK,
+                        error,
+                    })?;
+            state.reporter.success(SHLINK);
+            bot.send_message(
+                message.chat.id,
+                format!(
+                    "{}\n\nSee how many times it was opened with /clicks {}",
+                    short_url.short_url, short_url.short_code
+                ),
+            )
+            .disable_web_page_preview(true)
+            .await?;
+        }
+        Command::Clicks(link) => {
+            let shortener_api = match &state.shortener_api {
+                Some(shortener_api) => shortener_api,
+                None => {
+                    bot.send_message(message.chat.id, "Short links aren't set up in this bot")
+                        .await?;
+                    return Ok(());
+                }
+            };
+            let link = link.trim();
+            // Either the whole short link or just its code
+            let code = match Url::parse(link) {
+                Ok(url) => url
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .unwrap_or_default()
+                    .to_string(),
+                Err(_) => link.to_string(),
+            };
+            if code.is_empty()
+                || !code
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                bot.send_message(
+                    message.chat.id,
+                    "Tell me the short link, e.g. /clicks abc12",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let short_url =
+                shortener_api
+                    .short_url(&code)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: SHLINK,
+                        error,
+                    })?;
+            state.reporter.success(SHLINK);
+            let text = match short_url {
+                Some(short_url) => format!(
+                    "{} was opened {} times, {} of them by people\n\nIt leads to {}",
+                    short_url.short_url,
+                    short_url.visits_summary.total,
+                    short_url.visits_summary.non_bots,
+                    short_url.long_url
+                ),
+                None => format!("I don't know the short link '{}'", code),
+            };
+            bot.send_message(message.chat.id, text)
+                .disable_web_page_preview(true)
+                .await?;
+        }
+        Command::Meme(args) => {
+            let (top, bottom) = meme::captions(&args);
+            if top.is_empty() && bottom.is_empty() {
+                bot.send_message(
+                    message.chat.id,
+                    "Tell me the captions, e.g. /meme such dog | very wow",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let upstream = |error| CommandError::Upstream {
+                upstream: DOG_CEO,
+                error,
+            };
+            // A photo replied to is the template, else a random dog
+            let photo = message
+                .reply_to_message()
+                .and_then(|replied| replied.photo())
+                .and_then(|sizes| sizes.last());
+            let image = match photo {
+                Some(photo) => match download(&bot, &photo.file_id).await {
+                    Ok(image) => image,
+                    Err(e) => {
+                        warn!("Could not download the photo -> {}", e);
+                        bot.send_message(
+                            message.chat.id,
+                            "I couldn't download that photo, please try again later",
+                        )
+                        .await?;
+                        return Ok(());
+                    }
+                },
+                None => {
+                    let dog = state.dog_api.random().await.map_err(upstream)?;
+                    let url = Url::parse(&dog.message).map_err(|e| CommandError::Malformed {
+                        upstream: DOG_CEO,
+                        reason: format!("'{}' is not an image URL: {}", dog.message, e),
+                    })?;
+                    let image = state
+                        .images
+                        .download(&url)
+                        .await
+                        .map_err(|e| e.blaming(DOG_CEO))?;
+                    state.reporter.success(DOG_CEO);
+                    image
+                }
+            };
+
+            match meme::render(&image, &top, &bottom) {
+                Ok(jpeg) => {
+                    bot.send_photo(
+                        message.chat.id,
+                        InputFile::memory(jpeg).file_name("meme.jpg"),
+                    )
+                    .await?
+                }
+                Err(e) => {
+                    info!("Could not draw on the image -> {}", e);
+                    bot.send_message(message.chat.id, "I couldn't draw on that image")
+                        .await?
+                }
+            };
+        }
+        Command::Color(color) => {
+            let color = match color.trim() {
+                "" => Err("Tell me the color, e.g. /color #ff6347 or /color tomato".to_string()),
+                color => color.parse::<Color>(),
+            };
+            let color = match color {
+                Ok(color) => color,
+                Err(e) => {
+                    bot.send_message(message.chat.id, e).await?;
+                    return Ok(());
+                }
+            };
+            match color.swatch() {
+                Ok(png) => {
+                    bot.send_photo(
+                        message.chat.id,
+                        InputFile::memory(png).file_name("color.png"),
+                    )
+                    .caption(color.describe())
+                    .await?
+                }
+                Err(e) => {
+                    error!("Could not draw a swatch -> {}", e);
+                    bot.send_message(message.chat.id, "I couldn't draw that color")
+                        .await?
+                }
+            };
+        }
+        Command::Qr(text) => {
+            let text = text.trim();
+            if !text.is_empty() {
+                match qr::encode(text) {
+                    Some(png) => {
+                        bot.send_photo(message.chat.id, InputFile::memory(png).file_name("qr.png"))
+                            .await?
+                    }
+                    None => {
+                        bot.send_message(message.chat.id, "That's too long for a QR code")
+                            .await?
+                    }
+                };
+                return Ok(());
+            }
+
+            let photo = message
+                .reply_to_message()
+                .and_then(|replied| replied.photo())
+                // The biggest size comes last
+                .and_then(|sizes| sizes.last());
+            let photo = match photo {
+                Some(photo) => photo,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        "Tell me the text, e.g. /qr https://dog.ceo, or reply with /qr to a photo of a QR code",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+            let text = match download(&bot, &photo.file_id).await {
+                Ok(image) => match qr::decode(&image) {
+                    Ok(texts) if !texts.is_empty() => fit(texts.join("\n\n"), MAX_MESSAGE),
+                    Ok(_) => "I couldn't find a QR code in that photo".to_string(),
+                    Err(e) => {
+                        info!("Could not read the photo -> {}", e);
+                        "I couldn't read that photo".to_string()
+                    }
+                },
+                Err(e) => {
+                    warn!("Could not download the photo -> {}", e);
+                    "I couldn't download that photo, please try again later".to_string()
+                }
+            };
+            bot.send_message(message.chat.id, text)
+                .reply_to_message_id(message.id)
+                .await?;
+        }
+        Command::Trivia(args) => {
+            let args = args.trim().to_lowercase();
+            let upstream = |error| CommandError::Upstream {
+                upstream: TRIVIA,
+                error,
+            };
+            match args.as_str() {
+                "scores" => {
+                    let text =
+                        trivia::scoreboard(state.storage.as_ref(), message.chat.id.0).await?;
+                    bot.send_message(message.chat.id, text).await?;
+                    return Ok(());
+                }
+                "categories" => {
+                    let categories = state.trivia_api.categories().await.map_err(upstream)?;
+                    state.reporter.success(TRIVIA);
+                    let names = categories
+                        .iter()
+                        .map(|category| category.name.as_str())
+                        .collect::<Vec<_>>();
+                    bot.send_message(
+                        message.chat.id,
+                        format!("Trivia categories:\n{}", names.join("\n")),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+                _ => {}
+            }
+            if state.trivia.is_running(message.chat.id) {
+                bot.send_message(
+                    message.chat.id,
+                    "There's a question waiting for answers already",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let mut words = args.split_whitespace().collect::<Vec<_>>();
+            let difficulty = match words.last() {
+                Some(word) if prefs::DIFFICULTIES.contains(word) => {
+                    let difficulty = word.to_string();
+                    words.pop();
+                    Some(difficulty)
+                }
+                _ => prefs.get().await.quiz_difficulty,
+            };
+            let category = match words.join(" ").as_str() {
+                "" => None,
+                name => {
+                    let categories = state.trivia_api.categories().await.map_err(upstream)?;
+                    match trivia::find_category(&categories, name) {
+                        Some(category) => Some(category.id),
+                        None => {
+                            bot.send_message(
+                                message.chat.id,
+                                format!(
+                                    "I don't know the category '{}', see /trivia categories",
+                                    name
+                                ),
+                            )
+                            .await?;
+                            return Ok(());
+                        }
+                    }
+                }
+            };
+
+            let question = state
+                .trivia_api
+                .question(category, difficulty.as_deref())
+                .await
+                .map_err(upstream)?;
+            state.reporter.success(TRIVIA);
+            match question {
+                Some(question) => trivia::start(&state, &bot, message.chat.id, question).await?,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        "I have no questions like that right now, try another category or difficulty",
+                    )
+                    .await?;
+                }
+            }
+        }
+        Command::Pokemon(name) => {
+            // PokeAPI names are lowercase with dashes, e.g. mr-mime
+            let name = name.trim().to_lowercase().replace(' ', "-");
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                bot.send_message(
+                    message.chat.id,
+                    "Tell me the Pokémon, e.g. /pokemon growlithe or /pokemon 58",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let pokemon =
+                state
+                    .pokemon_api
+                    .pokemon(&name)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: POKEAPI,
+                        error,
+                    })?;
+            state.reporter.success(POKEAPI);
+            let pokemon = match pokemon {
+                Some(pokemon) => pokemon,
+                None => {
+                    bot.send_message(
+                        message.chat.id,
+                        format!("I don't know the Pokémon '{}'", name),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let sprite = pokemon
+                .sprite
+                .as_deref()
+                .and_then(|sprite| Url::parse(sprite).ok());
+            match sprite {
+                Some(sprite) => {
+                    send_photo(
+                        &bot,
+                        &state,
+                        message.chat.id,
+                        POKEAPI,
+                        sprite,
+                        Some(pokemon.render()),
+                        None,
+                    )
+                    .await?
+                }
+                None => bot.send_message(message.chat.id, pokemon.render()).await?,
+            };
+        }
+        Command::Urban(term) => {
+            if nsfw::is_filtered(state.storage.as_ref(), message.chat.id.0).await {
+                bot.send_message(
+                    message.chat.id,
+                    "Urban Dictionary is off in this chat, see /nsfwfilter",
+                )
+                .await?;
+                return Ok(());
+            }
+            let term = term.trim().to_lowercase();
+            if term.is_empty() {
+                bot.send_message(message.chat.id, "Tell me the term, e.g. /urban doggo")
+                    .await?;
+                return Ok(());
+            }
+
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let (text, keyboard) = urban_page(&state, user.id, &term, 0).await?;
+            let request = bot.send_message(message.chat.id, text);
+            match keyboard {
+                Some(keyboard) => request.reply_markup(keyboard).await?,
+                None => request.await?,
+            };
+        }
+        Command::NsfwFilter(value) => {
+            let chat_id = message.chat.id.0;
+            let text = match value.trim().to_lowercase().as_str() {
+                "" => {
+                    if nsfw::is_filtered(state.storage.as_ref(), chat_id).await {
+                        "The NSFW filter is on, turn it off with /nsfwfilter off"
+                    } else {
+                        "The NSFW filter is off, turn it on with /nsfwfilter on"
+                    }
+                }
+                "on" | "off" if !is_chat_admin(&bot, &message).await? => {
+                    "Only the admins of the chat can change its NSFW filter"
+                }
+                "on" => {
+                    nsfw::set_filter(state.storage.as_ref(), chat_id, true).await?;
+                    "The NSFW filter is on"
+                }
+                "off" => {
+                    nsfw::set_filter(state.storage.as_ref(), chat_id, false).await?;
+                    "The NSFW filter is off"
+                }
+                _ => "Use /nsfwfilter on or /nsfwfilter off",
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::AltText(value) => {
+            let chat_id = message.chat.id.0;
+            let text = match value.trim().to_lowercase().as_str() {
+                "" => {
+                    if alt_text::is_enabled(state.storage.as_ref(), chat_id).await {
+                        "The dogs are captioned with their breed, turn it off with /alttext off"
+                    } else {
+                        "The dogs aren't captioned, turn it on with /alttext on"
+                    }
+                }
+                "on" | "off" if !is_chat_admin(&bot, &message).await? => {
+                    "Only the admins of the chat can change its captions"
+                }
+                "on" => {
+                    alt_text::set(state.storage.as_ref(), chat_id, true).await?;
+                    "The dogs are captioned with their breed, e.g. \"Photo of a golden retriever\""
+                }
+                "off" => {
+                    alt_text::set(state.storage.as_ref(), chat_id, false).await?;
+                    "The dogs aren't captioned anymore"
+                }
+                _ => "Use /alttext on or /alttext off",
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::News(args) => {
+            let chat_id = message.chat.id.0;
+            let args = args.trim();
+            let (first, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+            if first.eq_ignore_ascii_case("source") {
+                let source = rest.trim();
+                let text = if source.is_empty() {
+                    match news::source_of_chat(state.storage.as_ref(), chat_id).await {
+                        Some(source) => format!("The news of this chat come from {}", source),
+                        None => "The news of this chat come from the default feed".to_string(),
+                    }
+                } else if !is_chat_admin(&bot, &message).await? {
+                    "Only the admins of the chat can change its news source".to_string()
+                } else if source.eq_ignore_ascii_case("default") {
+                    news::set_source(state.storage.as_ref(), chat_id, None).await?;
+                    "The news of this chat come from the default feed again".to_string()
+                } else if !source.starts_with("https://") && !source.starts_with("http://") {
+                    format!("'{}' isn't a link to an RSS feed", source)
+                } else {
+                    // Only feeds that can be read are kept
+                    match state.news_api.headlines(Some(source)).await {
+                        Ok(_) => {
+                            news::set_source(
+                                state.storage.as_ref(),
+                                chat_id,
+                                Some(source.to_string()),
+                            )
+                            .await?;
+                            format!("The news of this chat now come from {}", source)
+                        }
+                        Err(NewsError::Private(_)) => {
+                            format!("{} is not on the internet, I can't read it", source)
+                        }
+                        Err(e) => {
+                            info!("Rejected the news source {} -> {}", source, e);
+                            format!("I couldn't read an RSS feed at {}", source)
+                        }
+                    }
+                };
+                bot.send_message(message.chat.id, text)
+                    .disable_web_page_preview(true)
+                    .await?;
+                return Ok(());
+            }
+
+            let topic = Some(args).filter(|topic| !topic.is_empty());
+            let source = news::source_of_chat(state.storage.as_ref(), chat_id).await;
+            let text = match state.news_api.headlines(source.as_deref()).await {
+                Ok(headlines) => {
+                    if source.is_none() {
+                        state.reporter.success(NEWS);
+                    }
+                    news::render(&headlines, topic)
+                }
+                // The chat's own feed is its business, only the default one is an upstream
+                Err(e) if source.is_some() => {
+                    warn!("Could not read the news source of the chat -> {}", e);
+                    "I couldn't read the news source of this chat, change it with /news source"
+                        .to_string()
+                }
+                Err(NewsError::Http(error)) => {
+                    return Err(CommandError::Upstream {
+                        upstream: NEWS,
+                        error,
+                    })
+                }
+                Err(e @ (NewsError::Feed(_) | NewsError::Private(_))) => {
+                    return Err(CommandError::Malformed {
+                        upstream: NEWS,
+                        reason: e.to_string(),
+                    })
+                }
+            };
+            bot.send_message(message.chat.id, text)
+                .disable_web_page_preview(true)
+                .await?;
+        }
+        Command::PopularBreeds => {
+            let (here, everywhere) = tokio::try_join!(
+                state
+                    .storage
+                    .popular_breeds(Some(message.chat.id.0), None, 10),
+                state.storage.popular_breeds(None, None, 10),
+            )?;
+
+            let text = format!(
+                "Most requested breeds in this chat:\n{}\nEverywhere:\n{}",
+                render::ranking(&here),
+                render::ranking(&everywhere)
+            );
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Uptime => {
+            bot.send_message(message.chat.id, Status::of(&state).await.text())
+                .await?;
+        }
+        Command::Version => {
+            bot.send_message(message.chat.id, BuildInfo::current().text())
+                .await?;
+        }
+        Command::Stats => {
+            let now = Utc::now();
+            let (daily, weekly, stats, inactive) = tokio::try_join!(
+                state.storage.active_users(now - chrono::Duration::days(1)),
+                state.storage.active_users(now - chrono::Duration::days(7)),
+                state.storage.command_stats(now - chrono::Duration::days(7)),
+                state.storage.inactive_chats(),
+            )?;
+
+            let mut text = format!(
+                "Active users: {} today, {} this week\n\nCommands this week:\n",
+                daily, weekly
+            );
+            for stats in stats {
+                write!(
+                    text,
+                    "/{} {}, {:.0}ms on average",
+                    stats.command, stats.count, stats.avg_latency_ms
+                )
+                .ok();
+                if stats.errors > 0 {
+                    write!(text, ", {} failed", stats.errors).ok();
+                }
+                text.push('\n');
+            }
+            if !inactive.is_empty() {
+                let reasons = subscriptions::count_by_reason(&inactive)
+                    .iter()
+                    .map(|(reason, count)| format!("{} {}", count, reason.replace('_', " ")))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(text, "\nInactive chats: {} ({})", inactive.len(), reasons).ok();
+            }
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Prefs(args) => {
+            let args = args.trim();
+            let text = if args.is_empty() {
+                prefs::describe(&prefs.get().await)
+            } else {
+                let (key, value) = args.split_once(' ').unwrap_or((args, ""));
+                let mut preferences = prefs.get().await;
+                match prefs::set(&mut preferences, key, value) {
+                    Ok(()) => {
+                        let text = prefs::describe(&preferences);
+                        prefs.save(preferences).await?;
+                        text
+                    }
+                    Err(e) => e,
+                }
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Subscribe(args) => {
+            let mut args = args.split_whitespace();
+            let kind = args.next().unwrap_or_default().to_lowercase();
+            let at = args.next().map(subscriptions::parse_time);
+            let timezone = timezones::of_chat(state.storage.as_ref(), message.chat.id.0).await;
+
+            let text = if !subscriptions::KINDS.contains(&kind.as_str()) {
+                format!(
+                    "Subscribe to one of: {}, e.g. /subscribe dailydog 09:00",
+                    subscriptions::KINDS.join(", ")
+                )
+            } else if let Some(Some(at)) = at {
+                subscriptions::subscribe(&state, message.chat.id, &kind, at).await?;
+                format!(
+                    "Subscribed to {}, {}",
+                    kind,
+                    subscriptions::schedule(&kind, at, timezone)
+                )
+            } else {
+                format!(
+                    "Tell me when, in HH:MM {}, e.g. /subscribe dailydog 09:00",
+                    timezone
+                )
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Unsubscribe(kind) => {
+            let kind = kind.trim().to_lowercase();
+            let text = if subscriptions::unsubscribe(&state, message.chat.id, &kind).await? {
+                format!("Unsubscribed from {}", kind)
+            } else {
+                format!("This chat isn't subscribed to '{}'", kind)
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Remind(args) => {
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let timezone = timezones::of_chat(state.storage.as_ref(), message.chat.id.0).await;
+            let text = match reminders::parse(&args, Utc::now(), timezone) {
+                Some((at, text)) => {
+                    reminders::add(&state, user.id.0 as i64, message.chat.id, at, text).await?;
+                    format!(
+                        "I'll remind you on {}",
+                        at.with_timezone(&timezone).format("%Y-%m-%d %H:%M %Z")
+                    )
+                }
+                None => "Tell me when and what, e.g. /remind in 20m walk the dog, \
+                     /remind tomorrow 9:00 call the vet or /remind 2025-01-01 12:00 party"
+                    .to_string(),
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Reminders => {
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let pending = state.storage.reminders(Some(user.id.0 as i64)).await?;
+            if pending.is_empty() {
+                bot.send_message(
+                    message.chat.id,
+                    "You have no reminders, add one with /remind",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let timezone = timezones::of_chat(state.storage.as_ref(), message.chat.id.0).await;
+            let mut text = String::from("Your reminders:\n");
+            let mut buttons = Vec::new();
+            for (position, reminder) in pending.iter().enumerate() {
+                writeln!(
+                    text,
+                    "{}. {} {}",
+                    position + 1,
+                    reminder
+                        .at
+                        .with_timezone(&timezone)
+                        .format("%Y-%m-%d %H:%M %Z"),
+                    reminder.text
+                )
+                .ok();
+                buttons.push([InlineKeyboardButton::callback(
+                    format!("Cancel {}", position + 1),
+                    format!("{}:{}:{}", reminders::CANCEL, user.id, reminder.id),
+                )]);
+            }
+            bot.send_message(message.chat.id, text)
+                .reply_markup(InlineKeyboardMarkup::new(buttons))
+                .await?;
+        }
+        Command::SetTimezone(name) => {
+            let name = name.trim();
+            let text = if name.is_empty() {
+                let timezone = timezones::of_chat(state.storage.as_ref(), message.chat.id.0).await;
+                format!(
+                    "The timezone of this chat is {}, change it with e.g. /settimezone Europe/Madrid",
+                    timezone
+                )
+            } else if !is_chat_admin(&bot, &message).await? {
+                "Only the admins of the chat can change its timezone".to_string()
+            } else {
+                match name.parse::<Tz>() {
+                    Ok(timezone) => {
+                        timezones::set_for_chat(
+                            state.storage.as_ref(),
+                            message.chat.id.0,
+                            timezone,
+                        )
+                        .await?;
+                        subscriptions::reschedule_chat(&state, message.chat.id).await?;
+                        format!("The timezone of this chat is now {}", timezone)
+                    }
+                    Err(_) => format!(
+                        "Unknown timezone '{}', use a name like Europe/Madrid or America/New_York",
+                        name
+                    ),
+                }
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Language(code) => {
+            let chat_id = message.chat.id.0;
+            let code = code.trim().to_lowercase();
+            let text = if code.is_empty() {
+                let (language, source) = language(&state, &message, &prefs).await;
+                format!(
+                    "The language of this chat is {} ({}), change it with e.g. /language es \
+                     or follow the one of each user with /language auto",
+                    language, source
+                )
+            } else if !is_chat_admin(&bot, &message).await? {
+                "Only the admins of the chat can change its language".to_string()
+            } else if code == "auto" {
+                i18n::set_for_chat(state.storage.as_ref(), chat_id, None).await?;
+                "This chat now follows the language of each user".to_string()
+            } else if i18n::is_code(&code) {
+                i18n::set_for_chat(state.storage.as_ref(), chat_id, Some(code.clone())).await?;
+                format!("The language of this chat is now {}", code)
+            } else {
+                format!("'{}' is not a language code, e.g. es", code)
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::QuietHours(range) => {
+            let chat_id = message.chat.id.0;
+            let range = range.trim();
+            let text = if range.is_empty() {
+                match quiet::of_chat(state.storage.as_ref(), chat_id).await {
+                    Some(quiet_hours) => format!("Quiet hours are {}", quiet_hours),
+                    None => {
+                        "No quiet hours, set them with e.g. /quiethours 23:00-08:00".to_string()
+                    }
+                }
+            } else if !is_chat_admin(&bot, &message).await? {
+                "Only the admins of the chat can change its quiet hours".to_string()
+            } else if range.eq_ignore_ascii_case("off") {
+                quiet::set_for_chat(state.storage.as_ref(), chat_id, None).await?;
+                "Quiet hours removed".to_string()
+            } else {
+                match range.parse::<QuietHours>() {
+                    Ok(quiet_hours) => {
+                        quiet::set_for_chat(state.storage.as_ref(), chat_id, Some(quiet_hours))
+                            .await?;
+                        let timezone = timezones::of_chat(state.storage.as_ref(), chat_id).await;
+                        format!(
+                            "Daily dogs, digests and alerts will wait until {} ends ({})",
+                            quiet_hours, timezone
+                        )
+                    }
+                    Err(e) => e,
+                }
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Settings(args) => {
+            let args = args.trim();
+            let chat_id = message.chat.id.0;
+            let config = state.commands.read().unwrap().clone();
+            let text = if args.is_empty() {
+                let settings = state.storage.chat_settings(chat_id).await?;
+                settings::describe(settings.as_ref(), &config)
+            } else if !is_chat_admin(&bot, &message).await? {
+                "Only the admins of the chat can change its settings".to_string()
+            } else {
+                let (key, value) = args.split_once(' ').unwrap_or((args, ""));
+                let mut settings =
+                    state
+                        .storage
+                        .chat_settings(chat_id)
+                        .await?
+                        .unwrap_or(ChatSettings {
+                            chat_id,
+                            ..Default::default()
+                        });
+                match settings::set(&mut settings, key, value) {
+                    Ok(()) => {
+                        state.storage.save_chat_settings(&settings).await?;
+                        settings::describe(Some(&settings), &config)
+                    }
+                    Err(e) => e,
+                }
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Privacy => {
+            bot.send_message(message.chat.id, privacy::disclosure(&schema::tables()))
+                .await?;
+        }
+        Command::MyData => {
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let data = UserData::collect(state.storage.as_ref(), user.id.0 as i64).await?;
+            // Sent privately, it shouldn't end up in a group
+            let document = InputFile::memory(data.to_json().into_bytes()).file_name("mydata.json");
+            bot.send_document(user.id, document).await?;
+        }
+        Command::ForgetMe => {
+            let user = message.from().ok_or(CommandError::NoSender)?;
+            let keyboard = InlineKeyboardMarkup::new([[
+                InlineKeyboardButton::callback(
+                    "Yes, delete everything",
+                    format!("{}:{}", privacy::FORGET_ME, user.id),
+                ),
+                InlineKeyboardButton::callback(
+                    "Cancel",
+                    format!("{}:{}", privacy::FORGET_ME_CANCEL, user.id),
+                ),
+            ]]);
+            bot.send_message(
+                message.chat.id,
+                "This deletes your preferences, favourites, alerts and the subscriptions of our private chat. \
+                 It can't be undone, are you sure?",
+            )
+            .reply_markup(keyboard)
+            .await?;
+        }
+        Command::Jobs => {
+            if !state.reporter.is_admin_chat(message.chat.id) {
+                bot.send_message(message.chat.id, "Only the admin chat can do that")
+                    .await
+                    .ok();
+                return Ok(());
+            }
+
+            let jobs = state.scheduler.jobs();
+            let mut text = format!("{} scheduled jobs\n", jobs.len());
+            for job in jobs {
+                write!(
+                    text,
+                    "\n{} ({})\nnext run {}",
+                    job.name,
+                    job.schedule,
+                    job.next_run.format("%Y-%m-%d %H:%M UTC")
+                )
+                .ok();
+                if let Some(last) = job.last {
+                    write!(
+                        text,
+                        ", last run {} {}, {} runs, {} failed",
+                        last.last_run.format("%Y-%m-%d %H:%M UTC"),
+                        last.last_error.as_deref().unwrap_or("ok"),
+                        last.runs,
+                        last.failures
+                    )
+                    .ok();
+                }
+                text.push('\n');
+            }
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::ReloadConfig => {
+            if !state.reporter.is_admin_chat(message.chat.id) {
+                bot.send_message(message.chat.id, "Only the admin chat can do that")
+                    .await
+                    .ok();
+                return Ok(());
+            }
+
+            let text = match Config::load() {
+                Ok(config) => {
+                    state.reconfigure(&config);
+                    info!("Configuration reloaded");
+                    "Configuration reloaded".to_string()
+                }
+                Err(e) => {
+                    error!("Could not reload the configuration -> {}", e);
+                    format!("Could not reload the configuration: {}", e)
+                }
+            };
+            bot.send_message(message.chat.id, text).await?;
+        }
+        Command::Breed(ref breed) | Command::BreedHd(ref breed) => {
+            let original = matches!(command, Command::BreedHd(_));
+            let breed = match (breed.trim(), prefs.get().await.favourite_breed) {
+                ("", Some(favourite)) => favourite,
+                _ => breed.clone(),
+            };
+            info!("Fetching a random dog of breed {}...", breed);
+
+            let query = match BreedQuery::from_str(&breed) {
+                Ok(query) => query,
+                Err(e) => {
+                    bot.send_message(message.chat.id, e.to_string()).await.ok();
+                    return Ok(());
+                }
+            };
+
+            let dog = state
+                .dog_api
+                .random_for_breed(&query)
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+            state.reporter.success(DOG_CEO);
+            if dog.status == "success" {
+                if original {
+                    send_original(&bot, &state, message.chat.id, &dog.message).await?;
+                } else {
+                    let keyboard =
+                        breed_keyboard(&state, message.from(), &query, &dog.message).await;
+                    send_dog(&bot, &state, message.chat.id, &dog.message, keyboard).await?;
+                }
+                if let Err(e) = state
+                    .storage
+                    .record_breed(message.chat.id.0, &query.to_string())
+                    .await
+                {
+                    warn!("Could not record the breed -> {}", e);
+                }
+            } else {
+                error!("Could not find a dog");
+                bot.send_message(message.chat.id, format!("Breed '{}' doesn't exist", breed))
+                    .await
+                    .ok();
+            }
+        }
+    };
+
+    Ok(())
+}
+
+/// Handle a press on one of the inline keyboard buttons sent with the answers.
+pub async fn answer_callback(
+    bot: AutoSend<Bot>,
+    query: CallbackQuery,
+    state: Arc<AppState>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let data = query.data.as_deref().unwrap_or_default();
+    let mut parts = data.splitn(3, ':');
+    let action = parts.next().unwrap_or_default();
+    let user_id = parts.next().unwrap_or_default();
+    let argument = parts.next().unwrap_or_default();
+    let span = info_span!("callback", action, user_id = query.from.id.0);
+
+    // Anybody in the chat can answer a trivia question, the round takes the place of the user
+    if action == trivia::ANSWER {
+        let text = match (&query.message, user_id.parse(), argument.parse()) {
+            (Some(message), Ok(round_id), Ok(index)) => {
+                trivia::guess(&state, message.chat.id, round_id, &query.from, index)
+            }
+            _ => "That question is over".to_string(),
+        };
+        bot.answer_callback_query(query.id).text(text).await?;
+        return Ok(());
+    }
+
+    // Buttons are bound to the user who asked, nobody else can press them for them
+    if user_id != query.from.id.to_string() {
+        bot.answer_callback_query(query.id)
+            .text("This button isn't for you")
+            .await?;
+        return Ok(());
+    }
+
+    let text = match action {
+        privacy::FORGET_ME => {
+            if let Err(e) = privacy::forget(&state, query.from.id.0 as i64).await {
+                span.in_scope(|| error!("Could not forget the user -> {}", e));
+                bot.answer_callback_query(query.id)
+                    .text("Sorry, something went wrong, please try again later.")
+                    .await?;
+                return Err(e.into());
+            }
+            "Done, I forgot everything about you".to_string()
+        }
+        privacy::FORGET_ME_CANCEL => "Ok, nothing was deleted".to_string(),
+        reminders::CANCEL => {
+            let id = argument.parse().unwrap_or_default();
+            if reminders::cancel(&state, query.from.id.0 as i64, id).await? {
+                "Reminder cancelled".to_string()
+            } else {
+                "That reminder was already sent or cancelled".to_string()
+            }
+        }
+        URBAN_PAGE => {
+            let (index, term) = argument.split_once(':').unwrap_or_default();
+            let index = index.parse().unwrap_or_default();
+            let (text, keyboard) = urban_page(&state, query.from.id, term, index).await?;
+            bot.answer_callback_query(query.id).await?;
+            if let Some(message) = query.message {
+                let request = bot.edit_message_text(message.chat.id, message.id, text);
+                match keyboard {
+                    Some(keyboard) => request.reply_markup(keyboard).await?,
+                    None => request.await?,
+                };
+            }
+            return Ok(());
+        }
+        BREED_PAGE => {
+            let (index, breed) = argument.split_once(':').unwrap_or_default();
+            let index = index.parse().unwrap_or_default();
+            bot.answer_callback_query(query.id).await?;
+            if let (Some(message), Ok(breed)) = (query.message, BreedQuery::from_str(breed)) {
+                breed_page(&bot, &state, &message, query.from.id, &breed, index).await?;
+            }
+            return Ok(());
+        }
+        alerts::PAUSE | alerts::DELETE => {
+            let user_id = query.from.id.0 as i64;
+            let id = argument.parse().unwrap_or_default();
+            let text = match alerts::of_user(&state, user_id, id).await? {
+                Some(alert) if action == alerts::DELETE => {
+                    state.storage.remove_alert(alert.id).await?;
+                    "Alert deleted"
+                }
+                Some(mut alert) => {
+                    alert.paused = !alert.paused;
+                    state.storage.update_alert(&alert).await?;
+                    if alert.paused {
+                        "Alert paused"
+                    } else {
+                        "Alert resumed"
+                    }
+                }
+                None => "That alert was already notified or deleted",
+            };
+            bot.answer_callback_query(query.id).text(text).await?;
+            if let Some(message) = query.message {
+                let alerts = state.storage.alerts(Some(user_id)).await?;
+                let (text, keyboard) = alerts::list(&alerts, query.from.id);
+                let request = bot.edit_message_text(message.chat.id, message.id, text);
+                match keyboard {
+                    Some(keyboard) => request.reply_markup(keyboard).await?,
+                    None => request.await?,
+                };
+            }
+            return Ok(());
+        }
+        alerts::EDIT => {
+            let user_id = query.from.id.0 as i64;
+            let id = argument.parse().unwrap_or_default();
+            let (alert, message) =
+                match (alerts::of_user(&state, user_id, id).await?, query.message) {
+                    (Some(alert), Some(message)) => (alert, message),
+                    _ => {
+                        bot.answer_callback_query(query.id)
+                            .text("That alert was already notified or deleted")
+                            .await?;
+                        return Ok(());
+                    }
+                };
+            state.alert_edits.start(message.chat.id, user_id, alert.id);
+            bot.answer_callback_query(query.id).await?;
+            let text = match alert.move_percent {
+                Some(percent) => format!(
+                    "Send me the percent {} has to move, it's {}% now",
+                    alert.symbol.to_uppercase(),
+                    percent
+                ),
+                None => format!(
+                    "Send me the new price for {}, it's ${} now",
+                    alert.symbol.to_uppercase(),
+                    alert.target
+                ),
+            };
+            // Replying lets the bot see the answer in groups too
+            bot.send_message(message.chat.id, text)
+                .reply_markup(ForceReply::new().selective(true))
+                .await?;
+            return Ok(());
+        }
+        PUNCHLINE => {
+            let joke = match argument.parse() {
+                Ok(id) => state.joke_api.by_id(id).await?,
+                Err(_) => None,
+            };
+            match joke {
+                Some(Joke {
+                    text,
+                    punchline: Some(punchline),
+                    ..
+                }) => format!("{}\n\n{}", text, punchline),
+                _ => "Sorry, I forgot how that joke ended".to_string(),
+            }
+        }
+        _ => {
+            span.in_scope(|| warn!("Unknown callback"));
+            bot.answer_callback_query(query.id).await?;
+            return Ok(());
+        }
+    };
+
+    bot.answer_callback_query(query.id).await?;
+    if let Some(message) = query.message {
+        bot.edit_message_text(message.chat.id, message.id, text)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Answer the messages that aren't commands, the new thresholds of the alerts being edited.
+pub async fn answer_message(
+    bot: AutoSend<Bot>,
+    message: Message,
+    state: Arc<AppState>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (user, text) = match (message.from(), message.text()) {
+        (Some(user), Some(text)) => (user, text),
+        _ => return Ok(()),
+    };
+    let user_id = user.id.0 as i64;
+    let id = match state.alert_edits.take(message.chat.id, user_id) {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let alert = match alerts::of_user(&state, user_id, id).await? {
+        Some(alert) => alert,
+        None => {
+            bot.send_message(
+                message.chat.id,
+                "That alert was already notified or deleted",
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let text = match alerts::edit(&state, &alert, text).await? {
+        Some(alert) => format!("Done, {}", alerts::describe(&alert)),
+        None => {
+            // Wait for another try
+            state.alert_edits.start(message.chat.id, user_id, alert.id);
+            if alert.move_percent.is_some() {
+                "That isn't a percent, send me e.g. 5%".to_string()
+            } else {
+                "That isn't a price, send me e.g. 70000".to_string()
+            }
+        }
+    };
+    bot.send_message(message.chat.id, text).await?;
+    Ok(())
+}
+
+/// Contents of a file sent to the bot.
+async fn download(
+    bot: &AutoSend<Bot>,
+    file_id: &str,
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let file = bot.get_file(file_id).await?;
+    let mut contents = Vec::new();
+    bot.inner()
+        .download_file(&file.file_path, &mut contents)
+        .await?;
+    Ok(contents)
+}
+
+/// Definition of the term at the index, with the buttons to the previous and next ones.
+async fn urban_page(
+    state: &AppState,
+    user_id: UserId,
+    term: &str,
+    index: usize,
+) -> Result<(String, Option<InlineKeyboardMarkup>), CommandError> {
+    let definitions =
+        state
+            .urban_api
+            .define(term)
+            .await
+            .map_err(|error| CommandError::Upstream {
+                upstream: URBAN,
+                error,
+            })?;
+    state.reporter.success(URBAN);
+    let definition = match definitions.get(index) {
+        Some(definition) => definition,
+        None => return Ok((format!("No definitions of '{}'", term), None)),
+    };
+    let text = format!(
+        "{}\n\n{}/{}",
+        definition.render(),
+        index + 1,
+        definitions.len()
+    );
+
+    let mut pages = Vec::new();
+    if index > 0 {
+        pages.push(("◀ Previous", index - 1));
+    }
+    if index + 1 < definitions.len() {
+        pages.push(("Next ▶", index + 1));
+    }
+    let buttons = pages
+        .into_iter()
+        .map(|(label, index)| {
+            (
+                label,
+                format!("{}:{}:{}:{}", URBAN_PAGE, user_id, index, term),
+            )
+        })
+        .collect::<Vec<_>>();
+    // Telegram refuses callback data over 64 bytes, the long terms can't be paged through
+    let keyboard =
+        (!buttons.is_empty() && buttons.iter().all(|(_, data)| data.len() <= 64)).then(|| {
+            InlineKeyboardMarkup::new([buttons
+                .into_iter()
+                .map(|(label, data)| InlineKeyboardButton::callback(label, data))])
+        });
+    Ok((fit(text, MAX_MESSAGE), keyboard))
+}
+
+/// Buttons browsing the images of the breed from the one just sent, `None` when there is nothing to browse.
+async fn breed_keyboard(
+    state: &AppState,
+    user: Option<&User>,
+    breed: &BreedQuery,
+    image: &str,
+) -> Option<InlineKeyboardMarkup> {
+    let user = user?;
+    let images = match breed_images(state, breed).await {
+        Ok(images) => images,
+        Err(e) => {
+            warn!("Could not list the images of the breed -> {}", e);
+            return None;
+        }
+    };
+    let index = images
+        .iter()
+        .position(|known| known == image)
+        .unwrap_or_default();
+    breed_buttons(user.id, breed, index, images.len())
+}
+
+/// Previous and next buttons around the image at `index`, wrapping around the ends.
+fn breed_buttons(
+    user_id: UserId,
+    breed: &BreedQuery,
+    index: usize,
+    len: usize,
+) -> Option<InlineKeyboardMarkup> {
+    let buttons = render::page_buttons(user_id, breed, index, len)?;
+    Some(InlineKeyboardMarkup::new([buttons.into_iter().map(
+        |(label, data)| InlineKeyboardButton::callback(label, data),
+    )]))
+}
+
+async fn breed_images(state: &AppState, breed: &BreedQuery) -> Result<Vec<String>, CommandError> {
+    let images = state
+        .dog_api
+        .images_for_breed(breed)
+        .await
+        .map_err(|error| CommandError::Upstream {
+            upstream: DOG_CEO,
+            error,
+        })?;
+    state.reporter.success(DOG_CEO);
+    if images.status == "success" {
+        Ok(images.message)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// Replace the image of the message with the one of the breed at `index`.
+async fn breed_page(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    message: &Message,
+    user_id: UserId,
+    breed: &BreedQuery,
+    index: usize,
+) -> Result<(), CommandError> {
+    let images = breed_images(state, breed).await?;
+    let image = match images.get(index) {
+        Some(image) => image,
+        // The list changed since the buttons were sent
+        None => return Ok(()),
+    };
+    let url = Url::from_str(image).map_err(|e| CommandError::Malformed {
+        upstream: DOG_CEO,
+        reason: format!("'{}' is not an image URL: {}", image, e),
+    })?;
+    let mut caption = format!("{}/{}", index + 1, images.len());
+    if alt_text::is_enabled(state.storage.as_ref(), message.chat.id.0).await {
+        caption = format!("{} ({})", alt_text::describe(image), caption);
+    }
+    let keyboard = breed_buttons(user_id, breed, index, images.len());
+    let edit = |photo: InputFile| {
+        let media = InputMedia::Photo(InputMediaPhoto::new(photo).caption(caption.clone()));
+        let request = bot.edit_message_media(message.chat.id, message.id, media);
+        match keyboard.clone() {
+            Some(keyboard) => request.reply_markup(keyboard),
+            None => request,
+        }
+    };
+
+    if let Some(file_id) = state.file_ids.get(image).await {
+        edit(InputFile::file_id(file_id)).await?;
+        return Ok(());
+    }
+    if !state.upload_images {
+        match edit(InputFile::url(url.clone())).await {
+            Err(e) if images::could_not_fetch(&e) => {
+                warn!("Telegram could not fetch {}, uploading it -> {}", url, e)
+            }
+            edited => {
+                remember_photo(state, image, &edited?).await;
+                return Ok(());
+            }
+        }
+    }
+
+    let file_name = images::file_name(&url, "dog.jpg");
+    let downloaded = state
+        .images
+        .download(&url)
+        .await
+        .map_err(|e| e.blaming(DOG_CEO))?;
+    let edited = edit(InputFile::memory(downloaded).file_name(file_name)).await?;
+    remember_photo(state, image, &edited).await;
+    Ok(())
+}
+
+/// Keep the file id of the photo, so the image isn't fetched or uploaded again.
+async fn remember_photo(state: &AppState, image: &str, sent: &Message) {
+    // The largest size comes last
+    if let Some(size) = sent.photo().and_then(|sizes| sizes.last()) {
+        state.file_ids.set(image, &size.file_id).await;
+    }
+}
+
+/// Language of the answers and where it comes from, see [`i18n::pick`].
+async fn language(
+    state: &AppState,
+    message: &Message,
+    prefs: &UserPrefs,
+) -> (String, i18n::Source) {
+    i18n::pick(
+        i18n::of_chat(state.storage.as_ref(), message.chat.id.0).await,
+        prefs.get().await.language,
+        message
+            .from()
+            .and_then(|user| user.language_code.as_deref()),
+    )
+}
+
+/// Time between two updates of a cooldown notice.
+const COOLDOWN_TICK: Duration = Duration::from_secs(5);
+
+/// Tell the user how long their cooldown lasts, counting down in the same message until it ends.
+async fn cooldown_notice(bot: AutoSend<Bot>, chat_id: ChatId, remaining: Duration) {
+    let text = |remaining: Duration| {
+        // Rounded up, it never says 0s while it isn't over
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        format!("⏳ Slow down, you can send commands again in {}s", secs)
+    };
+    let notice = match bot.send_message(chat_id, text(remaining)).await {
+        Ok(notice) => notice,
+        Err(e) => {
+            warn!("Could not send the cooldown notice -> {}", e);
+            return;
+        }
+    };
+
+    let mut remaining = remaining;
+    while !remaining.is_zero() {
+        let tick = remaining.min(COOLDOWN_TICK);
+        tokio::time::sleep(tick).await;
+        remaining -= tick;
+        let text = if remaining.is_zero() {
+            "✅ You can send commands again".to_string()
+        } else {
+            text(remaining)
+        };
+        if let Err(e) = bot.edit_message_text(chat_id, notice.id, text).await {
+            warn!("Could not update the cooldown notice -> {}", e);
+            return;
+        }
+    }
+}
+
+/// The sender can change the settings of the chat, anyone can in a private chat.
+async fn is_chat_admin(bot: &AutoSend<Bot>, message: &Message) -> Result<bool, CommandError> {
+    if message.chat.is_private() {
+        return Ok(true);
+    }
+    let user = message.from().ok_or(CommandError::NoSender)?;
+    let member = bot.get_chat_member(message.chat.id, user.id).await?;
+    Ok(member.is_privileged())
+}
+
+/// Remember who used the bot, the command is answered even if it fails.
+async fn remember(state: &AppState, message: &Message) {
+    if let Some(user) = message.from() {
+        if let Err(e) = state
+            .storage
+            .see_user(user.id.0 as i64, user.username.as_deref())
+            .await
+        {
+            warn!("Could not remember the user -> {}", e);
+        }
+    }
+}
+
+fn chat_type(chat: &Chat) -> &'static str {
+    if chat.is_private() {
+        "private"
+    } else if chat.is_supergroup() {
+        "supergroup"
+    } else if chat.is_group() {
+        "group"
+    } else {
+        "channel"
+    }
+}
+
+/// Send the image, reusing the file already uploaded to Telegram if it was sent before.
+pub async fn send_dog(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    chat_id: ChatId,
+    image: &str,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> Result<(), CommandError> {
+    let url = Url::from_str(image).map_err(|e| CommandError::Malformed {
+        upstream: DOG_CEO,
+        reason: format!("'{}' is not an image URL: {}", image, e),
+    })?;
+
+    let caption = alt_text::caption(state.storage.as_ref(), chat_id.0, image).await;
+    let sent = match state.file_ids.get(image).await {
+        Some(file_id) => {
+            let mut request = bot.send_photo(chat_id, InputFile::file_id(file_id));
+            if let Some(caption) = caption {
+                request = request.caption(caption);
+            }
+            match keyboard {
+                Some(keyboard) => request.reply_markup(keyboard).await?,
+                None => request.await?,
+            }
+        }
+        None => send_photo(bot, state, chat_id, DOG_CEO, url, caption, keyboard).await?,
+    };
+    info!("Dog sent with success");
+    remember_photo(state, image, &sent).await;
+
+    Ok(())
+}
+
+/// Send the dog as a document, so Telegram keeps the image as is instead of recompressing it.
+async fn send_original(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    chat_id: ChatId,
+    image: &str,
+) -> Result<(), CommandError> {
+    let url = Url::from_str(image).map_err(|e| CommandError::Malformed {
+        upstream: DOG_CEO,
+        reason: format!("'{}' is not an image URL: {}", image, e),
+    })?;
+    let original = state
+        .images
+        .original(&url)
+        .await
+        .map_err(|e| e.blaming(DOG_CEO))?;
+    let file_name = images::file_name(&url, "dog.jpg");
+
+    bot.send_document(chat_id, InputFile::memory(original).file_name(file_name))
+        .await?;
+    info!("Original dog sent with success");
+    Ok(())
+}
+
+/// Album with a photo of each breed, captioned with how they compare.
+async fn compare_breeds(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    chat_id: ChatId,
+    breeds: &[BreedQuery; 2],
+) -> Result<(), CommandError> {
+    let (dogs, infos) = future::join(
+        future::try_join_all(
+            breeds
+                .iter()
+                .map(|breed| state.dog_api.random_for_breed(breed)),
+        ),
+        future::join_all(breeds.iter().map(|breed| breed_info(state, breed))),
+    )
+    .await;
+    let dogs = dogs.map_err(|error| CommandError::Upstream {
+        upstream: DOG_CEO,
+        error,
+    })?;
+    state.reporter.success(DOG_CEO);
+
+    let mut urls = Vec::new();
+    for (breed, dog) in breeds.iter().zip(dogs) {
+        if dog.status != "success" {
+            bot.send_message(chat_id, format!("Breed '{}' doesn't exist", breed))
+                .await?;
+            return Ok(());
+        }
+        urls.push(
+            Url::from_str(&dog.message).map_err(|e| CommandError::Malformed {
+                upstream: DOG_CEO,
+                reason: format!("'{}' is not an image URL: {}", dog.message, e),
+            })?,
+        );
+    }
+
+    let caption = compare::caption([
+        (&breeds[0], infos[0].as_ref()),
+        (&breeds[1], infos[1].as_ref()),
+    ]);
+    send_album(bot, state, chat_id, DOG_CEO, urls, fit_caption(caption)).await?;
+    info!("Comparison sent with success");
+    Ok(())
+}
+
+/// What TheDogAPI knows about the breed, the comparison goes on without it when it fails.
+async fn breed_info(state: &AppState, breed: &BreedQuery) -> Option<BreedInfo> {
+    match state.breed_info_api.search(&breed.to_string()).await {
+        Ok(info) => {
+            state.reporter.success(THE_DOG_API);
+            info
+        }
+        Err(e) => {
+            warn!("Could not get the info of {} -> {}", breed, e);
+            state.reporter.failure(THE_DOG_API, e).await;
+            None
+        }
+    }
+}
+
+/// Upload the bark the first time, then send it by its file id.
+async fn send_bark(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    chat_id: ChatId,
+    bark: Sound,
+) -> Result<(), CommandError> {
+    let sent = match state.file_ids.get(&bark.name).await {
+        Some(file_id) => bot.send_voice(chat_id, InputFile::file_id(file_id)).await?,
+        None => {
+            let file_name = bark.name.rsplit('/').next().unwrap_or_default().to_string();
+            bot.send_voice(chat_id, InputFile::memory(bark.ogg).file_name(file_name))
+                .await?
+        }
+    };
+    if let Some(voice) = sent.voice() {
+        state.file_ids.set(&bark.name, &voice.file_id).await;
+    }
+    Ok(())
+}
+
+/// Send the photos at the URLs as an album captioned by the first one, uploading them when
+/// Telegram can't fetch them by itself or when the Bot API server is self-hosted.
+async fn send_album(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    chat_id: ChatId,
+    upstream: &'static str,
+    urls: Vec<Url>,
+    caption: String,
+) -> Result<(), CommandError> {
+    let send = |photos: Vec<InputFile>| {
+        let media = photos.into_iter().enumerate().map(|(index, photo)| {
+            let photo = InputMediaPhoto::new(photo);
+            InputMedia::Photo(if index == 0 {
+                photo.caption(caption.clone())
+            } else {
+                photo
+            })
+        });
+        bot.send_media_group(chat_id, media)
+    };
+
+    if !state.upload_images {
+        match send(urls.iter().cloned().map(InputFile::url).collect()).await {
+            Err(e) if images::could_not_fetch(&e) => {
+                warn!("Telegram could not fetch the album, uploading it -> {}", e)
+            }
+            sent => {
+                sent?;
+                return Ok(());
+            }
+        }
+    }
+
+    let mut photos = Vec::new();
+    for url in &urls {
+        let file_name = images::file_name(url, "image.jpg");
+        let image = state
+            .images
+            .download(url)
+            .await
+            .map_err(|e| e.blaming(upstream))?;
+        photos.push(InputFile::memory(image).file_name(file_name));
+    }
+    send(photos).await?;
+    Ok(())
+}
+
+/// Send the photo at the URL, uploading it when Telegram can't fetch it by itself
+/// or when the Bot API server is self-hosted.
+async fn send_photo(
+    bot: &AutoSend<Bot>,
+    state: &AppState,
+    chat_id: ChatId,
+    upstream: &'static str,
+    url: Url,
+    caption: Option<String>,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> Result<Message, CommandError> {
+    let send = |photo: InputFile| {
+        let mut request = bot.send_photo(chat_id, photo);
+        if let Some(caption) = caption.clone() {
+            request = request.caption(caption);
+        }
+        if let Some(keyboard) = keyboard.clone() {
+            request = request.reply_markup(keyboard);
+        }
+        request
+    };
+
+    if !state.upload_images {
+        match send(InputFile::url(url.clone())).await {
+            Err(e) if images::could_not_fetch(&e) => {
+                warn!("Telegram could not fetch {}, uploading it -> {}", url, e)
+            }
+            sent => return Ok(sent?),
+        }
+    }
+
+    let file_name = images::file_name(&url, "image.jpg");
+    let image = state
+        .images
+        .download(&url)
+        .await
+        .map_err(|e| e.blaming(upstream))?;
+    Ok(send(InputFile::memory(image).file_name(file_name)).await?)
+}
@@ -0,0 +1,74 @@
+use crate::{api::breed_info::BreedInfo, breed::BreedQuery};
+use std::fmt::Write;
+
+/// Words between the breeds, in any case.
+const SEPARATORS: &[&str] = &[" vs. ", " vs ", " versus "];
+
+/// The two breeds of `/comparebreeds husky vs pug`, as written.
+pub fn split(text: &str) -> Option<(&str, &str)> {
+    let (at, len) = text.char_indices().find_map(|(at, _)| {
+        SEPARATORS
+            .iter()
+            .find(|separator| {
+                text.get(at..at + separator.len())
+                    .is_some_and(|word| word.eq_ignore_ascii_case(separator))
+            })
+            .map(|separator| (at, separator.len()))
+    })?;
+    let (a, b) = (text[..at].trim(), text[at + len..].trim());
+    if a.is_empty() || b.is_empty() {
+        None
+    } else {
+        Some((a, b))
+    }
+}
+
+/// Value of a row of the caption for a breed.
+type Row = fn(&BreedInfo) -> Option<String>;
+
+/// Side by side size, temperament and lifespan of the breeds, unknown when there is no info about one.
+pub fn caption(breeds: [(&BreedQuery, Option<&BreedInfo>); 2]) -> String {
+    let names = breeds.map(|(breed, info)| match info {
+        Some(info) => info.name.clone(),
+        None => capitalize(&breed.to_string()),
+    });
+    let mut text = format!("{} vs {}\n", names[0], names[1]);
+
+    let rows: [(&str, Row); 3] = [
+        ("📏 Size", size),
+        ("🧠 Temperament", |info| info.temperament.clone()),
+        ("⏳ Lifespan", |info| info.life_span.clone()),
+    ];
+    for (title, value) in rows {
+        writeln!(text, "\n{}", title).ok();
+        for (name, (_, info)) in names.iter().zip(breeds) {
+            let value = info.and_then(value);
+            writeln!(
+                text,
+                "• {}: {}",
+                name,
+                value.as_deref().unwrap_or("unknown")
+            )
+            .ok();
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// e.g. `16 - 27 kg, 51 - 60 cm`.
+fn size(info: &BreedInfo) -> Option<String> {
+    let weight = info.weight.metric.as_ref().map(|kg| format!("{} kg", kg));
+    let height = info.height.metric.as_ref().map(|cm| format!("{} cm", cm));
+    match (weight, height) {
+        (Some(weight), Some(height)) => Some(format!("{}, {}", weight, height)),
+        (weight, height) => weight.or(height),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
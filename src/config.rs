@@ -0,0 +1,193 @@
+use crate::{
+    alerts::AlertsConfig,
+    api::{
+        apod::Nasa,
+        breed_info::TheDogApi,
+        defi::DefiLlama,
+        dictionary::FreeDictionary,
+        dog::DogCeo,
+        fx::Frankfurter,
+        holidays::NagerDate,
+        joke::JokeApiDev,
+        news::Rss,
+        nft::OpenSea,
+        pokemon::PokeApi,
+        price::{Binance, CoinGecko},
+        quote::Quotable,
+        stock::YahooFinance,
+        trivia::OpenTriviaDb,
+        urban::UrbanDictionary,
+        weather::OpenMeteo,
+        wiki::Wikipedia,
+        xkcd::Xkcd,
+    },
+    assets::AssetsConfig,
+    cache::CacheConfig,
+    commands::CommandsConfig,
+    discord::DiscordConfig,
+    http::HttpConfig,
+    images::ImagesConfig,
+    inline::InlineConfig,
+    logging::LogConfig,
+    matrix::MatrixConfig,
+    prefetch::PrefetchConfig,
+    queue::QueueConfig,
+    reporter::AdminConfig,
+    server::ServerConfig,
+    storage::StorageConfig,
+    telemetry::TelemetryConfig,
+};
+use serde::Deserialize;
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Path used when `CONFIG_PATH` is not set.
+pub const DEFAULT_PATH: &str = "config.toml";
+
+/// Bot settings, every field has a default so the file is optional.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub api: ApiConfig,
+    pub http: HttpConfig,
+    pub commands: CommandsConfig,
+    pub admin: AdminConfig,
+    pub log: LogConfig,
+    pub telemetry: TelemetryConfig,
+    pub server: ServerConfig,
+    pub storage: StorageConfig,
+    pub cache: CacheConfig,
+    pub images: ImagesConfig,
+    pub prefetch: PrefetchConfig,
+    pub alerts: AlertsConfig,
+    pub queue: QueueConfig,
+    pub assets: AssetsConfig,
+    pub inline: InlineConfig,
+    /// Bots served by this process, the one at `TELOXIDE_TOKEN` is used when empty.
+    pub bots: Vec<BotConfig>,
+    /// Only used when built with the `discord` feature.
+    pub discord: DiscordConfig,
+    /// Only used when built with the `matrix` feature.
+    pub matrix: MatrixConfig,
+    /// Log the messages of the bots instead of sending them, also enabled by `--dry-run`.
+    pub dry_run: bool,
+    /// JSONL file the received updates are appended to, also set by `--record <path>`.
+    pub record: Option<PathBuf>,
+}
+
+/// A bot account, e.g. a staging and a production bot sharing the same process.
+#[derive(Deserialize, Clone, Debug)]
+pub struct BotConfig {
+    /// Shown in the logs and the health report.
+    pub name: String,
+    pub token: String,
+}
+
+/// Base URLs of the upstream services.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub dog_ceo: String,
+    /// Size, temperament and lifespan of the breeds, for `/comparebreeds`.
+    pub thedogapi: String,
+    /// Optional, raises the rate limit of TheDogAPI.
+    pub thedogapi_api_key: Option<String>,
+    pub coingecko: String,
+    pub binance: String,
+    pub defillama: String,
+    /// Historical exchange rates of the fiat currencies, for `/chart`.
+    pub frankfurter: String,
+    pub opensea: String,
+    /// `/floor` asks OpenSea instead of CoinGecko when set.
+    pub opensea_api_key: Option<String>,
+    pub yahoo_finance: String,
+    pub open_meteo: String,
+    pub open_meteo_geocoding: String,
+    pub jokeapi: String,
+    /// Leave out the jokes that aren't suitable for everyone.
+    pub jokeapi_safe_mode: bool,
+    pub quotable: String,
+    pub xkcd: String,
+    pub nasa: String,
+    /// The demo key allows a few dozen requests an hour, get a free key at https://api.nasa.gov for more.
+    pub nasa_api_key: String,
+    /// `{lang}` is replaced by the language of the chat.
+    pub wikipedia: String,
+    pub dictionaryapi: String,
+    pub urban_dictionary: String,
+    pub opentdb: String,
+    pub pokeapi: String,
+    pub nager_date: String,
+    /// RSS feed of `/news` in the chats without one of their own.
+    pub news_feed: String,
+    /// LibreTranslate instance used by `/translate`, which is disabled without one.
+    pub libretranslate: Option<String>,
+    pub libretranslate_api_key: Option<String>,
+    /// Shlink instance used by `/shorten`, which is disabled without one.
+    pub shlink: Option<String>,
+    pub shlink_api_key: String,
+    pub telegram: String,
+    /// `telegram` is a self-hosted Bot API server running with `--local`.
+    ///
+    /// The bot then uploads the images itself instead of Telegram fetching them, lifting the 20MB limit.
+    pub telegram_local: bool,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            dog_ceo: DogCeo::BASE_URL.to_string(),
+            thedogapi: TheDogApi::BASE_URL.to_string(),
+            thedogapi_api_key: None,
+            coingecko: CoinGecko::BASE_URL.to_string(),
+            binance: Binance::BASE_URL.to_string(),
+            defillama: DefiLlama::BASE_URL.to_string(),
+            frankfurter: Frankfurter::BASE_URL.to_string(),
+            opensea: OpenSea::BASE_URL.to_string(),
+            opensea_api_key: None,
+            yahoo_finance: YahooFinance::BASE_URL.to_string(),
+            open_meteo: OpenMeteo::BASE_URL.to_string(),
+            open_meteo_geocoding: OpenMeteo::GEOCODING_URL.to_string(),
+            jokeapi: JokeApiDev::BASE_URL.to_string(),
+            jokeapi_safe_mode: true,
+            quotable: Quotable::BASE_URL.to_string(),
+            xkcd: Xkcd::BASE_URL.to_string(),
+            nasa: Nasa::BASE_URL.to_string(),
+            nasa_api_key: Nasa::DEMO_KEY.to_string(),
+            wikipedia: Wikipedia::BASE_URL.to_string(),
+            dictionaryapi: FreeDictionary::BASE_URL.to_string(),
+            urban_dictionary: UrbanDictionary::BASE_URL.to_string(),
+            opentdb: OpenTriviaDb::BASE_URL.to_string(),
+            pokeapi: PokeApi::BASE_URL.to_string(),
+            nager_date: NagerDate::BASE_URL.to_string(),
+            news_feed: Rss::DEFAULT_FEED.to_string(),
+            libretranslate: None,
+            libretranslate_api_key: None,
+            shlink: None,
+            shlink_api_key: String::new(),
+            telegram: "https://api.telegram.org".to_string(),
+            telegram_local: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let content = fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Load the file at `CONFIG_PATH` (or [`DEFAULT_PATH`]), falling back to the defaults if it doesn't exist.
+    pub fn load() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let path = env::var("CONFIG_PATH").unwrap_or_else(|_| DEFAULT_PATH.to_string());
+        if Path::new(&path).exists() {
+            Self::from_file(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
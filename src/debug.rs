@@ -0,0 +1,116 @@
+//! `dog-bot debug ...`, fetching like the commands do and printing the message the bot would send.
+//!
+//! Nothing connects to Telegram, so the API clients can be tried out without a bot token.
+
+use crate::{
+    api::{dog::DogApi, price::PriceApi},
+    breed::BreedQuery,
+    commands::{DOG_CEO, PRICES},
+    error::CommandError,
+    render,
+};
+use std::{fmt, str::FromStr};
+
+pub const USAGE: &str = "Usage:
+  dog-bot debug dog [--breed <breed>]
+  dog-bot debug price <symbol>";
+
+/// What to fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Debug {
+    /// Like `/doggo`, or `/breed` with a breed.
+    Dog { breed: Option<String> },
+    /// Current USD price of the symbol, e.g. `btc`.
+    Price(String),
+}
+
+impl Debug {
+    /// The subcommand from the arguments after `debug`.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut args = args.iter().map(String::as_str);
+        match args.next() {
+            Some("dog") => {
+                let rest = args.collect::<Vec<_>>();
+                match rest.as_slice() {
+                    [] => Ok(Self::Dog { breed: None }),
+                    ["--breed", breed @ ..] if !breed.is_empty() => Ok(Self::Dog {
+                        breed: Some(breed.join(" ")),
+                    }),
+                    _ => Err(format!("unexpected arguments '{}'", rest.join(" "))),
+                }
+            }
+            Some("price") => match (args.next(), args.next()) {
+                (Some(symbol), None) => Ok(Self::Price(symbol.to_lowercase())),
+                _ => Err("tell me a single symbol, e.g. btc".to_string()),
+            },
+            Some(other) => Err(format!("unknown subcommand '{}'", other)),
+            None => Err("missing subcommand".to_string()),
+        }
+    }
+}
+
+/// Message the bot would send, printed as the Bot API method and its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Preview {
+    Text(String),
+    Photo(String),
+}
+
+impl fmt::Display for Preview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => write!(f, "sendMessage\n{}", text),
+            Self::Photo(url) => write!(f, "sendPhoto\n{}", url),
+        }
+    }
+}
+
+/// Fetch what the subcommand asks for, failing like the commands when an API does.
+pub async fn run(
+    debug: &Debug,
+    dog_api: &dyn DogApi,
+    price_api: &dyn PriceApi,
+) -> Result<Preview, CommandError> {
+    match debug {
+        Debug::Dog { breed: None } => {
+            let dog = dog_api
+                .random()
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+            Ok(Preview::Photo(dog.message))
+        }
+        Debug::Dog { breed: Some(breed) } => {
+            let query = match BreedQuery::from_str(breed) {
+                Ok(query) => query,
+                Err(e) => return Ok(Preview::Text(e.to_string())),
+            };
+            let dog =
+                dog_api
+                    .random_for_breed(&query)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: DOG_CEO,
+                        error,
+                    })?;
+            Ok(if dog.status == "success" {
+                Preview::Photo(dog.message)
+            } else {
+                Preview::Text(format!("Breed '{}' doesn't exist", breed))
+            })
+        }
+        Debug::Price(symbol) => {
+            let price =
+                price_api
+                    .usd_price(symbol)
+                    .await
+                    .map_err(|error| CommandError::Upstream {
+                        upstream: PRICES,
+                        error,
+                    })?;
+            Ok(Preview::Text(render::price(symbol, price)))
+        }
+    }
+}
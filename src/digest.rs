@@ -0,0 +1,71 @@
+use crate::{
+    i18n,
+    storage::{self, Storage},
+};
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+/// Commands that send a dog.
+const DOG_COMMANDS: &[&str] = &["doggo", "breed"];
+
+/// What happened in a chat, posted by the `weeklydigest` subscription.
+#[derive(Debug, PartialEq)]
+pub struct Digest {
+    pub dogs: i64,
+    /// With how many times it was requested.
+    pub top_breed: Option<(String, i64)>,
+    pub commands: i64,
+}
+
+impl Digest {
+    /// Everything done in the chat since the given time.
+    pub async fn build(
+        storage: &dyn Storage,
+        chat_id: i64,
+        since: DateTime<Utc>,
+    ) -> storage::Result<Self> {
+        let (counts, top_breeds) = tokio::try_join!(
+            storage.command_counts(Some(chat_id), since),
+            storage.popular_breeds(Some(chat_id), Some(since), 1),
+        )?;
+        let top_breed = top_breeds.into_iter().next();
+
+        Ok(Self {
+            dogs: counts
+                .iter()
+                .filter(|(command, _)| DOG_COMMANDS.contains(&command.as_str()))
+                .map(|(_, count)| count)
+                .sum(),
+            top_breed,
+            commands: counts.iter().map(|(_, count)| count).sum(),
+        })
+    }
+
+    /// In the language, see [`i18n::text`].
+    pub fn render(&self, language: &str) -> String {
+        if self.commands == 0 {
+            return i18n::text(language, "digest-quiet", &[]);
+        }
+
+        let mut text = i18n::text(language, "digest-title", &[]);
+        text.push('\n');
+        let dogs = i18n::text(language, "digest-dogs", &[("count", self.dogs.into())]);
+        writeln!(text, "- {}", dogs).ok();
+        if let Some((breed, count)) = &self.top_breed {
+            let args = [("breed", breed.as_str().into()), ("count", (*count).into())];
+            writeln!(
+                text,
+                "- {}",
+                i18n::text(language, "digest-top-breed", &args)
+            )
+            .ok();
+        }
+        let commands = i18n::text(
+            language,
+            "digest-commands",
+            &[("count", self.commands.into())],
+        );
+        writeln!(text, "- {}", commands).ok();
+        text
+    }
+}
@@ -0,0 +1,175 @@
+//! Discord adapter, answering the [`Portable`](crate::messenger::Portable) commands in the channels the bot is in.
+//!
+//! Built with the `discord` feature, the settings are read either way so the same file serves both builds.
+
+use serde::Deserialize;
+
+/// Connection to Discord.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DiscordConfig {
+    /// Token of the Discord bot, which is disabled without it.
+    pub token: Option<String>,
+    /// Start of the commands, e.g. `!doggo`.
+    pub prefix: String,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            prefix: "!".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "discord")]
+pub use adapter::{run, DiscordChat};
+
+#[cfg(feature = "discord")]
+mod adapter {
+    use super::DiscordConfig;
+    use crate::{
+        error::CommandError,
+        messenger::{self, Chat, Portable},
+        state::AppState,
+    };
+    use async_trait::async_trait;
+    use reqwest::Url;
+    use serenity::{
+        builder::{CreateEmbed, CreateMessage},
+        client::{Client, Context, EventHandler},
+        http::Http,
+        model::{channel::Message, id::ChannelId},
+        prelude::GatewayIntents,
+    };
+    use std::sync::Arc;
+    use tracing::{error, info, info_span, warn, Instrument};
+
+    const PLATFORM: &str = "discord";
+
+    /// A Discord channel, answered by the bot.
+    pub struct DiscordChat {
+        http: Arc<Http>,
+        channel_id: ChannelId,
+    }
+
+    impl DiscordChat {
+        pub fn new(http: Arc<Http>, channel_id: ChannelId) -> Self {
+            Self { http, channel_id }
+        }
+    }
+
+    fn failed(e: serenity::Error) -> CommandError {
+        CommandError::Platform {
+            platform: PLATFORM,
+            reason: e.to_string(),
+        }
+    }
+
+    #[async_trait]
+    impl Chat for DiscordChat {
+        fn platform(&self) -> &'static str {
+            PLATFORM
+        }
+
+        async fn send_text(&self, text: &str) -> Result<(), CommandError> {
+            self.channel_id
+                .say(&self.http, text)
+                .await
+                .map_err(failed)?;
+            Ok(())
+        }
+
+        async fn send_photo(&self, url: &Url, caption: Option<&str>) -> Result<(), CommandError> {
+            let mut message = CreateMessage::new().embed(CreateEmbed::new().image(url.as_str()));
+            if let Some(caption) = caption {
+                message = message.content(caption);
+            }
+            self.channel_id
+                .send_message(&self.http, message)
+                .await
+                .map_err(failed)?;
+            Ok(())
+        }
+    }
+
+    struct Handler {
+        state: Arc<AppState>,
+        prefix: String,
+    }
+
+    #[serenity::async_trait]
+    impl EventHandler for Handler {
+        async fn message(&self, ctx: Context, message: Message) {
+            if message.author.bot {
+                return;
+            }
+            let text = match message.content.strip_prefix(self.prefix.as_str()) {
+                Some(text) => text.trim(),
+                None => return,
+            };
+            let (name, args) = text.split_once(' ').unwrap_or((text, ""));
+            let chat = DiscordChat::new(ctx.http.clone(), message.channel_id);
+            let command = match Portable::parse(name, args) {
+                Some(command) => command,
+                None if name.eq_ignore_ascii_case("help") => {
+                    if let Err(e) = chat.send_text(&Portable::help(&self.prefix)).await {
+                        warn!("Could not send the help -> {}", e);
+                    }
+                    return;
+                }
+                None => return,
+            };
+
+            let span = info_span!(
+                "command",
+                platform = PLATFORM,
+                command = name,
+                channel_id = message.channel_id.get()
+            );
+            if let Err(e) = messenger::run(&self.state, &chat, command)
+                .instrument(span.clone())
+                .await
+            {
+                span.in_scope(|| error!("Could not answer the command -> {}", e));
+                if let Some(upstream) = e.upstream() {
+                    self.state.reporter.failure(upstream, &e).await;
+                }
+                if let Some(text) = e.user_message() {
+                    chat.send_text(text).await.ok();
+                }
+            }
+        }
+    }
+
+    /// Answer the commands sent on Discord until the connection is lost for good.
+    pub async fn run(config: DiscordConfig, state: Arc<AppState>) {
+        let token = match config.token {
+            Some(token) if !token.is_empty() => token,
+            _ => return,
+        };
+        let intents = GatewayIntents::GUILD_MESSAGES
+            | GatewayIntents::DIRECT_MESSAGES
+            | GatewayIntents::MESSAGE_CONTENT;
+        let handler = Handler {
+            state,
+            prefix: config.prefix,
+        };
+        let mut client = match Client::builder(&token, intents)
+            .event_handler(handler)
+            .await
+        {
+            Ok(client) => client,
+            Err(e) => {
+                error!("Could not connect to Discord -> {}", e);
+                return;
+            }
+        };
+
+        info!("Answering the commands sent on Discord");
+        if let Err(e) = client.start().await {
+            error!("Discord client stopped -> {}", e);
+        }
+    }
+}
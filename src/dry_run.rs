@@ -0,0 +1,214 @@
+//! Dry-run mode, the bots receive the updates and run the handlers but their messages are only logged.
+//!
+//! The bots talk to a local Bot API proxy instead of Telegram. It forwards the reads, e.g. `getUpdates`, `getMe`
+//! or the files, and answers every other method itself as if it was sent, so the handlers don't know the difference.
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use chrono::Utc;
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+use std::sync::{
+    atomic::{AtomicI32, Ordering},
+    Arc,
+};
+use tracing::{error, info, warn};
+
+struct DryRun {
+    client: reqwest::Client,
+    /// The real Bot API, e.g. `https://api.telegram.org`.
+    upstream: Url,
+    /// Ids of the messages "sent", so the edits of a handler point to the right one.
+    next_message_id: AtomicI32,
+}
+
+pub fn router(client: reqwest::Client, upstream: Url) -> Router {
+    Router::new().fallback(proxy).with_state(Arc::new(DryRun {
+        client,
+        upstream,
+        next_message_id: AtomicI32::new(1),
+    }))
+}
+
+/// Serve the proxy on a free local port, returning the URL the bots should use as their API.
+pub async fn start(client: reqwest::Client, upstream: Url) -> std::io::Result<Url> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = Url::parse(&format!("http://{}", listener.local_addr()?))
+        .expect("a socket address is a valid host");
+
+    info!("Dry run, the messages are logged instead of sent to Telegram");
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(client, upstream)).await {
+            error!("Dry run proxy stopped -> {}", e);
+        }
+    });
+    Ok(url)
+}
+
+/// Methods answered by Telegram, which don't change anything.
+fn is_read(path: &str, method: &str) -> bool {
+    path.starts_with("/file/") || method.to_lowercase().starts_with("get")
+}
+
+async fn proxy(
+    State(dry_run): State<Arc<DryRun>>,
+    http_method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = uri.path();
+    let method = path.rsplit('/').next().unwrap_or_default();
+    if is_read(path, method) {
+        return forward(&dry_run, http_method, &uri, &headers, body).await;
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let params = params(content_type, &body);
+    info!("Dry run, not sent: {} {}", method, params);
+
+    let result = fake_result(&dry_run, method, &params);
+    axum::Json(json!({ "ok": true, "result": result })).into_response()
+}
+
+async fn forward(
+    dry_run: &DryRun,
+    http_method: Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Response {
+    let url = format!(
+        "{}{}",
+        dry_run.upstream.as_str().trim_end_matches('/'),
+        uri.path_and_query().map_or("", |path| path.as_str())
+    );
+    // reqwest is on another version of `http`, so the types are converted through their bytes
+    let http_method = reqwest::Method::from_bytes(http_method.as_str().as_bytes())
+        .expect("axum only accepts valid methods");
+    let mut request = dry_run
+        .client
+        .request(http_method, &url)
+        .body(body.to_vec());
+    if let Some(content_type) = headers.get(header::CONTENT_TYPE) {
+        request = request.header(reqwest::header::CONTENT_TYPE, content_type.as_bytes());
+    }
+
+    let forwarded = match request.send().await {
+        Ok(res) => res,
+        Err(e) => {
+            warn!("Could not forward {} to Telegram -> {}", uri.path(), e);
+            return StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+    let status =
+        StatusCode::from_u16(forwarded.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let content_type = forwarded
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| HeaderValue::from_bytes(value.as_bytes()).ok());
+    match forwarded.bytes().await {
+        Ok(bytes) => {
+            let mut response = (status, bytes).into_response();
+            if let Some(content_type) = content_type {
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, content_type);
+            }
+            response
+        }
+        Err(e) => {
+            warn!("Could not read the answer of Telegram -> {}", e);
+            StatusCode::BAD_GATEWAY.into_response()
+        }
+    }
+}
+
+/// What Telegram would answer, a message for the methods sending or editing one and `true` for the rest.
+fn fake_result(dry_run: &DryRun, method: &str, params: &Value) -> Value {
+    let method = method.to_lowercase();
+    // Inline messages belong to no chat, their edits answer `true`
+    let inline = params.get("inline_message_id").is_some();
+    match method.as_str() {
+        "sendchataction" => json!(true),
+        "sendmediagroup" => {
+            let count = params
+                .get("media")
+                .and_then(Value::as_array)
+                .map_or(1, Vec::len);
+            (0..count).map(|_| fake_message(dry_run, params)).collect()
+        }
+        "copymessage" => json!({ "message_id": next_id(dry_run) }),
+        "forwardmessage" => fake_message(dry_run, params),
+        method if method.starts_with("send") => fake_message(dry_run, params),
+        method if method.starts_with("edit") && !inline => {
+            let mut message = fake_message(dry_run, params);
+            if let Some(message_id) = params.get("message_id") {
+                message["message_id"] = message_id.clone();
+            }
+            message
+        }
+        _ => json!(true),
+    }
+}
+
+fn next_id(dry_run: &DryRun) -> i32 {
+    dry_run.next_message_id.fetch_add(1, Ordering::Relaxed)
+}
+
+fn fake_message(dry_run: &DryRun, params: &Value) -> Value {
+    // `@channel` usernames are left as an unknown chat
+    let chat_id = params.get("chat_id").and_then(Value::as_i64).unwrap_or(0);
+    let text = params
+        .get("text")
+        .or_else(|| params.get("caption"))
+        .and_then(Value::as_str)
+        .unwrap_or("dry run");
+    json!({
+        "message_id": next_id(dry_run),
+        "date": Utc::now().timestamp(),
+        "chat": { "id": chat_id, "type": "private", "first_name": "dry run" },
+        "text": text
+    })
+}
+
+/// Parameters of a request, teloxide sends JSON bodies or multipart forms when the request carries files.
+fn params(content_type: &str, body: &[u8]) -> Value {
+    let boundary = match content_type.split_once("boundary=") {
+        Some((_, boundary)) => format!("--{}", boundary),
+        None => return serde_json::from_slice(body).unwrap_or(Value::Null),
+    };
+
+    let body = String::from_utf8_lossy(body);
+    let mut params = Map::new();
+    for part in body.split(boundary.as_str()) {
+        let (headers, value) = match part.split_once("\r\n\r\n") {
+            Some(part) => part,
+            None => continue,
+        };
+        let name = match headers
+            .split("name=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+        {
+            Some(name) => name,
+            None => continue,
+        };
+        let value = if headers.contains("filename=") {
+            json!("<file>")
+        } else {
+            let value = value.trim_end_matches("\r\n");
+            serde_json::from_str(value).unwrap_or_else(|_| json!(value))
+        };
+        params.insert(name.to_string(), value);
+    }
+    Value::Object(params)
+}
@@ -0,0 +1,91 @@
+use crate::{circuit::CircuitOpen, quota::QuotaExceeded, storage::StorageError};
+use std::fmt;
+use teloxide::RequestError;
+
+/// Why a command couldn't be answered.
+#[derive(Debug)]
+pub enum CommandError {
+    /// An upstream answered with data that can't be used.
+    Malformed {
+        upstream: &'static str,
+        reason: String,
+    },
+    /// An upstream couldn't be reached.
+    Upstream {
+        upstream: &'static str,
+        error: reqwest_middleware::Error,
+    },
+    /// The message has no sender, e.g. it was posted on behalf of a channel.
+    NoSender,
+    Telegram(RequestError),
+    /// Another messaging platform failed, e.g. Discord.
+    Platform {
+        platform: &'static str,
+        reason: String,
+    },
+    Storage(StorageError),
+}
+
+impl CommandError {
+    /// Upstream to blame, if any.
+    pub fn upstream(&self) -> Option<&'static str> {
+        match self {
+            Self::Malformed { upstream, .. } | Self::Upstream { upstream, .. } => Some(*upstream),
+            Self::NoSender | Self::Telegram(_) | Self::Platform { .. } | Self::Storage(_) => None,
+        }
+    }
+
+    /// Text sent back to the user, if telling them makes sense.
+    pub fn user_message(&self) -> Option<&'static str> {
+        match self {
+            Self::Upstream {
+                error: reqwest_middleware::Error::Middleware(e),
+                ..
+            } if e.is::<CircuitOpen>() => {
+                Some("This service is temporarily unavailable, please try again in a few minutes.")
+            }
+            Self::Upstream {
+                error: reqwest_middleware::Error::Middleware(e),
+                ..
+            } if e.is::<QuotaExceeded>() => {
+                Some("This service was asked too much lately, please try again later.")
+            }
+            Self::Malformed { .. } | Self::Upstream { .. } | Self::Storage(_) => {
+                Some("Sorry, something went wrong, please try again later.")
+            }
+            Self::NoSender => Some("This command can only be used by a user."),
+            Self::Telegram(_) | Self::Platform { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { upstream, reason } => {
+                write!(f, "{} answered with malformed data: {}", upstream, reason)
+            }
+            Self::Upstream { upstream, error } => write!(f, "{} failed: {}", upstream, error),
+            Self::NoSender => write!(f, "The message has no sender"),
+            Self::Telegram(e) => write!(f, "Telegram request failed: {}", e),
+            Self::Platform { platform, reason } => {
+                write!(f, "{} request failed: {}", platform, reason)
+            }
+            Self::Storage(e) => write!(f, "Storage failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<RequestError> for CommandError {
+    fn from(e: RequestError) -> Self {
+        Self::Telegram(e)
+    }
+}
+
+impl From<StorageError> for CommandError {
+    fn from(e: StorageError) -> Self {
+        Self::Storage(e)
+    }
+}
@@ -0,0 +1,189 @@
+use crate::version::BuildInfo;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use teloxide::prelude::*;
+use tracing::warn;
+
+/// How often Telegram is pinged with `getMe`.
+const TELEGRAM_PING_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Heartbeat {
+    last: Option<Instant>,
+    max_age: Duration,
+}
+
+/// Liveness of the bot's components and when each upstream last answered.
+pub struct Health {
+    started: Instant,
+    heartbeats: Mutex<HashMap<String, Heartbeat>>,
+    upstreams: Mutex<HashMap<String, Instant>>,
+    requests: Mutex<HashMap<String, RequestCounts>>,
+    /// Updates that reached the handlers since the start.
+    updates: AtomicU64,
+}
+
+/// Requests to an upstream since the start, and how many of them failed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RequestCounts {
+    pub total: u64,
+    pub failed: u64,
+}
+
+#[derive(Serialize)]
+pub struct ComponentReport {
+    pub healthy: bool,
+    pub last_seen_secs_ago: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct HealthReport {
+    /// No component has stopped beating.
+    pub alive: bool,
+    /// Every component has beaten recently.
+    pub ready: bool,
+    pub uptime_secs: u64,
+    pub components: BTreeMap<String, ComponentReport>,
+    pub upstreams_last_success_secs_ago: BTreeMap<String, u64>,
+    /// What the running binary was built from.
+    pub build: BuildInfo,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            heartbeats: Mutex::default(),
+            upstreams: Mutex::default(),
+            requests: Mutex::default(),
+            updates: AtomicU64::default(),
+        }
+    }
+}
+
+impl Health {
+    /// Expect the component to beat at least once every `max_age`.
+    pub fn register(&self, component: &str, max_age: Duration) {
+        self.heartbeats.lock().unwrap().insert(
+            component.to_string(),
+            Heartbeat {
+                last: None,
+                max_age,
+            },
+        );
+    }
+
+    pub fn beat(&self, component: &str) {
+        if let Some(heartbeat) = self.heartbeats.lock().unwrap().get_mut(component) {
+            heartbeat.last = Some(Instant::now());
+        }
+    }
+
+    /// An upstream answered successfully.
+    pub fn upstream_ok(&self, upstream: &str) {
+        self.upstreams
+            .lock()
+            .unwrap()
+            .insert(upstream.to_string(), Instant::now());
+    }
+
+    /// A request to the upstream ended, failing when it had no answer or a server error.
+    pub fn upstream_request(&self, upstream: &str, failed: bool) {
+        let mut requests = self.requests.lock().unwrap();
+        let counts = requests.entry(upstream.to_string()).or_default();
+        counts.total += 1;
+        counts.failed += u64::from(failed);
+    }
+
+    /// How long ago the upstream last answered successfully, None if it never did.
+    pub fn last_success(&self, upstream: &str) -> Option<Duration> {
+        self.upstreams
+            .lock()
+            .unwrap()
+            .get(upstream)
+            .map(|last| last.elapsed())
+    }
+
+    pub fn update_received(&self) {
+        self.updates.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn updates(&self) -> u64 {
+        self.updates.load(Ordering::Relaxed)
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// By upstream.
+    pub fn request_counts(&self) -> BTreeMap<String, RequestCounts> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(upstream, counts)| (upstream.clone(), *counts))
+            .collect()
+    }
+
+    pub fn report(&self) -> HealthReport {
+        let components = self
+            .heartbeats
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, heartbeat)| {
+                let age = heartbeat.last.map(|last| last.elapsed());
+                let report = ComponentReport {
+                    healthy: age.map(|age| age <= heartbeat.max_age).unwrap_or(false),
+                    last_seen_secs_ago: age.map(|age| age.as_secs()),
+                };
+                (name.clone(), report)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // A component that hasn't beaten yet is still starting, not dead
+        let alive = components
+            .values()
+            .all(|c| c.healthy || c.last_seen_secs_ago.is_none());
+        let ready = components.values().all(|c| c.healthy);
+
+        let upstreams_last_success_secs_ago = self
+            .upstreams
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, last)| (name.clone(), last.elapsed().as_secs()))
+            .collect();
+
+        HealthReport {
+            alive,
+            ready,
+            uptime_secs: self.uptime().as_secs(),
+            components,
+            upstreams_last_success_secs_ago,
+            build: BuildInfo::current(),
+        }
+    }
+}
+
+/// Keep checking that Telegram is reachable with the given bot, reported as `telegram:<name>`.
+pub async fn watch_telegram(bot: AutoSend<Bot>, name: String, health: std::sync::Arc<Health>) {
+    let component = format!("telegram:{}", name);
+    health.register(&component, TELEGRAM_PING_INTERVAL * 3);
+
+    let mut interval = tokio::time::interval(TELEGRAM_PING_INTERVAL);
+    loop {
+        interval.tick().await;
+        match bot.get_me().await {
+            Ok(_) => health.beat(&component),
+            Err(e) => warn!("Telegram is unreachable from the {} bot -> {}", name, e),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{
+    api::holidays::Holiday,
+    storage::{self, Storage},
+};
+use std::fmt::Write;
+use tracing::warn;
+
+/// Holidays shown by `/holidays`.
+pub const UPCOMING: usize = 5;
+
+/// Two letter country code, uppercase, `None` if it doesn't look like one.
+pub fn country_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| code.to_ascii_uppercase())
+}
+
+/// Default country of `/holidays` in the chat, if it has one.
+pub async fn country_of_chat(storage: &dyn Storage, chat_id: i64) -> Option<String> {
+    match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings?.country,
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            None
+        }
+    }
+}
+
+pub async fn set_country(
+    storage: &dyn Storage,
+    chat_id: i64,
+    country: Option<String>,
+) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| settings.country = country).await
+}
+
+/// The next holidays, with their local name and the English one when they differ.
+pub fn render(country: &str, holidays: &[Holiday]) -> String {
+    if holidays.is_empty() {
+        return format!("No upcoming public holidays in {}", country);
+    }
+
+    let mut text = format!("Upcoming public holidays in {}:\n", country);
+    for holiday in holidays.iter().take(UPCOMING) {
+        write!(
+            text,
+            "\n{} {}",
+            holiday.date.format("%a %-d %b %Y"),
+            holiday.local_name
+        )
+        .ok();
+        if holiday.name != holiday.local_name {
+            write!(text, " ({})", holiday.name).ok();
+        }
+    }
+    text
+}
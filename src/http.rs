@@ -0,0 +1,332 @@
+use crate::{
+    circuit::CircuitBreaker,
+    health::Health,
+    quota::{self, Budget, Quotas},
+};
+use async_trait::async_trait;
+use metrics::{histogram, increment_counter};
+use reqwest::{Request, Response, StatusCode, Url};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, Next, Result};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use task_local_extensions::Extensions;
+use tracing::{field, info, info_span, warn, Instrument};
+
+pub type HttpClient = ClientWithMiddleware;
+
+/// Most requests in flight at once to an upstream that is asked for several things.
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Settings of the client used for the upstream APIs.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// How many times a transient failure (timeouts, 5xx, 429) is retried.
+    pub max_retries: u32,
+    /// Time allowed to establish a connection.
+    pub connect_timeout_secs: u64,
+    /// Time allowed for a single attempt, from sending the request to reading the whole body.
+    pub request_timeout_secs: u64,
+    /// Failures in a row of an upstream host before it isn't called for a while, never when 0.
+    pub circuit_breaker_threshold: u32,
+    /// How long a failing upstream host isn't called before trying it again.
+    pub circuit_breaker_cooldown_secs: u64,
+    /// `http://`, `https://` or `socks5://` proxy used for the upstream APIs and Telegram.
+    ///
+    /// Without it the `HTTP_PROXY`/`HTTPS_PROXY` environment variables are honored.
+    pub proxy: Option<String>,
+    /// Calls allowed to each upstream host, e.g. `api.coingecko.com`, by every feature together.
+    pub quotas: HashMap<String, Budget>,
+    /// Longest a call over the minute budget of its upstream waits for the next minute, instead of failing.
+    pub quota_max_wait_secs: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            connect_timeout_secs: 5,
+            request_timeout_secs: 10,
+            circuit_breaker_threshold: 5,
+            circuit_breaker_cooldown_secs: 30,
+            proxy: None,
+            quotas: HashMap::new(),
+            quota_max_wait_secs: 5,
+        }
+    }
+}
+
+/// Build the client shared by all the upstream APIs.
+///
+/// Every attempt is logged and measured, retries wrap around them, the quotas around the retries, so a request counts
+/// once however many times it's retried, and the circuit breaker around everything, so the calls it turns down don't
+/// spend the budget.
+pub fn client(config: &HttpConfig, health: Arc<Health>) -> HttpClient {
+    with_middleware(ClientBuilder::new(reqwest_client(config)), config, health).build()
+}
+
+/// [`client`] refusing the calls to other machines, a replay only reaches the upstreams mocked locally.
+pub fn local_client(config: &HttpConfig, health: Arc<Health>) -> HttpClient {
+    let builder = ClientBuilder::new(reqwest_client(config)).with(LocalOnlyMiddleware);
+    with_middleware(builder, config, health).build()
+}
+
+fn reqwest_client(config: &HttpConfig) -> reqwest::Client {
+    with_proxy(reqwest::Client::builder(), config)
+        .user_agent(USER_AGENT)
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .timeout(Duration::from_secs(config.request_timeout_secs))
+        .build()
+        .expect("could not build the HTTP client")
+}
+
+fn with_middleware(
+    builder: ClientBuilder,
+    config: &HttpConfig,
+    health: Arc<Health>,
+) -> ClientBuilder {
+    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(config.max_retries);
+
+    let circuit_breaker = CircuitBreaker::new(
+        config.circuit_breaker_threshold,
+        Duration::from_secs(config.circuit_breaker_cooldown_secs),
+    );
+
+    let quotas = QuotaMiddleware {
+        quotas: Arc::new(Quotas::new(config.quotas.clone())),
+        max_wait: Duration::from_secs(config.quota_max_wait_secs),
+    };
+
+    builder
+        .with(CircuitBreakerMiddleware(Arc::new(circuit_breaker)))
+        .with(quotas)
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .with(LoggingMiddleware)
+        .with(MetricsMiddleware)
+        .with(HealthMiddleware(health))
+}
+
+/// Build the client used to talk to Telegram, with teloxide's defaults.
+pub fn telegram_client(config: &HttpConfig) -> reqwest::Client {
+    with_proxy(teloxide::net::default_reqwest_settings(), config)
+        .build()
+        .expect("could not build the Telegram HTTP client")
+}
+
+fn with_proxy(builder: reqwest::ClientBuilder, config: &HttpConfig) -> reqwest::ClientBuilder {
+    match &config.proxy {
+        Some(proxy) => builder.proxy(reqwest::Proxy::all(proxy).expect("invalid proxy URL")),
+        None => builder,
+    }
+}
+
+/// Returned instead of calling an upstream on another machine, see [`local_client`].
+#[derive(Debug)]
+pub struct NotLocal {
+    pub upstream: String,
+}
+
+impl fmt::Display for NotLocal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} isn't on this machine, not calling it", self.upstream)
+    }
+}
+
+impl std::error::Error for NotLocal {}
+
+/// Only let through the calls to this machine, e.g. `http://localhost:8080` or `http://127.0.0.1:8080`.
+pub struct LocalOnlyMiddleware;
+
+#[async_trait]
+impl Middleware for LocalOnlyMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let host = req.url().host_str().unwrap_or_default();
+        let local = host == "localhost"
+            || host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback());
+        if !local {
+            let e = NotLocal {
+                upstream: upstream(req.url()),
+            };
+            return Err(reqwest_middleware::Error::Middleware(e.into()));
+        }
+
+        next.run(req, extensions).await
+    }
+}
+
+/// Keep the calls to each upstream within its budget.
+pub struct QuotaMiddleware {
+    pub quotas: Arc<Quotas>,
+    pub max_wait: Duration,
+}
+
+#[async_trait]
+impl Middleware for QuotaMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let host = req.url().host_str().unwrap_or_default().to_string();
+        if let Err(e) = self.quotas.wait(&host, self.max_wait).await {
+            increment_counter!("upstream_quota_exceeded_total", "host" => host, "window" => e.window.name());
+            return Err(reqwest_middleware::Error::Middleware(e.into()));
+        }
+
+        next.run(req, extensions).await
+    }
+}
+
+/// Fail fast while an upstream keeps failing, once all the retries are exhausted.
+pub struct CircuitBreakerMiddleware(pub Arc<CircuitBreaker>);
+
+#[async_trait]
+impl Middleware for CircuitBreakerMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let upstream = upstream(req.url());
+        self.0
+            .allow(&upstream)
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+
+        let res = next.run(req, extensions).await;
+
+        // Answers like 404 mean the upstream works, a call over quota says nothing about it
+        match &res {
+            Err(e) if quota::is_exceeded(e) => self.0.skipped(&upstream),
+            Ok(res)
+                if !res.status().is_server_error()
+                    && res.status() != StatusCode::TOO_MANY_REQUESTS =>
+            {
+                self.0.success(&upstream)
+            }
+            _ => self.0.failure(&upstream),
+        }
+
+        res
+    }
+}
+
+/// Host and port, e.g. `dog.ceo:443`.
+fn upstream(url: &Url) -> String {
+    format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
+
+/// Log every upstream call with its latency, inside an `upstream` span.
+pub struct LoggingMiddleware;
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let method = req.method().clone();
+        let url = req.url().clone();
+        let span = info_span!(
+            "upstream",
+            otel.name = %format!("{} {}", method, url.host_str().unwrap_or_default()),
+            http.method = %method,
+            http.url = %url,
+            http.status_code = field::Empty,
+        );
+        let start = Instant::now();
+
+        let res = next.run(req, extensions).instrument(span.clone()).await;
+
+        let elapsed = start.elapsed();
+        let _enter = span.enter();
+        match &res {
+            Ok(res) => info!("{} {} -> {} in {:?}", method, url, res.status(), elapsed),
+            Err(e) => warn!("{} {} failed in {:?}: {}", method, url, elapsed, e),
+        }
+
+        res
+    }
+}
+
+/// Record the count and duration of the upstream calls, labeled by host.
+pub struct MetricsMiddleware;
+
+#[async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let host = req.url().host_str().unwrap_or_default().to_string();
+        let start = Instant::now();
+
+        let res = next.run(req, extensions).await;
+
+        let status = match &res {
+            Ok(res) => res.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        histogram!("upstream_request_duration_seconds", start.elapsed(), "host" => host.clone());
+        increment_counter!("upstream_requests_total", "host" => host, "status" => status);
+
+        res
+    }
+}
+
+/// Remember when each upstream host last answered successfully, and count its requests and failures.
+pub struct HealthMiddleware(pub Arc<Health>);
+
+#[async_trait]
+impl Middleware for HealthMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let host = req.url().host_str().unwrap_or_default().to_string();
+
+        let res = next.run(req, extensions).await;
+
+        if matches!(&res, Ok(res) if res.status().is_success()) {
+            self.0.upstream_ok(&host);
+        }
+        let failed = match &res {
+            Ok(res) => {
+                res.status().is_server_error() || res.status() == StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(_) => true,
+        };
+        self.0.upstream_request(&host, failed);
+
+        res
+    }
+}
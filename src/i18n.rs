@@ -0,0 +1,135 @@
+use crate::storage::{self, Storage};
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use std::{collections::HashMap, fmt, sync::OnceLock};
+use tracing::warn;
+use unic_langid::LanguageIdentifier;
+
+/// Language of the answers when neither the chat, the user nor Telegram tell.
+pub const DEFAULT: &str = "en";
+
+/// Messages translated with Fluent, the ones missing from a language are in English.
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+    ("ru", include_str!("../locales/ru.ftl")),
+];
+
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        TRANSLATIONS
+            .iter()
+            .map(|(code, source)| {
+                let language = code
+                    .parse::<LanguageIdentifier>()
+                    .expect("the languages are valid");
+                let resource = FluentResource::try_new(source.to_string())
+                    .expect("the translations are valid Fluent");
+                let mut bundle = FluentBundle::new_concurrent(vec![language]);
+                // Telegram would show the marks isolating the arguments
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .expect("no message is translated twice");
+                (*code, bundle)
+            })
+            .collect()
+    })
+}
+
+/// The message in the language, e.g. `digest-dogs` with its `count`, picking the plural form of the language.
+pub fn text(language: &str, id: &str, args: &[(&str, FluentValue)]) -> String {
+    let args = args.iter().cloned().collect::<FluentArgs>();
+    [language, DEFAULT]
+        .into_iter()
+        .find_map(|language| {
+            let bundle = bundles().get(language)?;
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, Some(&args), &mut errors);
+            if !errors.is_empty() {
+                warn!(
+                    "Could not format the message {} in {} -> {:?}",
+                    id, language, errors
+                );
+            }
+            Some(text.into_owned())
+        })
+        .unwrap_or_else(|| {
+            warn!("The message {} is missing", id);
+            id.to_string()
+        })
+}
+
+/// Where the language of the answers comes from, the first one known wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Set with `/language`.
+    Chat,
+    /// The `/prefs language` of the user.
+    User,
+    /// The language of the user's Telegram app.
+    Locale,
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Chat => write!(f, "set for this chat"),
+            Self::User => write!(f, "from your /prefs"),
+            Self::Locale => write!(f, "from your Telegram app"),
+            Self::Default => write!(f, "by default"),
+        }
+    }
+}
+
+/// Two or three lowercase letters, e.g. `es`. It ends up in host names.
+pub fn is_code(code: &str) -> bool {
+    (2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase())
+}
+
+/// Language of a locale as Telegram gives it, e.g. `pt` for `pt-BR`.
+pub fn from_locale(locale: &str) -> Option<String> {
+    let code = locale.split(['-', '_']).next()?.to_lowercase();
+    if is_code(&code) {
+        Some(code)
+    } else {
+        None
+    }
+}
+
+/// Language of the answers: the one of the chat, else the one of the user, else the one of their Telegram app.
+pub fn pick(chat: Option<String>, user: Option<String>, locale: Option<&str>) -> (String, Source) {
+    [
+        (chat, Source::Chat),
+        (user, Source::User),
+        (locale.and_then(from_locale), Source::Locale),
+    ]
+    .into_iter()
+    .find_map(|(code, source)| code.filter(|code| is_code(code)).map(|code| (code, source)))
+    .unwrap_or_else(|| (DEFAULT.to_string(), Source::Default))
+}
+
+/// Language set with `/language`, if any.
+pub async fn of_chat(storage: &dyn Storage, chat_id: i64) -> Option<String> {
+    match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings.and_then(|settings| settings.language),
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            None
+        }
+    }
+}
+
+/// `None` goes back to the language of each user.
+pub async fn set_for_chat(
+    storage: &dyn Storage,
+    chat_id: i64,
+    language: Option<String>,
+) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| settings.language = language).await
+}
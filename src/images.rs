@@ -0,0 +1,221 @@
+use crate::{error::CommandError, http::HttpClient};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageError, ImageOutputFormat};
+use reqwest::{header::CONTENT_TYPE, Url};
+use serde::Deserialize;
+use std::{fmt, io::Cursor};
+use teloxide::{ApiError, RequestError};
+use tracing::{info, warn};
+
+/// Biggest photo Telegram accepts as an upload.
+pub const MAX_BYTES: usize = 10 * 1024 * 1024;
+/// Biggest document Telegram accepts as an upload, e.g. an image kept in full quality.
+pub const MAX_DOCUMENT_BYTES: usize = 50 * 1024 * 1024;
+/// Telegram refuses photos whose width and height add up to more.
+pub const MAX_DIMENSIONS: u32 = 10_000;
+/// Biggest image downloaded to be shrunk, bigger ones aren't worth the memory.
+const MAX_DOWNLOAD_BYTES: usize = 4 * MAX_BYTES;
+
+/// What is done to the images before uploading them.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ImagesConfig {
+    /// Downscale and compress the images over Telegram's limits instead of giving up on them.
+    pub resize: bool,
+    /// Of the JPEGs the shrunk images are encoded as, from 1 to 100.
+    pub jpeg_quality: u8,
+}
+
+impl Default for ImagesConfig {
+    fn default() -> Self {
+        Self {
+            resize: false,
+            jpeg_quality: 85,
+        }
+    }
+}
+
+/// Why an image couldn't be downloaded.
+#[derive(Debug)]
+pub enum DownloadError {
+    Http(reqwest_middleware::Error),
+    /// The server answered with something else, e.g. an HTML error page.
+    NotAnImage(String),
+    /// Over the given number of bytes.
+    TooBig(usize),
+}
+
+impl DownloadError {
+    pub fn blaming(self, upstream: &'static str) -> CommandError {
+        match self {
+            Self::Http(error) => CommandError::Upstream { upstream, error },
+            e => CommandError::Malformed {
+                upstream,
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{}", e),
+            Self::NotAnImage(content_type) => write!(f, "'{}' is not an image", content_type),
+            Self::TooBig(max_bytes) => write!(f, "the image is bigger than {} bytes", max_bytes),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e.into())
+    }
+}
+
+/// Downloads the images that are uploaded to Telegram instead of sent by URL.
+pub struct Images {
+    client: HttpClient,
+    config: ImagesConfig,
+}
+
+impl Images {
+    pub fn new(client: HttpClient, config: &ImagesConfig) -> Self {
+        Self {
+            client,
+            config: config.clone(),
+        }
+    }
+
+    /// The image, as long as it is one and Telegram would take it, shrunk first if enabled.
+    pub async fn download(&self, url: &Url) -> Result<Vec<u8>, DownloadError> {
+        if !self.config.resize {
+            return self.fetch(url, MAX_BYTES).await;
+        }
+
+        let image = self.fetch(url, MAX_DOWNLOAD_BYTES).await?;
+        let size = image.len();
+        match shrink(&image, self.config.jpeg_quality) {
+            Ok(Some(shrunk)) => {
+                info!(
+                    "Image {} shrunk from {} to {} bytes",
+                    url,
+                    size,
+                    shrunk.len()
+                );
+                Ok(shrunk)
+            }
+            Ok(None) => Ok(image),
+            Err(e) => {
+                warn!("Could not shrink the image {} -> {}", url, e);
+                // Telegram may still make sense of it
+                if size > MAX_BYTES {
+                    return Err(DownloadError::TooBig(MAX_BYTES));
+                }
+                Ok(image)
+            }
+        }
+    }
+
+    /// The image as is, to be sent as a document that Telegram doesn't recompress.
+    pub async fn original(&self, url: &Url) -> Result<Vec<u8>, DownloadError> {
+        self.fetch(url, MAX_DOCUMENT_BYTES).await
+    }
+
+    async fn fetch(&self, url: &Url, max_bytes: usize) -> Result<Vec<u8>, DownloadError> {
+        let mut res = self
+            .client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(DownloadError::Http)?
+            .error_for_status()?;
+
+        // Plenty of servers don't tell, only the ones telling something else are refused
+        if let Some(content_type) = res
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+        {
+            if !content_type.starts_with("image/")
+                && !content_type.starts_with("application/octet-stream")
+            {
+                return Err(DownloadError::NotAnImage(content_type.to_string()));
+            }
+        }
+        if res.content_length().unwrap_or(0) > max_bytes as u64 {
+            return Err(DownloadError::TooBig(max_bytes));
+        }
+
+        // The length may be missing or wrong, stop reading as soon as it's too much
+        let mut image = Vec::new();
+        while let Some(chunk) = res.chunk().await? {
+            if image.len() + chunk.len() > max_bytes {
+                return Err(DownloadError::TooBig(max_bytes));
+            }
+            image.extend_from_slice(&chunk);
+        }
+        Ok(image)
+    }
+}
+
+/// Last segment of the path of the image, e.g. `n02085620_1558.jpg`, or the default without one.
+pub fn file_name(url: &Url, default: &str) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .unwrap_or(default)
+        .to_string()
+}
+
+/// JPEG within Telegram's limits of size and dimensions, `None` if the image already is.
+pub fn shrink(image: &[u8], quality: u8) -> Result<Option<Vec<u8>>, ImageError> {
+    let decoded = image::load_from_memory(image)?;
+    let (width, height) = decoded.dimensions();
+    if image.len() <= MAX_BYTES && width + height <= MAX_DIMENSIONS {
+        return Ok(None);
+    }
+
+    let mut scale = (MAX_DIMENSIONS as f64 / (width + height) as f64).min(1.0);
+    loop {
+        let resized = if scale < 1.0 {
+            decoded.resize(
+                ((width as f64 * scale) as u32).max(1),
+                ((height as f64 * scale) as u32).max(1),
+                FilterType::Triangle,
+            )
+        } else {
+            decoded.clone()
+        };
+        let jpeg = encode_jpeg(resized, quality)?;
+        if jpeg.len() <= MAX_BYTES {
+            return Ok(Some(jpeg));
+        }
+        // Halving the pixels roughly halves the bytes
+        scale *= 0.7;
+    }
+}
+
+fn encode_jpeg(image: DynamicImage, quality: u8) -> Result<Vec<u8>, ImageError> {
+    let mut jpeg = Vec::new();
+    // JPEGs have no alpha channel
+    DynamicImage::ImageRgb8(image.to_rgb8()).write_to(
+        &mut Cursor::new(&mut jpeg),
+        ImageOutputFormat::Jpeg(quality),
+    )?;
+    Ok(jpeg)
+}
+
+/// Whether Telegram failed to fetch a photo sent by URL, so uploading it may work.
+pub fn could_not_fetch(error: &RequestError) -> bool {
+    match error {
+        RequestError::Api(ApiError::WrongFileIdOrUrl | ApiError::FailedToGetUrlContent) => true,
+        RequestError::Api(ApiError::Unknown(description)) => {
+            description.contains("HTTP URL")
+                || description.contains("IMAGE_PROCESS_FAILED")
+                || description.contains("wrong type of the web page content")
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{
+    breed::BreedQuery,
+    commands::{DOG_CEO, PRICES},
+    error::CommandError,
+    render,
+    state::AppState,
+};
+use futures::future;
+use reqwest::Url;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    error::Error,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use teloxide::{
+    prelude::*,
+    types::{
+        InlineQueryResult, InlineQueryResultArticle, InlineQueryResultPhoto, InputMessageContent,
+        InputMessageContentText,
+    },
+};
+use tokio::sync::OnceCell;
+use tracing::{info_span, warn, Instrument};
+
+/// Dogs offered for an inline query.
+const DOGS: usize = 6;
+/// Most coins priced for an inline query.
+const MAX_SYMBOLS: usize = 10;
+
+/// How long the answers to the inline queries are reused, by the bot and by Telegram.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct InlineConfig {
+    pub dogs_cache_secs: u32,
+    pub prices_cache_secs: u32,
+}
+
+impl Default for InlineConfig {
+    fn default() -> Self {
+        Self {
+            dogs_cache_secs: 10,
+            prices_cache_secs: 30,
+        }
+    }
+}
+
+/// What `@bot <query>` asks for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InlineQueryKind {
+    /// Random dogs, of the breed if any, e.g. `@bot husky`.
+    Dogs(Option<BreedQuery>),
+    /// USD prices of the coins, e.g. `@bot $btc eth`.
+    Prices(Vec<String>),
+}
+
+impl InlineQueryKind {
+    /// `None` when the query is neither a breed nor prices.
+    pub fn parse(query: &str) -> Option<Self> {
+        let query = query.trim().to_lowercase();
+        if let Some(symbols) = query.strip_prefix('$') {
+            let mut unique: Vec<String> = Vec::new();
+            for symbol in symbols.split(|c: char| c.is_whitespace() || c == ',' || c == '$') {
+                if !symbol.is_empty() && !unique.iter().any(|known| known == symbol) {
+                    unique.push(symbol.to_string());
+                }
+            }
+            unique.truncate(MAX_SYMBOLS);
+            return if unique.is_empty() {
+                None
+            } else {
+                Some(Self::Prices(unique))
+            };
+        }
+        match query.as_str() {
+            "" | "dog" | "doggo" => Some(Self::Dogs(None)),
+            breed => BreedQuery::from_str(breed)
+                .ok()
+                .map(|breed| Self::Dogs(Some(breed))),
+        }
+    }
+
+    /// Same for every way of writing the same query, e.g. `$BTC` and `$btc`.
+    fn key(&self) -> String {
+        match self {
+            Self::Dogs(None) => "dogs".to_string(),
+            Self::Dogs(Some(breed)) => format!("dogs:{}", breed.path()),
+            Self::Prices(symbols) => format!("prices:{}", symbols.join(",")),
+        }
+    }
+}
+
+struct Entry {
+    created: Instant,
+    ttl: Duration,
+    results: Arc<OnceCell<Vec<InlineQueryResult>>>,
+}
+
+/// Results of the inline queries, generated once for all the identical queries arriving while they are fresh.
+pub struct InlineCache {
+    config: InlineConfig,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl InlineCache {
+    pub fn new(config: &InlineConfig) -> Self {
+        Self {
+            config: config.clone(),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Queries whose results are kept, fresh or not.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Seconds Telegram and the bot keep the results of the query.
+    pub fn cache_secs(&self, kind: &InlineQueryKind) -> u32 {
+        match kind {
+            InlineQueryKind::Dogs(_) => self.config.dogs_cache_secs,
+            InlineQueryKind::Prices(_) => self.config.prices_cache_secs,
+        }
+    }
+
+    /// The fresh results of the query, or the ones being generated for an identical query, or new ones.
+    ///
+    /// Failures aren't cached, the next query tries again.
+    async fn get_or_generate<F, Fut>(
+        &self,
+        kind: &InlineQueryKind,
+        generate: F,
+    ) -> Result<Vec<InlineQueryResult>, CommandError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<InlineQueryResult>, CommandError>>,
+    {
+        let results = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.retain(|_, entry| entry.created.elapsed() < entry.ttl);
+            let ttl = Duration::from_secs(self.cache_secs(kind).into());
+            entries
+                .entry(kind.key())
+                .or_insert_with(|| Entry {
+                    created: Instant::now(),
+                    ttl,
+                    results: Arc::default(),
+                })
+                .results
+                .clone()
+        };
+        results.get_or_try_init(generate).await.cloned()
+    }
+}
+
+/// Answer `@bot <query>` typed in any chat with dogs or prices.
+pub async fn answer_inline(
+    bot: AutoSend<Bot>,
+    query: InlineQuery,
+    state: Arc<AppState>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let span = info_span!(
+        "inline",
+        query = query.query.as_str(),
+        user_id = query.from.id.0
+    );
+    async move {
+        let kind = match InlineQueryKind::parse(&query.query) {
+            Some(kind) => kind,
+            None => {
+                bot.answer_inline_query(query.id, Vec::new()).await?;
+                return Ok(());
+            }
+        };
+
+        let results = state
+            .inline
+            .get_or_generate(&kind, || generate(&state, &kind))
+            .await;
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => {
+                warn!("Could not answer the inline query -> {}", e);
+                // Not cached, so the query is tried again as the user types
+                bot.answer_inline_query(query.id, Vec::new())
+                    .cache_time(0)
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        // Nothing in the results depends on who asked, Telegram can share them
+        bot.answer_inline_query(query.id, results)
+            .cache_time(state.inline.cache_secs(&kind))
+            .is_personal(false)
+            .await?;
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+async fn generate(
+    state: &AppState,
+    kind: &InlineQueryKind,
+) -> Result<Vec<InlineQueryResult>, CommandError> {
+    match kind {
+        InlineQueryKind::Dogs(breed) => {
+            let dogs = future::join_all((0..DOGS).map(|_| async {
+                match breed {
+                    Some(breed) => state.dog_api.random_for_breed(breed).await,
+                    None => state.dog_api.random().await,
+                }
+            }))
+            .await;
+
+            let mut urls = Vec::new();
+            for dog in dogs {
+                let dog = dog.map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+                if dog.status != "success" {
+                    // The breed doesn't exist
+                    break;
+                }
+                match Url::parse(&dog.message) {
+                    Ok(url) if !urls.contains(&url) => urls.push(url),
+                    Ok(_) => {}
+                    Err(e) => warn!("'{}' is not an image URL -> {}", dog.message, e),
+                }
+            }
+            Ok(urls
+                .into_iter()
+                .enumerate()
+                .map(|(index, url)| {
+                    InlineQueryResult::Photo(InlineQueryResultPhoto::new(
+                        index.to_string(),
+                        url.clone(),
+                        url,
+                    ))
+                })
+                .collect())
+        }
+        InlineQueryKind::Prices(symbols) => {
+            let prices = state.price_api.usd_prices(symbols).await.map_err(|error| {
+                CommandError::Upstream {
+                    upstream: PRICES,
+                    error,
+                }
+            })?;
+            Ok(symbols
+                .iter()
+                .filter_map(|symbol| {
+                    let price = prices.get(symbol)?;
+                    let text = render::price(symbol, Some(*price));
+                    Some(InlineQueryResult::Article(InlineQueryResultArticle::new(
+                        symbol.clone(),
+                        text.clone(),
+                        InputMessageContent::Text(InputMessageContentText::new(text)),
+                    )))
+                })
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,62 @@
+pub mod admin_api;
+pub mod alerts;
+pub mod alt_text;
+pub mod api;
+pub mod assets;
+pub mod breed;
+pub mod cache;
+pub mod calc;
+pub mod chart;
+pub mod circuit;
+pub mod color;
+pub mod commands;
+pub mod compare;
+pub mod config;
+pub mod debug;
+pub mod digest;
+pub mod discord;
+pub mod dry_run;
+pub mod error;
+pub mod health;
+pub mod holidays;
+pub mod http;
+pub mod i18n;
+pub mod images;
+pub mod inline;
+pub mod limits;
+pub mod logging;
+pub mod markdown;
+pub mod matrix;
+pub mod meme;
+pub mod messenger;
+pub mod money;
+pub mod news;
+pub mod notify;
+pub mod nsfw;
+pub mod offsets;
+pub mod prefetch;
+pub mod prefs;
+pub mod privacy;
+pub mod qr;
+pub mod queue;
+pub mod quiet;
+pub mod quota;
+pub mod random;
+pub mod reminders;
+pub mod render;
+pub mod replay;
+pub mod reporter;
+pub mod scheduler;
+pub mod server;
+pub mod settings;
+pub mod state;
+pub mod status;
+pub mod storage;
+pub mod subscriptions;
+pub mod telemetry;
+pub mod timezones;
+pub mod trivia;
+pub mod units;
+pub mod version;
+pub mod watchdog;
+pub mod weather;
@@ -0,0 +1,200 @@
+use crate::commands::CommandsConfig;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use teloxide::types::{ChatId, UserId};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+struct Slots {
+    global: Arc<Semaphore>,
+    chats: HashMap<ChatId, Arc<Semaphore>>,
+    max_per_chat: usize,
+    max_per_user: usize,
+    user_window: Duration,
+}
+
+impl Slots {
+    fn new(config: &CommandsConfig) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(config.max_concurrent)),
+            chats: HashMap::new(),
+            max_per_chat: config.max_per_chat,
+            max_per_user: config.max_per_user,
+            user_window: Duration::from_secs(config.user_window_secs),
+        }
+    }
+}
+
+/// Commands a user ran within the window, oldest first.
+#[derive(Default)]
+struct Recent {
+    commands: VecDeque<Instant>,
+    /// The user was told about their cooldown, they aren't told again until it ends.
+    noticed: bool,
+}
+
+/// A user ran too many commands lately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cooldown {
+    /// Until the user can run a command again.
+    pub remaining: Duration,
+    /// First command turned down in this cooldown, the user is told about it only once.
+    pub first: bool,
+}
+
+/// How heavy a command is to answer, each class has its own cooldown in the chat, see [`crate::settings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandClass {
+    /// A dog photo or the like.
+    Cheap,
+    /// Rendered or sent as several files, e.g. a chart or an album.
+    Expensive,
+}
+
+impl CommandClass {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cheap => "cheap",
+            Self::Expensive => "expensive",
+        }
+    }
+}
+
+/// The user ran a command of the class in the chat, they wait until `until` for the next one.
+struct Spaced {
+    until: Instant,
+    noticed: bool,
+}
+
+/// Caps how many commands run at once, in total and in each chat, and how many a user runs in a while.
+pub struct Limiter {
+    slots: Mutex<Slots>,
+    users: Mutex<HashMap<UserId, Recent>>,
+    classes: Mutex<HashMap<(ChatId, UserId, CommandClass), Spaced>>,
+}
+
+/// Slot of a running command, released when dropped.
+pub struct Permit {
+    _global: OwnedSemaphorePermit,
+    _chat: OwnedSemaphorePermit,
+}
+
+impl Limiter {
+    pub fn new(config: &CommandsConfig) -> Self {
+        Self {
+            slots: Mutex::new(Slots::new(config)),
+            users: Mutex::default(),
+            classes: Mutex::default(),
+        }
+    }
+
+    /// Apply new limits, the commands already running keep their slot.
+    pub fn reconfigure(&self, config: &CommandsConfig) {
+        *self.slots.lock().unwrap() = Slots::new(config);
+    }
+
+    /// Count a command of the user, unless they already ran as many as allowed within the window.
+    pub fn hit(&self, user_id: UserId) -> Result<(), Cooldown> {
+        let (max_per_user, window) = {
+            let slots = self.slots.lock().unwrap();
+            (slots.max_per_user, slots.user_window)
+        };
+        if max_per_user == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut users = self.users.lock().unwrap();
+        // Forget the users with nothing left in their window
+        users.retain(|_, recent| {
+            while matches!(recent.commands.front(), Some(at) if now.duration_since(*at) >= window) {
+                recent.commands.pop_front();
+            }
+            !recent.commands.is_empty()
+        });
+
+        let recent = users.entry(user_id).or_default();
+        if recent.commands.len() < max_per_user {
+            recent.commands.push_back(now);
+            recent.noticed = false;
+            return Ok(());
+        }
+
+        let oldest = recent.commands[recent.commands.len() - max_per_user];
+        let first = !recent.noticed;
+        recent.noticed = true;
+        Err(Cooldown {
+            remaining: window.saturating_sub(now.duration_since(oldest)),
+            first,
+        })
+    }
+
+    /// Count a command of the user and, if it has a class, of its class in the chat, unless the user ran as many
+    /// as allowed within the window or another one of the class less than its cooldown ago. A command turned down
+    /// counts for neither.
+    pub fn hit_command(
+        &self,
+        chat_id: ChatId,
+        user_id: UserId,
+        class: Option<(CommandClass, Duration)>,
+    ) -> Result<(), Cooldown> {
+        let (class, cooldown) = match class {
+            Some((class, cooldown)) if !cooldown.is_zero() => (class, cooldown),
+            _ => return self.hit(user_id),
+        };
+
+        let now = Instant::now();
+        let mut classes = self.classes.lock().unwrap();
+        // Forget the cooldowns already over
+        classes.retain(|_, spaced| spaced.until > now);
+
+        if let Some(spaced) = classes.get_mut(&(chat_id, user_id, class)) {
+            let first = !spaced.noticed;
+            spaced.noticed = true;
+            return Err(Cooldown {
+                remaining: spaced.until - now,
+                first,
+            });
+        }
+        self.hit(user_id)?;
+        classes.insert(
+            (chat_id, user_id, class),
+            Spaced {
+                until: now + cooldown,
+                noticed: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Wait for a free slot, or give up right away if the chat already has too many commands running.
+    pub async fn acquire(&self, chat_id: ChatId) -> Option<Permit> {
+        let (global, chat) = {
+            let mut slots = self.slots.lock().unwrap();
+            let max_per_chat = slots.max_per_chat;
+            // Forget the chats with nothing running
+            slots
+                .chats
+                .retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+            let chat = slots
+                .chats
+                .entry(chat_id)
+                .or_insert_with(|| Arc::new(Semaphore::new(max_per_chat)))
+                .clone();
+            (slots.global.clone(), chat)
+        };
+
+        let chat = chat.try_acquire_owned().ok()?;
+        let global = global
+            .acquire_owned()
+            .await
+            .expect("the global semaphore is never closed");
+
+        Some(Permit {
+            _global: global,
+            _chat: chat,
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use crate::telemetry::{self, TelemetryConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use serde::Deserialize;
+use std::fs;
+use tracing::{error, Level};
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, reload, Registry};
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line, for Loki/ELK.
+    Json,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    /// `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: String,
+    /// Also write the logs to daily rotated files.
+    pub file: Option<LogFileConfig>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Text,
+            level: "info".to_string(),
+            file: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LogFileConfig {
+    pub directory: String,
+    /// Files are named `<prefix>.<date>.log`.
+    pub prefix: String,
+    /// How many files to keep, older ones are deleted.
+    pub max_files: usize,
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        Self {
+            directory: "logs".to_string(),
+            prefix: "dog-bot".to_string(),
+            max_files: 7,
+        }
+    }
+}
+
+/// Changes the level of the installed subscriber.
+#[derive(Clone)]
+pub struct LevelHandle(reload::Handle<LevelFilter, Registry>);
+
+impl LevelHandle {
+    pub fn set(&self, level: &str) {
+        if let Err(e) = self.0.reload(LevelFilter::from_level(parse_level(level))) {
+            error!("Could not change the log level -> {}", e);
+        }
+    }
+}
+
+/// Keeps the log file and the trace exporter alive, flushing them when dropped.
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+    _sentry: Option<sentry::ClientInitGuard>,
+    tracer_provider: Option<SdkTracerProvider>,
+    level: LevelHandle,
+}
+
+impl LogGuard {
+    pub fn level(&self) -> LevelHandle {
+        self.level.clone()
+    }
+}
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Could not flush the traces: {}", e);
+            }
+        }
+    }
+}
+
+fn parse_level(level: &str) -> Level {
+    level.parse().unwrap_or(Level::INFO)
+}
+
+/// Install the global subscriber.
+///
+/// The returned guard must live as long as the bot.
+pub fn init(config: &LogConfig, telemetry: &TelemetryConfig) -> LogGuard {
+    let (level, level_handle) =
+        reload::Layer::new(LevelFilter::from_level(parse_level(&config.level)));
+
+    let (file_writer, guard) = match &config.file {
+        Some(file) => {
+            fs::create_dir_all(&file.directory).expect("could not create the log directory");
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(&file.prefix)
+                .filename_suffix("log")
+                .max_log_files(file.max_files)
+                .build(&file.directory)
+                .expect("could not create the log file");
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(writer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let sentry = telemetry::init_sentry(telemetry);
+    let tracer_provider = telemetry::tracer_provider(telemetry);
+
+    let registry = tracing_subscriber::registry()
+        .with(level)
+        .with(sentry.as_ref().map(|_| sentry_tracing::layer()))
+        .with(tracer_provider.as_ref().map(telemetry::layer));
+
+    let result = match config.format {
+        LogFormat::Text => registry
+            .with(fmt::layer())
+            .with(file_writer.map(|writer| fmt::layer().with_ansi(false).with_writer(writer)))
+            .try_init(),
+        LogFormat::Json => registry
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(false),
+            )
+            .with(file_writer.map(|writer| {
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(false)
+                    .with_writer(writer)
+            }))
+            .try_init(),
+    };
+
+    result.expect("setting default subscriber failed");
+
+    LogGuard {
+        _file: guard,
+        _sentry: sentry,
+        tracer_provider,
+        level: LevelHandle(level_handle),
+    }
+}
@@ -1,195 +1,479 @@
+use dog_bot::{
+    alerts::{self, Edits, PriceHistory},
+    api::{
+        apod::{ApodApi, Nasa},
+        breed_info::{BreedInfoApi, TheDogApi},
+        defi::{DefiApi, DefiLlama},
+        dictionary::{DictionaryApi, FreeDictionary},
+        dog::{CachedDogApi, DogApi, DogCeo, MockDogApi},
+        fx::{Frankfurter, FxApi},
+        holidays::{HolidaysApi, NagerDate},
+        joke::{JokeApi, JokeApiDev},
+        market::{CoinGeckoMarket, MarketApi},
+        news::{NewsApi, Rss},
+        nft::{CoinGeckoNfts, NftApi, OpenSea},
+        pokemon::{PokeApi, PokemonApi},
+        price::{Binance, CachedPriceApi, CoinGecko, MockPriceApi, PriceApi, PriceBackend},
+        quote::{Quotable, QuoteApi},
+        shortener::{Shlink, ShortenerApi},
+        stock::{StockApi, YahooFinance},
+        translate::{LibreTranslate, TranslateApi},
+        trivia::{OpenTriviaDb, TriviaApi},
+        urban::{UrbanApi, UrbanDictionary},
+        weather::{OpenMeteo, WeatherApi},
+        wiki::{WikiApi, Wikipedia},
+        xkcd::{Xkcd, XkcdApi},
+    },
+    assets::Assets,
+    cache::{self, FileIds},
+    commands,
+    config::Config,
+    debug::{self, Debug},
+    dry_run,
+    health::{self, Health},
+    http::{self, HttpClient},
+    images::Images,
+    inline::InlineCache,
+    limits::Limiter,
+    logging,
+    offsets::UpdateOffsets,
+    prefetch::{self, DogBuffer},
+    reminders,
+    replay::{self, Recorder},
+    reporter::{self, ErrorReporter},
+    scheduler::Scheduler,
+    server,
+    state::AppState,
+    storage,
+    subscriptions::{self, Subscriptions},
+    trivia::Rounds,
+    watchdog,
+};
+use metrics_exporter_prometheus::PrometheusBuilder;
 use reqwest::Url;
-use serde::Deserialize;
-use std::fmt::Write;
-use std::{collections::HashMap, error::Error, str::FromStr};
-use teloxide::{prelude::*, types::InputFile, utils::command::BotCommands};
-use tracing::{error, info, Level};
-use tracing_subscriber::FmtSubscriber;
-
-#[derive(Deserialize)]
-struct DogResponse<T> {
-    message: T,
-    status: String,
-}
+use std::{
+    env,
+    path::PathBuf,
+    process,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use teloxide::prelude::*;
+use tracing::{error, info};
 
-#[derive(Deserialize)]
-struct GoingeckoCoinValue {
-    usd: f32,
-}
+#[tokio::main]
+async fn main() {
+    let config = Config::load().expect("could not load the config");
 
-async fn get_random_dog() -> Result<DogResponse<String>, reqwest::Error> {
-    reqwest::get("https://dog.ceo/api/breeds/image/random")
-        .await?
-        .json::<DogResponse<String>>()
-        .await
-}
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("debug") {
+        return print_debug(&config, &args[1..]).await;
+    }
 
-type BreedsList = HashMap<String, Vec<String>>;
-async fn get_list_of_breeds() -> Result<DogResponse<BreedsList>, reqwest::Error> {
-    reqwest::get("https://dog.ceo/api/breeds/list/all")
-        .await?
-        .json::<DogResponse<BreedsList>>()
-        .await
-}
+    let log_guard = logging::init(&config.log, &config.telemetry);
+
+    info!("Starting the bot...");
+
+    let telegram_url = Url::parse(&config.api.telegram).expect("invalid Telegram API URL");
+    let telegram_client = http::telegram_client(&config.http);
+    // A replay answers the recorded updates, which must not reach their chats again
+    let replaying = args.first().map(String::as_str) == Some("replay");
+    let config = if replaying {
+        replay::isolate(config)
+    } else {
+        config
+    };
+    let telegram_url = if replaying || config.dry_run || args.iter().any(|arg| arg == "--dry-run") {
+        dry_run::start(telegram_client.clone(), telegram_url)
+            .await
+            .expect("could not start the dry run")
+    } else {
+        telegram_url
+    };
+    let bots = if config.bots.is_empty() {
+        vec![(
+            "default".to_string(),
+            Bot::from_env_with_client(telegram_client),
+        )]
+    } else {
+        config
+            .bots
+            .iter()
+            .map(|bot| {
+                let client = telegram_client.clone();
+                (bot.name.clone(), Bot::with_client(&bot.token, client))
+            })
+            .collect()
+    };
+    let bots = bots
+        .into_iter()
+        .map(|(name, bot)| (name, bot.set_api_url(telegram_url.clone()).auto_send()))
+        .collect::<Vec<_>>();
+
+    let health = Arc::new(Health::default());
+
+    let metrics = match PrometheusBuilder::new().install_recorder() {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+            error!("Could not record the metrics -> {}", e);
+            None
+        }
+    };
+
+    let client = if replaying {
+        http::local_client(&config.http, health.clone())
+    } else {
+        http::client(&config.http, health.clone())
+    };
 
-async fn get_random_dog_from_breed(breed: &str) -> Result<DogResponse<String>, reqwest::Error> {
-    let breed = breed.to_lowercase();
-    let breed = breed
-        .split_whitespace()
-        .rev()
-        .collect::<Vec<&str>>()
-        .join("/");
-    reqwest::get(format!("https://dog.ceo/api/breed/{}/images/random", breed))
-        .await?
-        .json::<DogResponse<String>>()
+    let cache = cache::connect(&config.cache)
         .await
-}
+        .expect("could not connect to the cache");
+
+    // A replay answers with the offline mocks, the other upstreams only when mocked on this machine
+    let dog_api: Arc<dyn DogApi> = if replaying {
+        Arc::new(MockDogApi::default())
+    } else {
+        Arc::new(CachedDogApi::new(
+            Arc::new(DogCeo::with_base_url(client.clone(), &config.api.dog_ceo)),
+            cache.clone(),
+            Duration::from_secs(config.cache.breeds_ttl_secs),
+        ))
+    };
+
+    let breed_info_api: Arc<dyn BreedInfoApi> = Arc::new(TheDogApi::with_base_url(
+        client.clone(),
+        &config.api.thedogapi,
+        config.api.thedogapi_api_key.clone(),
+    ));
+
+    let weather_api: Arc<dyn WeatherApi> = Arc::new(OpenMeteo::with_base_urls(
+        client.clone(),
+        &config.api.open_meteo,
+        &config.api.open_meteo_geocoding,
+    ));
+
+    let joke_api: Arc<dyn JokeApi> = Arc::new(JokeApiDev::with_base_url(
+        client.clone(),
+        &config.api.jokeapi,
+        config.api.jokeapi_safe_mode,
+    ));
+
+    let quote_api: Arc<dyn QuoteApi> = Arc::new(Quotable::with_base_url(
+        client.clone(),
+        &config.api.quotable,
+    ));
+
+    let xkcd_api: Arc<dyn XkcdApi> =
+        Arc::new(Xkcd::with_base_url(client.clone(), &config.api.xkcd));
+
+    let apod_api: Arc<dyn ApodApi> = Arc::new(Nasa::with_base_url(
+        client.clone(),
+        &config.api.nasa,
+        &config.api.nasa_api_key,
+    ));
 
-async fn get_euro_usd() -> Result<Option<f32>, reqwest::Error> {
-    let res = reqwest::get(
-        "https://api.coingecko.com/api/v3/simple/price?ids=tether-eurt&vs_currencies=usd",
-    )
-    .await?
-    .json::<HashMap<String, GoingeckoCoinValue>>()
-    .await?;
+    let wiki_api: Arc<dyn WikiApi> = Arc::new(Wikipedia::with_base_url(
+        client.clone(),
+        &config.api.wikipedia,
+    ));
+
+    let dictionary_api: Arc<dyn DictionaryApi> = Arc::new(FreeDictionary::with_base_url(
+        client.clone(),
+        &config.api.dictionaryapi,
+    ));
+
+    let stock_api: Arc<dyn StockApi> = Arc::new(YahooFinance::with_base_url(
+        client.clone(),
+        &config.api.yahoo_finance,
+    ));
+
+    let urban_api: Arc<dyn UrbanApi> = Arc::new(UrbanDictionary::with_base_url(
+        client.clone(),
+        &config.api.urban_dictionary,
+    ));
+
+    let trivia_api: Arc<dyn TriviaApi> = Arc::new(OpenTriviaDb::with_base_url(
+        client.clone(),
+        &config.api.opentdb,
+    ));
+
+    let pokemon_api: Arc<dyn PokemonApi> =
+        Arc::new(PokeApi::with_base_url(client.clone(), &config.api.pokeapi));
+
+    let holidays_api: Arc<dyn HolidaysApi> = Arc::new(NagerDate::with_base_url(
+        client.clone(),
+        &config.api.nager_date,
+    ));
+
+    let news_api: Arc<dyn NewsApi> = Arc::new(Rss::with_default_feed(
+        client.clone(),
+        &config.api.news_feed,
+    ));
+
+    let translate_api = config.api.libretranslate.as_ref().map(|url| {
+        Arc::new(LibreTranslate::new(
+            client.clone(),
+            url,
+            config.api.libretranslate_api_key.clone(),
+        )) as Arc<dyn TranslateApi>
+    });
+
+    let shortener_api = config.api.shlink.as_ref().map(|url| {
+        Arc::new(Shlink::new(client.clone(), url, &config.api.shlink_api_key))
+            as Arc<dyn ShortenerApi>
+    });
+
+    let nft_api: Arc<dyn NftApi> = match &config.api.opensea_api_key {
+        Some(api_key) => Arc::new(OpenSea::with_base_url(
+            client.clone(),
+            &config.api.opensea,
+            api_key,
+        )),
+        None => Arc::new(CoinGeckoNfts::with_base_url(
+            client.clone(),
+            &config.api.coingecko,
+        )),
+    };
 
-    let euro = res.get("tether-eurt");
+    let market_api: Arc<dyn MarketApi> = Arc::new(CoinGeckoMarket::with_base_url(
+        client.clone(),
+        &config.api.coingecko,
+    ));
 
-    if let Some(euro) = euro {
-        Ok(Some(euro.usd))
+    let fx_api: Arc<dyn FxApi> = Arc::new(Frankfurter::with_base_url(
+        client.clone(),
+        &config.api.frankfurter,
+    ));
+
+    let defi_api: Arc<dyn DefiApi> = Arc::new(DefiLlama::with_base_url(
+        client.clone(),
+        &config.api.defillama,
+    ));
+
+    let images = Images::new(client.clone(), &config.images);
+
+    let price_api: Arc<dyn PriceApi> = if replaying {
+        Arc::new(match env::var("PRICE_FIXTURE") {
+            Ok(fixture) => {
+                MockPriceApi::from_fixture(fixture).expect("could not load PRICE_FIXTURE")
+            }
+            Err(_) => MockPriceApi::default(),
+        })
     } else {
-        Ok(None)
-    }
-}
+        Arc::new(CachedPriceApi::new(
+            price_api(&config, client),
+            cache.clone(),
+            Duration::from_secs(config.cache.price_ttl_secs),
+        ))
+    };
 
-#[derive(BotCommands, Clone)]
-#[command(rename = "lowercase", description = "These commands are supported:")]
-enum Command {
-    #[command(description = "Random dog")]
-    Doggo,
+    let storage = storage::connect(&config.storage)
+        .await
+        .expect("could not open the storage");
 
-    #[command(description = "Random dog from the specified breed")]
-    Breed(String),
+    // Alerts and subscriptions are delivered by the first bot
+    let reporter = Arc::new(ErrorReporter::new(bots[0].1.clone(), config.admin.clone()));
+    reporter::install_panic_hook(reporter.clone());
+    let subscriptions = Subscriptions::new(bots[0].1.clone(), &config.queue);
 
-    #[command(description = "List the breeds of dogs")]
-    Breeds,
+    let state = Arc::new(AppState {
+        dog_api,
+        breed_info_api,
+        price_api,
+        nft_api,
+        market_api,
+        fx_api,
+        defi_api,
+        stock_api,
+        weather_api,
+        joke_api,
+        quote_api,
+        xkcd_api,
+        apod_api,
+        wiki_api,
+        dictionary_api,
+        urban_api,
+        trivia_api,
+        pokemon_api,
+        holidays_api,
+        news_api,
+        translate_api,
+        shortener_api,
+        reporter,
+        health: health.clone(),
+        scheduler: Scheduler::new(storage.clone()),
+        storage,
+        price_history: PriceHistory::new(cache.clone(), &config.alerts),
+        alert_edits: Edits::default(),
+        file_ids: FileIds::new(cache, &config.cache),
+        images,
+        assets: Assets::load(&config.assets).expect("could not read the assets"),
+        dogs: DogBuffer::new(config.prefetch.dogs),
+        subscriptions,
+        inline: InlineCache::new(&config.inline),
+        commands: RwLock::new(config.commands.clone()),
+        limiter: Limiter::new(&config.commands),
+        trivia: Rounds::default(),
+        log_level: Some(log_guard.level()),
+        metrics,
+        upload_images: config.api.telegram_local,
+    });
 
-    #[command(description = "Get the value of EURO in USD")]
-    Euro,
-}
+    if replaying {
+        return replay_file(&args[1..], bots[0].1.clone(), state).await;
+    }
 
-#[tokio::main]
-async fn main() {
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
+    match subscriptions::load(&state).await {
+        Ok(count) => info!("Delivering {} subscriptions", count),
+        Err(e) => error!("Could not load the subscriptions -> {}", e),
+    }
+    match reminders::load(&state).await {
+        Ok(count) => info!("Waiting for {} reminders", count),
+        Err(e) => error!("Could not load the reminders -> {}", e),
+    }
+    alerts::start(&state, &config.alerts);
+    prefetch::start(&state, &config.prefetch);
+    watchdog::start(&state, &config.admin);
+    #[cfg(feature = "discord")]
+    tokio::spawn(dog_bot::discord::run(config.discord.clone(), state.clone()));
+    #[cfg(feature = "matrix")]
+    tokio::spawn(dog_bot::matrix::run(config.matrix.clone(), state.clone()));
 
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    if let Some(addr) = config.server.listen {
+        tokio::spawn(server::serve(addr, state.clone(), config.server.clone()));
+    }
 
-    info!("Starting the bot...");
+    let record = args
+        .iter()
+        .position(|arg| arg == "--record")
+        .and_then(|index| args.get(index + 1))
+        .map(PathBuf::from)
+        .or_else(|| config.record.clone());
+    let recorder = match record {
+        Some(path) => {
+            info!("Recording the updates to {}", path.display());
+            Some(Arc::new(
+                Recorder::open(&path)
+                    .await
+                    .expect("could not open the recording"),
+            ))
+        }
+        None => None,
+    };
 
-    let bot = Bot::from_env().auto_send();
+    let dispatchers = bots
+        .into_iter()
+        .map(|(name, bot)| {
+            tokio::spawn(health::watch_telegram(
+                bot.clone(),
+                name.clone(),
+                health.clone(),
+            ));
+            tokio::spawn(dispatch(name, bot, state.clone(), recorder.clone()))
+        })
+        .collect::<Vec<_>>();
 
-    teloxide::commands_repl(bot, answer, Command::ty()).await;
+    for dispatcher in dispatchers {
+        if let Err(e) = dispatcher.await {
+            error!("A dispatcher stopped unexpectedly -> {}", e);
+        }
+    }
 }
 
-async fn answer(
-    bot: AutoSend<Bot>,
-    message: Message,
-    command: Command,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    match command {
-        Command::Breeds => {
-            info!("Fetching a the list of dogs...");
-
-            let breeds = get_list_of_breeds().await;
-
-            if let Ok(breeds) = breeds {
-                if breeds.status == "success" {
-                    let mut msg = String::new();
-
-                    for (key, value) in breeds.message.iter() {
-                        writeln!(msg, "-│ {}", key).unwrap();
-                        for variant in value {
-                            writeln!(msg, "     |> {}", variant).unwrap();
-                        }
-                    }
-
-                    let res = bot.send_message(message.from().unwrap().id, msg).await;
-                    if let Err(e) = res {
-                        error!("Error while sending message {:?} ", e);
-                    } else {
-                        info!("Dog sent with success");
-                    }
-                } else {
-                    error!("Could not get the list of breeds");
-                }
-            } else {
-                error!("Could not get the list of breeds");
-            }
+/// The backend picked by `PRICE_BACKEND`, CoinGecko by default.
+fn price_api(config: &Config, client: HttpClient) -> Arc<dyn PriceApi> {
+    let price_backend = env::var("PRICE_BACKEND")
+        .map(|backend| PriceBackend::from_str(&backend).expect("invalid PRICE_BACKEND"))
+        .unwrap_or(PriceBackend::CoinGecko);
+
+    match price_backend {
+        PriceBackend::CoinGecko => {
+            Arc::new(CoinGecko::with_base_url(client, &config.api.coingecko))
         }
-        Command::Doggo => {
-            info!("Fetching a random dog...");
-
-            let dog = get_random_dog().await;
-
-            if let Ok(dog) = dog {
-                if dog.status == "success" {
-                    let url = Url::from_str(&dog.message).unwrap();
-                    let res = bot.send_photo(message.chat.id, InputFile::url(url)).await;
-                    if let Err(e) = res {
-                        error!("Error while sending message {:?} ", e);
-                    } else {
-                        info!("Dog sent with success");
-                    }
-                } else {
-                    error!("Could not find a dog");
-                }
-            } else {
-                error!("Could not find a dog");
-            }
+        PriceBackend::Binance => Arc::new(Binance::with_base_url(client, &config.api.binance)),
+        PriceBackend::Mock => {
+            let fixture =
+                env::var("PRICE_FIXTURE").expect("PRICE_FIXTURE is required by the mock backend");
+            Arc::new(MockPriceApi::from_fixture(fixture).expect("could not load PRICE_FIXTURE"))
         }
-        Command::Euro => {
-            let euro = get_euro_usd().await;
-
-            if let Ok(Some(euro)) = euro {
-                let res = bot
-                    .send_message(message.chat.id, format!("${}", euro))
-                    .await;
-                if let Err(e) = res {
-                    error!("Error while sending message {:?} ", e);
-                } else {
-                    info!("Dog sent with success");
-                }
-            } else if let Err(e) = euro {
-                error!("Could not fetch the value of Euro -> {}", e);
-            }
+    }
+}
+
+/// `dog-bot debug ...`, printing the message the bot would send without connecting to Telegram.
+async fn print_debug(config: &Config, args: &[String]) {
+    let subcommand = match Debug::parse(args) {
+        Ok(subcommand) => subcommand,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, debug::USAGE);
+            process::exit(2);
         }
-        Command::Breed(breed) => {
-            info!("Fetching a random dog of breed {}...", breed);
-
-            let dog = get_random_dog_from_breed(&breed).await;
-
-            if let Ok(dog) = dog {
-                if dog.status == "success" {
-                    let url = Url::from_str(&dog.message).unwrap();
-                    let res = bot.send_photo(message.chat.id, InputFile::url(url)).await;
-                    if let Err(e) = res {
-                        error!("Error while sending message {:?} ", e);
-                    } else {
-                        info!("Dog sent with success");
-                    }
-                } else {
-                    error!("Could not find a dog");
-                    bot.send_message(message.chat.id, format!("Breed '{}' doesn't exist", breed))
-                        .await
-                        .ok();
-                }
-            } else {
-                error!("Could not find a dog");
-            }
+    };
+    let client = http::client(&config.http, Arc::default());
+    let dog_api = DogCeo::with_base_url(client.clone(), &config.api.dog_ceo);
+    let price_api = price_api(config, client);
+
+    match debug::run(&subcommand, &dog_api, price_api.as_ref()).await {
+        Ok(preview) => println!("{}", preview),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// `dog-bot replay <file> [username]`, feeding the recorded updates through the handlers without sending anything.
+async fn replay_file(args: &[String], bot: AutoSend<Bot>, state: Arc<AppState>) {
+    let path = match args.first() {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: dog-bot replay <updates.jsonl> [bot username]");
+            process::exit(2);
+        }
+    };
+    let updates = match replay::load(path) {
+        Ok(updates) => updates,
+        Err(e) => {
+            eprintln!("Could not read {} -> {}", path, e);
+            process::exit(1);
         }
     };
+    let username = args.get(1).map_or(replay::DEFAULT_USERNAME, String::as_str);
+
+    let count = updates.len();
+    let failed = replay::replay(updates, bot, replay::me(username), state).await;
+    println!("Replayed {} updates, {} failed", count, failed);
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+/// Answer the commands sent to the bot until Ctrl+C.
+async fn dispatch(
+    name: String,
+    bot: AutoSend<Bot>,
+    state: Arc<AppState>,
+    recorder: Option<Arc<Recorder>>,
+) {
+    info!("Dispatching the updates of the {} bot", name);
+
+    let offsets = Arc::new(UpdateOffsets::load(state.storage.clone(), &name).await);
+    let saving = tokio::spawn(offsets.clone().keep_saved());
+    let handler = offsets.clone().handler(commands::handler());
+    let handler = match recorder {
+        Some(recorder) => recorder.handler(handler),
+        None => handler,
+    };
+
+    Dispatcher::builder(bot, handler)
+        .dependencies(dptree::deps![state])
+        .default_handler(|_| async {})
+        .build()
+        .setup_ctrlc_handler()
+        .dispatch()
+        .await;
 
-    Ok(())
+    saving.abort();
+    offsets.save().await;
 }
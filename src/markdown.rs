@@ -0,0 +1,66 @@
+//! Text sent with the MarkdownV2 parse mode, where an unescaped `.`, `-` or `!` makes Telegram refuse the message.
+//!
+//! Breed names, coins, quotes or anything else coming from the users or the upstreams only become an
+//! [`EscapedText`] escaped, and [`send_message`] only takes one, so interpolating them can't break a reply. Replies
+//! without a parse mode are sent as they are and need none of this.
+
+use std::{fmt, ops::Add};
+use teloxide::{prelude::*, types::ParseMode, utils::markdown};
+
+/// Text safe to send as MarkdownV2.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EscapedText(String);
+
+impl EscapedText {
+    /// The text shown as is, whatever characters it has.
+    pub fn escape(text: &str) -> Self {
+        Self(markdown::escape(text))
+    }
+
+    /// Markup written by the bot, already escaped, e.g. `"\n— "`. Only literals, so no text can sneak in unescaped.
+    pub fn markup(markup: &'static str) -> Self {
+        Self(markup.to_string())
+    }
+
+    pub fn bold(self) -> Self {
+        Self(markdown::bold(&self.0))
+    }
+
+    pub fn italic(self) -> Self {
+        Self(markdown::italic(&self.0))
+    }
+
+    /// Hidden until tapped.
+    pub fn spoiler(self) -> Self {
+        Self(format!("||{}||", self.0))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Add for EscapedText {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.0.push_str(&other.0);
+        self
+    }
+}
+
+impl fmt::Display for EscapedText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Send the text to the chat as MarkdownV2.
+pub fn send_message(
+    bot: &AutoSend<Bot>,
+    chat_id: ChatId,
+    text: EscapedText,
+) -> <AutoSend<Bot> as Requester>::SendMessage {
+    bot.send_message(chat_id, text.0)
+        .parse_mode(ParseMode::MarkdownV2)
+}
@@ -0,0 +1,263 @@
+//! Matrix adapter, answering the [`Portable`](crate::messenger::Portable) commands in the rooms the account is in.
+//!
+//! Built with the `matrix` feature, the settings are read either way so the same file serves both builds.
+
+use reqwest::Url;
+use serde::Deserialize;
+
+/// Account on a Matrix homeserver, logged in with an existing access token.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MatrixConfig {
+    /// e.g. `https://matrix.org`, the adapter is disabled without it.
+    pub homeserver: Option<Url>,
+    /// e.g. `@dogbot:matrix.org`.
+    pub user_id: Option<String>,
+    /// Device the access token was issued for.
+    pub device_id: Option<String>,
+    pub access_token: Option<String>,
+    /// Start of the commands, e.g. `!doggo`.
+    pub prefix: String,
+}
+
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        Self {
+            homeserver: None,
+            user_id: None,
+            device_id: None,
+            access_token: None,
+            prefix: "!".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "matrix")]
+pub use adapter::{run, MatrixChat};
+
+#[cfg(feature = "matrix")]
+mod adapter {
+    use super::MatrixConfig;
+    use crate::{
+        commands::DOG_CEO,
+        error::CommandError,
+        messenger::{self, Chat, Portable},
+        state::AppState,
+    };
+    use async_trait::async_trait;
+    use image::ImageFormat;
+    use matrix_sdk::{
+        attachment::AttachmentConfig,
+        config::SyncSettings,
+        matrix_auth::{MatrixSession, MatrixSessionTokens},
+        ruma::{
+            events::room::{
+                member::StrippedRoomMemberEvent,
+                message::{MessageType, OriginalSyncRoomMessageEvent, RoomMessageEventContent},
+            },
+            OwnedDeviceId, OwnedUserId,
+        },
+        Client, Room, RoomState, SessionMeta,
+    };
+    use reqwest::Url;
+    use std::sync::Arc;
+    use tracing::{error, info, info_span, warn, Instrument};
+
+    const PLATFORM: &str = "matrix";
+
+    /// A Matrix room, answered by the account.
+    pub struct MatrixChat {
+        room: Room,
+        state: Arc<AppState>,
+    }
+
+    impl MatrixChat {
+        /// The photos are downloaded with the images of the state, Matrix only shows the uploaded ones.
+        pub fn new(room: Room, state: Arc<AppState>) -> Self {
+            Self { room, state }
+        }
+    }
+
+    fn failed(e: matrix_sdk::Error) -> CommandError {
+        CommandError::Platform {
+            platform: PLATFORM,
+            reason: e.to_string(),
+        }
+    }
+
+    #[async_trait]
+    impl Chat for MatrixChat {
+        fn platform(&self) -> &'static str {
+            PLATFORM
+        }
+
+        async fn send_text(&self, text: &str) -> Result<(), CommandError> {
+            self.room
+                .send(RoomMessageEventContent::text_plain(text))
+                .await
+                .map_err(failed)?;
+            Ok(())
+        }
+
+        async fn send_photo(&self, url: &Url, caption: Option<&str>) -> Result<(), CommandError> {
+            let image = self
+                .state
+                .images
+                .download(url)
+                .await
+                .map_err(|e| e.blaming(DOG_CEO))?;
+            let content_type = match image::guess_format(&image) {
+                Ok(ImageFormat::Png) => mime::IMAGE_PNG,
+                Ok(ImageFormat::Gif) => mime::IMAGE_GIF,
+                _ => mime::IMAGE_JPEG,
+            };
+            let name = url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .unwrap_or("dog.jpg")
+                .to_string();
+            self.room
+                .send_attachment(&name, &content_type, image, AttachmentConfig::new())
+                .await
+                .map_err(failed)?;
+            if let Some(caption) = caption {
+                self.send_text(caption).await?;
+            }
+            Ok(())
+        }
+    }
+
+    async fn answer(
+        event: OriginalSyncRoomMessageEvent,
+        room: Room,
+        client: Client,
+        state: Arc<AppState>,
+        prefix: Arc<String>,
+    ) {
+        if room.state() != RoomState::Joined || Some(&*event.sender) == client.user_id() {
+            return;
+        }
+        let body = match &event.content.msgtype {
+            MessageType::Text(text) => text.body.as_str(),
+            _ => return,
+        };
+        let text = match body.strip_prefix(prefix.as_str()) {
+            Some(text) => text.trim(),
+            None => return,
+        };
+        let (name, args) = text.split_once(' ').unwrap_or((text, ""));
+        let chat = MatrixChat::new(room.clone(), state.clone());
+        let command = match Portable::parse(name, args) {
+            Some(command) => command,
+            None if name.eq_ignore_ascii_case("help") => {
+                if let Err(e) = chat.send_text(&Portable::help(&prefix)).await {
+                    warn!("Could not send the help -> {}", e);
+                }
+                return;
+            }
+            None => return,
+        };
+
+        let span = info_span!(
+            "command",
+            platform = PLATFORM,
+            command = name,
+            room_id = %room.room_id()
+        );
+        if let Err(e) = messenger::run(&state, &chat, command)
+            .instrument(span.clone())
+            .await
+        {
+            span.in_scope(|| error!("Could not answer the command -> {}", e));
+            if let Some(upstream) = e.upstream() {
+                state.reporter.failure(upstream, &e).await;
+            }
+            if let Some(text) = e.user_message() {
+                chat.send_text(text).await.ok();
+            }
+        }
+    }
+
+    /// Accept the invites to the rooms, so the bot can be added like on the other platforms.
+    async fn join(event: StrippedRoomMemberEvent, room: Room, client: Client) {
+        if Some(&*event.state_key) != client.user_id() {
+            return;
+        }
+        match room.join().await {
+            Ok(_) => info!("Joined the Matrix room {}", room.room_id()),
+            Err(e) => warn!("Could not join the Matrix room {} -> {}", room.room_id(), e),
+        }
+    }
+
+    /// Answer the commands sent on Matrix until the connection is lost for good.
+    pub async fn run(config: MatrixConfig, state: Arc<AppState>) {
+        let (homeserver, user_id, device_id, access_token) = match (
+            config.homeserver,
+            config.user_id,
+            config.device_id,
+            config.access_token,
+        ) {
+            (Some(homeserver), Some(user_id), Some(device_id), Some(access_token)) => {
+                (homeserver, user_id, device_id, access_token)
+            }
+            (None, ..) => return,
+            _ => {
+                error!("The Matrix adapter needs the user_id, device_id and access_token");
+                return;
+            }
+        };
+        let user_id = match OwnedUserId::try_from(user_id) {
+            Ok(user_id) => user_id,
+            Err(e) => {
+                error!("Invalid Matrix user id -> {}", e);
+                return;
+            }
+        };
+
+        let client = match Client::builder().homeserver_url(homeserver).build().await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("Could not reach the Matrix homeserver -> {}", e);
+                return;
+            }
+        };
+        let session = MatrixSession {
+            meta: SessionMeta {
+                user_id,
+                device_id: OwnedDeviceId::from(device_id),
+            },
+            tokens: MatrixSessionTokens {
+                access_token,
+                refresh_token: None,
+            },
+        };
+        if let Err(e) = client.restore_session(session).await {
+            error!("Could not log in to Matrix -> {}", e);
+            return;
+        }
+
+        // The messages sent while the bot was away are skipped
+        let synced = match client.sync_once(SyncSettings::default()).await {
+            Ok(synced) => synced,
+            Err(e) => {
+                error!("Could not sync with Matrix -> {}", e);
+                return;
+            }
+        };
+        let prefix = Arc::new(config.prefix);
+        client.add_event_handler(join);
+        client.add_event_handler(
+            move |event: OriginalSyncRoomMessageEvent, room: Room, client: Client| {
+                answer(event, room, client, state.clone(), prefix.clone())
+            },
+        );
+
+        info!("Answering the commands sent on Matrix");
+        if let Err(e) = client
+            .sync(SyncSettings::default().token(synced.next_batch))
+            .await
+        {
+            error!("Matrix sync stopped -> {}", e);
+        }
+    }
+}
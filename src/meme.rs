@@ -0,0 +1,60 @@
+use image::{DynamicImage, ImageError, ImageOutputFormat, Rgb, RgbImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use rusttype::{Font, Scale};
+use std::io::Cursor;
+
+/// DejaVu Sans Bold, free to redistribute, see https://dejavu-fonts.github.io/License.html
+const FONT: &[u8] = include_bytes!("../assets/DejaVuSans-Bold.ttf");
+
+/// Smallest text, in pixels, longer captions overflow instead of shrinking further.
+const MIN_SIZE: f32 = 16.0;
+
+/// Top and bottom captions from `top | bottom`, either can be empty.
+pub fn captions(args: &str) -> (String, String) {
+    let (top, bottom) = args.split_once('|').unwrap_or((args, ""));
+    (top.trim().to_uppercase(), bottom.trim().to_uppercase())
+}
+
+/// JPEG of the image with the captions over its top and bottom, white with a black outline.
+pub fn render(image: &[u8], top: &str, bottom: &str) -> Result<Vec<u8>, ImageError> {
+    let font = Font::try_from_bytes(FONT).expect("the bundled font is valid");
+    let mut image = image::load_from_memory(image)?.to_rgb8();
+
+    let margin = image.height() as i32 / 30;
+    if !top.is_empty() {
+        draw_caption(&mut image, &font, top, |_| margin);
+    }
+    if !bottom.is_empty() {
+        let bottom_edge = image.height() as i32 - margin;
+        draw_caption(&mut image, &font, bottom, |height| bottom_edge - height);
+    }
+
+    let mut jpeg = Vec::new();
+    DynamicImage::ImageRgb8(image)
+        .write_to(&mut Cursor::new(&mut jpeg), ImageOutputFormat::Jpeg(90))?;
+    Ok(jpeg)
+}
+
+/// Centered, as big as fits the width, `y` places it given its height.
+fn draw_caption(image: &mut RgbImage, font: &Font, text: &str, y: impl Fn(i32) -> i32) {
+    let max_width = image.width() as i32 * 9 / 10;
+    let mut size = image.height() as f32 / 8.0;
+    let (width, height) = loop {
+        let (width, height) = text_size(Scale::uniform(size), font, text);
+        if width <= max_width || size <= MIN_SIZE {
+            break (width, height);
+        }
+        size = (size * 0.9).max(MIN_SIZE);
+    };
+    let scale = Scale::uniform(size);
+    let x = (image.width() as i32 - width) / 2;
+    let y = y(height);
+
+    let outline = (size / 15.0).max(1.0) as i32;
+    for dx in -outline..=outline {
+        for dy in -outline..=outline {
+            draw_text_mut(image, Rgb([0, 0, 0]), x + dx, y + dy, scale, font, text);
+        }
+    }
+    draw_text_mut(image, Rgb([255, 255, 255]), x, y, scale, font, text);
+}
@@ -0,0 +1,272 @@
+//! The commands answered on every platform, written against [`Chat`] instead of a Telegram bot.
+//!
+//! Telegram answers them through [`TelegramChat`], Discord through `discord::DiscordChat` with the `discord`
+//! feature and Matrix through `matrix::MatrixChat` with the `matrix` one. The commands that need what only
+//! Telegram has, e.g. inline keyboards, stay in [`crate::commands`].
+
+use crate::{
+    breed::BreedQuery,
+    commands::{DEFILLAMA, DOG_CEO, PRICES},
+    error::CommandError,
+    money,
+    state::AppState,
+};
+use async_trait::async_trait;
+use reqwest::Url;
+use std::{fmt::Write, str::FromStr};
+use teloxide::{prelude::*, types::InputFile};
+use tracing::info;
+
+/// Chains listed by `/tvl chains`.
+const TOP_CHAINS: usize = 10;
+
+/// Conversation a command came from, where its answer goes.
+#[async_trait]
+pub trait Chat: Send + Sync {
+    /// Name of the platform, e.g. in the logs.
+    fn platform(&self) -> &'static str;
+    async fn send_text(&self, text: &str) -> Result<(), CommandError>;
+    async fn send_photo(&self, url: &Url, caption: Option<&str>) -> Result<(), CommandError>;
+}
+
+/// A Telegram chat, answered by the bot.
+pub struct TelegramChat {
+    bot: AutoSend<Bot>,
+    chat_id: ChatId,
+}
+
+impl TelegramChat {
+    pub fn new(bot: AutoSend<Bot>, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl Chat for TelegramChat {
+    fn platform(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn send_text(&self, text: &str) -> Result<(), CommandError> {
+        self.bot.send_message(self.chat_id, text).await?;
+        Ok(())
+    }
+
+    async fn send_photo(&self, url: &Url, caption: Option<&str>) -> Result<(), CommandError> {
+        let request = self
+            .bot
+            .send_photo(self.chat_id, InputFile::url(url.clone()));
+        match caption {
+            Some(caption) => request.caption(caption).await?,
+            None => request.await?,
+        };
+        Ok(())
+    }
+}
+
+/// The commands every platform answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Portable {
+    Doggo,
+    Breed(String),
+    Euro,
+    Dominance,
+    Tvl(String),
+}
+
+impl Portable {
+    /// The command with the name, without its prefix, e.g. `breed` and `husky`.
+    pub fn parse(name: &str, args: &str) -> Option<Self> {
+        let args = args.trim().to_string();
+        Some(match name.to_lowercase().as_str() {
+            "doggo" => Self::Doggo,
+            "breed" => Self::Breed(args),
+            "euro" => Self::Euro,
+            "dominance" => Self::Dominance,
+            "tvl" => Self::Tvl(args),
+            _ => return None,
+        })
+    }
+
+    /// Help of the commands, with the given prefix, e.g. `!`.
+    pub fn help(prefix: &str) -> String {
+        [
+            ("doggo", "Random dog"),
+            ("breed husky", "Random dog of the breed"),
+            ("euro", "Value of the euro in USD"),
+            (
+                "dominance",
+                "Crypto market cap and the dominance of BTC and ETH",
+            ),
+            (
+                "tvl lido",
+                "Value locked in a DeFi protocol, or the top chains with `tvl chains`",
+            ),
+        ]
+        .iter()
+        .map(|(command, description)| format!("{}{} - {}", prefix, command, description))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+}
+
+/// Answer the command in the chat.
+pub async fn run(state: &AppState, chat: &dyn Chat, command: Portable) -> Result<(), CommandError> {
+    match command {
+        Portable::Doggo => {
+            let image =
+                match state.dogs.pop() {
+                    Some(image) => image,
+                    None => {
+                        let dog = state.dog_api.random().await.map_err(|error| {
+                            CommandError::Upstream {
+                                upstream: DOG_CEO,
+                                error,
+                            }
+                        })?;
+                        state.reporter.success(DOG_CEO);
+                        dog.message
+                    }
+                };
+            chat.send_photo(&dog_url(&image)?, None).await?;
+        }
+        Portable::Breed(breed) => {
+            let query = match BreedQuery::from_str(&breed) {
+                Ok(query) => query,
+                Err(e) => return chat.send_text(&e.to_string()).await,
+            };
+            let dog = state
+                .dog_api
+                .random_for_breed(&query)
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+            state.reporter.success(DOG_CEO);
+            if dog.status == "success" {
+                chat.send_photo(&dog_url(&dog.message)?, None).await?;
+            } else {
+                chat.send_text(&format!("Breed '{}' doesn't exist", breed))
+                    .await?;
+            }
+        }
+        Portable::Euro => chat.send_text(&euro(state).await?).await?,
+        Portable::Dominance => chat.send_text(&dominance(state).await?).await?,
+        Portable::Tvl(protocol) => chat.send_text(&tvl(state, &protocol).await?).await?,
+    }
+    info!("Answered on {}", chat.platform());
+    Ok(())
+}
+
+fn dog_url(image: &str) -> Result<Url, CommandError> {
+    Url::from_str(image).map_err(|e| CommandError::Malformed {
+        upstream: DOG_CEO,
+        reason: format!("'{}' is not an image URL: {}", image, e),
+    })
+}
+
+async fn euro(state: &AppState) -> Result<String, CommandError> {
+    let euro = state
+        .price_api
+        .usd_price("eur")
+        .await
+        .map_err(|error| CommandError::Upstream {
+            upstream: PRICES,
+            error,
+        })?;
+    state.reporter.success(PRICES);
+    Ok(match euro {
+        Some(euro) => format!("${}", euro),
+        None => "I don't know the value of the euro right now".to_string(),
+    })
+}
+
+async fn dominance(state: &AppState) -> Result<String, CommandError> {
+    let market = state
+        .market_api
+        .global()
+        .await
+        .map_err(|error| CommandError::Upstream {
+            upstream: PRICES,
+            error,
+        })?;
+    state.reporter.success(PRICES);
+
+    let mut text = format!("Market cap: {}", money::usd_short(market.market_cap));
+    if let Some(change) = market.market_cap_change_24h {
+        write!(
+            text,
+            " {} {} in 24h",
+            money::trend(change),
+            money::change(change)
+        )
+        .ok();
+    }
+    write!(
+        text,
+        "\n24h volume: {}",
+        money::usd_short(market.volume_24h)
+    )
+    .ok();
+    for symbol in ["btc", "eth"] {
+        if let Some(dominance) = market.dominance.get(symbol) {
+            write!(
+                text,
+                "\n{} dominance: {:.2}%",
+                symbol.to_uppercase(),
+                dominance
+            )
+            .ok();
+        }
+    }
+    Ok(text)
+}
+
+async fn tvl(state: &AppState, protocol: &str) -> Result<String, CommandError> {
+    let upstream = |error| CommandError::Upstream {
+        upstream: DEFILLAMA,
+        error,
+    };
+    let protocol = protocol.trim().to_lowercase();
+    Ok(match protocol.as_str() {
+        "" => "Tell me the protocol, e.g. /tvl lido, or /tvl chains".to_string(),
+        "chains" => {
+            let chains = state.defi_api.chains().await.map_err(upstream)?;
+            state.reporter.success(DEFILLAMA);
+            let mut text = "Top chains by value locked\n".to_string();
+            for (rank, chain) in chains.iter().take(TOP_CHAINS).enumerate() {
+                write!(
+                    text,
+                    "\n{}. {}: {}",
+                    rank + 1,
+                    chain.name,
+                    money::usd_short(chain.tvl)
+                )
+                .ok();
+            }
+            text
+        }
+        name => {
+            let found = state.defi_api.protocol(name).await.map_err(upstream)?;
+            state.reporter.success(DEFILLAMA);
+            match found {
+                Some(found) => {
+                    let mut text = format!(
+                        "{} TVL: {}",
+                        found.name,
+                        found
+                            .tvl
+                            .map(money::usd_short)
+                            .unwrap_or_else(|| "unknown".to_string())
+                    );
+                    if let Some(change) = found.change_7d {
+                        write!(text, " ({} in 7d)", money::change(change)).ok();
+                    }
+                    text
+                }
+                None => format!("I don't know the protocol '{}'", name),
+            }
+        }
+    })
+}
@@ -0,0 +1,62 @@
+//! How prices are shown, e.g. `$35,120.50`, `$12.35B`, `10.52 ETH` and `+1.25%`.
+
+/// Dollars with thousands separators, and more decimals below a dollar.
+pub fn usd(value: f64) -> String {
+    if value.abs() < 1.0 {
+        return format!("${}", trimmed(value, 6));
+    }
+    let cents = format!("{:.2}", value.abs());
+    let (units, decimals) = cents.split_once('.').unwrap_or((&cents, "00"));
+    let mut grouped = String::new();
+    for (index, digit) in units.chars().enumerate() {
+        if index > 0 && (units.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    format!("{}${}.{}", sign, grouped, decimals)
+}
+
+/// Large dollar amounts in short, e.g. `$12.35B`.
+pub fn usd_short(value: f64) -> String {
+    let (value, suffix) = match value.abs() {
+        abs if abs >= 1e12 => (value / 1e12, "T"),
+        abs if abs >= 1e9 => (value / 1e9, "B"),
+        abs if abs >= 1e6 => (value / 1e6, "M"),
+        abs if abs >= 1e3 => (value / 1e3, "K"),
+        _ => return usd(value),
+    };
+    let sign = if value < 0.0 { "-" } else { "" };
+    format!("{}${:.2}{}", sign, value.abs(), suffix)
+}
+
+/// An amount of a coin, e.g. `10.52 ETH`, without trailing zeros.
+pub fn amount(value: f64, symbol: &str) -> String {
+    format!("{} {}", trimmed(value, 4), symbol)
+}
+
+/// Whether the change is up or down, e.g. `📈`.
+pub fn trend(percent: f64) -> &'static str {
+    if percent > 0.0 {
+        "📈"
+    } else if percent < 0.0 {
+        "📉"
+    } else {
+        "➖"
+    }
+}
+
+/// A change in percent, always signed, e.g. `+1.25%`.
+pub fn change(percent: f64) -> String {
+    format!("{:+.2}%", percent)
+}
+
+fn trimmed(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
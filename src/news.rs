@@ -0,0 +1,62 @@
+use crate::{
+    api::news::Headline,
+    storage::{self, Storage},
+};
+use std::fmt::Write;
+use tracing::warn;
+
+/// Headlines shown by `/news`.
+pub const HEADLINES: usize = 5;
+
+/// Feed of the chat, `None` for the default one.
+pub async fn source_of_chat(storage: &dyn Storage, chat_id: i64) -> Option<String> {
+    match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings?.news_source,
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            None
+        }
+    }
+}
+
+pub async fn set_source(
+    storage: &dyn Storage,
+    chat_id: i64,
+    source: Option<String>,
+) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| settings.news_source = source).await
+}
+
+/// The first headlines, about the topic if any.
+pub fn render(headlines: &[Headline], topic: Option<&str>) -> String {
+    let mut matching = headlines
+        .iter()
+        .filter(|headline| topic.is_none_or(|topic| headline.is_about(topic)))
+        .take(HEADLINES)
+        .peekable();
+    if matching.peek().is_none() {
+        return match topic {
+            Some(topic) => format!("No headlines about '{}' right now", topic),
+            None => "No headlines right now".to_string(),
+        };
+    }
+
+    let mut text = match topic {
+        Some(topic) => format!("Top headlines about {}:\n", topic),
+        None => "Top headlines:\n".to_string(),
+    };
+    for (position, headline) in matching.enumerate() {
+        write!(
+            text,
+            "\n{}. {}\n{}\n",
+            position + 1,
+            headline.title,
+            headline.link
+        )
+        .ok();
+    }
+    text
+}
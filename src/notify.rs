@@ -0,0 +1,161 @@
+//! `POST /notify` of the embedded server, relaying the messages of e.g. CI systems or home automation through the bot.
+//!
+//! Each token is given in the `Authorization: Bearer <token>` header and may only message its own chats.
+
+use crate::{
+    server::{self, ApiError},
+    state::AppState,
+    subscriptions::{chat_is_gone, deactivate_chat},
+};
+use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
+use futures::FutureExt;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use teloxide::{
+    prelude::*,
+    types::{InputFile, ParseMode},
+};
+use tracing::{info, warn};
+
+/// A token of `/notify` and where its messages may go.
+#[derive(Deserialize, Clone, Debug)]
+pub struct NotifyRule {
+    pub token: String,
+    /// Chats the token may message, the first is the one of the messages without a chat.
+    pub chats: Vec<i64>,
+}
+
+/// Body of `POST /notify`, with a text, a photo or both.
+#[derive(Deserialize)]
+struct Notification {
+    /// The first chat of the token when missing.
+    chat_id: Option<i64>,
+    text: Option<String>,
+    /// URL of the photo, the text is its caption.
+    photo: Option<String>,
+    /// `MarkdownV2`, `HTML` or `Markdown`, plain text when missing.
+    parse_mode: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Notified {
+    chat_id: i64,
+    message_id: i32,
+}
+
+struct Notify {
+    state: Arc<AppState>,
+    rules: Vec<NotifyRule>,
+}
+
+pub fn router(state: Arc<AppState>, rules: Vec<NotifyRule>) -> Router {
+    Router::new()
+        .route("/notify", post(notify))
+        .with_state(Arc::new(Notify { state, rules }))
+}
+
+fn parse_mode(name: &str) -> Option<ParseMode> {
+    match name.to_lowercase().as_str() {
+        "markdownv2" => Some(ParseMode::MarkdownV2),
+        "html" => Some(ParseMode::Html),
+        #[allow(deprecated)]
+        "markdown" => Some(ParseMode::Markdown),
+        _ => None,
+    }
+}
+
+async fn notify(
+    State(notify): State<Arc<Notify>>,
+    headers: HeaderMap,
+    Json(notification): Json<Notification>,
+) -> Result<Json<Notified>, ApiError> {
+    let rule = server::bearer(&headers)
+        .and_then(|given| {
+            notify
+                .rules
+                .iter()
+                .find(|rule| server::same_token(given, &rule.token))
+        })
+        .ok_or(ApiError::Unauthorized)?;
+    let chat_id = match notification.chat_id.or_else(|| rule.chats.first().copied()) {
+        Some(chat_id) if rule.chats.contains(&chat_id) => chat_id,
+        Some(chat_id) => {
+            return Err(ApiError::Forbidden(format!(
+                "the token can't message the chat {}",
+                chat_id
+            )))
+        }
+        None => return Err(ApiError::Forbidden("the token has no chats".to_string())),
+    };
+    let parse_mode = match notification.parse_mode.as_deref() {
+        Some(name) => Some(
+            parse_mode(name)
+                .ok_or_else(|| ApiError::BadRequest(format!("unknown parse mode '{}'", name)))?,
+        ),
+        None => None,
+    };
+    let photo = match notification.photo.as_deref() {
+        Some(photo) => Some(
+            Url::parse(photo)
+                .map_err(|_| ApiError::BadRequest(format!("'{}' isn't a URL", photo)))?,
+        ),
+        None => None,
+    };
+    let text = notification.text.filter(|text| !text.trim().is_empty());
+    if text.is_none() && photo.is_none() {
+        return Err(ApiError::BadRequest(
+            "a text or a photo is needed".to_string(),
+        ));
+    }
+
+    let subscriptions = &notify.state.subscriptions;
+    let sent = subscriptions
+        .queue
+        .send(|| {
+            let bot = &subscriptions.bot;
+            // Both requests are answered with the message, boxed to share a type
+            match &photo {
+                Some(photo) => {
+                    let mut request =
+                        bot.send_photo(ChatId(chat_id), InputFile::url(photo.clone()));
+                    if let Some(caption) = &text {
+                        request = request.caption(caption.clone());
+                    }
+                    if let Some(parse_mode) = parse_mode {
+                        request = request.parse_mode(parse_mode);
+                    }
+                    request.boxed()
+                }
+                None => {
+                    let mut request =
+                        bot.send_message(ChatId(chat_id), text.clone().unwrap_or_default());
+                    if let Some(parse_mode) = parse_mode {
+                        request = request.parse_mode(parse_mode);
+                    }
+                    request.boxed()
+                }
+            }
+        })
+        .await;
+    match sent {
+        Ok(message) => {
+            info!("Notification relayed to the chat {}", chat_id);
+            Ok(Json(Notified {
+                chat_id,
+                message_id: message.id,
+            }))
+        }
+        Err(e) if chat_is_gone(&e) => {
+            deactivate_chat(&notify.state, ChatId(chat_id), &e).await?;
+            Err(ApiError::ChatGone)
+        }
+        Err(e) => {
+            warn!(
+                "Could not relay the notification to the chat {} -> {}",
+                chat_id, e
+            );
+            Err(ApiError::Telegram(e))
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::storage::{self, Storage};
+use tracing::warn;
+
+/// The chat asked not to get content that may not be safe for work, like Urban Dictionary.
+pub async fn is_filtered(storage: &dyn Storage, chat_id: i64) -> bool {
+    match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings.is_some_and(|settings| settings.nsfw_filter),
+        Err(e) => {
+            // Better safe than sorry
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            true
+        }
+    }
+}
+
+pub async fn set_filter(storage: &dyn Storage, chat_id: i64, filter: bool) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| settings.nsfw_filter = filter).await
+}
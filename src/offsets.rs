@@ -0,0 +1,135 @@
+//! The last update each bot handled, so a restart doesn't handle the updates Telegram sends again.
+//!
+//! Telegram only forgets the updates once the next `getUpdates` confirms them, after a crash the bot receives the
+//! last batch a second time. Updates are claimed before their handlers run, a crash in the middle of one loses it
+//! rather than answering a command twice. The offset is saved every few seconds, not for every update, so a crash
+//! may still handle the updates of the last seconds again.
+
+use crate::storage::Storage;
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use teloxide::{dispatching::UpdateHandler, prelude::*, types::Update};
+use tracing::{info, warn};
+
+/// How often the offset is saved.
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct UpdateOffsets {
+    storage: Arc<dyn Storage>,
+    bot: String,
+    claimed: Mutex<Claimed>,
+}
+
+#[derive(Default)]
+struct Claimed {
+    /// Every update up to this one was claimed, `None` before the first one.
+    last: Option<i64>,
+    /// Claimed after `last`, the chats are handled concurrently so the updates are claimed out of order.
+    ahead: BTreeSet<i64>,
+    /// The first update ahead at the previous save, see [`UpdateOffsets::save`].
+    waiting_since_save: Option<i64>,
+    /// `last` as saved in the storage.
+    saved: Option<i64>,
+}
+
+impl Claimed {
+    fn advance(&mut self) {
+        if let Some(last) = self.last.as_mut() {
+            while self.ahead.remove(&(*last + 1)) {
+                *last += 1;
+            }
+        }
+    }
+}
+
+impl UpdateOffsets {
+    /// The offset the bot saved before it stopped.
+    pub async fn load(storage: Arc<dyn Storage>, bot: &str) -> Self {
+        let last = match storage.update_offset(bot).await {
+            Ok(last) => last,
+            Err(e) => {
+                warn!(
+                    "Could not load the update offset of the {} bot -> {}",
+                    bot, e
+                );
+                None
+            }
+        };
+        if let Some(last) = last {
+            info!("The {} bot resumes after the update {}", bot, last);
+        }
+        Self {
+            storage,
+            bot: bot.to_string(),
+            claimed: Mutex::new(Claimed {
+                last,
+                saved: last,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Whether the update wasn't handled yet, marking it as handled.
+    pub fn claim(&self, update_id: i32) -> bool {
+        let update_id = i64::from(update_id);
+        let mut claimed = self.claimed.lock().unwrap();
+        let last = *claimed.last.get_or_insert(update_id - 1);
+        if update_id <= last || !claimed.ahead.insert(update_id) {
+            info!("Skipping the update {}, it was already handled", update_id);
+            return false;
+        }
+        claimed.advance();
+        true
+    }
+
+    /// Save the last update before which every update was claimed.
+    ///
+    /// Telegram leaves gaps between the ids now and then, e.g. after a week without updates, so an update
+    /// still missing since the previous save is given up on instead of holding the offset back forever.
+    pub async fn save(&self) {
+        let last = {
+            let mut claimed = self.claimed.lock().unwrap();
+            let first_ahead = claimed.ahead.first().copied();
+            if let Some(first_ahead) = first_ahead {
+                if claimed.waiting_since_save == Some(first_ahead) {
+                    claimed.last = Some(first_ahead - 1);
+                    claimed.advance();
+                }
+            }
+            claimed.waiting_since_save = claimed.ahead.first().copied();
+            match claimed.last {
+                Some(last) if claimed.saved != Some(last) => last,
+                _ => return,
+            }
+        };
+
+        match self.storage.save_update_offset(&self.bot, last).await {
+            Ok(()) => self.claimed.lock().unwrap().saved = Some(last),
+            Err(e) => warn!(
+                "Could not save the update offset of the {} bot -> {}",
+                self.bot, e
+            ),
+        }
+    }
+
+    /// Save the offset every [`SAVE_INTERVAL`], for as long as the bot runs.
+    pub async fn keep_saved(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(SAVE_INTERVAL);
+        loop {
+            interval.tick().await;
+            self.save().await;
+        }
+    }
+
+    /// Let only the updates not handled yet reach the given handlers.
+    pub fn handler(
+        self: Arc<Self>,
+        handler: UpdateHandler<Box<dyn Error + Send + Sync>>,
+    ) -> UpdateHandler<Box<dyn Error + Send + Sync>> {
+        dptree::filter(move |update: Update| self.claim(update.id)).chain(handler)
+    }
+}
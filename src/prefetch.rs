@@ -0,0 +1,108 @@
+use crate::{
+    scheduler::{JobError, Schedule},
+    state::AppState,
+};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::{info, warn};
+
+/// Random dogs fetched ahead, so `/doggo` doesn't wait for dog.ceo.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PrefetchConfig {
+    /// Dogs kept ready, none are prefetched when 0.
+    pub dogs: usize,
+    /// Time between two refills of the buffer.
+    pub refill_interval_secs: u64,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            dogs: 5,
+            refill_interval_secs: 30,
+        }
+    }
+}
+
+/// URLs of random dogs not sent yet, oldest first.
+#[derive(Default)]
+pub struct DogBuffer {
+    urls: Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+impl DogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            urls: Mutex::default(),
+            capacity,
+        }
+    }
+
+    pub fn pop(&self) -> Option<String> {
+        self.urls.lock().unwrap().pop_front()
+    }
+
+    /// Dropped when the buffer is already full.
+    pub fn push(&self, url: String) {
+        let mut urls = self.urls.lock().unwrap();
+        if urls.len() < self.capacity {
+            urls.push_back(url);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn missing(&self) -> usize {
+        self.capacity.saturating_sub(self.len())
+    }
+}
+
+/// Keep the buffer of dogs full on a schedule, starting right away.
+pub fn start(state: &Arc<AppState>, config: &PrefetchConfig) {
+    if config.dogs == 0 {
+        return;
+    }
+
+    let interval = Duration::from_secs(config.refill_interval_secs.max(1));
+    let job = {
+        let state = state.clone();
+        move || refill(state.clone())
+    };
+    state
+        .scheduler
+        .add("prefetch", Schedule::Every(interval), job);
+    // The first scheduled run is an interval away
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = refill(state).await {
+            warn!("Could not prefetch the dogs -> {}", e);
+        }
+    });
+}
+
+/// Fetch the dogs missing from the buffer.
+pub async fn refill(state: Arc<AppState>) -> Result<(), JobError> {
+    let missing = state.dogs.missing();
+    for _ in 0..missing {
+        let dog = state.dog_api.random().await?;
+        if dog.status == "success" {
+            state.dogs.push(dog.message);
+        }
+    }
+    if missing > 0 {
+        info!("{} dogs prefetched", missing);
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use crate::{
+    breed::BreedQuery,
+    storage::{self, Preferences, Storage},
+};
+use std::{fmt::Write, str::FromStr, sync::Arc};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+pub const DIFFICULTIES: &[&str] = &["easy", "medium", "hard"];
+
+/// Preferences of the user who sent the update, read from the storage the first time they're needed.
+pub struct UserPrefs {
+    storage: Arc<dyn Storage>,
+    user_id: Option<i64>,
+    loaded: Mutex<Option<Preferences>>,
+}
+
+impl UserPrefs {
+    pub fn new(storage: Arc<dyn Storage>, user_id: Option<i64>) -> Self {
+        Self {
+            storage,
+            user_id,
+            loaded: Mutex::default(),
+        }
+    }
+
+    /// The defaults are used for unknown users, or if the storage fails.
+    pub async fn get(&self) -> Preferences {
+        let mut loaded = self.loaded.lock().await;
+        if let Some(preferences) = &*loaded {
+            return preferences.clone();
+        }
+
+        let preferences = match self.user_id {
+            Some(user_id) => match self.storage.preferences(user_id).await {
+                Ok(preferences) => preferences.unwrap_or(Preferences {
+                    user_id,
+                    ..Default::default()
+                }),
+                Err(e) => {
+                    warn!("Could not load the preferences -> {}", e);
+                    Preferences {
+                        user_id,
+                        ..Default::default()
+                    }
+                }
+            },
+            None => Preferences::default(),
+        };
+        *loaded = Some(preferences.clone());
+        preferences
+    }
+
+    /// Persist the preferences, nothing is saved for messages without a sender.
+    pub async fn save(&self, preferences: Preferences) -> storage::Result<()> {
+        if self.user_id.is_some() {
+            self.storage.save_preferences(&preferences).await?;
+        }
+        *self.loaded.lock().await = Some(preferences);
+        Ok(())
+    }
+}
+
+/// Validate `value` and set it as the preference `key`, an empty value goes back to the default.
+pub fn set(preferences: &mut Preferences, key: &str, value: &str) -> Result<(), String> {
+    let value = value.trim().to_lowercase();
+    let value = if value.is_empty() { None } else { Some(value) };
+
+    match key {
+        "breed" => {
+            preferences.favourite_breed = value
+                .map(|breed| BreedQuery::from_str(&breed).map(|query| query.to_string()))
+                .transpose()
+                .map_err(|e| e.to_string())?;
+        }
+        "currency" => {
+            if let Some(currency) = &value {
+                if !(2..=10).contains(&currency.len())
+                    || !currency.chars().all(|c| c.is_ascii_alphanumeric())
+                {
+                    return Err(format!("'{}' is not a currency symbol", currency));
+                }
+            }
+            preferences.currency = value;
+        }
+        "language" => {
+            if let Some(language) = &value {
+                if language.len() != 2 || !language.chars().all(|c| c.is_ascii_lowercase()) {
+                    return Err(format!(
+                        "'{}' is not a two letter language code, e.g. en",
+                        language
+                    ));
+                }
+            }
+            preferences.language = value;
+        }
+        "difficulty" => {
+            if let Some(difficulty) = &value {
+                if !DIFFICULTIES.contains(&difficulty.as_str()) {
+                    return Err(format!(
+                        "The difficulty must be one of {}",
+                        DIFFICULTIES.join(", ")
+                    ));
+                }
+            }
+            preferences.quiz_difficulty = value;
+        }
+        "city" => {
+            if let Some(city) = &value {
+                if city.len() > 100 {
+                    return Err("That city name is too long".to_string());
+                }
+            }
+            preferences.city = value;
+        }
+        other => {
+            return Err(format!(
+                "Unknown preference '{}', use breed, currency, language, difficulty or city",
+                other
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// Summary shown by `/prefs`.
+pub fn describe(preferences: &Preferences) -> String {
+    let or_default = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+    let mut text = String::from("Your preferences:\n");
+    writeln!(text, "breed: {}", or_default(&preferences.favourite_breed)).ok();
+    writeln!(text, "currency: {}", or_default(&preferences.currency)).ok();
+    writeln!(text, "language: {}", or_default(&preferences.language)).ok();
+    writeln!(
+        text,
+        "difficulty: {}",
+        or_default(&preferences.quiz_difficulty)
+    )
+    .ok();
+    writeln!(text, "city: {}", or_default(&preferences.city)).ok();
+    text.push_str("\nChange them with e.g. /prefs breed husky");
+    text
+}
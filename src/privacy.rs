@@ -0,0 +1,148 @@
+use crate::{
+    reminders,
+    state::AppState,
+    storage::{self, schema::Table, Alert, Preferences, Reminder, Storage, User},
+    subscriptions,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+use teloxide::types::ChatId;
+use tracing::info;
+
+/// Prefix of the callback data of the `/forgetme` confirmation buttons, followed by `:<user id>`.
+pub const FORGET_ME: &str = "forgetme";
+pub const FORGET_ME_CANCEL: &str = "forgetme-cancel";
+
+/// Whom the rows of a table are about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subject {
+    User,
+    Chat,
+    Nobody,
+}
+
+/// Why each table is kept, its columns are read from the schema.
+pub const PURPOSES: &[(&str, Subject, &str)] = &[
+    ("users", Subject::User, "who talked to me and when"),
+    ("preferences", Subject::User, "your /prefs"),
+    ("favourites", Subject::User, "your favourite breeds"),
+    ("alerts", Subject::User, "your price alerts"),
+    ("reminders", Subject::User, "your pending reminders"),
+    (
+        "trivia_scores",
+        Subject::User,
+        "your trivia points, with the name on the scoreboard",
+    ),
+    (
+        "command_log",
+        Subject::User,
+        "the commands you used, for the usage statistics, kept without your id after /forgetme",
+    ),
+    (
+        "audit_log",
+        Subject::User,
+        "when your data was deleted, to prove it was",
+    ),
+    ("chat_settings", Subject::Chat, "the settings of the chat"),
+    ("subscriptions", Subject::Chat, "the deliveries to the chat"),
+    (
+        "breed_requests",
+        Subject::Chat,
+        "the breeds asked for, for /popularbreeds",
+    ),
+    (
+        "inactive_chats",
+        Subject::Chat,
+        "that I can't write to the chat anymore, until it writes to me again",
+    ),
+    ("job_runs", Subject::Nobody, "how the scheduled jobs went"),
+    (
+        "update_offsets",
+        Subject::Nobody,
+        "the last update I handled, not to answer a command twice after a restart",
+    ),
+];
+
+/// What `/privacy` says, one line per table of the schema.
+///
+/// A table without a purpose is still listed, nothing stored goes unmentioned.
+pub fn disclosure(tables: &[Table]) -> String {
+    let sections = [
+        (Some(Subject::User), "About you"),
+        (Some(Subject::Chat), "About this chat"),
+        (Some(Subject::Nobody), "About nobody"),
+        (None, "Other"),
+    ];
+
+    let mut text = "This is everything I store, and why.\n".to_string();
+    for (subject, title) in sections {
+        let lines: Vec<String> = tables
+            .iter()
+            .filter_map(|table| {
+                let purpose = PURPOSES.iter().find(|(name, ..)| *name == table.name);
+                if purpose.map(|(_, subject, _)| *subject) != subject {
+                    return None;
+                }
+                let purpose = purpose.map_or("no description yet", |(.., purpose)| *purpose);
+                Some(format!(
+                    "• {} ({}): {}",
+                    table.name,
+                    table.columns.join(", "),
+                    purpose
+                ))
+            })
+            .collect();
+        if !lines.is_empty() {
+            write!(text, "\n{}:\n{}\n", title, lines.join("\n")).ok();
+        }
+    }
+    text.push_str("\nSend /mydata to get what I store about you, or /forgetme to delete it.");
+    text
+}
+
+/// Everything the bot stores about a user, as given to them by `/mydata`.
+#[derive(Serialize, Debug)]
+pub struct UserData {
+    pub user: Option<User>,
+    pub preferences: Option<Preferences>,
+    pub favourites: Vec<String>,
+    pub alerts: Vec<Alert>,
+    pub reminders: Vec<Reminder>,
+    /// How many times each command was used.
+    pub commands: BTreeMap<String, i64>,
+}
+
+impl UserData {
+    pub async fn collect(storage: &dyn Storage, user_id: i64) -> storage::Result<Self> {
+        Ok(Self {
+            user: storage.user(user_id).await?,
+            preferences: storage.preferences(user_id).await?,
+            favourites: storage.favourites(user_id).await?,
+            alerts: storage.alerts(Some(user_id)).await?,
+            reminders: storage.reminders(Some(user_id)).await?,
+            commands: storage
+                .user_command_counts(user_id)
+                .await?
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the user data is always serializable")
+    }
+}
+
+/// Delete everything stored about the user, including the subscriptions of their private chat.
+pub async fn forget(state: &Arc<AppState>, user_id: i64) -> storage::Result<()> {
+    let pending = state.storage.reminders(Some(user_id)).await?;
+    state.storage.forget_user(user_id).await?;
+    for reminder in pending {
+        reminders::stop(state, reminder.id).await;
+    }
+    // The private chat with a user has the same id as the user
+    subscriptions::forget_chat(state, ChatId(user_id)).await?;
+    state.storage.record_audit(FORGET_ME, user_id).await?;
+    info!(user_id, "Forgot the user");
+    Ok(())
+}
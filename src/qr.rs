@@ -0,0 +1,30 @@
+use image::{ImageError, ImageOutputFormat, Luma};
+use qrcode::QrCode;
+use std::io::Cursor;
+
+/// Side of the QR code images, in pixels.
+const SIZE: u32 = 512;
+
+/// PNG image of the QR code of the text, `None` if it's too long for one.
+pub fn encode(text: &str) -> Option<Vec<u8>> {
+    let code = QrCode::new(text.as_bytes()).ok()?;
+    let image = code.render::<Luma<u8>>().min_dimensions(SIZE, SIZE).build();
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// Texts of the QR codes found in the image.
+pub fn decode(image: &[u8]) -> Result<Vec<String>, ImageError> {
+    let image = image::load_from_memory(image)?.to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare(image);
+    Ok(prepared
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok())
+        .map(|(_, text)| text)
+        .collect())
+}
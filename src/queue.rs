@@ -0,0 +1,131 @@
+use crate::error::CommandError;
+use metrics::{decrement_gauge, increment_gauge};
+use serde::Deserialize;
+use std::{
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use teloxide::RequestError;
+use tracing::warn;
+
+/// How the messages sent on a schedule, e.g. the daily dogs and the alerts, are paced.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct QueueConfig {
+    /// Most messages sent in a second, Telegram allows 30 to different chats. Not paced when 0.
+    pub messages_per_second: u32,
+    /// How many times a message Telegram asked to slow down for is sent again.
+    pub max_retries: u32,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            messages_per_second: 25,
+            max_retries: 3,
+        }
+    }
+}
+
+/// Errors that may be Telegram asking to wait before sending anything else.
+pub trait RetryAfter {
+    fn retry_after(&self) -> Option<Duration>;
+}
+
+impl RetryAfter for RequestError {
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RetryAfter(after) => Some(*after),
+            _ => None,
+        }
+    }
+}
+
+impl RetryAfter for CommandError {
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Telegram(e) => e.retry_after(),
+            _ => None,
+        }
+    }
+}
+
+/// Sends the scheduled messages one after the other, under Telegram's global rate limit.
+///
+/// Every sender waits for its turn, in order. When Telegram answers with `retry_after`
+/// the whole queue waits that long before the message is sent again.
+pub struct SendQueue {
+    interval: Duration,
+    max_retries: u32,
+    /// When the next message may be sent.
+    next: Mutex<Instant>,
+}
+
+impl SendQueue {
+    pub fn new(config: &QueueConfig) -> Self {
+        Self {
+            interval: Duration::from_secs(1)
+                .checked_div(config.messages_per_second)
+                .unwrap_or_default(),
+            max_retries: config.max_retries,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait for the turn of `send`, and again whenever Telegram asks to.
+    pub async fn send<T, E, F, Fut>(&self, mut send: F) -> Result<T, E>
+    where
+        E: RetryAfter,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let _waiting = Waiting::new();
+        let mut retries = 0;
+        loop {
+            self.turn().await;
+            match send().await {
+                Err(e) => match e.retry_after() {
+                    Some(wait) if retries < self.max_retries => {
+                        warn!("Telegram asked to wait {:?} before sending again", wait);
+                        self.pause(wait);
+                        retries += 1;
+                    }
+                    _ => return Err(e),
+                },
+                sent => return sent,
+            }
+        }
+    }
+
+    async fn turn(&self) {
+        let at = {
+            let mut next = self.next.lock().unwrap();
+            let at = (*next).max(Instant::now());
+            *next = at + self.interval;
+            at
+        };
+        tokio::time::sleep_until(at.into()).await;
+    }
+
+    fn pause(&self, wait: Duration) {
+        let mut next = self.next.lock().unwrap();
+        *next = (*next).max(Instant::now() + wait);
+    }
+}
+
+/// Counts a message in `send_queue_depth` until it's sent or given up on, even when the sender is cancelled.
+struct Waiting;
+
+impl Waiting {
+    fn new() -> Self {
+        increment_gauge!("send_queue_depth", 1.0);
+        Self
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        decrement_gauge!("send_queue_depth", 1.0);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    scheduler::Schedule,
+    storage::{self, Storage},
+    timezones,
+};
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use std::{fmt, str::FromStr};
+use tracing::{info, warn};
+
+/// Part of the day when a chat doesn't get scheduled messages, e.g. `23:00-08:00`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// The window may go past midnight.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// When the chat can get messages again, `None` if it already can.
+    pub fn ends_after(&self, now: DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+        if !self.contains(now.with_timezone(&timezone).time()) {
+            return None;
+        }
+        Some(Schedule::Daily(self.end, timezone).next_after(now))
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("'{}' isn't a range like 23:00-08:00", s);
+        let (start, end) = s.trim().split_once('-').ok_or_else(error)?;
+        let parse =
+            |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| error());
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start == end {
+            return Err(error());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// Quiet hours of the chat, if it has any.
+pub async fn of_chat(storage: &dyn Storage, chat_id: i64) -> Option<QuietHours> {
+    match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings?.quiet_hours?.parse().ok(),
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            None
+        }
+    }
+}
+
+pub async fn set_for_chat(
+    storage: &dyn Storage,
+    chat_id: i64,
+    quiet_hours: Option<QuietHours>,
+) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| {
+        settings.quiet_hours = quiet_hours.map(|quiet_hours| quiet_hours.to_string())
+    })
+    .await
+}
+
+/// When the quiet hours of the chat end, `None` if it can get messages now.
+pub async fn ends_after(
+    storage: &dyn Storage,
+    chat_id: i64,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let quiet_hours = of_chat(storage, chat_id).await?;
+    let timezone = timezones::of_chat(storage, chat_id).await;
+    quiet_hours.ends_after(now, timezone)
+}
+
+/// Wait for the quiet hours of the chat to end, if it's in them.
+pub async fn hold(storage: &dyn Storage, chat_id: i64) {
+    let now = Utc::now();
+    if let Some(end) = ends_after(storage, chat_id, now).await {
+        info!("Holding the message to {} until {}", chat_id, end);
+        tokio::time::sleep((end - now).to_std().unwrap_or_default()).await;
+    }
+}
@@ -0,0 +1,148 @@
+//! Budgets of calls to the upstreams, per minute and per day, shared by every feature calling them: the commands,
+//! the scheduled jobs and the prefetcher.
+//!
+//! A call over the minute budget waits for the next minute if it's close enough, otherwise it fails with
+//! [`QuotaExceeded`] without reaching the upstream, and the cached APIs answer with their last value instead.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, sync::Mutex, time::Duration};
+use tracing::warn;
+
+/// Calls allowed to an upstream host, without a limit when missing.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Budget {
+    pub per_minute: Option<u64>,
+    pub per_day: Option<u64>,
+}
+
+/// The window of a budget, counted in whole minutes and UTC days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Minute,
+    Day,
+}
+
+impl Window {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Minute => "minute",
+            Self::Day => "day",
+        }
+    }
+
+    fn secs(self) -> i64 {
+        match self {
+            Self::Minute => 60,
+            Self::Day => 24 * 60 * 60,
+        }
+    }
+}
+
+/// Returned instead of calling an upstream that used up its budget.
+#[derive(Debug)]
+pub struct QuotaExceeded {
+    pub upstream: String,
+    pub window: Window,
+    /// Until the window starts over.
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} used up its calls of the {}, not calling it for {:?}",
+            self.upstream,
+            self.window.name(),
+            self.retry_after
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
+
+/// Whether the call failed because its upstream is over budget, so a stale answer is better than none.
+pub fn is_exceeded(error: &reqwest_middleware::Error) -> bool {
+    matches!(error, reqwest_middleware::Error::Middleware(e) if e.is::<QuotaExceeded>())
+}
+
+/// Calls made to an upstream in the current minute and day.
+#[derive(Default)]
+struct Usage {
+    minute: i64,
+    in_minute: u64,
+    day: i64,
+    in_day: u64,
+}
+
+/// Counts the calls to each upstream host against its budget.
+pub struct Quotas {
+    budgets: HashMap<String, Budget>,
+    usage: Mutex<HashMap<String, Usage>>,
+}
+
+impl Quotas {
+    pub fn new(budgets: HashMap<String, Budget>) -> Self {
+        Self {
+            budgets,
+            usage: Mutex::default(),
+        }
+    }
+
+    /// Count a call to the host, unless it already made as many as its budget allows.
+    pub fn acquire(&self, host: &str, now: DateTime<Utc>) -> Result<(), QuotaExceeded> {
+        let budget = match self.budgets.get(host) {
+            Some(budget) => budget,
+            None => return Ok(()),
+        };
+
+        let now = now.timestamp();
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage.entry(host.to_string()).or_default();
+        let (minute, day) = (
+            now.div_euclid(Window::Minute.secs()),
+            now.div_euclid(Window::Day.secs()),
+        );
+        if usage.minute != minute {
+            usage.minute = minute;
+            usage.in_minute = 0;
+        }
+        if usage.day != day {
+            usage.day = day;
+            usage.in_day = 0;
+        }
+
+        for (window, used, allowed) in [
+            (Window::Day, usage.in_day, budget.per_day),
+            (Window::Minute, usage.in_minute, budget.per_minute),
+        ] {
+            if matches!(allowed, Some(allowed) if used >= allowed) {
+                let left = window.secs() - now.rem_euclid(window.secs());
+                return Err(QuotaExceeded {
+                    upstream: host.to_string(),
+                    window,
+                    retry_after: Duration::from_secs(left as u64),
+                });
+            }
+        }
+
+        usage.in_minute += 1;
+        usage.in_day += 1;
+        Ok(())
+    }
+
+    /// Count a call to the host, waiting for the next minute when over its minute budget and it comes within
+    /// `max_wait`.
+    pub async fn wait(&self, host: &str, max_wait: Duration) -> Result<(), QuotaExceeded> {
+        match self.acquire(host, Utc::now()) {
+            Err(e) if e.window == Window::Minute && e.retry_after <= max_wait => {
+                warn!("{}, deferring the call", e);
+                tokio::time::sleep(e.retry_after).await;
+                self.acquire(host, Utc::now())
+            }
+            result => result,
+        }
+    }
+}
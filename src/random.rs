@@ -0,0 +1,176 @@
+use rand::{seq::SliceRandom, Rng};
+use std::{fmt, str::FromStr};
+
+/// Most dice rolled at once, their results have to fit in a message.
+const MAX_DICE: u32 = 100;
+const MAX_SIDES: u32 = 1000;
+
+/// Dice in the usual notation, e.g. `2d20` for two dice of twenty sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+}
+
+impl Default for Dice {
+    fn default() -> Self {
+        Self { count: 1, sides: 6 }
+    }
+}
+
+impl Dice {
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<u32> {
+        (0..self.count)
+            .map(|_| rng.gen_range(1..=self.sides))
+            .collect()
+    }
+}
+
+impl FromStr for Dice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("'{}' isn't a roll like 2d20", s);
+        let roll = s.trim().to_lowercase();
+        let (count, sides) = roll.split_once('d').ok_or_else(error)?;
+        // `d20` is a single die
+        let count = match count {
+            "" => 1,
+            count => count.parse::<u32>().map_err(|_| error())?,
+        };
+        let sides = sides.parse::<u32>().map_err(|_| error())?;
+        if !(1..=MAX_DICE).contains(&count) {
+            return Err(format!("I can roll between 1 and {} dice", MAX_DICE));
+        }
+        if !(2..=MAX_SIDES).contains(&sides) {
+            return Err(format!("Dice have between 2 and {} sides", MAX_SIDES));
+        }
+        Ok(Self { count, sides })
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)
+    }
+}
+
+pub fn flip(rng: &mut impl Rng) -> &'static str {
+    if rng.gen() {
+        "Heads"
+    } else {
+        "Tails"
+    }
+}
+
+/// Options separated by `|`, e.g. `pizza | sushi | tacos`.
+pub fn options(text: &str) -> Vec<&str> {
+    text.split('|')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .collect()
+}
+
+pub fn pick<'a>(options: &[&'a str], rng: &mut impl Rng) -> Option<&'a str> {
+    options.choose(rng).copied()
+}
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&*+-=?@^_~";
+
+/// Characters a password is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    /// Letters, digits and symbols.
+    All,
+    /// Letters and digits.
+    Alphanumeric,
+    Letters,
+    Digits,
+}
+
+impl Charset {
+    /// Every password has at least one character of each of these.
+    fn classes(&self) -> &'static [&'static str] {
+        match self {
+            Self::All => &[LOWERCASE, UPPERCASE, DIGITS, SYMBOLS],
+            Self::Alphanumeric => &[LOWERCASE, UPPERCASE, DIGITS],
+            Self::Letters => &[LOWERCASE, UPPERCASE],
+            Self::Digits => &[DIGITS],
+        }
+    }
+}
+
+/// Length and charset of a password, e.g. `24`, `letters` or `16 digits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordOptions {
+    pub length: usize,
+    pub charset: Charset,
+}
+
+impl PasswordOptions {
+    pub const MIN_LENGTH: usize = 4;
+    pub const MAX_LENGTH: usize = 128;
+}
+
+impl Default for PasswordOptions {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            charset: Charset::All,
+        }
+    }
+}
+
+impl FromStr for PasswordOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut options = Self::default();
+        for word in s.split_whitespace() {
+            if let Ok(length) = word.parse::<usize>() {
+                if !(Self::MIN_LENGTH..=Self::MAX_LENGTH).contains(&length) {
+                    return Err(format!(
+                        "Passwords have between {} and {} characters",
+                        Self::MIN_LENGTH,
+                        Self::MAX_LENGTH
+                    ));
+                }
+                options.length = length;
+                continue;
+            }
+            options.charset = match word.to_lowercase().as_str() {
+                "all" | "symbols" => Charset::All,
+                "alphanumeric" | "alnum" | "nosymbols" => Charset::Alphanumeric,
+                "letters" => Charset::Letters,
+                "digits" | "pin" => Charset::Digits,
+                _ => {
+                    return Err(format!(
+                        "I don't know '{}', try a length and one of all, alnum, letters or digits",
+                        word
+                    ))
+                }
+            };
+        }
+        Ok(options)
+    }
+}
+
+pub fn password(options: &PasswordOptions, rng: &mut impl Rng) -> String {
+    let classes = options.charset.classes();
+    let characters = classes.concat().chars().collect::<Vec<_>>();
+    loop {
+        let password = (0..options.length)
+            .map(|_| *characters.choose(rng).expect("charsets aren't empty"))
+            .collect::<String>();
+        // Retrying keeps every character equally likely, unlike forcing one of each in
+        if classes
+            .iter()
+            .all(|class| password.chars().any(|c| class.contains(c)))
+        {
+            return password;
+        }
+    }
+}
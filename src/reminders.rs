@@ -0,0 +1,171 @@
+use crate::{
+    scheduler::{JobError, Schedule},
+    state::AppState,
+    storage::{self, Reminder},
+    subscriptions::{chat_is_gone, deactivate_chat, migrate_chat, migrated_to},
+    timezones,
+};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use tracing::info;
+
+/// Prefix of the callback data of the buttons cancelling a reminder, followed by `:<user id>:<reminder id>`.
+pub const CANCEL: &str = "reminder-cancel";
+
+fn job_name(id: i64) -> String {
+    format!("reminder:{}", id)
+}
+
+/// When the reminder is due and its text, times are in the given timezone:
+/// - `in 20m walk the dog`, also with `h` and `d`
+/// - `tomorrow 9:00 call the vet`, or `today`
+/// - `2025-01-01 12:00 happy new year`
+/// - `18:30 dinner`, the next time it's 18:30
+///
+/// `None` if it can't be parsed, has no text or is in the past.
+pub fn parse(args: &str, now: DateTime<Utc>, timezone: Tz) -> Option<(DateTime<Utc>, String)> {
+    let today = now.with_timezone(&timezone).date_naive();
+    let mut words = args.split_whitespace();
+    let first = words.next()?;
+    let at = match first.to_lowercase().as_str() {
+        "in" => {
+            let amount = words.next()?;
+            let digits = amount.len()
+                - amount
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            let (number, unit) = amount.split_at(digits);
+            let unit = match unit {
+                "" => words.next()?,
+                unit => unit,
+            };
+            let number = i64::from(number.parse::<u32>().ok()?);
+            let delay = match unit.to_lowercase().as_str() {
+                "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(number),
+                "h" | "hour" | "hours" => Duration::hours(number),
+                "d" | "day" | "days" => Duration::days(number),
+                _ => return None,
+            };
+            now.checked_add_signed(delay)?
+        }
+        "today" => timezones::resolve(timezone, today.and_time(parse_time(words.next()?)?))?,
+        "tomorrow" => timezones::resolve(
+            timezone,
+            (today + Duration::days(1)).and_time(parse_time(words.next()?)?),
+        )?,
+        _ => {
+            if let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+                timezones::resolve(timezone, date.and_time(parse_time(words.next()?)?))?
+            } else {
+                let time = parse_time(first)?;
+                [today, today + Duration::days(1)]
+                    .into_iter()
+                    .filter_map(|day| timezones::resolve(timezone, day.and_time(time)))
+                    .find(|at| *at > now)?
+            }
+        }
+    };
+
+    let text = words.collect::<Vec<_>>().join(" ");
+    if at <= now || text.is_empty() {
+        return None;
+    }
+    Some((at, text))
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+/// Schedule every stored reminder, the ones due while the bot was down are sent right away.
+pub async fn load(state: &Arc<AppState>) -> storage::Result<usize> {
+    let reminders = state.storage.reminders(None).await?;
+    let count = reminders.len();
+    for reminder in reminders {
+        start(state, reminder);
+    }
+    Ok(count)
+}
+
+pub async fn add(
+    state: &Arc<AppState>,
+    user_id: i64,
+    chat_id: ChatId,
+    at: DateTime<Utc>,
+    text: String,
+) -> storage::Result<Reminder> {
+    let mut reminder = Reminder {
+        id: 0,
+        user_id,
+        chat_id: chat_id.0,
+        at,
+        text,
+    };
+    reminder.id = state.storage.add_reminder(&reminder).await?;
+    start(state, reminder.clone());
+    Ok(reminder)
+}
+
+/// Returns whether the user had the reminder.
+pub async fn cancel(state: &Arc<AppState>, user_id: i64, id: i64) -> storage::Result<bool> {
+    let reminders = state.storage.reminders(Some(user_id)).await?;
+    if !reminders.iter().any(|reminder| reminder.id == id) {
+        return Ok(false);
+    }
+    state.storage.remove_reminder(id).await?;
+    stop(state, id).await;
+    Ok(true)
+}
+
+/// Stop waiting for the reminder, it must be removed from the storage separately.
+pub async fn stop(state: &Arc<AppState>, id: i64) {
+    state.scheduler.remove(&job_name(id)).await;
+}
+
+fn start(state: &Arc<AppState>, reminder: Reminder) {
+    let job = {
+        let state = state.clone();
+        let reminder = reminder.clone();
+        move || run(state.clone(), reminder.clone())
+    };
+    state
+        .scheduler
+        .add(job_name(reminder.id), Schedule::Once(reminder.at), job);
+}
+
+/// A reminder that couldn't be sent stays in the storage and is retried on the next start.
+async fn run(state: Arc<AppState>, reminder: Reminder) -> Result<(), JobError> {
+    let subscriptions = &state.subscriptions;
+    let text = &format!("Reminder: {}", reminder.text);
+    let send = move |chat_id| {
+        subscriptions
+            .queue
+            .send(move || subscriptions.bot.send_message(chat_id, text.clone()))
+    };
+    let mut chat_id = ChatId(reminder.chat_id);
+    let sent = match send(chat_id).await {
+        Err(e) => match migrated_to(&e) {
+            Some(to) => {
+                migrate_chat(&state, chat_id, to).await?;
+                chat_id = to;
+                send(to).await
+            }
+            None => Err(e),
+        },
+        sent => sent,
+    };
+    match sent {
+        Ok(_) => {}
+        Err(e) if chat_is_gone(&e) => {
+            info!("Dropping the reminder {}, the chat is gone", reminder.id);
+            deactivate_chat(&state, chat_id, &e).await?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    state.storage.remove_reminder(reminder.id).await?;
+    stop(&state, reminder.id).await;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Text of the messages, built apart from the handlers so their formatting is tested on its own.
+
+use crate::{api::dog::BreedsList, breed::BreedQuery, commands::BREED_PAGE, money};
+use std::fmt::Write;
+use teloxide::types::UserId;
+
+/// Longest text Telegram accepts in a message.
+pub const MAX_MESSAGE: usize = 4096;
+/// Longest caption Telegram accepts on a photo.
+pub const MAX_CAPTION: usize = 1024;
+/// Longest callback data Telegram accepts on a button, in bytes.
+const MAX_CALLBACK_DATA: usize = 64;
+
+/// Cut the text to `max` characters, marking where it was cut.
+pub fn fit(text: String, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text;
+    }
+    let mut fitted = text.chars().take(max - 1).collect::<String>();
+    fitted.push('…');
+    fitted
+}
+
+/// Cut the text to the longest caption Telegram accepts on a photo.
+pub fn fit_caption(text: String) -> String {
+    fit(text, MAX_CAPTION)
+}
+
+/// The breeds and their sub-breeds, alphabetically.
+pub fn breeds(breeds: &BreedsList) -> String {
+    let mut names = breeds.keys().collect::<Vec<_>>();
+    names.sort();
+
+    let mut text = String::new();
+    for name in names {
+        writeln!(text, "-│ {}", name).ok();
+        for variant in &breeds[name] {
+            writeln!(text, "     |> {}", variant).ok();
+        }
+    }
+    fit(text, MAX_MESSAGE)
+}
+
+/// The price of a coin in dollars, e.g. `BTC: $106,000.00`.
+pub fn price(symbol: &str, usd: Option<f64>) -> String {
+    match usd {
+        Some(usd) => format!("{}: {}", symbol.to_uppercase(), money::usd(usd)),
+        None => format!("I don't know the price of '{}'", symbol),
+    }
+}
+
+/// Breeds by how often they were asked for, most first.
+pub fn ranking(ranking: &[(String, i64)]) -> String {
+    if ranking.is_empty() {
+        return "Nothing yet, try /breed husky\n".to_string();
+    }
+    let mut text = String::new();
+    for (position, (breed, count)) in ranking.iter().enumerate() {
+        writeln!(text, "{}. {} ({})", position + 1, breed, count).ok();
+    }
+    text
+}
+
+/// Labels and callback data of the previous and next buttons around the image at `index`, wrapping around the
+/// ends. None with a single image, or when the breed doesn't fit in the callback data.
+pub fn page_buttons(
+    user_id: UserId,
+    breed: &BreedQuery,
+    index: usize,
+    len: usize,
+) -> Option<[(&'static str, String); 2]> {
+    if len < 2 {
+        return None;
+    }
+    let buttons =
+        [("◀️", (index + len - 1) % len), ("▶️", (index + 1) % len)].map(|(label, index)| {
+            (
+                label,
+                format!("{}:{}:{}:{}", BREED_PAGE, user_id, index, breed),
+            )
+        });
+    if buttons
+        .iter()
+        .any(|(_, data)| data.len() > MAX_CALLBACK_DATA)
+    {
+        return None;
+    }
+    Some(buttons)
+}
@@ -0,0 +1,125 @@
+//! Recording of the updates the bots receive, one JSON per line, and their replay through the handlers.
+//!
+//! Live traffic recorded with `--record` and replayed against mock APIs shows whether the handlers still answer it
+//! the same way.
+
+use crate::{commands, config::Config, state::AppState};
+use std::{error::Error, fs, ops::ControlFlow, path::Path, sync::Arc};
+use teloxide::{
+    dispatching::UpdateHandler,
+    prelude::*,
+    types::{Me, Update},
+};
+use tokio::{fs::File, io::AsyncWriteExt, sync::Mutex};
+use tracing::{info, warn};
+
+/// Username of the bot replaying the updates when none is given, commands addressed to another bot are ignored.
+pub const DEFAULT_USERNAME: &str = "dog_bot";
+
+/// Appends the updates to a JSONL file.
+pub struct Recorder {
+    file: Mutex<File>,
+}
+
+impl Recorder {
+    /// Append to the file at the path, creating it if needed.
+    pub async fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    pub async fn record(&self, update: &Update) {
+        let mut line = match serde_json::to_string(update) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Could not record the update {} -> {}", update.id, e);
+                return;
+            }
+        };
+        line.push('\n');
+        if let Err(e) = self.file.lock().await.write_all(line.as_bytes()).await {
+            warn!("Could not record the update {} -> {}", update.id, e);
+        }
+    }
+
+    /// Record every update before it reaches the given handlers.
+    pub fn handler(
+        self: Arc<Self>,
+        handler: UpdateHandler<Box<dyn Error + Send + Sync>>,
+    ) -> UpdateHandler<Box<dyn Error + Send + Sync>> {
+        dptree::filter_async(move |update: Update| {
+            let recorder = self.clone();
+            async move {
+                recorder.record(&update).await;
+                true
+            }
+        })
+        .chain(handler)
+    }
+}
+
+/// The updates of a recording, in the order they were received.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Update>, Box<dyn Error + Send + Sync>> {
+    fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("invalid update on line {}: {}", index + 1, e).into())
+        })
+        .collect()
+}
+
+/// The config of a replay, which must not touch the data of the running bot: the storage and the cache live in
+/// memory and the images are never uploaded.
+pub fn isolate(mut config: Config) -> Config {
+    config.storage.url = "memory://".to_string();
+    config.cache.redis_url = None;
+    config.api.telegram_local = false;
+    config
+}
+
+/// The bot as Telegram would describe it, the commands are parsed against its username.
+pub fn me(username: &str) -> Me {
+    serde_json::from_value(serde_json::json!({
+        "id": 1,
+        "is_bot": true,
+        "first_name": username,
+        "username": username,
+        "can_join_groups": true,
+        "can_read_all_group_messages": false,
+        "supports_inline_queries": true
+    }))
+    .expect("the bot is a valid user")
+}
+
+/// Feed the updates through the handlers one after the other, returning how many of them failed.
+pub async fn replay(
+    updates: Vec<Update>,
+    bot: AutoSend<Bot>,
+    me: Me,
+    state: Arc<AppState>,
+) -> usize {
+    let handler = commands::handler();
+    let mut failed = 0;
+    for update in updates {
+        let id = update.id;
+        let deps = dptree::deps![update, bot.clone(), me.clone(), state.clone()];
+        match handler.dispatch(deps).await {
+            ControlFlow::Break(Ok(())) => info!("Replayed the update {}", id),
+            ControlFlow::Break(Err(e)) => {
+                warn!("Replaying the update {} failed -> {}", id, e);
+                failed += 1;
+            }
+            ControlFlow::Continue(_) => info!("No handler for the update {}", id),
+        }
+    }
+    failed
+}
@@ -0,0 +1,181 @@
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    panic,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use teloxide::prelude::*;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Where and how often to alert the operator.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AdminConfig {
+    /// Chat that receives the alerts, alerting is disabled without it.
+    pub chat_id: Option<i64>,
+    /// Consecutive failures of an upstream before alerting.
+    pub failure_threshold: u32,
+    /// Minimum time between two alerts about the same problem.
+    pub alert_interval_secs: u64,
+    /// Share of the requests to an upstream failing over the window before alerting, never when 0.
+    pub error_rate_threshold: f64,
+    pub error_rate_window_secs: u64,
+    /// Fewer requests over the window say nothing about the error rate.
+    pub error_rate_min_requests: u64,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            chat_id: None,
+            failure_threshold: 3,
+            alert_interval_secs: 600,
+            error_rate_threshold: 0.5,
+            error_rate_window_secs: 300,
+            error_rate_min_requests: 10,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ReporterState {
+    failures: HashMap<String, u32>,
+    last_alerts: HashMap<String, Instant>,
+}
+
+/// Send condensed alerts about upstream outages and panics to the admin chat.
+pub struct ErrorReporter {
+    bot: AutoSend<Bot>,
+    config: Mutex<AdminConfig>,
+    state: Mutex<ReporterState>,
+}
+
+impl ErrorReporter {
+    pub fn new(bot: AutoSend<Bot>, config: AdminConfig) -> Self {
+        Self {
+            bot,
+            config: Mutex::new(config),
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn reconfigure(&self, config: AdminConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    pub fn config(&self) -> AdminConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Whether the chat is the one receiving the alerts.
+    pub fn is_admin_chat(&self, chat_id: ChatId) -> bool {
+        self.config.lock().unwrap().chat_id == Some(chat_id.0)
+    }
+
+    /// The upstream answered, so it's no longer failing.
+    pub fn success(&self, source: &str) {
+        self.state.lock().unwrap().failures.remove(source);
+    }
+
+    /// The upstream failed, alert once it has failed `failure_threshold` times in a row.
+    pub async fn failure(&self, source: &str, error: impl Display) {
+        let failures = {
+            let mut state = self.state.lock().unwrap();
+            let failures = state.failures.entry(source.to_string()).or_default();
+            *failures += 1;
+            *failures
+        };
+
+        let threshold = self.config.lock().unwrap().failure_threshold;
+        if failures >= threshold {
+            let text = format!(
+                "⚠️ {} failed {} times in a row\nLast error: {}",
+                source, failures, error
+            );
+            self.alert(source, text).await;
+        }
+    }
+
+    /// Something panicked.
+    pub async fn panic(&self, location: &str, message: &str) {
+        let text = format!("🔥 Panic at {}\n{}", location, message);
+        self.alert(&format!("panic:{}", location), text).await;
+    }
+
+    /// Send the text to the admin chat right away, if there is one.
+    pub async fn notify(&self, text: String) {
+        let chat_id = match self.config.lock().unwrap().chat_id {
+            Some(chat_id) => ChatId(chat_id),
+            None => return,
+        };
+        if let Err(e) = self.bot.send_message(chat_id, text).await {
+            error!("Could not alert the admin chat -> {}", e);
+        }
+    }
+
+    /// Send the alert unless the same one was sent recently.
+    async fn alert(&self, key: &str, text: String) {
+        let (chat_id, interval) = {
+            let config = self.config.lock().unwrap();
+            match config.chat_id {
+                Some(chat_id) => (
+                    ChatId(chat_id),
+                    Duration::from_secs(config.alert_interval_secs),
+                ),
+                None => return,
+            }
+        };
+
+        {
+            let mut state = self.state.lock().unwrap();
+            let recently_alerted = state
+                .last_alerts
+                .get(key)
+                .map(|last| last.elapsed() < interval)
+                .unwrap_or(false);
+            if recently_alerted {
+                return;
+            }
+            state.last_alerts.insert(key.to_string(), Instant::now());
+        }
+
+        if let Err(e) = self.bot.send_message(chat_id, text).await {
+            error!("Could not alert the admin chat -> {}", e);
+        }
+    }
+}
+
+/// Forward every panic to the admin chat, after the default hook has printed it.
+pub fn install_panic_hook(reporter: Arc<ErrorReporter>) {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<(String, String)>();
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line()))
+            .unwrap_or_else(|| "unknown location".to_string());
+        let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = info.payload().downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        };
+
+        if sender.send((location, message)).is_err() {
+            warn!("Panic reporter is gone");
+        }
+    }));
+
+    tokio::spawn(async move {
+        while let Some((location, message)) = receiver.recv().await {
+            reporter.panic(&location, &message).await;
+        }
+    });
+}
@@ -0,0 +1,230 @@
+use crate::{
+    storage::{JobRun, Storage},
+    timezones,
+};
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+use tracing::{error, info_span, warn, Instrument};
+
+pub type JobError = Box<dyn Error + Send + Sync>;
+type JobFuture = Pin<Box<dyn Future<Output = Result<(), JobError>> + Send>>;
+type JobFn = Arc<dyn Fn() -> JobFuture + Send + Sync>;
+
+/// When a job runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    /// Every day at the wall-clock time in the timezone, following its daylight saving changes.
+    Daily(NaiveTime, Tz),
+    /// Every week on the day, at the wall-clock time in the timezone.
+    Weekly(Weekday, NaiveTime, Tz),
+    /// At a fixed interval, the first run is one interval after the job is added.
+    Every(Duration),
+    /// A single run at the time, right away if it's already past.
+    Once(DateTime<Utc>),
+}
+
+impl Schedule {
+    /// First run strictly after `now`, or the time of a [`Schedule::Once`].
+    pub fn next_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Daily(at, timezone) => {
+                let today = now.with_timezone(timezone).date_naive();
+                (0..=2)
+                    .filter_map(|days| {
+                        timezones::resolve(
+                            *timezone,
+                            (today + chrono::Duration::days(days)).and_time(*at),
+                        )
+                    })
+                    .find(|next| *next > now)
+                    .unwrap_or(now + chrono::Duration::days(1))
+            }
+            Self::Weekly(day, at, timezone) => {
+                let today = now.with_timezone(timezone).date_naive();
+                (0..=14)
+                    .map(|days| today + chrono::Duration::days(days))
+                    .filter(|date| date.weekday() == *day)
+                    .filter_map(|date| timezones::resolve(*timezone, date.and_time(*at)))
+                    .find(|next| *next > now)
+                    .unwrap_or(now + chrono::Duration::weeks(1))
+            }
+            Self::Every(interval) => {
+                now + chrono::Duration::milliseconds(interval.as_millis() as i64)
+            }
+            Self::Once(at) => *at,
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily(at, timezone) => {
+                write!(f, "every day at {} {}", at.format("%H:%M"), timezone)
+            }
+            Self::Weekly(day, at, timezone) => write!(
+                f,
+                "every {} at {} {}",
+                day_name(*day),
+                at.format("%H:%M"),
+                timezone
+            ),
+            Self::Every(interval) => write!(f, "every {}s", interval.as_secs()),
+            Self::Once(at) => write!(f, "once at {}", at.format("%Y-%m-%d %H:%M UTC")),
+        }
+    }
+}
+
+fn day_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// What `/jobs` shows about a job.
+#[derive(Clone, Debug)]
+pub struct JobStatus {
+    pub name: String,
+    pub schedule: Schedule,
+    pub next_run: DateTime<Utc>,
+    /// Survives restarts, it's kept in the storage.
+    pub last: Option<JobRun>,
+}
+
+struct Job {
+    status: Arc<Mutex<JobStatus>>,
+    task: JoinHandle<()>,
+}
+
+/// Runs the recurring jobs, e.g. the deliveries of the subscriptions.
+///
+/// Each run is a task of its own, a job that fails or panics is logged and runs again on its next turn.
+pub struct Scheduler {
+    storage: Arc<dyn Storage>,
+    jobs: Mutex<BTreeMap<String, Job>>,
+}
+
+impl Scheduler {
+    /// The outcome of the runs is saved to the storage.
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            storage,
+            jobs: Mutex::default(),
+        }
+    }
+
+    /// Add the job, replacing the one with the same name.
+    pub fn add<F, Fut>(&self, name: impl Into<String>, schedule: Schedule, job: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), JobError>> + Send + 'static,
+    {
+        let name = name.into();
+        let job: JobFn = Arc::new(move || Box::pin(job()));
+        let status = Arc::new(Mutex::new(JobStatus {
+            name: name.clone(),
+            schedule,
+            next_run: schedule.next_after(Utc::now()),
+            last: None,
+        }));
+        let task = tokio::spawn(
+            run(self.storage.clone(), status.clone(), job)
+                .instrument(info_span!("job", name = name.as_str())),
+        );
+
+        let previous = self.jobs.lock().unwrap().insert(name, Job { status, task });
+        if let Some(previous) = previous {
+            previous.task.abort();
+        }
+    }
+
+    /// Stop the job and forget its runs, returns whether it existed.
+    ///
+    /// A run in progress is finished, so a job can remove itself.
+    pub async fn remove(&self, name: &str) -> bool {
+        let job = self.jobs.lock().unwrap().remove(name);
+        match job {
+            Some(job) => {
+                job.task.abort();
+                if let Err(e) = self.storage.remove_job_run(name).await {
+                    warn!("Could not forget the runs of the job {} -> {}", name, e);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Every job, by name.
+    pub fn jobs(&self) -> Vec<JobStatus> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|job| job.status.lock().unwrap().clone())
+            .collect()
+    }
+}
+
+async fn run(storage: Arc<dyn Storage>, status: Arc<Mutex<JobStatus>>, job: JobFn) {
+    let name = status.lock().unwrap().name.clone();
+    match storage.job_run(&name).await {
+        Ok(last) => status.lock().unwrap().last = last,
+        Err(e) => warn!("Could not load the last run -> {}", e),
+    }
+
+    loop {
+        let next_run = status.lock().unwrap().next_run;
+        let wait = (next_run - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        // Spawned so a panic only fails this run, and removing the job from the job itself doesn't cut it short
+        let started = Utc::now();
+        let error = match tokio::spawn(job()).await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(e) => Some(format!("The job panicked: {}", e)),
+        };
+        if let Some(e) = &error {
+            error!("Job failed -> {}", e);
+        }
+
+        let (last, once) = {
+            let mut status = status.lock().unwrap();
+            let previous = status.last.take();
+            let last = JobRun {
+                name: name.clone(),
+                last_run: started,
+                last_error: error.clone(),
+                runs: previous.as_ref().map_or(0, |run| run.runs) + 1,
+                failures: previous.as_ref().map_or(0, |run| run.failures)
+                    + i64::from(error.is_some()),
+            };
+            status.last = Some(last.clone());
+            status.next_run = status.schedule.next_after(Utc::now());
+            (last, matches!(status.schedule, Schedule::Once(_)))
+        };
+        if let Err(e) = storage.save_job_run(&last).await {
+            warn!("Could not save the run -> {}", e);
+        }
+        if once {
+            break;
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{admin_api, notify, state::AppState, storage::StorageError};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use metrics::gauge;
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, sync::Arc};
+use teloxide::RequestError;
+use tracing::{error, info};
+
+/// Embedded HTTP server.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address to listen on, e.g. `0.0.0.0:8080`. The server is disabled without it.
+    pub listen: Option<SocketAddr>,
+    /// Bearer token of the admin API under `/admin`, which is disabled without it.
+    pub admin_token: Option<String>,
+    /// Tokens of `POST /notify` and the chats each can message, the endpoint is disabled without any.
+    pub notify: Vec<notify::NotifyRule>,
+}
+
+pub fn router(state: Arc<AppState>, config: &ServerConfig) -> Router {
+    let mut router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics))
+        .with_state(state.clone());
+    if !config.notify.is_empty() {
+        router = router.merge(notify::router(state.clone(), config.notify.clone()));
+    }
+    match config.admin_token.as_deref() {
+        Some(token) if !token.is_empty() => {
+            router.merge(admin_api::router(state, token.to_string()))
+        }
+        _ => router,
+    }
+}
+
+pub async fn serve(addr: SocketAddr, state: Arc<AppState>, config: ServerConfig) {
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Could not listen on {} -> {}", addr, e);
+            return;
+        }
+    };
+
+    info!("Listening on {}", addr);
+    if let Err(e) = axum::serve(listener, router(state, &config)).await {
+        error!("HTTP server stopped -> {}", e);
+    }
+}
+
+fn status(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn healthz(State(state): State<Arc<AppState>>) -> (StatusCode, Json<impl serde::Serialize>) {
+    let report = state.health.report();
+    (status(report.alive), Json(report))
+}
+
+async fn readyz(State(state): State<Arc<AppState>>) -> (StatusCode, Json<impl serde::Serialize>) {
+    let report = state.health.report();
+    (status(report.ready), Json(report))
+}
+
+/// Prometheus text format, e.g. the latency and outcome of the commands and of the upstream requests.
+async fn metrics(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match &state.metrics {
+        Some(handle) => {
+            record_sizes(&state);
+            (StatusCode::OK, handle.render())
+        }
+        None => (StatusCode::NOT_FOUND, String::new()),
+    }
+}
+
+/// The gauges read from the state when scraped, rather than updated on every change.
+fn record_sizes(state: &AppState) {
+    gauge!("dog_buffer_size", state.dogs.len() as f64);
+    gauge!("inline_cache_entries", state.inline.len() as f64);
+    gauge!("scheduled_jobs", state.scheduler.jobs().len() as f64);
+}
+
+/// The token of the `Authorization: Bearer <token>` header.
+pub(crate) fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+/// Compare the whole tokens, so the time taken doesn't tell how much of it was right.
+pub(crate) fn same_token(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Failure of a request to the APIs of the server, answered as `{"error": "..."}`.
+pub(crate) enum ApiError {
+    BadRequest(String),
+    Unauthorized,
+    Forbidden(String),
+    Storage(StorageError),
+    Telegram(RequestError),
+    ChatGone,
+}
+
+impl From<StorageError> for ApiError {
+    fn from(e: StorageError) -> Self {
+        Self::Storage(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            Self::BadRequest(reason) => (StatusCode::BAD_REQUEST, reason),
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "unknown token".to_string()),
+            Self::Forbidden(reason) => (StatusCode::FORBIDDEN, reason),
+            Self::Storage(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            Self::Telegram(e) => (StatusCode::BAD_GATEWAY, e.to_string()),
+            Self::ChatGone => (
+                StatusCode::NOT_FOUND,
+                "the bot can't write to the chat".to_string(),
+            ),
+        };
+        (status, Json(ErrorBody { error })).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
@@ -0,0 +1,100 @@
+//! `/settings` of a chat: how long its users wait between two commands of each [`CommandClass`].
+
+use crate::{
+    commands::CommandsConfig,
+    limits::CommandClass,
+    storage::{ChatSettings, Storage},
+};
+use std::{fmt::Write, time::Duration};
+use tracing::warn;
+
+/// Longest cooldown a chat can set, an hour.
+pub const MAX_COOLDOWN_SECS: i64 = 60 * 60;
+
+/// Seconds of the class set in the chat, the configured ones when it set none.
+pub fn cooldown_secs(
+    settings: Option<&ChatSettings>,
+    class: CommandClass,
+    config: &CommandsConfig,
+) -> u64 {
+    let set = settings.and_then(|settings| match class {
+        CommandClass::Cheap => settings.cheap_cooldown_secs,
+        CommandClass::Expensive => settings.expensive_cooldown_secs,
+    });
+    match (set, class) {
+        (Some(secs), _) => secs.max(0) as u64,
+        (None, CommandClass::Cheap) => config.cheap_cooldown_secs,
+        (None, CommandClass::Expensive) => config.expensive_cooldown_secs,
+    }
+}
+
+/// Cooldown of the class in the chat, the configured one if the storage fails.
+pub async fn cooldown(
+    storage: &dyn Storage,
+    chat_id: i64,
+    class: CommandClass,
+    config: &CommandsConfig,
+) -> Duration {
+    let settings = match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            None
+        }
+    };
+    Duration::from_secs(cooldown_secs(settings.as_ref(), class, config))
+}
+
+/// Validate `value` and set it as the cooldown of the class `key`, `default` goes back to the configured one.
+pub fn set(settings: &mut ChatSettings, key: &str, value: &str) -> Result<(), String> {
+    let value = value.trim().to_lowercase();
+    let secs = match value.as_str() {
+        "default" => None,
+        "off" => Some(0),
+        secs => match secs.trim_end_matches('s').parse::<i64>() {
+            Ok(secs) if (0..=MAX_COOLDOWN_SECS).contains(&secs) => Some(secs),
+            _ => {
+                return Err(format!(
+                    "The cooldown must be a number of seconds up to {}, off or default",
+                    MAX_COOLDOWN_SECS
+                ))
+            }
+        },
+    };
+
+    match key.trim().to_lowercase().as_str() {
+        "cheap" => settings.cheap_cooldown_secs = secs,
+        "expensive" => settings.expensive_cooldown_secs = secs,
+        other => {
+            return Err(format!(
+                "Unknown setting '{}', use cheap or expensive",
+                other
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Summary shown by `/settings`.
+pub fn describe(settings: Option<&ChatSettings>, config: &CommandsConfig) -> String {
+    let mut text = String::from("Cooldowns of the chat, between two commands of a user:\n");
+    for (class, examples) in [
+        (CommandClass::Cheap, "dog photos"),
+        (CommandClass::Expensive, "charts and albums"),
+    ] {
+        let secs = cooldown_secs(settings, class, config);
+        let secs = if secs == 0 {
+            "off".to_string()
+        } else {
+            format!("{}s", secs)
+        };
+        writeln!(text, "{} ({}): {}", class.name(), examples, secs).ok();
+    }
+    text.push_str(
+        "\nChat admins change them with e.g. /settings expensive 60, or /settings cheap default",
+    );
+    text
+}
@@ -0,0 +1,95 @@
+use crate::{
+    alerts::{Edits, PriceHistory},
+    api::{
+        apod::ApodApi, breed_info::BreedInfoApi, defi::DefiApi, dictionary::DictionaryApi,
+        dog::DogApi, fx::FxApi, holidays::HolidaysApi, joke::JokeApi, market::MarketApi,
+        news::NewsApi, nft::NftApi, pokemon::PokemonApi, price::PriceApi, quote::QuoteApi,
+        shortener::ShortenerApi, stock::StockApi, translate::TranslateApi, trivia::TriviaApi,
+        urban::UrbanApi, weather::WeatherApi, wiki::WikiApi, xkcd::XkcdApi,
+    },
+    assets::Assets,
+    cache::FileIds,
+    commands::CommandsConfig,
+    config::Config,
+    health::Health,
+    images::Images,
+    inline::InlineCache,
+    limits::Limiter,
+    logging::LevelHandle,
+    prefetch::DogBuffer,
+    reporter::ErrorReporter,
+    scheduler::Scheduler,
+    storage::Storage,
+    subscriptions::Subscriptions,
+    trivia::Rounds,
+};
+use metrics_exporter_prometheus::PrometheusHandle;
+use std::sync::{Arc, RwLock};
+
+/// Everything the handlers depend on.
+pub struct AppState {
+    pub dog_api: Arc<dyn DogApi>,
+    pub breed_info_api: Arc<dyn BreedInfoApi>,
+    pub price_api: Arc<dyn PriceApi>,
+    pub nft_api: Arc<dyn NftApi>,
+    pub market_api: Arc<dyn MarketApi>,
+    pub fx_api: Arc<dyn FxApi>,
+    pub defi_api: Arc<dyn DefiApi>,
+    pub stock_api: Arc<dyn StockApi>,
+    pub weather_api: Arc<dyn WeatherApi>,
+    pub joke_api: Arc<dyn JokeApi>,
+    pub quote_api: Arc<dyn QuoteApi>,
+    pub xkcd_api: Arc<dyn XkcdApi>,
+    pub apod_api: Arc<dyn ApodApi>,
+    pub wiki_api: Arc<dyn WikiApi>,
+    pub dictionary_api: Arc<dyn DictionaryApi>,
+    pub urban_api: Arc<dyn UrbanApi>,
+    pub trivia_api: Arc<dyn TriviaApi>,
+    pub pokemon_api: Arc<dyn PokemonApi>,
+    pub holidays_api: Arc<dyn HolidaysApi>,
+    pub news_api: Arc<dyn NewsApi>,
+    /// Missing when no instance is configured.
+    pub translate_api: Option<Arc<dyn TranslateApi>>,
+    /// `None` when no shortener is configured.
+    pub shortener_api: Option<Arc<dyn ShortenerApi>>,
+    pub reporter: Arc<ErrorReporter>,
+    pub health: Arc<Health>,
+    pub storage: Arc<dyn Storage>,
+    pub file_ids: FileIds,
+    /// Prices seen by the alerts on the moves of the price.
+    pub price_history: PriceHistory,
+    /// Alerts waiting for their new threshold, see `/alerts`.
+    pub alert_edits: Edits,
+    /// Random dogs fetched ahead for `/doggo`.
+    pub dogs: DogBuffer,
+    pub images: Images,
+    /// Media bundled with the bot, e.g. the barks.
+    pub assets: Assets,
+    pub subscriptions: Subscriptions,
+    /// Answers of the inline queries.
+    pub inline: InlineCache,
+    pub scheduler: Scheduler,
+    pub commands: RwLock<CommandsConfig>,
+    pub limiter: Limiter,
+    pub trivia: Rounds,
+    /// Missing when the bot doesn't own the global subscriber, e.g. in the tests.
+    pub log_level: Option<LevelHandle>,
+    /// Renders the metrics served at `/metrics`, missing when the bot doesn't own the global recorder.
+    pub metrics: Option<PrometheusHandle>,
+    /// Upload the images instead of sending their URL, see [`crate::config::ApiConfig::telegram_local`].
+    pub upload_images: bool,
+}
+
+impl AppState {
+    /// Apply the settings that can change while running.
+    ///
+    /// The upstream URLs, the HTTP client, the telemetry and the server need a restart.
+    pub fn reconfigure(&self, config: &Config) {
+        *self.commands.write().unwrap() = config.commands.clone();
+        self.limiter.reconfigure(&config.commands);
+        self.reporter.reconfigure(config.admin.clone());
+        if let Some(log_level) = &self.log_level {
+            log_level.set(&config.log.level);
+        }
+    }
+}
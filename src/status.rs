@@ -0,0 +1,100 @@
+//! `/uptime`: how long the bot has been running and how its parts are doing, a quick check for the operators and
+//! the users alike.
+
+use crate::state::AppState;
+use chrono::Utc;
+use std::{fmt::Write, time::Duration};
+
+/// Hosts of the upstreams whose last success is shown, as counted by [`crate::health::Health`].
+const DOG_CEO_HOST: &str = "dog.ceo";
+const COINGECKO_HOST: &str = "api.coingecko.com";
+
+/// Snapshot of the bot, see [`Status::text`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Status {
+    pub uptime: Duration,
+    /// Updates handled since the start.
+    pub updates: u64,
+    /// How long ago each upstream last answered successfully.
+    pub dog_ceo: Option<Duration>,
+    pub coingecko: Option<Duration>,
+    /// How long ago the list of breeds was fetched.
+    pub breeds: Option<Duration>,
+    /// Random dogs fetched ahead.
+    pub buffered_dogs: usize,
+    pub inline_answers: usize,
+    pub jobs: usize,
+    /// Jobs whose last run failed.
+    pub failing_jobs: usize,
+}
+
+impl Status {
+    pub async fn of(state: &AppState) -> Self {
+        let jobs = state.scheduler.jobs();
+        Self {
+            uptime: state.health.uptime(),
+            updates: state.health.updates(),
+            dog_ceo: state.health.last_success(DOG_CEO_HOST),
+            coingecko: state.health.last_success(COINGECKO_HOST),
+            breeds: state
+                .dog_api
+                .breeds_fetched_at()
+                .await
+                .and_then(|at| (Utc::now() - at).to_std().ok()),
+            buffered_dogs: state.dogs.len(),
+            inline_answers: state.inline.len(),
+            failing_jobs: jobs
+                .iter()
+                .filter(|job| matches!(&job.last, Some(last) if last.last_error.is_some()))
+                .count(),
+            jobs: jobs.len(),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = format!("⏱ Up for {}\n", duration(self.uptime));
+        writeln!(text, "Updates handled: {}", self.updates).ok();
+        writeln!(text, "\nLast success").ok();
+        writeln!(text, "dog.ceo: {}", ago(self.dog_ceo)).ok();
+        writeln!(text, "CoinGecko: {}", ago(self.coingecko)).ok();
+        writeln!(text, "\nCache").ok();
+        writeln!(text, "breeds fetched: {}", ago(self.breeds)).ok();
+        writeln!(text, "dogs ready: {}", self.buffered_dogs).ok();
+        writeln!(text, "inline answers: {}", self.inline_answers).ok();
+        write!(
+            text,
+            "\nScheduled jobs: {}, {} failing",
+            self.jobs, self.failing_jobs
+        )
+        .ok();
+        text
+    }
+}
+
+fn ago(elapsed: Option<Duration>) -> String {
+    match elapsed {
+        Some(elapsed) => format!("{} ago", duration(elapsed)),
+        None => "never".to_string(),
+    }
+}
+
+/// The two largest units, e.g. `2d 3h`, `5m 12s` or `40s`.
+fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let units = [
+        (secs / 86400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+    let first = units
+        .iter()
+        .position(|(value, _)| *value > 0)
+        .unwrap_or(units.len() - 1);
+    units[first..]
+        .iter()
+        .take(2)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
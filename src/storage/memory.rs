@@ -0,0 +1,523 @@
+use super::{
+    Alert, ChatSettings, CommandRecord, CommandStats, InactiveChat, JobRun, Preferences, Reminder,
+    Result, Storage, Subscription, User,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+#[derive(Default)]
+struct Data {
+    next_id: i64,
+    users: HashMap<i64, User>,
+    preferences: HashMap<i64, Preferences>,
+    chat_settings: HashMap<i64, ChatSettings>,
+    subscriptions: BTreeMap<i64, Subscription>,
+    alerts: BTreeMap<i64, Alert>,
+    reminders: BTreeMap<i64, Reminder>,
+    favourites: BTreeSet<(i64, String)>,
+    command_log: Vec<CommandRecord>,
+    breed_requests: Vec<(i64, String, DateTime<Utc>)>,
+    audit_log: Vec<(String, i64, DateTime<Utc>)>,
+    job_runs: HashMap<String, JobRun>,
+    /// Name and points, by chat and user.
+    trivia_scores: BTreeMap<(i64, i64), (String, i64)>,
+    inactive_chats: HashMap<i64, InactiveChat>,
+    update_offsets: HashMap<String, i64>,
+}
+
+impl Data {
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+/// [`Storage`] that forgets everything on restart, for the tests.
+#[derive(Default)]
+pub struct MemoryStorage {
+    data: Mutex<Data>,
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn see_user(&self, id: i64, username: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        let mut data = self.data.lock().unwrap();
+        let user = data.users.entry(id).or_insert_with(|| User {
+            id,
+            username: None,
+            first_seen: now,
+            last_seen: now,
+        });
+        user.username = username.map(str::to_string);
+        user.last_seen = now;
+        Ok(())
+    }
+
+    async fn user(&self, id: i64) -> Result<Option<User>> {
+        Ok(self.data.lock().unwrap().users.get(&id).cloned())
+    }
+
+    async fn forget_user(&self, id: i64) -> Result<()> {
+        let mut data = self.data.lock().unwrap();
+        data.users.remove(&id);
+        data.preferences.remove(&id);
+        data.favourites.retain(|(user_id, _)| *user_id != id);
+        data.alerts.retain(|_, alert| alert.user_id != id);
+        data.reminders.retain(|_, reminder| reminder.user_id != id);
+        data.trivia_scores.retain(|(_, user_id), _| *user_id != id);
+        for record in &mut data.command_log {
+            if record.user_id == Some(id) {
+                record.user_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn record_audit(&self, action: &str, user_id: i64) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .audit_log
+            .push((action.to_string(), user_id, Utc::now()));
+        Ok(())
+    }
+
+    async fn preferences(&self, user_id: i64) -> Result<Option<Preferences>> {
+        Ok(self.data.lock().unwrap().preferences.get(&user_id).cloned())
+    }
+
+    async fn save_preferences(&self, preferences: &Preferences) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .preferences
+            .insert(preferences.user_id, preferences.clone());
+        Ok(())
+    }
+
+    async fn chat_settings(&self, chat_id: i64) -> Result<Option<ChatSettings>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .chat_settings
+            .get(&chat_id)
+            .cloned())
+    }
+
+    async fn save_chat_settings(&self, settings: &ChatSettings) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .chat_settings
+            .insert(settings.chat_id, settings.clone());
+        Ok(())
+    }
+
+    async fn migrate_chat(&self, from: i64, to: i64) -> Result<()> {
+        let mut data = self.data.lock().unwrap();
+        if let Some(mut settings) = data.chat_settings.remove(&from) {
+            settings.chat_id = to;
+            data.chat_settings.entry(to).or_insert(settings);
+        }
+        let kinds = data
+            .subscriptions
+            .values()
+            .filter(|subscription| subscription.chat_id == to)
+            .map(|subscription| subscription.kind.clone())
+            .collect::<Vec<_>>();
+        data.subscriptions.retain(|_, subscription| {
+            subscription.chat_id != from || !kinds.contains(&subscription.kind)
+        });
+        for subscription in data.subscriptions.values_mut() {
+            if subscription.chat_id == from {
+                subscription.chat_id = to;
+            }
+        }
+        let scores = data
+            .trivia_scores
+            .iter()
+            .filter(|((chat_id, _), _)| *chat_id == from)
+            .map(|(key, score)| (*key, score.clone()))
+            .collect::<Vec<_>>();
+        for ((_, user_id), score) in scores {
+            data.trivia_scores.remove(&(from, user_id));
+            data.trivia_scores.entry((to, user_id)).or_insert(score);
+        }
+        for alert in data.alerts.values_mut() {
+            if alert.chat_id == from {
+                alert.chat_id = to;
+            }
+        }
+        for reminder in data.reminders.values_mut() {
+            if reminder.chat_id == from {
+                reminder.chat_id = to;
+            }
+        }
+        for record in &mut data.command_log {
+            if record.chat_id == from {
+                record.chat_id = to;
+            }
+        }
+        for (chat_id, _, _) in &mut data.breed_requests {
+            if *chat_id == from {
+                *chat_id = to;
+            }
+        }
+        Ok(())
+    }
+
+    async fn deactivate_chat(&self, chat: &InactiveChat) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .inactive_chats
+            .insert(chat.chat_id, chat.clone());
+        Ok(())
+    }
+
+    async fn reactivate_chat(&self, chat_id: i64) -> Result<()> {
+        self.data.lock().unwrap().inactive_chats.remove(&chat_id);
+        Ok(())
+    }
+
+    async fn inactive_chats(&self) -> Result<Vec<InactiveChat>> {
+        let mut chats = self
+            .data
+            .lock()
+            .unwrap()
+            .inactive_chats
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        chats.sort_by_key(|chat| Reverse(chat.since));
+        Ok(chats)
+    }
+
+    async fn save_subscription(&self, subscription: &Subscription) -> Result<i64> {
+        let mut data = self.data.lock().unwrap();
+        let existing = data
+            .subscriptions
+            .values()
+            .find(|s| s.chat_id == subscription.chat_id && s.kind == subscription.kind)
+            .map(|s| s.id);
+        let id = match existing {
+            Some(id) => id,
+            None => data.next_id(),
+        };
+        data.subscriptions.insert(
+            id,
+            Subscription {
+                id,
+                ..subscription.clone()
+            },
+        );
+        Ok(id)
+    }
+
+    async fn subscriptions(&self) -> Result<Vec<Subscription>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .subscriptions
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn chat_subscriptions(&self, chat_id: i64) -> Result<Vec<Subscription>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .subscriptions
+            .values()
+            .filter(|subscription| subscription.chat_id == chat_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn remove_subscription(&self, id: i64) -> Result<()> {
+        self.data.lock().unwrap().subscriptions.remove(&id);
+        Ok(())
+    }
+
+    async fn add_alert(&self, alert: &Alert) -> Result<i64> {
+        let mut data = self.data.lock().unwrap();
+        let id = data.next_id();
+        data.alerts.insert(
+            id,
+            Alert {
+                id,
+                ..alert.clone()
+            },
+        );
+        Ok(id)
+    }
+
+    async fn alerts(&self, user_id: Option<i64>) -> Result<Vec<Alert>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .alerts
+            .values()
+            .filter(|alert| user_id.is_none_or(|user_id| alert.user_id == user_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_alert(&self, alert: &Alert) -> Result<()> {
+        if let Some(stored) = self.data.lock().unwrap().alerts.get_mut(&alert.id) {
+            stored.target = alert.target;
+            stored.above = alert.above;
+            stored.move_percent = alert.move_percent;
+            stored.paused = alert.paused;
+        }
+        Ok(())
+    }
+
+    async fn remove_alert(&self, id: i64) -> Result<()> {
+        self.data.lock().unwrap().alerts.remove(&id);
+        Ok(())
+    }
+
+    async fn add_reminder(&self, reminder: &Reminder) -> Result<i64> {
+        let mut data = self.data.lock().unwrap();
+        let id = data.next_id();
+        data.reminders.insert(
+            id,
+            Reminder {
+                id,
+                ..reminder.clone()
+            },
+        );
+        Ok(id)
+    }
+
+    async fn reminders(&self, user_id: Option<i64>) -> Result<Vec<Reminder>> {
+        let mut reminders = self
+            .data
+            .lock()
+            .unwrap()
+            .reminders
+            .values()
+            .filter(|reminder| user_id.is_none_or(|user_id| reminder.user_id == user_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        reminders.sort_by_key(|reminder| (reminder.at, reminder.id));
+        Ok(reminders)
+    }
+
+    async fn remove_reminder(&self, id: i64) -> Result<()> {
+        self.data.lock().unwrap().reminders.remove(&id);
+        Ok(())
+    }
+
+    async fn add_favourite(&self, user_id: i64, breed: &str) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .favourites
+            .insert((user_id, breed.to_string()));
+        Ok(())
+    }
+
+    async fn favourites(&self, user_id: i64) -> Result<Vec<String>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .favourites
+            .iter()
+            .filter(|(user, _)| *user == user_id)
+            .map(|(_, breed)| breed.clone())
+            .collect())
+    }
+
+    async fn remove_favourite(&self, user_id: i64, breed: &str) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .favourites
+            .remove(&(user_id, breed.to_string()));
+        Ok(())
+    }
+
+    async fn record_command(&self, record: &CommandRecord) -> Result<()> {
+        self.data.lock().unwrap().command_log.push(record.clone());
+        Ok(())
+    }
+
+    async fn command_counts(
+        &self,
+        chat_id: Option<i64>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(String, i64)>> {
+        let mut counts = HashMap::<String, i64>::new();
+        for record in &self.data.lock().unwrap().command_log {
+            if chat_id.is_none_or(|chat_id| chat_id == record.chat_id) && record.at >= since {
+                *counts.entry(record.command.clone()).or_default() += 1;
+            }
+        }
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(counts)
+    }
+
+    async fn command_stats(&self, since: DateTime<Utc>) -> Result<Vec<CommandStats>> {
+        let mut stats = HashMap::<String, CommandStats>::new();
+        for record in &self.data.lock().unwrap().command_log {
+            if record.at < since {
+                continue;
+            }
+            let stats = stats
+                .entry(record.command.clone())
+                .or_insert_with(|| CommandStats {
+                    command: record.command.clone(),
+                    count: 0,
+                    errors: 0,
+                    avg_latency_ms: 0.0,
+                });
+            // Running mean
+            stats.count += 1;
+            stats.avg_latency_ms +=
+                (record.latency_ms as f64 - stats.avg_latency_ms) / stats.count as f64;
+            if !record.success {
+                stats.errors += 1;
+            }
+        }
+        let mut stats = stats.into_values().collect::<Vec<_>>();
+        stats.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.command.cmp(&b.command))
+        });
+        Ok(stats)
+    }
+
+    async fn user_command_counts(&self, user_id: i64) -> Result<Vec<(String, i64)>> {
+        let mut counts = HashMap::<String, i64>::new();
+        for record in &self.data.lock().unwrap().command_log {
+            if record.user_id == Some(user_id) {
+                *counts.entry(record.command.clone()).or_default() += 1;
+            }
+        }
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(counts)
+    }
+
+    async fn active_users(&self, since: DateTime<Utc>) -> Result<i64> {
+        let users = self
+            .data
+            .lock()
+            .unwrap()
+            .command_log
+            .iter()
+            .filter(|record| record.at >= since)
+            .filter_map(|record| record.user_id)
+            .collect::<BTreeSet<_>>();
+        Ok(users.len() as i64)
+    }
+
+    async fn job_run(&self, name: &str) -> Result<Option<JobRun>> {
+        Ok(self.data.lock().unwrap().job_runs.get(name).cloned())
+    }
+
+    async fn save_job_run(&self, run: &JobRun) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .job_runs
+            .insert(run.name.clone(), run.clone());
+        Ok(())
+    }
+
+    async fn remove_job_run(&self, name: &str) -> Result<()> {
+        self.data.lock().unwrap().job_runs.remove(name);
+        Ok(())
+    }
+
+    async fn update_offset(&self, bot: &str) -> Result<Option<i64>> {
+        Ok(self.data.lock().unwrap().update_offsets.get(bot).copied())
+    }
+
+    async fn save_update_offset(&self, bot: &str, update_id: i64) -> Result<()> {
+        let mut data = self.data.lock().unwrap();
+        let offset = data
+            .update_offsets
+            .entry(bot.to_string())
+            .or_insert(update_id);
+        *offset = (*offset).max(update_id);
+        Ok(())
+    }
+
+    async fn record_breed(&self, chat_id: i64, breed: &str) -> Result<()> {
+        self.data
+            .lock()
+            .unwrap()
+            .breed_requests
+            .push((chat_id, breed.to_string(), Utc::now()));
+        Ok(())
+    }
+
+    async fn popular_breeds(
+        &self,
+        chat_id: Option<i64>,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>> {
+        let mut counts = HashMap::<String, i64>::new();
+        for (chat, breed, at) in &self.data.lock().unwrap().breed_requests {
+            if chat_id.map(|chat_id| chat_id == *chat).unwrap_or(true)
+                && since.is_none_or(|since| *at >= since)
+            {
+                *counts.entry(breed.clone()).or_default() += 1;
+            }
+        }
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(limit as usize);
+        Ok(counts)
+    }
+
+    async fn add_trivia_points(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        name: &str,
+        points: i64,
+    ) -> Result<()> {
+        let mut data = self.data.lock().unwrap();
+        let score = data
+            .trivia_scores
+            .entry((chat_id, user_id))
+            .or_insert_with(|| (String::new(), 0));
+        score.0 = name.to_string();
+        score.1 += points;
+        Ok(())
+    }
+
+    async fn trivia_scores(&self, chat_id: i64, limit: i64) -> Result<Vec<(String, i64)>> {
+        let mut scores = self
+            .data
+            .lock()
+            .unwrap()
+            .trivia_scores
+            .iter()
+            .filter(|((chat, _), _)| *chat == chat_id)
+            .map(|(_, score)| score.clone())
+            .collect::<Vec<_>>();
+        scores.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scores.truncate(limit as usize);
+        Ok(scores)
+    }
+}
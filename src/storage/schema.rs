@@ -0,0 +1,131 @@
+//! Tables and columns of the database, read from the migrations so they can't drift from what is stored.
+
+use super::sqlite;
+
+/// A table as left by the migrations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+/// Words opening a table constraint instead of a column definition.
+const CONSTRAINTS: &[&str] = &["PRIMARY", "UNIQUE", "FOREIGN", "CHECK", "CONSTRAINT"];
+
+/// Tables of the current schema, in the order they were created.
+///
+/// The SQLite migrations are the reference, the Postgres ones create the same tables.
+pub fn tables() -> Vec<Table> {
+    parse(
+        sqlite::MIGRATOR
+            .iter()
+            .map(|migration| migration.sql.as_ref()),
+    )
+}
+
+/// Apply the `CREATE TABLE`, `DROP TABLE` and `ALTER TABLE` statements of the migrations, in order.
+pub fn parse<'a>(migrations: impl IntoIterator<Item = &'a str>) -> Vec<Table> {
+    // Without the comments, each migration ending its last statement
+    let sql: String = migrations
+        .into_iter()
+        .map(|sql| {
+            let lines: Vec<&str> = sql
+                .lines()
+                .map(|line| line.split("--").next().unwrap_or_default())
+                .collect();
+            lines.join("\n") + ";"
+        })
+        .collect();
+
+    let mut tables: Vec<Table> = Vec::new();
+    for statement in sql.split(';') {
+        let words: Vec<&str> = statement.split_whitespace().collect();
+        let upper: Vec<String> = words.iter().map(|word| word.to_uppercase()).collect();
+        let upper: Vec<&str> = upper.iter().map(String::as_str).collect();
+        match upper.as_slice() {
+            ["CREATE", "TABLE", ..] => {
+                let name = match upper.get(2..5) {
+                    Some(["IF", "NOT", "EXISTS"]) => words.get(5),
+                    _ => words.get(2),
+                };
+                let name = match name {
+                    Some(name) => unquote(name.split('(').next().unwrap_or_default()),
+                    None => continue,
+                };
+                let columns = columns(statement);
+                tables.retain(|table| table.name != name);
+                tables.push(Table { name, columns });
+            }
+            ["DROP", "TABLE", ..] => {
+                if let Some(name) = words.last() {
+                    let name = unquote(name);
+                    tables.retain(|table| table.name != name);
+                }
+            }
+            ["ALTER", "TABLE", _, rest @ ..] => {
+                let name = unquote(words[2]);
+                let table = match tables.iter_mut().find(|table| table.name == name) {
+                    Some(table) => table,
+                    None => continue,
+                };
+                let words = &words[3..];
+                match rest {
+                    ["ADD", "COLUMN", _, ..] => table.columns.push(unquote(words[2])),
+                    ["ADD", _, ..] => table.columns.push(unquote(words[1])),
+                    ["DROP", "COLUMN", _, ..] => {
+                        let column = unquote(words[2]);
+                        table.columns.retain(|known| *known != column);
+                    }
+                    ["RENAME", "COLUMN", _, "TO", _, ..] => {
+                        let (from, to) = (unquote(words[2]), unquote(words[4]));
+                        if let Some(column) = table.columns.iter_mut().find(|known| **known == from)
+                        {
+                            *column = to;
+                        }
+                    }
+                    ["RENAME", "TO", _, ..] => table.name = unquote(words[2]),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    tables
+}
+
+/// Names of the columns defined between the outer parentheses of a `CREATE TABLE`.
+fn columns(statement: &str) -> Vec<String> {
+    let (start, end) = match (statement.find('('), statement.rfind(')')) {
+        (Some(start), Some(end)) if start < end => (start, end),
+        _ => return Vec::new(),
+    };
+
+    let mut definitions = Vec::new();
+    let mut depth = 0;
+    let mut definition = String::new();
+    for c in statement[start + 1..end].chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                definitions.push(std::mem::take(&mut definition));
+                continue;
+            }
+            _ => {}
+        }
+        definition.push(c);
+    }
+    definitions.push(definition);
+
+    definitions
+        .iter()
+        .filter_map(|definition| definition.split_whitespace().next())
+        .filter(|name| !CONSTRAINTS.contains(&name.to_uppercase().as_str()))
+        .map(unquote)
+        .collect()
+}
+
+fn unquote(name: &str) -> String {
+    name.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .to_lowercase()
+}
@@ -0,0 +1,534 @@
+use super::{
+    Alert, ChatSettings, CommandRecord, CommandStats, InactiveChat, JobRun, Preferences, Reminder,
+    Result, Storage, Subscription, User,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
+use std::str::FromStr;
+
+pub(super) static MIGRATOR: Migrator = sqlx::migrate!("migrations/sqlite");
+
+/// [`Storage`] in a SQLite file.
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Open the database at `url` (e.g. `sqlite://dog-bot.db` or `sqlite::memory:`), migrating it to the latest schema.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        // Every connection to an in-memory database would get its own empty database
+        let max_connections = if url.contains(":memory:") { 1 } else { 5 };
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await?;
+
+        MIGRATOR.run(&pool).await?;
+        super::log_schema_version(&MIGRATOR);
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn see_user(&self, id: i64, username: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO users (id, username, first_seen, last_seen) VALUES (?, ?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET username = excluded.username, last_seen = excluded.last_seen",
+        )
+        .bind(id)
+        .bind(username)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn user(&self, id: i64) -> Result<Option<User>> {
+        Ok(sqlx::query_as("SELECT * FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn forget_user(&self, id: i64) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        for statement in [
+            "DELETE FROM users WHERE id = ?",
+            "DELETE FROM preferences WHERE user_id = ?",
+            "DELETE FROM favourites WHERE user_id = ?",
+            "DELETE FROM alerts WHERE user_id = ?",
+            "DELETE FROM reminders WHERE user_id = ?",
+            "DELETE FROM trivia_scores WHERE user_id = ?",
+            "UPDATE command_log SET user_id = NULL WHERE user_id = ?",
+        ] {
+            sqlx::query(statement)
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn record_audit(&self, action: &str, user_id: i64) -> Result<()> {
+        sqlx::query("INSERT INTO audit_log (action, user_id, at) VALUES (?, ?, ?)")
+            .bind(action)
+            .bind(user_id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn preferences(&self, user_id: i64) -> Result<Option<Preferences>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM preferences WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn save_preferences(&self, preferences: &Preferences) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO preferences (user_id, favourite_breed, currency, language, quiz_difficulty, city)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (user_id) DO UPDATE SET favourite_breed = excluded.favourite_breed,
+                currency = excluded.currency, language = excluded.language,
+                quiz_difficulty = excluded.quiz_difficulty, city = excluded.city",
+        )
+        .bind(preferences.user_id)
+        .bind(&preferences.favourite_breed)
+        .bind(&preferences.currency)
+        .bind(&preferences.language)
+        .bind(&preferences.quiz_difficulty)
+        .bind(&preferences.city)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn chat_settings(&self, chat_id: i64) -> Result<Option<ChatSettings>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM chat_settings WHERE chat_id = ?")
+                .bind(chat_id)
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn save_chat_settings(&self, settings: &ChatSettings) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO chat_settings (chat_id, language, timezone, quiet_hours, news_source, nsfw_filter, country, alt_text,
+             cheap_cooldown_secs, expensive_cooldown_secs)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (chat_id) DO UPDATE SET language = excluded.language, timezone = excluded.timezone,
+             quiet_hours = excluded.quiet_hours, news_source = excluded.news_source,
+             nsfw_filter = excluded.nsfw_filter, country = excluded.country, alt_text = excluded.alt_text,
+             cheap_cooldown_secs = excluded.cheap_cooldown_secs, expensive_cooldown_secs = excluded.expensive_cooldown_secs",
+        )
+        .bind(settings.chat_id)
+        .bind(&settings.language)
+        .bind(&settings.timezone)
+        .bind(&settings.quiet_hours)
+        .bind(&settings.news_source)
+        .bind(settings.nsfw_filter)
+        .bind(&settings.country)
+        .bind(settings.alt_text)
+        .bind(settings.cheap_cooldown_secs)
+        .bind(settings.expensive_cooldown_secs)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn migrate_chat(&self, from: i64, to: i64) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        for statement in [
+            "DELETE FROM chat_settings WHERE chat_id = ?2 AND EXISTS (SELECT 1 FROM chat_settings WHERE chat_id = ?1)",
+            "UPDATE chat_settings SET chat_id = ?1 WHERE chat_id = ?2",
+            "DELETE FROM subscriptions WHERE chat_id = ?2 AND kind IN (SELECT kind FROM subscriptions WHERE chat_id = ?1)",
+            "UPDATE subscriptions SET chat_id = ?1 WHERE chat_id = ?2",
+            "DELETE FROM trivia_scores WHERE chat_id = ?2 AND user_id IN (SELECT user_id FROM trivia_scores WHERE chat_id = ?1)",
+            "UPDATE trivia_scores SET chat_id = ?1 WHERE chat_id = ?2",
+            "UPDATE alerts SET chat_id = ?1 WHERE chat_id = ?2",
+            "UPDATE reminders SET chat_id = ?1 WHERE chat_id = ?2",
+            "UPDATE command_log SET chat_id = ?1 WHERE chat_id = ?2",
+            "UPDATE breed_requests SET chat_id = ?1 WHERE chat_id = ?2",
+        ] {
+            sqlx::query(statement)
+                .bind(to)
+                .bind(from)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn deactivate_chat(&self, chat: &InactiveChat) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO inactive_chats (chat_id, reason, since) VALUES (?, ?, ?)
+             ON CONFLICT (chat_id) DO UPDATE SET reason = excluded.reason, since = excluded.since",
+        )
+        .bind(chat.chat_id)
+        .bind(&chat.reason)
+        .bind(chat.since)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn reactivate_chat(&self, chat_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM inactive_chats WHERE chat_id = ?")
+            .bind(chat_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn inactive_chats(&self) -> Result<Vec<InactiveChat>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM inactive_chats ORDER BY since DESC")
+                .fetch_all(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn save_subscription(&self, subscription: &Subscription) -> Result<i64> {
+        Ok(sqlx::query_scalar(
+            "INSERT INTO subscriptions (chat_id, kind, schedule, options) VALUES (?, ?, ?, ?)
+             ON CONFLICT (chat_id, kind) DO UPDATE SET schedule = excluded.schedule, options = excluded.options
+             RETURNING id",
+        )
+        .bind(subscription.chat_id)
+        .bind(&subscription.kind)
+        .bind(&subscription.schedule)
+        .bind(&subscription.options)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn subscriptions(&self) -> Result<Vec<Subscription>> {
+        Ok(sqlx::query_as("SELECT * FROM subscriptions ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    async fn chat_subscriptions(&self, chat_id: i64) -> Result<Vec<Subscription>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM subscriptions WHERE chat_id = ? ORDER BY id")
+                .bind(chat_id)
+                .fetch_all(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn remove_subscription(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM subscriptions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_alert(&self, alert: &Alert) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO alerts (user_id, chat_id, symbol, target, above, move_percent, window_secs, created_at, paused)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(alert.user_id)
+        .bind(alert.chat_id)
+        .bind(&alert.symbol)
+        .bind(alert.target)
+        .bind(alert.above)
+        .bind(alert.move_percent)
+        .bind(alert.window_secs)
+        .bind(alert.created_at)
+        .bind(alert.paused)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn alerts(&self, user_id: Option<i64>) -> Result<Vec<Alert>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM alerts WHERE ? IS NULL OR user_id = ? ORDER BY id")
+                .bind(user_id)
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn update_alert(&self, alert: &Alert) -> Result<()> {
+        sqlx::query(
+            "UPDATE alerts SET target = ?, above = ?, move_percent = ?, paused = ? WHERE id = ?",
+        )
+        .bind(alert.target)
+        .bind(alert.above)
+        .bind(alert.move_percent)
+        .bind(alert.paused)
+        .bind(alert.id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_alert(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM alerts WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_reminder(&self, reminder: &Reminder) -> Result<i64> {
+        Ok(sqlx::query_scalar(
+            "INSERT INTO reminders (user_id, chat_id, at, text) VALUES (?, ?, ?, ?) RETURNING id",
+        )
+        .bind(reminder.user_id)
+        .bind(reminder.chat_id)
+        .bind(reminder.at)
+        .bind(&reminder.text)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn reminders(&self, user_id: Option<i64>) -> Result<Vec<Reminder>> {
+        Ok(
+            sqlx::query_as(
+                "SELECT * FROM reminders WHERE ? IS NULL OR user_id = ? ORDER BY at, id",
+            )
+            .bind(user_id)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?,
+        )
+    }
+
+    async fn remove_reminder(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM reminders WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_favourite(&self, user_id: i64, breed: &str) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO favourites (user_id, breed) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(breed)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn favourites(&self, user_id: i64) -> Result<Vec<String>> {
+        Ok(
+            sqlx::query_scalar("SELECT breed FROM favourites WHERE user_id = ? ORDER BY breed")
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn remove_favourite(&self, user_id: i64, breed: &str) -> Result<()> {
+        sqlx::query("DELETE FROM favourites WHERE user_id = ? AND breed = ?")
+            .bind(user_id)
+            .bind(breed)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_command(&self, record: &CommandRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO command_log (command, user_id, chat_id, chat_type, at, latency_ms, success)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&record.command)
+        .bind(record.user_id)
+        .bind(record.chat_id)
+        .bind(&record.chat_type)
+        .bind(record.at)
+        .bind(record.latency_ms)
+        .bind(record.success)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn command_counts(
+        &self,
+        chat_id: Option<i64>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(String, i64)>> {
+        Ok(sqlx::query_as(
+            "SELECT command, COUNT(*) AS count FROM command_log
+             WHERE (? IS NULL OR chat_id = ?) AND at >= ?
+             GROUP BY command ORDER BY count DESC, command",
+        )
+        .bind(chat_id)
+        .bind(chat_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn command_stats(&self, since: DateTime<Utc>) -> Result<Vec<CommandStats>> {
+        Ok(sqlx::query_as(
+            "SELECT command, COUNT(*) AS count,
+                    SUM(CASE WHEN success THEN 0 ELSE 1 END) AS errors,
+                    CAST(AVG(latency_ms) AS DOUBLE PRECISION) AS avg_latency_ms
+             FROM command_log WHERE at >= ?
+             GROUP BY command ORDER BY count DESC, command",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn user_command_counts(&self, user_id: i64) -> Result<Vec<(String, i64)>> {
+        Ok(sqlx::query_as(
+            "SELECT command, COUNT(*) AS count FROM command_log WHERE user_id = ?
+             GROUP BY command ORDER BY count DESC, command",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn active_users(&self, since: DateTime<Utc>) -> Result<i64> {
+        Ok(
+            sqlx::query_scalar("SELECT COUNT(DISTINCT user_id) FROM command_log WHERE at >= ?")
+                .bind(since)
+                .fetch_one(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn job_run(&self, name: &str) -> Result<Option<JobRun>> {
+        Ok(sqlx::query_as("SELECT * FROM job_runs WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn save_job_run(&self, run: &JobRun) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO job_runs (name, last_run, last_error, runs, failures) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (name) DO UPDATE SET last_run = excluded.last_run, last_error = excluded.last_error,
+             runs = excluded.runs, failures = excluded.failures",
+        )
+        .bind(&run.name)
+        .bind(run.last_run)
+        .bind(&run.last_error)
+        .bind(run.runs)
+        .bind(run.failures)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_job_run(&self, name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM job_runs WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_offset(&self, bot: &str) -> Result<Option<i64>> {
+        Ok(
+            sqlx::query_scalar("SELECT update_id FROM update_offsets WHERE bot = ?")
+                .bind(bot)
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn save_update_offset(&self, bot: &str, update_id: i64) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO update_offsets (bot, update_id) VALUES (?, ?)
+             ON CONFLICT (bot) DO UPDATE SET update_id = MAX(update_offsets.update_id, excluded.update_id)",
+        )
+        .bind(bot)
+        .bind(update_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_breed(&self, chat_id: i64, breed: &str) -> Result<()> {
+        sqlx::query("INSERT INTO breed_requests (chat_id, breed, at) VALUES (?, ?, ?)")
+            .bind(chat_id)
+            .bind(breed)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn popular_breeds(
+        &self,
+        chat_id: Option<i64>,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>> {
+        Ok(sqlx::query_as(
+            "SELECT breed, COUNT(*) AS count FROM breed_requests
+             WHERE (? IS NULL OR chat_id = ?) AND (? IS NULL OR at >= ?)
+             GROUP BY breed ORDER BY count DESC, breed LIMIT ?",
+        )
+        .bind(chat_id)
+        .bind(chat_id)
+        .bind(since)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn add_trivia_points(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        name: &str,
+        points: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO trivia_scores (chat_id, user_id, name, points) VALUES (?, ?, ?, ?)
+             ON CONFLICT (chat_id, user_id) DO UPDATE SET name = excluded.name,
+                points = trivia_scores.points + excluded.points",
+        )
+        .bind(chat_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(points)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn trivia_scores(&self, chat_id: i64, limit: i64) -> Result<Vec<(String, i64)>> {
+        Ok(sqlx::query_as(
+            "SELECT name, points FROM trivia_scores WHERE chat_id = ?
+             ORDER BY points DESC, name LIMIT ?",
+        )
+        .bind(chat_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
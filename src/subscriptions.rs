@@ -0,0 +1,297 @@
+use crate::{
+    commands::{send_dog, DOG_CEO},
+    digest::Digest,
+    error::CommandError,
+    i18n,
+    queue::{QueueConfig, SendQueue},
+    quiet, reminders,
+    scheduler::{JobError, Schedule},
+    state::AppState,
+    storage::{self, InactiveChat, Subscription},
+    timezones,
+};
+use chrono::{NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use std::{collections::BTreeMap, future::Future, pin::Pin, sync::Arc};
+use teloxide::{prelude::*, ApiError, RequestError};
+use tracing::{info, warn};
+
+/// A random dog every day.
+pub const DAILY_DOG: &str = "dailydog";
+
+/// What happened in the chat during the week, on Sundays.
+pub const WEEKLY_DIGEST: &str = "weeklydigest";
+
+pub const KINDS: &[&str] = &[DAILY_DOG, WEEKLY_DIGEST];
+
+/// When a subscription of the kind is delivered, at the given time of the day.
+pub fn schedule(kind: &str, at: NaiveTime, timezone: Tz) -> Schedule {
+    match kind {
+        WEEKLY_DIGEST => Schedule::Weekly(Weekday::Sun, at, timezone),
+        _ => Schedule::Daily(at, timezone),
+    }
+}
+
+/// Deliveries of the subscriptions, each one is a job of the [`crate::scheduler::Scheduler`].
+pub struct Subscriptions {
+    pub(crate) bot: AutoSend<Bot>,
+    /// Every scheduled message waits for its turn here.
+    pub(crate) queue: SendQueue,
+}
+
+impl Subscriptions {
+    /// Deliveries, and reminders, are sent by the given bot.
+    pub fn new(bot: AutoSend<Bot>, queue: &QueueConfig) -> Self {
+        Self {
+            bot,
+            queue: SendQueue::new(queue),
+        }
+    }
+}
+
+fn job_name(id: i64) -> String {
+    format!("subscription:{}", id)
+}
+
+/// `HH:MM`, in the timezone of the chat.
+pub fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+/// Start delivering every stored subscription, returns how many there are.
+pub async fn load(state: &Arc<AppState>) -> storage::Result<usize> {
+    let subscriptions = state.storage.subscriptions().await?;
+    let count = subscriptions.len();
+    for subscription in subscriptions {
+        start(state, subscription).await;
+    }
+    Ok(count)
+}
+
+/// Store the subscription and (re)start its deliveries, a chat has at most one subscription of each kind.
+pub async fn subscribe(
+    state: &Arc<AppState>,
+    chat_id: ChatId,
+    kind: &str,
+    at: NaiveTime,
+) -> storage::Result<()> {
+    let mut subscription = Subscription {
+        id: 0,
+        chat_id: chat_id.0,
+        kind: kind.to_string(),
+        schedule: at.format("%H:%M").to_string(),
+        options: "{}".to_string(),
+    };
+    subscription.id = state.storage.save_subscription(&subscription).await?;
+    start(state, subscription).await;
+    Ok(())
+}
+
+/// Restart the deliveries of the chat, e.g. after its timezone changed.
+///
+/// Boxed, a delivery may migrate its chat and so reschedule it from within.
+pub fn reschedule_chat(
+    state: &Arc<AppState>,
+    chat_id: ChatId,
+) -> Pin<Box<dyn Future<Output = storage::Result<()>> + Send + '_>> {
+    Box::pin(async move {
+        for subscription in state.storage.chat_subscriptions(chat_id.0).await? {
+            start(state, subscription).await;
+        }
+        Ok(())
+    })
+}
+
+/// Returns whether the chat was subscribed.
+pub async fn unsubscribe(
+    state: &Arc<AppState>,
+    chat_id: ChatId,
+    kind: &str,
+) -> storage::Result<bool> {
+    let subscriptions = state.storage.chat_subscriptions(chat_id.0).await?;
+    let mut found = false;
+    for subscription in subscriptions.iter().filter(|s| s.kind == kind) {
+        state.storage.remove_subscription(subscription.id).await?;
+        state.scheduler.remove(&job_name(subscription.id)).await;
+        found = true;
+    }
+    Ok(found)
+}
+
+/// Drop every subscription of a chat the bot can't write to anymore.
+pub async fn forget_chat(state: &Arc<AppState>, chat_id: ChatId) -> storage::Result<()> {
+    let subscriptions = state.storage.chat_subscriptions(chat_id.0).await?;
+    for subscription in &subscriptions {
+        state.storage.remove_subscription(subscription.id).await?;
+        state.scheduler.remove(&job_name(subscription.id)).await;
+    }
+    info!("Forgot the subscriptions of {}", chat_id.0);
+    Ok(())
+}
+
+/// The group was upgraded to a supergroup, the messages must go to the id it has now.
+pub fn migrated_to(error: &RequestError) -> Option<ChatId> {
+    match error {
+        RequestError::MigrateToChatId(id) => Some(ChatId(*id)),
+        _ => None,
+    }
+}
+
+/// Move the settings, subscriptions, alerts and the rest of the group to the supergroup it became, restarting the
+/// deliveries for the new id.
+pub async fn migrate_chat(state: &Arc<AppState>, from: ChatId, to: ChatId) -> storage::Result<()> {
+    state.storage.migrate_chat(from.0, to.0).await?;
+    reschedule_chat(state, to).await?;
+    info!("Migrated the chat {} to the supergroup {}", from.0, to.0);
+    Ok(())
+}
+
+/// Why the bot can't write to the chat anymore, `None` when it still can.
+pub fn gone_reason(error: &RequestError) -> Option<&'static str> {
+    match error {
+        RequestError::Api(ApiError::BotBlocked) => Some("blocked"),
+        RequestError::Api(ApiError::BotKicked | ApiError::BotKickedFromSupergroup) => {
+            Some("kicked")
+        }
+        RequestError::Api(ApiError::ChatNotFound) => Some("not_found"),
+        RequestError::Api(ApiError::UserDeactivated) => Some("deactivated"),
+        _ => None,
+    }
+}
+
+/// The bot was blocked or removed, retrying is pointless.
+pub fn chat_is_gone(error: &RequestError) -> bool {
+    gone_reason(error).is_some()
+}
+
+/// Mark the chat inactive and drop its subscriptions, alerts and reminders, the bot can't write to it anymore.
+pub async fn deactivate_chat(
+    state: &Arc<AppState>,
+    chat_id: ChatId,
+    error: &RequestError,
+) -> storage::Result<()> {
+    let reason = gone_reason(error).unwrap_or("unknown");
+    state
+        .storage
+        .deactivate_chat(&InactiveChat {
+            chat_id: chat_id.0,
+            reason: reason.to_string(),
+            since: Utc::now(),
+        })
+        .await?;
+    forget_chat(state, chat_id).await?;
+    for alert in state.storage.alerts(None).await? {
+        if alert.chat_id == chat_id.0 {
+            state.storage.remove_alert(alert.id).await?;
+        }
+    }
+    for reminder in state.storage.reminders(None).await? {
+        if reminder.chat_id == chat_id.0 {
+            state.storage.remove_reminder(reminder.id).await?;
+            reminders::stop(state, reminder.id).await;
+        }
+    }
+    info!("The chat {} is inactive, {}", chat_id.0, reason);
+    Ok(())
+}
+
+/// How many chats are inactive for each reason.
+pub fn count_by_reason(chats: &[InactiveChat]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for chat in chats {
+        *counts.entry(chat.reason.clone()).or_default() += 1;
+    }
+    counts
+}
+
+async fn start(state: &Arc<AppState>, subscription: Subscription) {
+    let at = match parse_time(&subscription.schedule) {
+        Some(at) => at,
+        None => {
+            warn!(
+                "Ignoring the subscription {} with the schedule '{}'",
+                subscription.id, subscription.schedule
+            );
+            return;
+        }
+    };
+    let timezone = timezones::of_chat(state.storage.as_ref(), subscription.chat_id).await;
+
+    let job = {
+        let state = state.clone();
+        let subscription = subscription.clone();
+        move || run(state.clone(), subscription.clone())
+    };
+    state.scheduler.add(
+        job_name(subscription.id),
+        schedule(&subscription.kind, at, timezone),
+        job,
+    );
+}
+
+async fn run(state: Arc<AppState>, mut subscription: Subscription) -> Result<(), JobError> {
+    let chat_id = ChatId(subscription.chat_id);
+    quiet::hold(state.storage.as_ref(), chat_id.0).await;
+    let delivered = match deliver(&state, &subscription).await {
+        Err(CommandError::Telegram(e)) => match migrated_to(&e) {
+            Some(to) => {
+                migrate_chat(&state, chat_id, to).await?;
+                subscription.chat_id = to.0;
+                deliver(&state, &subscription).await
+            }
+            None => Err(CommandError::Telegram(e)),
+        },
+        delivered => delivered,
+    };
+    match delivered {
+        Err(CommandError::Telegram(e)) if chat_is_gone(&e) => {
+            deactivate_chat(&state, ChatId(subscription.chat_id), &e).await?;
+            Ok(())
+        }
+        Err(e) => Err(format!(
+            "Could not deliver the {} subscription to {} -> {}",
+            subscription.kind, chat_id.0, e
+        )
+        .into()),
+        Ok(()) => Ok(()),
+    }
+}
+
+async fn deliver(state: &AppState, subscription: &Subscription) -> Result<(), CommandError> {
+    let chat_id = ChatId(subscription.chat_id);
+    match subscription.kind.as_str() {
+        DAILY_DOG => {
+            let dog = state
+                .dog_api
+                .random()
+                .await
+                .map_err(|error| CommandError::Upstream {
+                    upstream: DOG_CEO,
+                    error,
+                })?;
+            let subscriptions = &state.subscriptions;
+            subscriptions
+                .queue
+                .send(|| send_dog(&subscriptions.bot, state, chat_id, &dog.message, None))
+                .await
+        }
+        WEEKLY_DIGEST => {
+            let since = Utc::now() - chrono::Duration::weeks(1);
+            let digest = Digest::build(state.storage.as_ref(), chat_id.0, since).await?;
+            let language = i18n::of_chat(state.storage.as_ref(), chat_id.0)
+                .await
+                .unwrap_or_else(|| i18n::DEFAULT.to_string());
+            let text = digest.render(&language);
+            let subscriptions = &state.subscriptions;
+            subscriptions
+                .queue
+                .send(|| subscriptions.bot.send_message(chat_id, text.clone()))
+                .await?;
+            Ok(())
+        }
+        other => {
+            warn!("Unknown subscription kind {}", other);
+            Ok(())
+        }
+    }
+}
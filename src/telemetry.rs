@@ -0,0 +1,71 @@
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+use serde::Deserialize;
+use tracing::Subscriber;
+use tracing_subscriber::{registry::LookupSpan, Layer};
+
+/// Distributed tracing and error tracking.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// gRPC endpoint of an OTLP collector, e.g. `http://localhost:4317`. Export is disabled without it.
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+    /// Report panics and error events to Sentry. Disabled without it.
+    pub sentry_dsn: Option<String>,
+    /// Environment shown in Sentry, e.g. `production` or `staging`.
+    pub environment: Option<String>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: env!("CARGO_PKG_NAME").to_string(),
+            sentry_dsn: None,
+            environment: None,
+        }
+    }
+}
+
+/// Provider exporting the spans to the configured collector, if any.
+pub fn tracer_provider(config: &TelemetryConfig) -> Option<SdkTracerProvider> {
+    let endpoint = config.otlp_endpoint.as_ref()?;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .expect("could not create the OTLP exporter");
+
+    let resource = Resource::builder()
+        .with_service_name(config.service_name.clone())
+        .build();
+
+    Some(
+        SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(resource)
+            .build(),
+    )
+}
+
+/// Layer turning the `tracing` spans into OpenTelemetry spans.
+pub fn layer<S>(provider: &SdkTracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+}
+
+/// Start the Sentry client, panics are captured from now on.
+pub fn init_sentry(config: &TelemetryConfig) -> Option<sentry::ClientInitGuard> {
+    let dsn = config.sentry_dsn.as_ref()?;
+
+    let mut options = sentry::ClientOptions::new();
+    options.release = sentry::release_name!();
+    options.environment = config.environment.clone().map(Into::into);
+
+    Some(sentry::init((dsn.as_str(), options)))
+}
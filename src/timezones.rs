@@ -0,0 +1,106 @@
+use crate::storage::{self, Storage};
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt::Write;
+use tracing::warn;
+
+/// Timezone of the chat, UTC unless set with `/settimezone`.
+pub async fn of_chat(storage: &dyn Storage, chat_id: i64) -> Tz {
+    let settings = match storage.chat_settings(chat_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!(
+                "Could not load the settings of the chat {} -> {}",
+                chat_id, e
+            );
+            None
+        }
+    };
+    settings
+        .and_then(|settings| settings.timezone)
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub async fn set_for_chat(
+    storage: &dyn Storage,
+    chat_id: i64,
+    timezone: Tz,
+) -> storage::Result<()> {
+    storage::update_chat_settings(storage, chat_id, |settings| {
+        settings.timezone = Some(timezone.name().to_string())
+    })
+    .await
+}
+
+/// The instant of a wall-clock time in the timezone.
+///
+/// A time skipped when the clocks go forward is moved forward by the gap, a time repeated when they go back
+/// is the first of the two.
+pub fn resolve(timezone: Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    let resolved = match timezone.from_local_datetime(&local) {
+        LocalResult::None => timezone
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest(),
+        resolved => resolved.earliest(),
+    };
+    resolved.map(|at| at.with_timezone(&Utc))
+}
+
+/// Local time in the timezone at `now`, and how far it is from the one in `reference`.
+pub fn compare(timezone: Tz, reference: Tz, now: DateTime<Utc>) -> String {
+    let local = now.with_timezone(&timezone);
+    let here = now.with_timezone(&reference);
+    let offset = local.offset().fix().local_minus_utc();
+    let difference = offset - here.offset().fix().local_minus_utc();
+
+    let mut text = format!(
+        "{}\n{} ({})\n",
+        local.format("%H:%M, %A %-d %B"),
+        timezone.name(),
+        format_offset(offset)
+    );
+    match difference {
+        0 => text.push_str("Same time as you"),
+        difference => {
+            write!(
+                text,
+                "{} {} you",
+                format_duration(difference.abs()),
+                if difference > 0 { "ahead of" } else { "behind" }
+            )
+            .ok();
+        }
+    }
+    match (local.date_naive() - here.date_naive()).num_days() {
+        0 => {}
+        1 => text.push_str(", already tomorrow there"),
+        -1 => text.push_str(", still yesterday there"),
+        _ => {}
+    }
+    text
+}
+
+/// `UTC+09:00`, `UTC-03:30`.
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// `1 hour`, `7 hours`, `5 hours 30 minutes`.
+fn format_duration(seconds: i32) -> String {
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    let mut text = match hours {
+        0 => String::new(),
+        1 => "1 hour".to_string(),
+        hours => format!("{} hours", hours),
+    };
+    if minutes > 0 {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        write!(text, "{} minutes", minutes).ok();
+    }
+    text
+}
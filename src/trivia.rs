@@ -0,0 +1,242 @@
+use crate::{
+    api::trivia::{Category, Question},
+    scheduler::{JobError, Schedule},
+    state::AppState,
+    storage::{self, Storage},
+    subscriptions::chat_is_gone,
+};
+use chrono::{Duration, Utc};
+use rand::{rngs::OsRng, seq::SliceRandom};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, User},
+    RequestError,
+};
+use tracing::info;
+
+/// Prefix of the callback data of the answer buttons, followed by `:<round id>:<answer index>`.
+///
+/// Unlike the other buttons, anybody in the chat can press them.
+pub const ANSWER: &str = "trivia-answer";
+
+/// Time the chat has to answer a question.
+pub const ROUND_SECS: i64 = 30;
+
+/// Players shown by `/trivia scores`.
+const SCOREBOARD: i64 = 10;
+
+/// Points of a right answer to a question of the difficulty.
+pub fn points(difficulty: &str) -> i64 {
+    match difficulty {
+        "hard" => 3,
+        "medium" => 2,
+        _ => 1,
+    }
+}
+
+/// First category whose name contains the given one, ignoring case.
+pub fn find_category<'a>(categories: &'a [Category], name: &str) -> Option<&'a Category> {
+    let name = name.to_lowercase();
+    categories
+        .iter()
+        .find(|category| category.name.to_lowercase().contains(&name))
+}
+
+/// A question waiting for answers.
+struct Round {
+    id: u64,
+    message_id: i32,
+    question: Question,
+    answers: Vec<String>,
+    correct: usize,
+    /// Name and answer of each player, by user id, only their first answer counts.
+    guesses: HashMap<u64, (String, usize)>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    by_chat: HashMap<i64, Round>,
+}
+
+/// Rounds being played, at most one per chat, they don't survive a restart.
+#[derive(Default)]
+pub struct Rounds {
+    inner: Mutex<Inner>,
+}
+
+impl Rounds {
+    pub fn is_running(&self, chat_id: ChatId) -> bool {
+        self.inner.lock().unwrap().by_chat.contains_key(&chat_id.0)
+    }
+}
+
+fn job_name(chat_id: ChatId) -> String {
+    format!("trivia:{}", chat_id.0)
+}
+
+/// Ask the question in the chat, the answer is revealed after [`ROUND_SECS`].
+pub async fn start(
+    state: &Arc<AppState>,
+    bot: &AutoSend<Bot>,
+    chat_id: ChatId,
+    question: Question,
+) -> Result<(), RequestError> {
+    let mut answers = question.incorrect_answers.clone();
+    answers.push(question.correct_answer.clone());
+    answers.shuffle(&mut OsRng);
+    let correct = answers
+        .iter()
+        .position(|answer| *answer == question.correct_answer)
+        .unwrap_or_default();
+
+    let id = {
+        let mut inner = state.trivia.inner.lock().unwrap();
+        inner.next_id += 1;
+        inner.next_id
+    };
+    let keyboard = InlineKeyboardMarkup::new(answers.iter().enumerate().map(|(index, answer)| {
+        [InlineKeyboardButton::callback(
+            answer.clone(),
+            format!("{}:{}:{}", ANSWER, id, index),
+        )]
+    }));
+    let sent = bot
+        .send_message(
+            chat_id,
+            format!(
+                "🧠 {} ({})\n\n{}\n\n{} seconds to answer!",
+                question.category, question.difficulty, question.question, ROUND_SECS
+            ),
+        )
+        .reply_markup(keyboard)
+        .await?;
+
+    state.trivia.inner.lock().unwrap().by_chat.insert(
+        chat_id.0,
+        Round {
+            id,
+            message_id: sent.id,
+            question,
+            answers,
+            correct,
+            guesses: HashMap::new(),
+        },
+    );
+    let job = {
+        let state = state.clone();
+        move || close(state.clone(), chat_id)
+    };
+    state.scheduler.add(
+        job_name(chat_id),
+        Schedule::Once(Utc::now() + Duration::seconds(ROUND_SECS)),
+        job,
+    );
+    Ok(())
+}
+
+/// Take the answer of the user to the round, returns what to tell them.
+pub fn guess(
+    state: &AppState,
+    chat_id: ChatId,
+    round_id: u64,
+    user: &User,
+    index: usize,
+) -> String {
+    let mut inner = state.trivia.inner.lock().unwrap();
+    let round = match inner.by_chat.get_mut(&chat_id.0) {
+        Some(round) if round.id == round_id => round,
+        _ => return "That question is over".to_string(),
+    };
+    let answer = match round.answers.get(index) {
+        Some(answer) => answer.clone(),
+        None => return "That question is over".to_string(),
+    };
+    match round.guesses.entry(user.id.0) {
+        Entry::Occupied(_) => "You already answered".to_string(),
+        Entry::Vacant(entry) => {
+            entry.insert((user.first_name.clone(), index));
+            format!("You answered {}, wait for the time to be up", answer)
+        }
+    }
+}
+
+/// Reveal the answer of the round of the chat and score the players who got it right.
+pub async fn close(state: Arc<AppState>, chat_id: ChatId) -> Result<(), JobError> {
+    let round = state
+        .trivia
+        .inner
+        .lock()
+        .unwrap()
+        .by_chat
+        .remove(&chat_id.0);
+    state.scheduler.remove(&job_name(chat_id)).await;
+    let round = match round {
+        Some(round) => round,
+        None => return Ok(()),
+    };
+
+    let points = points(&round.question.difficulty);
+    let mut winners = round
+        .guesses
+        .iter()
+        .filter(|(_, (_, index))| *index == round.correct)
+        .map(|(user_id, (name, _))| (*user_id, name.as_str()))
+        .collect::<Vec<_>>();
+    winners.sort_by_key(|(_, name)| *name);
+    for (user_id, name) in &winners {
+        state
+            .storage
+            .add_trivia_points(chat_id.0, *user_id as i64, name, points)
+            .await?;
+    }
+
+    let mut text = format!(
+        "🧠 {}\n\n✅ {}\n\n",
+        round.question.question, round.question.correct_answer
+    );
+    if winners.is_empty() {
+        text.push_str("Nobody got it right");
+    } else {
+        let names = winners
+            .iter()
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(text, "Right: {} (+{})", names, points).ok();
+    }
+    text.push_str("\n\nSee the scores with /trivia scores");
+
+    // Without a keyboard, the buttons go away
+    let edited = state
+        .subscriptions
+        .bot
+        .edit_message_text(chat_id, round.message_id, text)
+        .await;
+    match edited {
+        Ok(_) => Ok(()),
+        Err(e) if chat_is_gone(&e) => {
+            info!("Could not reveal the trivia answer, the chat is gone");
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Best players of the chat.
+pub async fn scoreboard(storage: &dyn Storage, chat_id: i64) -> storage::Result<String> {
+    let scores = storage.trivia_scores(chat_id, SCOREBOARD).await?;
+    if scores.is_empty() {
+        return Ok("No scores yet, play with /trivia".to_string());
+    }
+    let mut text = String::from("Trivia scores:\n");
+    for (position, (name, points)) in scores.iter().enumerate() {
+        writeln!(text, "{}. {} ({})", position + 1, name, points).ok();
+    }
+    Ok(text)
+}
@@ -0,0 +1,229 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Length,
+    Weight,
+    Temperature,
+    Data,
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Length => "a length",
+            Self::Weight => "a weight",
+            Self::Temperature => "a temperature",
+            Self::Data => "a data size",
+        })
+    }
+}
+
+/// A unit is `factor` times the base one of its quantity, plus `offset`.
+/// The bases are the meter, the kilogram, the kelvin and the byte.
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    /// The first one is how it is shown.
+    pub names: &'static [&'static str],
+    pub quantity: Quantity,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(names: &'static [&'static str], quantity: Quantity, factor: f64) -> Unit {
+    Unit {
+        names,
+        quantity,
+        factor,
+        offset: 0.0,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    unit(
+        &["mm", "millimeter", "millimeters"],
+        Quantity::Length,
+        0.001,
+    ),
+    unit(&["cm", "centimeter", "centimeters"], Quantity::Length, 0.01),
+    unit(
+        &["m", "meter", "meters", "metre", "metres"],
+        Quantity::Length,
+        1.0,
+    ),
+    unit(&["km", "kilometer", "kilometers"], Quantity::Length, 1000.0),
+    unit(&["in", "inch", "inches"], Quantity::Length, 0.0254),
+    unit(&["ft", "foot", "feet"], Quantity::Length, 0.3048),
+    unit(&["yd", "yard", "yards"], Quantity::Length, 0.9144),
+    unit(&["mi", "mile", "miles"], Quantity::Length, 1609.344),
+    unit(&["nmi"], Quantity::Length, 1852.0),
+    unit(
+        &["mg", "milligram", "milligrams"],
+        Quantity::Weight,
+        0.000_001,
+    ),
+    unit(&["g", "gram", "grams"], Quantity::Weight, 0.001),
+    unit(
+        &["kg", "kilo", "kilos", "kilogram", "kilograms"],
+        Quantity::Weight,
+        1.0,
+    ),
+    unit(&["t", "tonne", "tonnes"], Quantity::Weight, 1000.0),
+    unit(
+        &["oz", "ounce", "ounces"],
+        Quantity::Weight,
+        0.028_349_523_125,
+    ),
+    unit(
+        &["lb", "lbs", "pound", "pounds"],
+        Quantity::Weight,
+        0.453_592_37,
+    ),
+    unit(&["st", "stone", "stones"], Quantity::Weight, 6.350_293_18),
+    Unit {
+        names: &["°C", "c", "celsius"],
+        quantity: Quantity::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        names: &["°F", "f", "fahrenheit"],
+        quantity: Quantity::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+    },
+    unit(&["K", "kelvin", "kelvins"], Quantity::Temperature, 1.0),
+    unit(&["bit", "bits"], Quantity::Data, 0.125),
+    unit(&["B", "byte", "bytes"], Quantity::Data, 1.0),
+    unit(&["KB", "kilobyte", "kilobytes"], Quantity::Data, 1e3),
+    unit(&["MB", "megabyte", "megabytes"], Quantity::Data, 1e6),
+    unit(&["GB", "gigabyte", "gigabytes"], Quantity::Data, 1e9),
+    unit(&["TB", "terabyte", "terabytes"], Quantity::Data, 1e12),
+    unit(&["KiB", "kibibyte", "kibibytes"], Quantity::Data, 1024.0),
+    unit(&["MiB", "mebibyte", "mebibytes"], Quantity::Data, 1048576.0),
+    unit(
+        &["GiB", "gibibyte", "gibibytes"],
+        Quantity::Data,
+        1073741824.0,
+    ),
+    unit(
+        &["TiB", "tebibyte", "tebibytes"],
+        Quantity::Data,
+        1099511627776.0,
+    ),
+];
+
+impl Unit {
+    /// Case doesn't matter, e.g. `KM`, `Km` and `kilometers` are all the kilometer.
+    pub fn find(name: &str) -> Option<&'static Unit> {
+        let name = name.trim().trim_start_matches('º').trim_start_matches('°');
+        UNITS.iter().find(|unit| {
+            unit.names
+                .iter()
+                .any(|known| known.trim_start_matches('°').eq_ignore_ascii_case(name))
+        })
+    }
+
+    fn to_base(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    fn in_unit(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.names[0])
+    }
+}
+
+/// A value to convert to another unit, e.g. `10 km mi` or `10km to mi`.
+#[derive(Debug, PartialEq)]
+pub struct Conversion {
+    pub value: f64,
+    pub from: &'static Unit,
+    pub to: &'static Unit,
+}
+
+impl Conversion {
+    pub fn result(&self) -> f64 {
+        self.to.in_unit(self.from.to_base(self.value))
+    }
+}
+
+impl FromStr for Conversion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("'{}' isn't a conversion like 10 km mi", s.trim());
+        let s = s.trim();
+        // The value may be stuck to its unit, as in `10km`
+        let number_end = s
+            .char_indices()
+            .find(|(i, c)| !(c.is_ascii_digit() || *c == '.' || (*i == 0 && *c == '-')))
+            .map_or(s.len(), |(i, _)| i);
+        let value = s[..number_end]
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(error)?;
+
+        let mut words = s[number_end..].split_whitespace().collect::<Vec<_>>();
+        if let [_, connector, _] = words.as_slice() {
+            if ["to", "in", "into"].contains(&connector.to_lowercase().as_str()) {
+                words.remove(1);
+            }
+        }
+        let (from, to) = match words.as_slice() {
+            [from, to] => (*from, *to),
+            _ => return Err(error()),
+        };
+
+        let find = |name: &str| {
+            Unit::find(name).ok_or_else(|| format!("I don't know the unit '{}'", name))
+        };
+        let (from, to) = (find(from)?, find(to)?);
+        if from.quantity != to.quantity {
+            return Err(format!(
+                "I can't convert {} into {}, {} is {} and {} is {}",
+                from, to, from, from.quantity, to, to.quantity
+            ));
+        }
+        Ok(Self { value, from, to })
+    }
+}
+
+impl fmt::Display for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} = {} {}",
+            number(self.value),
+            self.from,
+            number(self.result()),
+            self.to
+        )
+    }
+}
+
+/// Six significant digits without trailing zeros, e.g. `6.21371` or `1000`.
+pub fn number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if value.abs() < 1e-4 || value.abs() >= 1e15 {
+        let text = format!("{:.5e}", value);
+        let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{}e{}", mantissa, exponent);
+    }
+    let decimals = (5 - value.abs().log10().floor() as i32).max(0) as usize;
+    let text = format!("{:.*}", decimals, value);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
@@ -0,0 +1,45 @@
+//! What the running binary was built from, embedded by `build.rs`, to tell the deployed instances apart.
+
+use serde::Serialize;
+
+/// Short hash of the commit, `VERGEN_IDEMPOTENT_OUTPUT` when built outside a git checkout.
+pub const GIT_SHA: &str = env!("VERGEN_GIT_SHA");
+/// RFC 3339, in UTC.
+pub const BUILD_TIMESTAMP: &str = env!("VERGEN_BUILD_TIMESTAMP");
+/// Cargo features enabled in the build, comma separated.
+const FEATURES: &str = env!("VERGEN_CARGO_FEATURES");
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub built_at: &'static str,
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: GIT_SHA,
+            built_at: BUILD_TIMESTAMP,
+            features: FEATURES
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Answer of `/version`.
+    pub fn text(&self) -> String {
+        let features = if self.features.is_empty() {
+            "none".to_string()
+        } else {
+            self.features.join(", ")
+        };
+        format!(
+            "dog-bot {}\ncommit: {}\nbuilt: {}\nfeatures: {}",
+            self.version, self.git_sha, self.built_at, features
+        )
+    }
+}
@@ -0,0 +1,139 @@
+//! Alerts the admin chat when the requests to an upstream keep failing, and again once they work.
+//!
+//! The request counters of [`crate::health::Health`] are sampled on a schedule, the error rate of an upstream is
+//! the share of its requests that failed since the oldest sample of the window.
+
+use crate::{health::RequestCounts, reporter::AdminConfig, scheduler::Schedule, state::AppState};
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// How often the counters are sampled.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// An upstream crossing the threshold, one way or the other.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    Failing {
+        upstream: String,
+        failed: u64,
+        total: u64,
+    },
+    Recovered {
+        upstream: String,
+    },
+}
+
+impl Change {
+    pub fn text(&self, window: Duration) -> String {
+        match self {
+            Self::Failing {
+                upstream,
+                failed,
+                total,
+            } => format!(
+                "📈 {} failed {} of {} requests over the last {} minutes",
+                upstream,
+                failed,
+                total,
+                window.as_secs() / 60
+            ),
+            Self::Recovered { upstream } => format!("✅ {} works again", upstream),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Watchdog {
+    samples: Mutex<VecDeque<(Instant, BTreeMap<String, RequestCounts>)>>,
+    /// Upstreams alerted about, until they recover.
+    failing: Mutex<HashSet<String>>,
+}
+
+impl Watchdog {
+    /// Add the sample of the counters, returning the upstreams that started failing or recovered.
+    ///
+    /// An upstream with too few requests over the window stays as it was, e.g. one not called while its circuit
+    /// breaker is open hasn't recovered.
+    pub fn observe(
+        &self,
+        now: Instant,
+        counts: BTreeMap<String, RequestCounts>,
+        config: &AdminConfig,
+    ) -> Vec<Change> {
+        let window = Duration::from_secs(config.error_rate_window_secs);
+        let mut samples = self.samples.lock().unwrap();
+        // The newest sample older than the window is the start of the window
+        while samples.len() > 1 && now.duration_since(samples[1].0) >= window {
+            samples.pop_front();
+        }
+        let start = samples.front().map(|(_, start)| start.clone());
+        samples.push_back((now, counts.clone()));
+        let start = match start {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+
+        let mut failing = self.failing.lock().unwrap();
+        counts
+            .into_iter()
+            .filter_map(|(upstream, counts)| {
+                let before = start.get(&upstream).copied().unwrap_or_default();
+                let total = counts.total.saturating_sub(before.total);
+                let failed = counts.failed.saturating_sub(before.failed);
+                if total == 0 || total < config.error_rate_min_requests {
+                    return None;
+                }
+
+                let over = failed as f64 / total as f64 >= config.error_rate_threshold;
+                if over && failing.insert(upstream.clone()) {
+                    Some(Change::Failing {
+                        upstream,
+                        failed,
+                        total,
+                    })
+                } else if !over && failing.remove(&upstream) {
+                    Some(Change::Recovered { upstream })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Sample the counters of the bot and tell the admin chat about the changes.
+    pub async fn check(&self, state: &AppState) {
+        let config = state.reporter.config();
+        let changes = self.observe(Instant::now(), state.health.request_counts(), &config);
+        for change in changes {
+            info!("Error rate watchdog: {:?}", change);
+            let window = Duration::from_secs(config.error_rate_window_secs);
+            state.reporter.notify(change.text(window)).await;
+        }
+    }
+}
+
+/// Check the error rates on a schedule, unless the threshold is 0.
+pub fn start(state: &Arc<AppState>, config: &AdminConfig) {
+    if config.error_rate_threshold <= 0.0 {
+        return;
+    }
+
+    let watchdog = Arc::new(Watchdog::default());
+    let job = {
+        let state = state.clone();
+        move || {
+            let (state, watchdog) = (state.clone(), watchdog.clone());
+            async move {
+                watchdog.check(&state).await;
+                Ok(())
+            }
+        }
+    };
+    state
+        .scheduler
+        .add("error_rates", Schedule::Every(CHECK_INTERVAL), job);
+}
@@ -0,0 +1,54 @@
+use crate::api::weather::{Forecast, Place};
+use std::fmt::Write;
+
+/// Days shown by `/weather`, today included.
+pub const FORECAST_DAYS: u8 = 3;
+
+/// Emoji and description of a WMO weather interpretation code, as used by Open-Meteo.
+pub fn describe(code: u8) -> (&'static str, &'static str) {
+    match code {
+        0 => ("☀️", "Clear sky"),
+        1 => ("🌤", "Mainly clear"),
+        2 => ("⛅", "Partly cloudy"),
+        3 => ("☁️", "Overcast"),
+        45 | 48 => ("🌫", "Fog"),
+        51 | 53 | 55 | 56 | 57 => ("🌦", "Drizzle"),
+        61 | 63 | 65 | 66 | 67 | 80 | 81 | 82 => ("🌧", "Rain"),
+        71 | 73 | 75 | 77 | 85 | 86 => ("🌨", "Snow"),
+        95 | 96 | 99 => ("⛈", "Thunderstorm"),
+        _ => ("🌡", "Unknown"),
+    }
+}
+
+pub fn render(place: &Place, forecast: &Forecast) -> String {
+    let mut text = match &place.country {
+        Some(country) => format!("Weather in {}, {}\n", place.name, country),
+        None => format!("Weather in {}\n", place.name),
+    };
+
+    let (emoji, description) = describe(forecast.current.weather_code);
+    writeln!(
+        text,
+        "{} {}, {:.0}°C (feels like {:.0}°C), wind {:.0} km/h\n",
+        emoji,
+        description,
+        forecast.current.temperature,
+        forecast.current.feels_like,
+        forecast.current.wind_speed
+    )
+    .ok();
+
+    for day in &forecast.days {
+        let (emoji, _) = describe(day.weather_code);
+        writeln!(
+            text,
+            "{} {} {:.0}°C / {:.0}°C",
+            day.date.format("%a %d"),
+            emoji,
+            day.max,
+            day.min
+        )
+        .ok();
+    }
+    text
+}
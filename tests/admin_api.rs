@@ -0,0 +1,174 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    server::{self, ServerConfig},
+    storage::Subscription,
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{method, path_regex},
+    Mock, ResponseTemplate,
+};
+
+const TOKEN: &str = "secret";
+
+/// Serve the routes on a free port, returning its base URL.
+async fn serve(harness: &Harness, admin_token: Option<&str>) -> String {
+    let config = ServerConfig {
+        admin_token: admin_token.map(str::to_string),
+        ..ServerConfig::default()
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = server::router(harness.state(), &config);
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn requests_need_the_token() {
+    let harness = Harness::start().await;
+    let url = serve(&harness, Some(TOKEN)).await;
+    let client = reqwest::Client::new();
+
+    let status =
+        |request: reqwest::RequestBuilder| async move { request.send().await.unwrap().status() };
+    let endpoint = format!("{}/admin/status", url);
+    assert_eq!(
+        status(client.get(&endpoint)).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(client.get(&endpoint).bearer_auth("wrong")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(client.get(&endpoint).bearer_auth(TOKEN)).await,
+        StatusCode::OK
+    );
+    // The probes stay open
+    assert_eq!(
+        status(client.get(format!("{}/healthz", url))).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn the_api_is_off_without_a_token() {
+    let harness = Harness::start().await;
+    let url = serve(&harness, None).await;
+
+    let res = reqwest::Client::new()
+        .get(format!("{}/admin/status", url))
+        .bearer_auth("")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn subscriptions_are_listed_by_chat() {
+    let harness = Harness::start().await;
+    for chat_id in [CHAT_ID, CHAT_ID + 1] {
+        harness
+            .storage
+            .save_subscription(&Subscription {
+                id: 0,
+                chat_id,
+                kind: "dailydog".to_string(),
+                schedule: "09:00".to_string(),
+                options: "{}".to_string(),
+            })
+            .await
+            .unwrap();
+    }
+    let url = serve(&harness, Some(TOKEN)).await;
+
+    let subscriptions: Value = reqwest::Client::new()
+        .get(format!("{}/admin/subscriptions?chat_id={}", url, CHAT_ID))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(subscriptions.as_array().unwrap().len(), 1);
+    assert_eq!(subscriptions[0]["chat_id"], CHAT_ID);
+    assert_eq!(subscriptions[0]["kind"], "dailydog");
+}
+
+#[tokio::test]
+async fn announcements_are_sent_to_the_chat() {
+    let harness = Harness::start().await;
+    let url = serve(&harness, Some(TOKEN)).await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/admin/announcements", url))
+        .bearer_auth(TOKEN)
+        .json(&json!({ "chat_id": CHAT_ID, "text": "Back online 🐶" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.json::<Value>().await.unwrap(),
+        json!({ "message_id": 1 })
+    );
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["chat_id"], CHAT_ID);
+    assert_eq!(messages[0]["text"], "Back online 🐶");
+}
+
+#[tokio::test]
+async fn blocked_chats_are_pruned_and_counted() {
+    let harness = Harness::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"^/botTOKEN/(?i:sendMessage)$"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "ok": false,
+            "error_code": 403,
+            "description": "Forbidden: bot was blocked by the user"
+        })))
+        .with_priority(1)
+        .mount(&harness.telegram)
+        .await;
+    harness
+        .storage
+        .save_subscription(&Subscription {
+            id: 0,
+            chat_id: CHAT_ID,
+            kind: "dailydog".to_string(),
+            schedule: "09:00".to_string(),
+            options: "{}".to_string(),
+        })
+        .await
+        .unwrap();
+    let url = serve(&harness, Some(TOKEN)).await;
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{}/admin/announcements", url))
+        .bearer_auth(TOKEN)
+        .json(&json!({ "chat_id": CHAT_ID, "text": "Back online 🐶" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(harness.storage.subscriptions().await.unwrap().is_empty());
+
+    let stats: Value = client
+        .get(format!("{}/admin/stats", url))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stats["inactive_chats"], json!({ "blocked": 1 }));
+}
@@ -0,0 +1,368 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::{
+    alerts::{self, AlertsConfig},
+    commands::{answer, answer_callback, answer_message, Command},
+    storage::{Alert, ChatSettings},
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+fn alert(symbol: &str, target: f64, above: bool) -> Alert {
+    Alert {
+        id: 0,
+        user_id: USER_ID as i64,
+        chat_id: CHAT_ID,
+        symbol: symbol.to_string(),
+        target,
+        above,
+        move_percent: None,
+        window_secs: None,
+        created_at: None,
+        paused: false,
+    }
+}
+
+#[tokio::test]
+async fn watched_coins_are_fetched_in_one_request() {
+    let harness = Harness::start().await;
+    for alert in [
+        alert("btc", 100.0, true),
+        alert("eth", 10.0, false),
+        alert("btc", 200.0, true),
+    ] {
+        harness.storage.add_alert(&alert).await.unwrap();
+    }
+
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .and(query_param("vs_currencies", "usd"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "bitcoin": { "usd": 150.0 },
+            "ethereum": { "usd": 20.0 }
+        })))
+        .expect(1)
+        .mount(&harness.coingecko)
+        .await;
+
+    alerts::poll(harness.state(), AlertsConfig::default())
+        .await
+        .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "BTC is now $150, above your target of $100"
+    );
+    let left = harness.storage.alerts(None).await.unwrap();
+    assert_eq!(left.len(), 2);
+    assert!(left.iter().all(|alert| alert.target != 100.0));
+}
+
+#[tokio::test]
+async fn big_watchlists_are_split_in_batches() {
+    let harness = Harness::start().await;
+    for symbol in ["btc", "eth", "doge"] {
+        harness
+            .storage
+            .add_alert(&alert(symbol, 1_000_000.0, true))
+            .await
+            .unwrap();
+    }
+
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(2)
+        .mount(&harness.coingecko)
+        .await;
+
+    let config = AlertsConfig {
+        batch_size: 2,
+        batch_delay_ms: 0,
+        ..AlertsConfig::default()
+    };
+    alerts::poll(harness.state(), config).await.unwrap();
+}
+
+#[tokio::test]
+async fn alerts_wait_for_the_quiet_hours_to_end() {
+    let harness = Harness::start().await;
+    harness
+        .storage
+        .add_alert(&alert("btc", 100.0, true))
+        .await
+        .unwrap();
+    // Quiet all day but one minute
+    let now = chrono::Utc::now().time();
+    let quiet_hours = format!(
+        "{}-{}",
+        (now + chrono::Duration::minutes(2)).format("%H:%M"),
+        (now + chrono::Duration::minutes(1)).format("%H:%M")
+    );
+    harness
+        .storage
+        .save_chat_settings(&ChatSettings {
+            chat_id: CHAT_ID,
+            quiet_hours: Some(quiet_hours),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "bitcoin": { "usd": 150.0 }
+        })))
+        .mount(&harness.coingecko)
+        .await;
+
+    alerts::poll(harness.state(), AlertsConfig::default())
+        .await
+        .unwrap();
+
+    assert!(harness.sent("sendMessage").await.is_empty());
+    assert_eq!(harness.storage.alerts(None).await.unwrap().len(), 1);
+}
+
+fn move_alert(percent: f64, window_hours: i64) -> Alert {
+    Alert {
+        move_percent: Some(percent),
+        window_secs: Some(window_hours * 3600),
+        created_at: Some(Utc::now() - Duration::hours(2)),
+        ..alert("btc", 100.0, false)
+    }
+}
+
+#[test]
+fn windows_are_whole_hours_up_to_a_day() {
+    assert_eq!(
+        alerts::parse_window("1h"),
+        Some(std::time::Duration::from_secs(3600))
+    );
+    assert_eq!(
+        alerts::parse_window("24H"),
+        Some(std::time::Duration::from_secs(24 * 3600))
+    );
+    assert_eq!(alerts::parse_window("0h"), None);
+    assert_eq!(alerts::parse_window("25h"), None);
+    assert_eq!(alerts::parse_window("1d"), None);
+}
+
+#[test]
+fn only_the_moves_within_the_window_count() {
+    let now = Utc::now();
+    let history = [
+        (now - Duration::minutes(90), 90.0),
+        (now - Duration::minutes(30), 104.0),
+        (now - Duration::minutes(10), 100.0),
+    ];
+    let alert = move_alert(5.0, 1);
+
+    // 100 -> 110 is the biggest move of the last hour, 90 is too old
+    let change = alerts::moved(&alert, &history, 110.0, now).unwrap();
+    assert!((change - 10.0).abs() < 1e-9);
+    assert_eq!(alerts::moved(&alert, &history, 103.0, now), None);
+    let change = alerts::moved(&alert, &history, 98.0, now).unwrap();
+    assert!((change + 5.769).abs() < 1e-3);
+}
+
+#[test]
+fn moves_before_the_alert_was_set_dont_count() {
+    let now = Utc::now();
+    let history = [(now - Duration::minutes(30), 90.0)];
+    let alert = Alert {
+        created_at: Some(now - Duration::minutes(10)),
+        ..move_alert(5.0, 24)
+    };
+    assert_eq!(alerts::moved(&alert, &history, 100.0, now), None);
+}
+
+#[tokio::test]
+async fn moves_are_noticed_across_checks() {
+    let harness = Harness::start().await;
+    harness
+        .storage
+        .add_alert(&move_alert(5.0, 1))
+        .await
+        .unwrap();
+
+    for (price, sent) in [(100.0, 0), (103.0, 0), (106.0, 1)] {
+        harness.coingecko.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/simple/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "bitcoin": { "usd": price }
+            })))
+            .mount(&harness.coingecko)
+            .await;
+
+        alerts::poll(harness.state(), AlertsConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(harness.sent("sendMessage").await.len(), sent);
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "BTC moved +6.00% in the last 1h, it's now $106"
+    );
+    assert!(harness.storage.alerts(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn alerts_are_listed_with_their_buttons() {
+    let harness = Harness::start().await;
+    let id = harness
+        .storage
+        .add_alert(&alert("btc", 70000.0, true))
+        .await
+        .unwrap();
+    harness
+        .storage
+        .add_alert(&move_alert(5.0, 1))
+        .await
+        .unwrap();
+
+    answer(
+        harness.bot(),
+        common::message("/alerts"),
+        Command::Alerts,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Your alerts:\n1. BTC above $70000\n2. BTC moves 5% within 1h\n"
+    );
+    let buttons = &messages[0]["reply_markup"]["inline_keyboard"][0];
+    assert_eq!(
+        buttons[0]["callback_data"],
+        format!("{}:{}:{}", alerts::PAUSE, USER_ID, id)
+    );
+    assert_eq!(
+        buttons[2]["callback_data"],
+        format!("{}:{}:{}", alerts::DELETE, USER_ID, id)
+    );
+}
+
+#[tokio::test]
+async fn paused_alerts_are_not_notified() {
+    let harness = Harness::start().await;
+    let id = harness
+        .storage
+        .add_alert(&alert("btc", 100.0, true))
+        .await
+        .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "bitcoin": { "usd": 150.0 }
+        })))
+        .expect(0)
+        .mount(&harness.coingecko)
+        .await;
+
+    let data = format!("{}:{}:{}", alerts::PAUSE, USER_ID, id);
+    answer_callback(harness.bot(), common::callback(&data), harness.state())
+        .await
+        .unwrap();
+    alerts::poll(harness.state(), AlertsConfig::default())
+        .await
+        .unwrap();
+
+    let left = harness.storage.alerts(None).await.unwrap();
+    assert!(left[0].paused);
+    let edits = harness.sent("editMessageText").await;
+    assert_eq!(
+        edits[0]["text"],
+        "Your alerts:\n1. BTC above $100 (paused)\n"
+    );
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
+
+#[tokio::test]
+async fn thresholds_are_edited_in_a_dialogue() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+    let id = harness
+        .storage
+        .add_alert(&alert("btc", 70000.0, true))
+        .await
+        .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "bitcoin": { "usd": 65000.0 }
+        })))
+        .mount(&harness.coingecko)
+        .await;
+
+    let data = format!("{}:{}:{}", alerts::EDIT, USER_ID, id);
+    answer_callback(harness.bot(), common::callback(&data), state.clone())
+        .await
+        .unwrap();
+    for text in ["soon", "60000"] {
+        answer_message(harness.bot(), common::message(text), state.clone())
+            .await
+            .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    let texts = messages
+        .iter()
+        .map(|message| message["text"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        texts,
+        [
+            "Send me the new price for BTC, it's $70000 now",
+            "That isn't a price, send me e.g. 70000",
+            "Done, BTC below $60000",
+        ]
+    );
+    let alert = &harness.storage.alerts(None).await.unwrap()[0];
+    assert_eq!((alert.target, alert.above), (60000.0, false));
+
+    // Nothing is waited for anymore
+    answer_message(harness.bot(), common::message("50000"), state)
+        .await
+        .unwrap();
+    assert_eq!(harness.sent("sendMessage").await.len(), 3);
+}
+
+#[tokio::test]
+async fn other_users_alerts_cant_be_deleted() {
+    let harness = Harness::start().await;
+    let id = harness
+        .storage
+        .add_alert(&Alert {
+            user_id: 1234,
+            ..alert("btc", 100.0, true)
+        })
+        .await
+        .unwrap();
+
+    let data = format!("{}:{}:{}", alerts::DELETE, USER_ID, id);
+    answer_callback(harness.bot(), common::callback(&data), harness.state())
+        .await
+        .unwrap();
+
+    assert_eq!(harness.storage.alerts(None).await.unwrap().len(), 1);
+    let answers = harness.sent("answerCallbackQuery").await;
+    assert_eq!(
+        answers[0]["text"],
+        "That alert was already notified or deleted"
+    );
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::alt_text;
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+const IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+
+#[test]
+fn images_are_described_by_their_breed() {
+    assert_eq!(alt_text::describe(IMAGE), "Photo of a husky");
+    assert_eq!(
+        alt_text::describe("https://images.dog.ceo/breeds/retriever-golden/n02099601_176.jpg"),
+        "Photo of a golden retriever"
+    );
+    assert_eq!(
+        alt_text::describe("https://images.dog.ceo/breeds/akita/Akita_Inu_dog.jpg"),
+        "Photo of an akita"
+    );
+    assert_eq!(
+        alt_text::describe("https://example.com/dog.jpg"),
+        "Photo of a dog"
+    );
+    assert_eq!(alt_text::describe("not a url"), "Photo of a dog");
+}
+
+#[tokio::test]
+async fn dogs_are_captioned_once_turned_on() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("/doggo"))
+        .await
+        .unwrap();
+    harness
+        .receive(&state, common::update("/alttext on"))
+        .await
+        .unwrap();
+    harness
+        .receive(&state, common::update("/doggo"))
+        .await
+        .unwrap();
+
+    assert!(alt_text::is_enabled(state.storage.as_ref(), CHAT_ID).await);
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 2);
+    assert!(photos[0].get("caption").is_none());
+    assert_eq!(photos[1]["caption"], "Photo of a husky");
+}
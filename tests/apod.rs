@@ -0,0 +1,78 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn images_are_sent_with_their_explanation() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/planetary/apod"))
+        .and(query_param("api_key", "KEY"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "date": "2024-06-01",
+            "title": "The Dog Star",
+            "explanation": "Sirius is the brightest star in the night sky.",
+            "url": "https://apod.nasa.gov/apod/image/sirius.jpg",
+            "media_type": "image",
+            "copyright": "\nJane Doe\n"
+        })))
+        .mount(&harness.nasa)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/apod"),
+        Command::Apod,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(
+        photos[0]["photo"],
+        "https://apod.nasa.gov/apod/image/sirius.jpg"
+    );
+    assert_eq!(
+        photos[0]["caption"],
+        "The Dog Star (© Jane Doe)\n\nSirius is the brightest star in the night sky."
+    );
+}
+
+#[tokio::test]
+async fn videos_are_sent_as_links() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/planetary/apod"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "date": "2024-06-02",
+            "title": "Eclipse",
+            "explanation": "The Moon covers the Sun.",
+            "url": "https://www.youtube.com/embed/abc",
+            "media_type": "video"
+        })))
+        .mount(&harness.nasa)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/apod"),
+        Command::Apod,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "https://www.youtube.com/embed/abc\n\nEclipse\n\nThe Moon covers the Sun."
+    );
+    assert!(harness.sent("sendPhoto").await.is_empty());
+}
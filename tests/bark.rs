@@ -0,0 +1,71 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    assets::{Assets, AssetsConfig, Sound},
+    commands::{answer, Command},
+};
+use std::fs;
+
+async fn bark(harness: &Harness) {
+    answer(
+        harness.bot(),
+        common::message("/bark"),
+        Command::Bark,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+}
+
+#[test]
+fn barks_are_the_ogg_files_of_the_assets() {
+    let dir = std::env::temp_dir().join(format!("barks-{}", std::process::id()));
+    fs::create_dir_all(dir.join("barks")).unwrap();
+    fs::write(dir.join("barks/woof.ogg"), b"OggS woof").unwrap();
+    fs::write(dir.join("barks/arf.ogg"), b"OggS arf").unwrap();
+    fs::write(dir.join("barks/notes.txt"), b"not a bark").unwrap();
+
+    let assets = Assets::load(&AssetsConfig { dir: dir.clone() }).unwrap();
+    let names: Vec<_> = assets
+        .barks()
+        .iter()
+        .map(|bark| bark.name.as_str())
+        .collect();
+    assert_eq!(names, ["barks/arf.ogg", "barks/woof.ogg"]);
+    assert_eq!(assets.barks()[1].ogg, b"OggS woof");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_barks_are_not_an_error() {
+    let assets = Assets::load(&AssetsConfig {
+        dir: "no/such/dir".into(),
+    })
+    .unwrap();
+    assert!(assets.barks().is_empty());
+}
+
+#[tokio::test]
+async fn barks_are_sent_as_voice_messages() {
+    let mut harness = Harness::start().await;
+    harness.barks = vec![Sound {
+        name: "barks/woof.ogg".to_string(),
+        ogg: b"OggS woof".to_vec(),
+    }];
+
+    bark(&harness).await;
+
+    assert_eq!(harness.sent("sendVoice").await.len(), 1);
+}
+
+#[tokio::test]
+async fn without_barks_the_bot_says_so() {
+    let harness = Harness::start().await;
+
+    bark(&harness).await;
+
+    assert!(harness.sent("sendVoice").await.is_empty());
+    assert_eq!(harness.sent("sendMessage").await.len(), 1);
+}
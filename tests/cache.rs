@@ -0,0 +1,93 @@
+mod common;
+
+use chrono::Utc;
+use common::Harness;
+use dog_bot::{
+    api::dog::{CachedDogApi, DogApi},
+    cache::{memory::MemoryCache, Cache, MeteredCache},
+};
+use serde_json::json;
+use std::{sync::Arc, time::Duration};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn memory_entries_expire() {
+    let cache = MemoryCache::default();
+
+    cache.set("fresh", "1", Duration::from_secs(60)).await;
+    cache.set("stale", "2", Duration::ZERO).await;
+
+    assert_eq!(cache.get("fresh").await.as_deref(), Some("1"));
+    assert_eq!(cache.get("stale").await, None);
+    assert_eq!(cache.get("missing").await, None);
+}
+
+#[tokio::test]
+async fn breeds_are_fetched_once() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/list/all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": { "husky": [] },
+            "status": "success"
+        })))
+        .expect(1)
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let dog_api = CachedDogApi::new(
+        harness.dog_api(),
+        Arc::new(MemoryCache::default()),
+        Duration::from_secs(60),
+    );
+
+    for _ in 0..3 {
+        let breeds = dog_api.breeds().await.unwrap();
+        assert!(breeds.message.contains_key("husky"));
+    }
+}
+
+#[tokio::test]
+async fn the_fetch_time_of_the_breeds_is_shared_with_the_cache() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/list/all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": { "husky": [] },
+            "status": "success"
+        })))
+        .expect(1)
+        .mount(&harness.dog_ceo)
+        .await;
+    let cache: Arc<dyn Cache> = Arc::new(MemoryCache::default());
+    let before = Utc::now();
+
+    let first = CachedDogApi::new(harness.dog_api(), cache.clone(), Duration::from_secs(60));
+    assert_eq!(first.breeds_fetched_at().await, None);
+    first.breeds().await.unwrap();
+
+    // Another instance, or the same one after a restart, answers from the cache
+    let second = CachedDogApi::new(harness.dog_api(), cache, Duration::from_secs(60));
+    second.breeds().await.unwrap();
+    let fetched_at = second.breeds_fetched_at().await.unwrap();
+    assert!(fetched_at >= before && fetched_at <= Utc::now());
+}
+
+#[tokio::test]
+async fn hits_are_counted_by_kind() {
+    let cache = MeteredCache::new(Arc::new(MemoryCache::default()));
+    cache
+        .set("file_id:https://a", "1", Duration::from_secs(60))
+        .await;
+
+    assert_eq!(cache.get("file_id:https://a").await.as_deref(), Some("1"));
+    assert_eq!(cache.get("file_id:https://b").await, None);
+    assert_eq!(cache.get("breeds").await, None);
+
+    assert_eq!(cache.hit_ratio("file_id"), Some(0.5));
+    assert_eq!(cache.hit_ratio("breeds"), Some(0.0));
+    assert_eq!(cache.hit_ratio("price"), None);
+}
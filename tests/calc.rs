@@ -0,0 +1,53 @@
+use dog_bot::calc::evaluate;
+
+#[test]
+fn arithmetic_follows_the_usual_precedence() {
+    assert_eq!(evaluate("(2+3)*4^2"), Ok(80.0));
+    assert_eq!(evaluate("2 + 3 * 4"), Ok(14.0));
+    assert_eq!(evaluate("-2^2"), Ok(-4.0));
+    assert_eq!(evaluate("2^3^2"), Ok(512.0));
+    assert_eq!(evaluate("7 ÷ 2 × 4"), Ok(14.0));
+}
+
+#[test]
+fn percentages_and_functions() {
+    assert_eq!(evaluate("80 + 15%"), Ok(92.0));
+    assert_eq!(evaluate("200 - 10%"), Ok(180.0));
+    assert_eq!(evaluate("50% * 30"), Ok(15.0));
+    assert_eq!(evaluate("sqrt(16) + sqrt 9"), Ok(7.0));
+    assert_eq!(evaluate("√(3^2 + 4^2)"), Ok(5.0));
+    assert_eq!(evaluate("round(PI * 100)"), Ok(314.0));
+}
+
+#[test]
+fn mistakes_are_explained() {
+    assert_eq!(evaluate("1/0"), Err("I can't divide by zero".to_string()));
+    assert_eq!(
+        evaluate("sqrt(-1)"),
+        Err("The result isn't a real number".to_string())
+    );
+    assert_eq!(
+        evaluate("(1+2"),
+        Err("A parenthesis isn't closed".to_string())
+    );
+    assert_eq!(
+        evaluate("1+2)"),
+        Err("A parenthesis is closed but never opened".to_string())
+    );
+    assert_eq!(
+        evaluate("2 3"),
+        Err("Put an operator between the numbers, e.g. 2*3".to_string())
+    );
+    assert_eq!(
+        evaluate("system(1)"),
+        Err("I don't know what 'system' means".to_string())
+    );
+    assert_eq!(
+        evaluate("3 *"),
+        Err("The expression ends too soon".to_string())
+    );
+    assert_eq!(
+        evaluate(&"(".repeat(100)),
+        Err("That's nested too deep for me".to_string())
+    );
+}
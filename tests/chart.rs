@@ -0,0 +1,88 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    chart::{self, Period},
+    commands::{answer, Command},
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path_regex, query_param},
+    Mock, ResponseTemplate,
+};
+
+#[test]
+fn periods_are_read_in_days_weeks_months_and_years() {
+    assert_eq!("90d".parse(), Ok(Period { days: 90 }));
+    assert_eq!("12W".parse(), Ok(Period { days: 84 }));
+    assert_eq!("6m".parse(), Ok(Period { days: 180 }));
+    assert_eq!("1y".parse(), Ok(Period { days: 365 }));
+    assert!("0d".parse::<Period>().is_err());
+    assert!("11y".parse::<Period>().is_err());
+    assert!("soon".parse::<Period>().is_err());
+}
+
+#[test]
+fn a_single_rate_is_not_charted() {
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+    assert!(chart::render("EUR/USD", &[(date, 1.09)]).is_none());
+}
+
+#[tokio::test]
+async fn fiat_pairs_are_charted() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/\d{4}-\d{2}-\d{2}\.\.\d{4}-\d{2}-\d{2}$"))
+        .and(query_param("from", "EUR"))
+        .and(query_param("to", "USD"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "amount": 1.0,
+            "base": "EUR",
+            "start_date": "2024-01-02",
+            "end_date": "2024-01-05",
+            "rates": {
+                "2024-01-02": { "USD": 1.1 },
+                "2024-01-03": { "USD": 1.0919 },
+                "2024-01-05": { "USD": 1.0956 }
+            }
+        })))
+        .mount(&harness.frankfurter)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/chart eur/usd 90d"),
+        Command::Chart("eur/usd 90d".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert_eq!(
+        photos[0]["caption"],
+        "EUR/USD, last 90 days: 1.100 → 1.096 (-0.40%)"
+    );
+}
+
+#[tokio::test]
+async fn unknown_currencies_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "message": "not found" })))
+        .mount(&harness.frankfurter)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/chart eurxyz"),
+        Command::Chart("eurxyz".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I don't know the pair 'EUR/XYZ'");
+}
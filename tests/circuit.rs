@@ -0,0 +1,113 @@
+use dog_bot::{
+    circuit::{CircuitBreaker, CircuitOpen},
+    error::CommandError,
+    health::Health,
+    http::{self, HttpConfig},
+};
+use std::{sync::Arc, time::Duration};
+use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn the_circuit_opens_after_failures_in_a_row() {
+    let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+    breaker.failure("dog.ceo");
+    breaker.failure("dog.ceo");
+    // A success starts the count over
+    breaker.success("dog.ceo");
+    breaker.failure("dog.ceo");
+    breaker.failure("dog.ceo");
+    assert!(breaker.allow("dog.ceo").is_ok());
+
+    breaker.failure("dog.ceo");
+    assert!(breaker.allow("dog.ceo").is_err());
+    assert!(breaker.allow("api.coingecko.com").is_ok());
+}
+
+#[test]
+fn a_single_call_tries_again_after_the_cooldown() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+    breaker.failure("dog.ceo");
+    assert!(breaker.allow("dog.ceo").is_err());
+
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.allow("dog.ceo").is_ok());
+    assert!(breaker.allow("dog.ceo").is_err());
+
+    // The try failed
+    breaker.failure("dog.ceo");
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.allow("dog.ceo").is_ok());
+    breaker.success("dog.ceo");
+    assert!(breaker.allow("dog.ceo").is_ok());
+    assert!(breaker.allow("dog.ceo").is_ok());
+}
+
+#[test]
+fn a_try_not_made_lets_the_next_call_try() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+    breaker.failure("dog.ceo");
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.allow("dog.ceo").is_ok());
+    assert!(breaker.allow("dog.ceo").is_err());
+
+    breaker.skipped("dog.ceo");
+    assert!(breaker.allow("dog.ceo").is_ok());
+}
+
+#[test]
+fn a_try_that_never_ends_lets_another_call_try_after_the_cooldown() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+    breaker.failure("dog.ceo");
+    std::thread::sleep(Duration::from_millis(60));
+    // The try is let through but its outcome is never reported
+    assert!(breaker.allow("dog.ceo").is_ok());
+    assert!(breaker.allow("dog.ceo").is_err());
+
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.allow("dog.ceo").is_ok());
+    assert!(breaker.allow("dog.ceo").is_err());
+}
+
+#[test]
+fn a_threshold_of_zero_never_opens() {
+    let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+    for _ in 0..10 {
+        breaker.failure("dog.ceo");
+    }
+    assert!(breaker.allow("dog.ceo").is_ok());
+}
+
+#[tokio::test]
+async fn a_failing_upstream_is_not_called_while_open() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let config = HttpConfig {
+        max_retries: 0,
+        circuit_breaker_threshold: 2,
+        ..HttpConfig::default()
+    };
+    let client = http::client(&config, Arc::new(Health::default()));
+
+    for _ in 0..2 {
+        let res = client.get(server.uri()).send().await.unwrap();
+        assert_eq!(res.status(), 503);
+    }
+    let error = client.get(server.uri()).send().await.unwrap_err();
+    match &error {
+        reqwest_middleware::Error::Middleware(e) => assert!(e.is::<CircuitOpen>()),
+        e => panic!("expected an open circuit, got {}", e),
+    }
+
+    let error = CommandError::Upstream {
+        upstream: "dog.ceo",
+        error,
+    };
+    assert_eq!(
+        error.user_message(),
+        Some("This service is temporarily unavailable, please try again in a few minutes.")
+    );
+}
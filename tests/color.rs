@@ -0,0 +1,70 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    color::Color,
+    commands::{answer, Command},
+};
+
+#[test]
+fn colors_are_names_or_hex_codes() {
+    let tomato = Color {
+        r: 255,
+        g: 99,
+        b: 71,
+    };
+    assert_eq!("tomato".parse(), Ok(tomato));
+    assert_eq!("#FF6347".parse(), Ok(tomato));
+    assert_eq!("ff6347".parse(), Ok(tomato));
+    assert_eq!(
+        "Rebecca Purple"
+            .parse::<Color>()
+            .map(|color| color.to_string()),
+        Ok("#663399".to_string())
+    );
+    assert_eq!(
+        "#f63".parse::<Color>().map(|color| color.to_string()),
+        Ok("#FF6633".to_string())
+    );
+    assert_eq!(
+        "#12345".parse::<Color>(),
+        Err("'#12345' isn't a color like #ff6347 or tomato".to_string())
+    );
+    assert!("zzz".parse::<Color>().is_err());
+}
+
+#[test]
+fn colors_are_described_in_rgb_and_hsl() {
+    assert_eq!(
+        "#ff6347".parse::<Color>().unwrap().describe(),
+        "#FF6347 (tomato)\nrgb(255, 99, 71)\nhsl(9, 100%, 64%)"
+    );
+    assert_eq!(
+        "#3366cc".parse::<Color>().unwrap().describe(),
+        "#3366CC\nrgb(51, 102, 204)\nhsl(220, 60%, 50%)"
+    );
+}
+
+#[test]
+fn swatches_are_filled_with_the_color() {
+    let png = "teal".parse::<Color>().unwrap().swatch().unwrap();
+
+    let image = image::load_from_memory(&png).unwrap().to_rgb8();
+    assert!(image.pixels().all(|pixel| pixel.0 == [0, 128, 128]));
+}
+
+#[tokio::test]
+async fn swatches_are_sent_with_a_caption() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/color tomato"),
+        Command::Color("tomato".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(harness.sent("sendPhoto").await.len(), 1);
+}
@@ -0,0 +1,544 @@
+#![allow(dead_code)]
+
+use dog_bot::{
+    alerts::{AlertsConfig, Edits, PriceHistory},
+    api::{
+        apod::{ApodApi, Nasa},
+        breed_info::{BreedInfoApi, TheDogApi},
+        defi::{DefiApi, DefiLlama},
+        dictionary::{DictionaryApi, FreeDictionary},
+        dog::{DogApi, DogCeo},
+        fx::{Frankfurter, FxApi},
+        holidays::{HolidaysApi, NagerDate},
+        joke::{JokeApi, JokeApiDev},
+        market::{CoinGeckoMarket, MarketApi},
+        news::{NewsApi, Rss},
+        nft::{CoinGeckoNfts, NftApi},
+        pokemon::{PokeApi, PokemonApi},
+        price::{CoinGecko, PriceApi},
+        quote::{Quotable, QuoteApi},
+        shortener::{Shlink, ShortenerApi},
+        stock::{StockApi, YahooFinance},
+        translate::{LibreTranslate, TranslateApi},
+        trivia::{OpenTriviaDb, TriviaApi},
+        urban::{UrbanApi, UrbanDictionary},
+        weather::{OpenMeteo, WeatherApi},
+        wiki::{WikiApi, Wikipedia},
+        xkcd::{Xkcd, XkcdApi},
+    },
+    assets::{Assets, Sound},
+    cache::{memory::MemoryCache, Cache, CacheConfig, FileIds},
+    commands::{self, CommandsConfig},
+    health::Health,
+    http::{self, HttpConfig},
+    images::{Images, ImagesConfig},
+    inline::{InlineCache, InlineConfig},
+    limits::Limiter,
+    prefetch::DogBuffer,
+    queue::QueueConfig,
+    replay,
+    reporter::{AdminConfig, ErrorReporter},
+    scheduler::Scheduler,
+    state::AppState,
+    storage::{memory::MemoryStorage, Storage},
+    subscriptions::Subscriptions,
+    trivia::Rounds,
+};
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+use std::{
+    error::Error,
+    ops::ControlFlow,
+    sync::{Arc, RwLock},
+};
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, Message, Update},
+};
+use wiremock::{
+    matchers::{method, path_regex},
+    Mock, MockServer, Request, ResponseTemplate,
+};
+
+pub const CHAT_ID: i64 = 1000;
+pub const USER_ID: u64 = 2000;
+
+/// Upstream APIs and a fake Telegram Bot API server, all served by wiremock.
+pub struct Harness {
+    pub dog_ceo: MockServer,
+    pub thedogapi: MockServer,
+    pub coingecko: MockServer,
+    pub defillama: MockServer,
+    pub frankfurter: MockServer,
+    pub yahoo_finance: MockServer,
+    /// Both the forecast and the geocoding API of Open-Meteo.
+    pub open_meteo: MockServer,
+    pub jokeapi: MockServer,
+    pub quotable: MockServer,
+    pub xkcd: MockServer,
+    pub nasa: MockServer,
+    /// Serves every language under `/<lang>`.
+    pub wikipedia: MockServer,
+    pub dictionaryapi: MockServer,
+    pub urban_dictionary: MockServer,
+    pub opentdb: MockServer,
+    pub pokeapi: MockServer,
+    pub nager_date: MockServer,
+    pub libretranslate: MockServer,
+    pub shlink: MockServer,
+    /// Serves the default feed under `/rss.xml`.
+    pub news: MockServer,
+    pub telegram: MockServer,
+    pub health: Arc<Health>,
+    pub storage: Arc<dyn Storage>,
+    /// Shared by the states, so the prices seen by one check are there for the next.
+    pub cache: Arc<dyn Cache>,
+    pub commands: CommandsConfig,
+    pub upload_images: bool,
+    pub images: ImagesConfig,
+    /// Capacity of the buffer of random dogs.
+    pub prefetched_dogs: usize,
+    /// Played by `/bark`.
+    pub barks: Vec<Sound>,
+}
+
+impl Harness {
+    pub async fn start() -> Self {
+        let telegram = MockServer::start().await;
+
+        // Albums answer with their messages, mounted first so it wins over the catch-all below
+        Mock::given(method("POST"))
+            .and(path_regex(r"^/botTOKEN/(?i:sendMediaGroup)$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "result": [message_json("sent"), message_json("sent")]
+            })))
+            .mount(&telegram)
+            .await;
+        // Every `send*` and `edit*` method answers with a message so teloxide can parse the response
+        Mock::given(method("POST"))
+            .and(path_regex(r"^/botTOKEN/(?i:send|edit)\w+$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "result": message_json("sent")
+            })))
+            .mount(&telegram)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(
+                r"^/botTOKEN/(?i:answerCallbackQuery|answerInlineQuery)$",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "result": true
+            })))
+            .mount(&telegram)
+            .await;
+
+        Self {
+            dog_ceo: MockServer::start().await,
+            thedogapi: MockServer::start().await,
+            coingecko: MockServer::start().await,
+            defillama: MockServer::start().await,
+            frankfurter: MockServer::start().await,
+            yahoo_finance: MockServer::start().await,
+            open_meteo: MockServer::start().await,
+            jokeapi: MockServer::start().await,
+            quotable: MockServer::start().await,
+            xkcd: MockServer::start().await,
+            nasa: MockServer::start().await,
+            wikipedia: MockServer::start().await,
+            dictionaryapi: MockServer::start().await,
+            urban_dictionary: MockServer::start().await,
+            opentdb: MockServer::start().await,
+            pokeapi: MockServer::start().await,
+            nager_date: MockServer::start().await,
+            libretranslate: MockServer::start().await,
+            shlink: MockServer::start().await,
+            news: MockServer::start().await,
+            telegram,
+            health: Arc::default(),
+            storage: Arc::new(MemoryStorage::default()),
+            cache: Arc::new(MemoryCache::default()),
+            commands: CommandsConfig::default(),
+            upload_images: false,
+            images: ImagesConfig::default(),
+            prefetched_dogs: 0,
+            barks: Vec::new(),
+        }
+    }
+
+    pub fn bot(&self) -> AutoSend<Bot> {
+        Bot::new("TOKEN")
+            .set_api_url(Url::parse(&self.telegram.uri()).unwrap())
+            .auto_send()
+    }
+
+    pub fn state(&self) -> Arc<AppState> {
+        Arc::new(AppState {
+            dog_api: self.dog_api(),
+            breed_info_api: self.breed_info_api(),
+            price_api: self.price_api(),
+            nft_api: self.nft_api(),
+            market_api: self.market_api(),
+            fx_api: self.fx_api(),
+            defi_api: self.defi_api(),
+            stock_api: self.stock_api(),
+            weather_api: self.weather_api(),
+            joke_api: self.joke_api(),
+            quote_api: self.quote_api(),
+            xkcd_api: self.xkcd_api(),
+            apod_api: self.apod_api(),
+            wiki_api: self.wiki_api(),
+            dictionary_api: self.dictionary_api(),
+            urban_api: self.urban_api(),
+            trivia_api: self.trivia_api(),
+            pokemon_api: self.pokemon_api(),
+            holidays_api: self.holidays_api(),
+            news_api: self.news_api(),
+            translate_api: Some(self.translate_api()),
+            shortener_api: Some(self.shortener_api()),
+            reporter: Arc::new(ErrorReporter::new(self.bot(), AdminConfig::default())),
+            health: self.health.clone(),
+            storage: self.storage.clone(),
+            file_ids: FileIds::new(Arc::new(MemoryCache::default()), &CacheConfig::default()),
+            price_history: PriceHistory::new(self.cache.clone(), &AlertsConfig::default()),
+            alert_edits: Edits::default(),
+            dogs: DogBuffer::new(self.prefetched_dogs),
+            images: Images::new(
+                http::client(&HttpConfig::default(), self.health.clone()),
+                &self.images,
+            ),
+            assets: Assets::new(self.barks.clone()),
+            subscriptions: Subscriptions::new(self.bot(), &QueueConfig::default()),
+            inline: InlineCache::new(&InlineConfig::default()),
+            scheduler: Scheduler::new(self.storage.clone()),
+            commands: RwLock::new(self.commands.clone()),
+            limiter: Limiter::new(&self.commands),
+            trivia: Rounds::default(),
+            log_level: None,
+            metrics: None,
+            upload_images: self.upload_images,
+        })
+    }
+
+    pub fn dog_api(&self) -> Arc<dyn DogApi> {
+        Arc::new(DogCeo::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.dog_ceo.uri(),
+        ))
+    }
+
+    pub fn breed_info_api(&self) -> Arc<dyn BreedInfoApi> {
+        Arc::new(TheDogApi::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.thedogapi.uri(),
+            None,
+        ))
+    }
+
+    pub fn price_api(&self) -> Arc<dyn PriceApi> {
+        Arc::new(CoinGecko::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.coingecko.uri(),
+        ))
+    }
+
+    /// Served by the `coingecko` server.
+    pub fn nft_api(&self) -> Arc<dyn NftApi> {
+        Arc::new(CoinGeckoNfts::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.coingecko.uri(),
+        ))
+    }
+
+    /// Served by the `coingecko` server.
+    pub fn market_api(&self) -> Arc<dyn MarketApi> {
+        Arc::new(CoinGeckoMarket::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.coingecko.uri(),
+        ))
+    }
+
+    pub fn fx_api(&self) -> Arc<dyn FxApi> {
+        Arc::new(Frankfurter::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.frankfurter.uri(),
+        ))
+    }
+
+    pub fn defi_api(&self) -> Arc<dyn DefiApi> {
+        Arc::new(DefiLlama::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.defillama.uri(),
+        ))
+    }
+
+    pub fn stock_api(&self) -> Arc<dyn StockApi> {
+        Arc::new(YahooFinance::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.yahoo_finance.uri(),
+        ))
+    }
+
+    pub fn weather_api(&self) -> Arc<dyn WeatherApi> {
+        Arc::new(OpenMeteo::with_base_urls(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.open_meteo.uri(),
+            self.open_meteo.uri(),
+        ))
+    }
+
+    pub fn joke_api(&self) -> Arc<dyn JokeApi> {
+        Arc::new(JokeApiDev::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.jokeapi.uri(),
+            true,
+        ))
+    }
+
+    pub fn quote_api(&self) -> Arc<dyn QuoteApi> {
+        Arc::new(Quotable::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.quotable.uri(),
+        ))
+    }
+
+    pub fn xkcd_api(&self) -> Arc<dyn XkcdApi> {
+        Arc::new(Xkcd::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.xkcd.uri(),
+        ))
+    }
+
+    pub fn apod_api(&self) -> Arc<dyn ApodApi> {
+        Arc::new(Nasa::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.nasa.uri(),
+            "KEY",
+        ))
+    }
+
+    pub fn wiki_api(&self) -> Arc<dyn WikiApi> {
+        Arc::new(Wikipedia::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            format!("{}/{{lang}}", self.wikipedia.uri()),
+        ))
+    }
+
+    pub fn dictionary_api(&self) -> Arc<dyn DictionaryApi> {
+        Arc::new(FreeDictionary::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.dictionaryapi.uri(),
+        ))
+    }
+
+    pub fn urban_api(&self) -> Arc<dyn UrbanApi> {
+        Arc::new(UrbanDictionary::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.urban_dictionary.uri(),
+        ))
+    }
+
+    pub fn trivia_api(&self) -> Arc<dyn TriviaApi> {
+        Arc::new(OpenTriviaDb::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.opentdb.uri(),
+        ))
+    }
+
+    pub fn pokemon_api(&self) -> Arc<dyn PokemonApi> {
+        Arc::new(PokeApi::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.pokeapi.uri(),
+        ))
+    }
+
+    pub fn holidays_api(&self) -> Arc<dyn HolidaysApi> {
+        Arc::new(NagerDate::with_base_url(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.nager_date.uri(),
+        ))
+    }
+
+    pub fn news_api(&self) -> Arc<dyn NewsApi> {
+        Arc::new(
+            Rss::with_default_feed(
+                http::client(&HttpConfig::default(), self.health.clone()),
+                format!("{}/rss.xml", self.news.uri()),
+            )
+            .with_private_feeds(),
+        )
+    }
+
+    pub fn translate_api(&self) -> Arc<dyn TranslateApi> {
+        Arc::new(LibreTranslate::new(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.libretranslate.uri(),
+            None,
+        ))
+    }
+
+    pub fn shortener_api(&self) -> Arc<dyn ShortenerApi> {
+        Arc::new(Shlink::new(
+            http::client(&HttpConfig::default(), self.health.clone()),
+            self.shlink.uri(),
+            "KEY",
+        ))
+    }
+
+    /// Feed the update through the whole dispatch tree, as the bot receives it from Telegram.
+    ///
+    /// Unlike calling a handler, the commands are parsed and an update no handler wants is fine.
+    pub async fn receive(
+        &self,
+        state: &Arc<AppState>,
+        update: Update,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let deps = dptree::deps![
+            update,
+            self.bot(),
+            replay::me(replay::DEFAULT_USERNAME),
+            state.clone()
+        ];
+        match commands::handler().dispatch(deps).await {
+            ControlFlow::Break(result) => result,
+            ControlFlow::Continue(_) => Ok(()),
+        }
+    }
+
+    /// The user is a member of the groups with the given status, e.g. `member` or `creator`.
+    pub async fn member_status(&self, status: &str) {
+        Mock::given(method("POST"))
+            .and(path_regex(r"^/botTOKEN/(?i:getChatMember)$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "result": {
+                    "status": status,
+                    "user": { "id": USER_ID, "is_bot": false, "first_name": "Marc" },
+                    "is_anonymous": false
+                }
+            })))
+            .mount(&self.telegram)
+            .await;
+    }
+
+    /// Every Bot API call made so far, in order, e.g. `("sendPhoto", {"chat_id": 1000, ...})`.
+    pub async fn calls(&self) -> Vec<(String, Value)> {
+        self.telegram
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|request| {
+                let bot_method = request.url.path().rsplit('/').next().unwrap_or_default();
+                // teloxide calls e.g. `SendPhoto`, named `sendPhoto` in the Bot API
+                let mut chars = bot_method.chars();
+                let bot_method = chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default();
+                (bot_method, request_params(&request))
+            })
+            .collect()
+    }
+
+    /// Parameters of the calls made to the given Bot API method, e.g. `sendPhoto`.
+    pub async fn sent(&self, bot_method: &str) -> Vec<Value> {
+        let path = format!("/botTOKEN/{}", bot_method).to_lowercase();
+        self.telegram
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|request| request.url.path().to_lowercase() == path)
+            .map(|request| request_params(&request))
+            .collect()
+    }
+}
+
+/// teloxide sends JSON bodies, or multipart forms when the request carries files.
+fn request_params(request: &Request) -> Value {
+    let content_type = request
+        .headers
+        .get(&"content-type".into())
+        .map(|value| value.as_str().to_string())
+        .unwrap_or_default();
+
+    let boundary = match content_type.split_once("boundary=") {
+        Some((_, boundary)) => format!("--{}", boundary),
+        None => return serde_json::from_slice(&request.body).unwrap(),
+    };
+
+    let body = String::from_utf8_lossy(&request.body);
+    let mut params = Map::new();
+    for part in body.split(boundary.as_str()) {
+        let (headers, value) = match part.split_once("\r\n\r\n") {
+            Some(part) => part,
+            None => continue,
+        };
+        let name = headers
+            .split("name=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next());
+        if let Some(name) = name {
+            let value = value.trim_end_matches("\r\n");
+            let value = serde_json::from_str(value).unwrap_or_else(|_| json!(value));
+            params.insert(name.to_string(), value);
+        }
+    }
+
+    // The files are parts of their own, e.g. `"photo": "attach://<uuid>"` along with a `<uuid>` part
+    let files = params.clone();
+    for value in params.values_mut() {
+        let file = value
+            .as_str()
+            .and_then(|value| value.strip_prefix("attach://"))
+            .and_then(|name| files.get(name));
+        if let Some(file) = file {
+            *value = file.clone();
+        }
+    }
+    Value::Object(params)
+}
+
+fn message_json(text: &str) -> Value {
+    json!({
+        "message_id": 1,
+        "date": 0,
+        "chat": { "id": CHAT_ID, "type": "private", "first_name": "Marc" },
+        "from": { "id": USER_ID, "is_bot": false, "first_name": "Marc" },
+        "text": text
+    })
+}
+
+/// Incoming message as sent by a user.
+pub fn message(text: &str) -> Message {
+    serde_json::from_value(message_json(text)).unwrap()
+}
+
+/// Incoming message as sent by a user in a group, see [`Harness::member_status`].
+pub fn group_message(text: &str) -> Message {
+    let mut message = message_json(text);
+    message["chat"] = json!({ "id": CHAT_ID, "type": "group", "title": "Dogs" });
+    serde_json::from_value(message).unwrap()
+}
+
+/// The message as an update from Telegram, see [`Harness::receive`].
+pub fn update(text: &str) -> Update {
+    // teloxide only makes sense of the message when parsing text, from a `Value` it's an error update
+    let update = json!({ "update_id": 1, "message": message_json(text) });
+    serde_json::from_str(&update.to_string()).unwrap()
+}
+
+/// Press on an inline keyboard button with the given data, by the user.
+pub fn callback(data: &str) -> CallbackQuery {
+    serde_json::from_value(json!({
+        "id": "1",
+        "from": { "id": USER_ID, "is_bot": false, "first_name": "Marc" },
+        "message": message_json("sent"),
+        "chat_instance": "1",
+        "data": data
+    }))
+    .unwrap()
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    api::breed_info::{BreedInfo, Measure},
+    breed::BreedQuery,
+    commands::{answer, Command},
+    compare,
+};
+use serde_json::json;
+use std::str::FromStr;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+const HUSKY: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+const PUG: &str = "https://images.dog.ceo/breeds/pug/n02110958_15626.jpg";
+
+#[test]
+fn breeds_are_split_on_vs() {
+    assert_eq!(compare::split("husky vs pug"), Some(("husky", "pug")));
+    assert_eq!(
+        compare::split("Golden Retriever VS. pug"),
+        Some(("Golden Retriever", "pug"))
+    );
+    assert_eq!(compare::split("husky versus pug"), Some(("husky", "pug")));
+    assert_eq!(compare::split("husky pug"), None);
+    assert_eq!(compare::split("husky vs "), None);
+}
+
+#[test]
+fn missing_info_is_unknown() {
+    let husky = BreedQuery::from_str("husky").unwrap();
+    let pug = BreedQuery::from_str("pug").unwrap();
+    let info = BreedInfo {
+        name: "Siberian Husky".to_string(),
+        weight: Measure {
+            imperial: Some("35 - 60".to_string()),
+            metric: Some("16 - 27".to_string()),
+        },
+        height: Measure {
+            imperial: Some("20 - 23.5".to_string()),
+            metric: Some("51 - 60".to_string()),
+        },
+        life_span: Some("12 - 15 years".to_string()),
+        temperament: Some("Outgoing, Friendly, Alert".to_string()),
+    };
+
+    assert_eq!(
+        compare::caption([(&husky, Some(&info)), (&pug, None)]),
+        "Siberian Husky vs Pug\n\n\
+         📏 Size\n• Siberian Husky: 16 - 27 kg, 51 - 60 cm\n• Pug: unknown\n\n\
+         🧠 Temperament\n• Siberian Husky: Outgoing, Friendly, Alert\n• Pug: unknown\n\n\
+         ⏳ Lifespan\n• Siberian Husky: 12 - 15 years\n• Pug: unknown"
+    );
+}
+
+async fn mount_breed(harness: &Harness, breed: &str, image: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/breed/{}/images/random", breed)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": image, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+}
+
+#[tokio::test]
+async fn both_breeds_are_sent_as_an_album() {
+    let harness = Harness::start().await;
+    mount_breed(&harness, "husky", HUSKY).await;
+    mount_breed(&harness, "pug", PUG).await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/search"))
+        .and(query_param("q", "pug"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "name": "Puggle", "life_span": "10 - 15 years" },
+            {
+                "name": "Pug",
+                "weight": { "imperial": "14 - 18", "metric": "6 - 8" },
+                "height": { "imperial": "10 - 12", "metric": "25 - 30" },
+                "life_span": "12 - 15 years",
+                "temperament": "Docile, Clever, Charming"
+            }
+        ])))
+        .mount(&harness.thedogapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/comparebreeds husky vs pug"),
+        Command::CompareBreeds("husky vs pug".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let albums = harness.sent("sendMediaGroup").await;
+    assert_eq!(albums.len(), 1);
+    assert_eq!(albums[0]["chat_id"], CHAT_ID);
+    let media = &albums[0]["media"];
+    assert_eq!(media[0]["media"], HUSKY);
+    assert_eq!(media[1]["media"], PUG);
+    // Without info about the husky, the pug's is still shown
+    let caption = media[0]["caption"].as_str().unwrap();
+    assert!(caption.starts_with("Husky vs Pug"));
+    assert!(caption.contains("• Husky: unknown"));
+    assert!(caption.contains("• Pug: 6 - 8 kg, 25 - 30 cm"));
+    assert!(caption.contains("• Pug: Docile, Clever, Charming"));
+}
+
+#[tokio::test]
+async fn breeds_must_be_separated_by_vs() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/comparebreeds husky pug"),
+        Command::CompareBreeds("husky pug".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Tell me which breeds, e.g. /comparebreeds husky vs pug"
+    );
+    assert!(harness.sent("sendMediaGroup").await.is_empty());
+}
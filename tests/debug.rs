@@ -0,0 +1,83 @@
+mod common;
+
+use common::Harness;
+use dog_bot::debug::{self, Debug, Preview};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+const IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+
+fn args(args: &str) -> Vec<String> {
+    args.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn subcommands_are_parsed() {
+    assert_eq!(Debug::parse(&args("dog")), Ok(Debug::Dog { breed: None }));
+    assert_eq!(
+        Debug::parse(&args("dog --breed golden retriever")),
+        Ok(Debug::Dog {
+            breed: Some("golden retriever".to_string())
+        })
+    );
+    assert_eq!(
+        Debug::parse(&args("price BTC")),
+        Ok(Debug::Price("btc".to_string()))
+    );
+
+    assert!(Debug::parse(&args("dog --breed")).is_err());
+    assert!(Debug::parse(&args("price")).is_err());
+    assert!(Debug::parse(&args("weather")).is_err());
+    assert!(Debug::parse(&[]).is_err());
+}
+
+#[tokio::test]
+async fn a_breed_prints_the_photo() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breed/husky/images/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let preview = debug::run(
+        &Debug::parse(&args("dog --breed husky")).unwrap(),
+        harness.dog_api().as_ref(),
+        harness.price_api().as_ref(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(preview, Preview::Photo(IMAGE.to_string()));
+    assert_eq!(preview.to_string(), format!("sendPhoto\n{}", IMAGE));
+}
+
+#[tokio::test]
+async fn a_price_prints_the_message() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "bitcoin": { "usd": 106000.0 } })),
+        )
+        .mount(&harness.coingecko)
+        .await;
+
+    let preview = debug::run(
+        &Debug::Price("btc".to_string()),
+        harness.dog_api().as_ref(),
+        harness.price_api().as_ref(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(preview.to_string(), "sendMessage\nBTC: $106,000.00");
+    // Nothing reaches Telegram
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
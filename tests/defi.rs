@@ -0,0 +1,76 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    money,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[test]
+fn large_amounts_are_shortened() {
+    assert_eq!(money::usd_short(23_450_000_000.0), "$23.45B");
+    assert_eq!(money::usd_short(1_500_000.0), "$1.50M");
+    assert_eq!(money::usd_short(950.0), "$950.00");
+}
+
+#[tokio::test]
+async fn protocols_are_found_by_name_or_slug() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/protocols"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "name": "Aave V3", "slug": "aave-v3", "tvl": 12_000_000_000.0, "change_7d": null },
+            { "name": "Lido", "slug": "lido", "tvl": 23_450_000_000.0, "change_7d": 2.1 }
+        ])))
+        .mount(&harness.defillama)
+        .await;
+
+    for (text, protocol) in [("/tvl Lido", "Lido"), ("/tvl aave-v3", "aave-v3")] {
+        answer(
+            harness.bot(),
+            common::message(text),
+            Command::Tvl(protocol.to_string()),
+            harness.state(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "Lido TVL: $23.45B (+2.10% in 7d)");
+    assert_eq!(messages[1]["text"], "Aave V3 TVL: $12.00B");
+}
+
+#[tokio::test]
+async fn chains_are_ranked_by_value_locked() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v2/chains"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "name": "Tron", "tvl": 8_000_000_000.0, "tokenSymbol": "TRX" },
+            { "name": "Ethereum", "tvl": 60_120_000_000.0, "tokenSymbol": "ETH" },
+            { "name": "Solana", "tvl": 9_500_000_000.0, "tokenSymbol": "SOL" }
+        ])))
+        .mount(&harness.defillama)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/tvl chains"),
+        Command::Tvl("chains".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Top chains by value locked\n\n1. Ethereum: $60.12B\n2. Solana: $9.50B\n3. Tron: $8.00B"
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn definitions_are_listed_per_sense() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/entries/en/woof"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "word": "woof",
+            "phonetics": [{ "text": "" }, { "text": "/wʊf/", "audio": "" }],
+            "meanings": [
+                {
+                    "partOfSpeech": "noun",
+                    "definitions": [{
+                        "definition": "The sound of a dog barking.",
+                        "example": "The dog gave a loud woof."
+                    }]
+                },
+                {
+                    "partOfSpeech": "verb",
+                    "definitions": [{ "definition": "To bark." }]
+                }
+            ]
+        }])))
+        .mount(&harness.dictionaryapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/define Woof"),
+        Command::Define("Woof".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "woof /wʊf/\n\n\
+         1. (noun) The sound of a dog barking.\n   \"The dog gave a loud woof.\"\n\
+         2. (verb) To bark."
+    );
+}
+
+#[tokio::test]
+async fn unknown_words_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/entries/en/grrrf"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "title": "No Definitions Found"
+        })))
+        .mount(&harness.dictionaryapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/define grrrf"),
+        Command::Define("grrrf".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I don't know the word 'grrrf'");
+}
@@ -0,0 +1,75 @@
+use chrono::{Duration, Utc};
+use dog_bot::{
+    digest::Digest,
+    storage::{memory::MemoryStorage, CommandRecord, Storage},
+};
+
+#[tokio::test]
+async fn digest_counts_the_week_of_the_chat() {
+    let storage = MemoryStorage::default();
+    let now = Utc::now();
+    let record = |command: &str, chat_id: i64, days_ago: i64| CommandRecord {
+        command: command.to_string(),
+        user_id: Some(1),
+        chat_id,
+        chat_type: "group".to_string(),
+        at: now - Duration::days(days_ago),
+        latency_ms: 100,
+        success: true,
+    };
+    for record in [
+        record("doggo", 1, 1),
+        record("breed", 1, 2),
+        record("breed", 1, 3),
+        record("euro", 1, 3),
+        record("doggo", 1, 10),
+        record("doggo", 2, 1),
+    ] {
+        storage.record_command(&record).await.unwrap();
+    }
+    for breed in ["husky", "akita", "husky"] {
+        storage.record_breed(1, breed).await.unwrap();
+    }
+
+    let digest = Digest::build(&storage, 1, now - Duration::weeks(1))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        digest,
+        Digest {
+            dogs: 3,
+            top_breed: Some(("husky".to_string(), 2)),
+            commands: 4,
+        }
+    );
+    assert_eq!(
+        digest.render("en"),
+        "This week in this chat:\n- 3 dogs sent\n- Most requested breed: husky (2 times)\n- 4 commands used\n"
+    );
+}
+
+#[test]
+fn counts_take_the_plural_of_the_language() {
+    let digest = |dogs, commands| Digest {
+        dogs,
+        top_breed: Some(("husky".to_string(), dogs)),
+        commands,
+    };
+
+    assert_eq!(
+        digest(1, 1).render("en"),
+        "This week in this chat:\n- 1 dog sent\n- Most requested breed: husky (1 time)\n- 1 command used\n"
+    );
+    assert_eq!(
+        digest(1, 5).render("es"),
+        "Esta semana en este chat:\n- 1 perro enviado\n- Raza más pedida: husky (1 vez)\n- 5 comandos usados\n"
+    );
+    assert_eq!(
+        digest(22, 5).render("ru"),
+        "На этой неделе в этом чате:\n- 22 собаки отправлены\n- Самая популярная порода: husky (22 раза)\n- 5 команд использовано\n"
+    );
+    assert!(digest(21, 11).render("ru").contains("21 собака отправлена"));
+    // Languages without translations fall back to English
+    assert!(digest(2, 2).render("de").contains("2 dogs sent"));
+}
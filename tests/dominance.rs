@@ -0,0 +1,41 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn dominance_shows_the_market_with_its_trend() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/global"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "total_market_cap": { "usd": 2_450_000_000_000.0, "eur": 2_250_000_000_000.0 },
+                "total_volume": { "usd": 98_100_000_000.0 },
+                "market_cap_percentage": { "btc": 52.104, "eth": 17.0, "usdt": 4.2 },
+                "market_cap_change_percentage_24h_usd": -1.2
+            }
+        })))
+        .mount(&harness.coingecko)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/dominance"),
+        Command::Dominance,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Market cap: $2.45T 📉 -1.20% in 24h\n24h volume: $98.10B\nBTC dominance: 52.10%\nETH dominance: 17.00%"
+    );
+}
@@ -0,0 +1,60 @@
+use dog_bot::dry_run;
+use reqwest::Url;
+use serde_json::json;
+use teloxide::prelude::*;
+use wiremock::{
+    matchers::{method, path_regex},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// A bot talking to Telegram through the dry run.
+async fn bot(telegram: &MockServer) -> AutoSend<Bot> {
+    let upstream = Url::parse(&telegram.uri()).unwrap();
+    let url = dry_run::start(reqwest::Client::new(), upstream)
+        .await
+        .unwrap();
+    Bot::new("TOKEN").set_api_url(url).auto_send()
+}
+
+#[tokio::test]
+async fn messages_are_not_sent() {
+    let telegram = MockServer::start().await;
+    let bot = bot(&telegram).await;
+
+    let sent = bot.send_message(ChatId(1000), "Woof").await.unwrap();
+    let edited = bot
+        .edit_message_text(ChatId(1000), sent.id, "Woof woof")
+        .await
+        .unwrap();
+
+    assert_eq!(sent.chat.id, ChatId(1000));
+    assert_eq!(sent.text(), Some("Woof"));
+    assert_eq!(edited.id, sent.id);
+    assert!(telegram.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reads_reach_telegram() {
+    let telegram = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"^/botTOKEN/(?i:getMe)$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "result": {
+                "id": 1,
+                "is_bot": true,
+                "first_name": "Dog",
+                "username": "dog_bot",
+                "can_join_groups": true,
+                "can_read_all_group_messages": false,
+                "supports_inline_queries": true
+            }
+        })))
+        .expect(1)
+        .mount(&telegram)
+        .await;
+    let bot = bot(&telegram).await;
+
+    let me = bot.get_me().await.unwrap();
+    assert_eq!(me.username(), "dog_bot");
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+const IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+
+async fn mock_random_dog(harness: &Harness) {
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+}
+
+#[tokio::test]
+async fn doggo_sends_exactly_one_photo() {
+    let harness = Harness::start().await;
+    mock_random_dog(&harness).await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("/doggo"))
+        .await
+        .unwrap();
+
+    let calls = harness.calls().await;
+    assert_eq!(calls.len(), 1, "{:?}", calls);
+    let (bot_method, params) = &calls[0];
+    assert_eq!(bot_method, "sendPhoto");
+    assert_eq!(params["chat_id"], CHAT_ID);
+    assert_eq!(params["photo"], IMAGE);
+}
+
+#[tokio::test]
+async fn commands_for_another_bot_are_ignored() {
+    let harness = Harness::start().await;
+    mock_random_dog(&harness).await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("/doggo@another_bot"))
+        .await
+        .unwrap();
+
+    assert!(harness.calls().await.is_empty());
+    assert!(harness
+        .dog_ceo
+        .received_requests()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn plain_messages_get_no_answer() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+
+    for text in ["just chatting", "/notacommand"] {
+        harness.receive(&state, common::update(text)).await.unwrap();
+    }
+
+    assert!(harness.calls().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_bot_username_may_follow_the_command() {
+    let harness = Harness::start().await;
+    mock_random_dog(&harness).await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("/doggo@dog_bot"))
+        .await
+        .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert_eq!(photos[0]["photo"], IMAGE);
+}
@@ -0,0 +1,766 @@
+mod common;
+
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::{
+    api::dog::DogCeo,
+    commands::{answer, answer_callback, Command, BREED_PAGE},
+    http::{self, HttpConfig},
+    quota::Budget,
+};
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+const IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+
+#[tokio::test]
+async fn doggo_sends_a_photo() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert_eq!(photos[0]["chat_id"], CHAT_ID);
+    assert_eq!(photos[0]["photo"], IMAGE);
+}
+
+#[tokio::test]
+async fn breed_with_sub_breed_requests_the_right_path() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breed/retriever/golden/images/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/breed Golden Retriever"),
+        Command::Breed("Golden Retriever".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert_eq!(photos[0]["photo"], IMAGE);
+}
+
+#[tokio::test]
+async fn unknown_breed_is_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breed/unicorn/images/random"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "message": "Breed not found (master breed does not exist)",
+            "status": "error",
+            "code": 404
+        })))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/breed unicorn"),
+        Command::Breed("unicorn".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["chat_id"], CHAT_ID);
+    assert_eq!(messages[0]["text"], "Breed 'unicorn' doesn't exist");
+}
+
+#[tokio::test]
+async fn breeds_are_listed_to_the_user() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/list/all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": { "retriever": ["golden"] },
+            "status": "success"
+        })))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/breeds"),
+        Command::Breeds,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["chat_id"], USER_ID);
+    assert_eq!(messages[0]["text"], "-│ retriever\n     |> golden\n");
+}
+
+#[tokio::test]
+async fn euro_sends_the_usd_value() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "tether-eurt": { "usd": 1.07 } })),
+        )
+        .mount(&harness.coingecko)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/euro"),
+        Command::Euro,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["text"], "$1.07");
+}
+
+#[tokio::test]
+async fn invalid_breed_name_is_rejected_before_calling_dog_ceo() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/breed ../breeds"),
+        Command::Breed("../breeds".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert!(harness
+        .dog_ceo
+        .received_requests()
+        .await
+        .unwrap()
+        .is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["text"], "'../breeds' is not a valid breed name");
+}
+
+#[tokio::test]
+async fn slow_command_is_aborted_and_reported() {
+    let mut harness = Harness::start().await;
+    harness.commands.timeout_secs = 1;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" }))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "Sorry, that took too long, please try again later."
+    );
+}
+
+#[tokio::test]
+async fn commands_over_the_chat_limit_are_turned_down() {
+    let mut harness = Harness::start().await;
+    harness.commands.max_per_chat = 1;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" }))
+                .set_delay(Duration::from_millis(500)),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let state = harness.state();
+    let (first, second) = tokio::join!(
+        answer(
+            harness.bot(),
+            common::message("/doggo"),
+            Command::Doggo,
+            state.clone(),
+        ),
+        answer(
+            harness.bot(),
+            common::message("/doggo"),
+            Command::Doggo,
+            state,
+        ),
+    );
+    first.unwrap();
+    second.unwrap();
+
+    assert_eq!(harness.sent("sendPhoto").await.len(), 1);
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "Please wait for your previous commands to finish."
+    );
+}
+
+#[tokio::test]
+async fn users_over_their_rate_are_told_once_how_long_to_wait() {
+    let mut harness = Harness::start().await;
+    harness.commands.max_per_user = 1;
+    harness.commands.user_window_secs = 1;
+
+    let state = harness.state();
+    for _ in 0..3 {
+        answer(
+            harness.bot(),
+            common::message("/flip"),
+            Command::Flip,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        messages[1]["text"],
+        "⏳ Slow down, you can send commands again in 1s"
+    );
+
+    // The notice counts down, then says the cooldown is over
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let edits = harness.sent("editMessageText").await;
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0]["text"], "✅ You can send commands again");
+}
+
+#[tokio::test]
+async fn reloadconfig_is_refused_outside_the_admin_chat() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/reloadconfig"),
+        Command::ReloadConfig,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["text"], "Only the admin chat can do that");
+}
+
+#[tokio::test]
+async fn malformed_image_url_is_reported_to_the_user() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": "not a url", "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let result = answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        harness.state(),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "Sorry, something went wrong, please try again later."
+    );
+}
+
+#[tokio::test]
+async fn response_missing_fields_does_not_panic() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/list/all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "success" })))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let result = answer(
+        harness.bot(),
+        common::message("/breeds"),
+        Command::Breeds,
+        harness.state(),
+    )
+    .await;
+
+    assert!(result.is_err());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "Sorry, something went wrong, please try again later."
+    );
+}
+
+#[tokio::test]
+async fn a_failed_status_is_reported_to_the_user() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "message": "", "status": "error" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let result = answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        harness.state(),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "Sorry, something went wrong, please try again later."
+    );
+}
+
+#[tokio::test]
+async fn doggo_is_unavailable_while_the_circuit_is_open() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&harness.dog_ceo)
+        .await;
+    let config = HttpConfig {
+        max_retries: 0,
+        circuit_breaker_threshold: 1,
+        ..HttpConfig::default()
+    };
+    let mut state = Arc::try_unwrap(harness.state()).ok().unwrap();
+    state.dog_api = Arc::new(DogCeo::with_base_url(
+        http::client(&config, harness.health.clone()),
+        harness.dog_ceo.uri(),
+    ));
+    let state = Arc::new(state);
+
+    for _ in 0..2 {
+        let result = answer(
+            harness.bot(),
+            common::message("/doggo"),
+            Command::Doggo,
+            state.clone(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        messages[0]["text"],
+        "Sorry, something went wrong, please try again later."
+    );
+    assert_eq!(
+        messages[1]["text"],
+        "This service is temporarily unavailable, please try again in a few minutes."
+    );
+}
+
+#[tokio::test]
+async fn doggo_tells_when_dog_ceo_is_over_its_quota() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&harness.dog_ceo)
+        .await;
+    let config = HttpConfig {
+        quotas: HashMap::from([(
+            "127.0.0.1".to_string(),
+            Budget {
+                per_minute: None,
+                per_day: Some(0),
+            },
+        )]),
+        ..HttpConfig::default()
+    };
+    let mut state = Arc::try_unwrap(harness.state()).ok().unwrap();
+    state.dog_api = Arc::new(DogCeo::with_base_url(
+        http::client(&config, harness.health.clone()),
+        harness.dog_ceo.uri(),
+    ));
+
+    let result = answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        Arc::new(state),
+    )
+    .await;
+
+    assert!(result.is_err());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0]["text"],
+        "This service was asked too much lately, please try again later."
+    );
+}
+
+#[tokio::test]
+async fn images_are_uploaded_with_a_local_bot_api_server() {
+    let mut harness = Harness::start().await;
+    harness.upload_images = true;
+    let image = format!("{}/images/husky.jpg", harness.dog_ceo.uri());
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": image, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/images/husky.jpg"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes("husky-bytes"))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert_eq!(photos[0]["photo"], "husky-bytes");
+}
+
+#[tokio::test]
+async fn breed_without_arguments_uses_the_favourite_breed() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breed/retriever/golden/images/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/prefs breed golden retriever"),
+        Command::Prefs("breed golden retriever".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+    answer(
+        harness.bot(),
+        common::message("/breed"),
+        Command::Breed(String::new()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("breed: golden retriever"));
+    assert_eq!(harness.sent("sendPhoto").await.len(), 1);
+}
+
+#[tokio::test]
+async fn invalid_preferences_are_rejected() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/prefs difficulty impossible"),
+        Command::Prefs("difficulty impossible".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "The difficulty must be one of easy, medium, hard"
+    );
+    assert_eq!(
+        harness.storage.preferences(USER_ID as i64).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn subscriptions_are_stored_once_per_kind() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+
+    for time in ["09:00", "10:30"] {
+        answer(
+            harness.bot(),
+            common::message("/subscribe"),
+            Command::Subscribe(format!("dailydog {}", time)),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let subscriptions = harness.storage.chat_subscriptions(CHAT_ID).await.unwrap();
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].schedule, "10:30");
+
+    answer(
+        harness.bot(),
+        common::message("/unsubscribe dailydog"),
+        Command::Unsubscribe("dailydog".to_string()),
+        state,
+    )
+    .await
+    .unwrap();
+
+    assert!(harness
+        .storage
+        .chat_subscriptions(CHAT_ID)
+        .await
+        .unwrap()
+        .is_empty());
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Subscribed to dailydog, every day at 09:00 UTC"
+    );
+    assert_eq!(messages[2]["text"], "Unsubscribed from dailydog");
+}
+
+#[tokio::test]
+async fn mydata_sends_the_stored_data_privately() {
+    let harness = Harness::start().await;
+    harness
+        .storage
+        .add_favourite(USER_ID as i64, "husky")
+        .await
+        .unwrap();
+
+    answer(
+        harness.bot(),
+        common::message("/mydata"),
+        Command::MyData,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let documents = harness.sent("sendDocument").await;
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0]["chat_id"], USER_ID);
+    let data = &documents[0]["document"];
+    assert_eq!(data["user"]["id"], USER_ID);
+    assert_eq!(data["favourites"], json!(["husky"]));
+}
+
+#[tokio::test]
+async fn forgetme_deletes_the_user_once_confirmed() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+    let user_id = USER_ID as i64;
+    harness
+        .storage
+        .add_favourite(user_id, "husky")
+        .await
+        .unwrap();
+
+    answer(
+        harness.bot(),
+        common::message("/forgetme"),
+        Command::ForgetMe,
+        state.clone(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    let buttons = &messages[0]["reply_markup"]["inline_keyboard"][0];
+    assert_eq!(buttons[0]["callback_data"], format!("forgetme:{}", USER_ID));
+    // Nothing is deleted until confirmed
+    assert!(harness.storage.user(user_id).await.unwrap().is_some());
+
+    answer_callback(
+        harness.bot(),
+        common::callback(&format!("forgetme:{}", USER_ID)),
+        state,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(harness.storage.user(user_id).await.unwrap(), None);
+    assert!(harness
+        .storage
+        .favourites(user_id)
+        .await
+        .unwrap()
+        .is_empty());
+    let edits = harness.sent("editMessageText").await;
+    assert_eq!(edits[0]["text"], "Done, I forgot everything about you");
+}
+
+#[tokio::test]
+async fn forgetme_buttons_only_work_for_the_user_who_asked() {
+    let harness = Harness::start().await;
+    let user_id = USER_ID as i64;
+    harness.storage.see_user(user_id, None).await.unwrap();
+
+    answer_callback(
+        harness.bot(),
+        common::callback("forgetme:1234"),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert!(harness.storage.user(user_id).await.unwrap().is_some());
+    let answers = harness.sent("answerCallbackQuery").await;
+    assert_eq!(answers[0]["text"], "This button isn't for you");
+}
+
+const OTHER_IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1511.jpg";
+
+async fn mount_husky_images(harness: &Harness) {
+    Mock::given(method("GET"))
+        .and(path("/breed/husky/images/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/breed/husky/images"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": [OTHER_IMAGE, IMAGE],
+            "status": "success"
+        })))
+        .mount(&harness.dog_ceo)
+        .await;
+}
+
+#[tokio::test]
+async fn breed_photos_come_with_buttons_browsing_the_breed() {
+    let harness = Harness::start().await;
+    mount_husky_images(&harness).await;
+
+    answer(
+        harness.bot(),
+        common::message("/breed husky"),
+        Command::Breed("husky".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    let buttons = &photos[0]["reply_markup"]["inline_keyboard"][0];
+    assert_eq!(
+        buttons[0]["callback_data"],
+        format!("{}:{}:0:husky", BREED_PAGE, USER_ID)
+    );
+    assert_eq!(
+        buttons[1]["callback_data"],
+        format!("{}:{}:0:husky", BREED_PAGE, USER_ID)
+    );
+}
+
+#[tokio::test]
+async fn breed_buttons_replace_the_image_in_place() {
+    let harness = Harness::start().await;
+    mount_husky_images(&harness).await;
+
+    let data = format!("{}:{}:0:husky", BREED_PAGE, USER_ID);
+    answer_callback(harness.bot(), common::callback(&data), harness.state())
+        .await
+        .unwrap();
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    let edits = harness.sent("editMessageMedia").await;
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0]["media"]["media"], OTHER_IMAGE);
+    assert_eq!(edits[0]["media"]["caption"], "1/2");
+    assert_eq!(
+        edits[0]["reply_markup"]["inline_keyboard"][0][1]["callback_data"],
+        format!("{}:{}:1:husky", BREED_PAGE, USER_ID)
+    );
+}
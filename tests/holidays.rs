@@ -0,0 +1,88 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+async fn mount_spain(harness: &Harness) {
+    Mock::given(method("GET"))
+        .and(path("/NextPublicHolidays/ES"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "date": "2024-08-15",
+                "localName": "Asunción",
+                "name": "Assumption Day",
+                "countryCode": "ES",
+                "global": true
+            },
+            {
+                "date": "2024-10-12",
+                "localName": "Fiesta Nacional de España",
+                "name": "Fiesta Nacional de España",
+                "countryCode": "ES",
+                "global": true
+            }
+        ])))
+        .mount(&harness.nager_date)
+        .await;
+}
+
+#[tokio::test]
+async fn upcoming_holidays_are_listed() {
+    let harness = Harness::start().await;
+    mount_spain(&harness).await;
+
+    answer(
+        harness.bot(),
+        common::message("/holidays es"),
+        Command::Holidays("es".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Upcoming public holidays in ES:\n\nThu 15 Aug 2024 Asunción (Assumption Day)\nSat 12 Oct 2024 Fiesta Nacional de España"
+    );
+}
+
+#[tokio::test]
+async fn the_chat_country_is_the_default() {
+    let harness = Harness::start().await;
+    mount_spain(&harness).await;
+
+    for args in ["", "country spain", "country es", ""] {
+        answer(
+            harness.bot(),
+            common::message(&format!("/holidays {}", args)),
+            Command::Holidays(args.to_string()),
+            harness.state(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Tell me the two letter code of the country, e.g. /holidays es, or set the one of the chat with /holidays country es"
+    );
+    assert_eq!(
+        messages[1]["text"],
+        "'spain' isn't a two letter country code, e.g. es"
+    );
+    assert_eq!(
+        messages[2]["text"],
+        "The holidays of this chat are now the ones of ES"
+    );
+    assert!(messages[3]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("Upcoming public holidays in ES:"));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::{
+    commands::{answer, Command},
+    i18n::{self, Source},
+};
+use serde_json::json;
+use teloxide::types::Message;
+
+fn message_from_locale(text: &str, locale: &str) -> Message {
+    serde_json::from_value(json!({
+        "message_id": 1,
+        "date": 0,
+        "chat": { "id": CHAT_ID, "type": "private", "first_name": "Marc" },
+        "from": { "id": USER_ID, "is_bot": false, "first_name": "Marc", "language_code": locale },
+        "text": text
+    }))
+    .unwrap()
+}
+
+async fn language(harness: &Harness, code: &str, locale: &str) -> String {
+    answer(
+        harness.bot(),
+        message_from_locale(&format!("/language {}", code), locale),
+        Command::Language(code.to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+    let messages = harness.sent("sendMessage").await;
+    messages.last().unwrap()["text"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn the_chat_wins_over_the_user_and_their_locale() {
+    assert_eq!(
+        i18n::pick(Some("de".to_string()), Some("fr".to_string()), Some("es")),
+        ("de".to_string(), Source::Chat)
+    );
+    assert_eq!(
+        i18n::pick(None, Some("fr".to_string()), Some("es")),
+        ("fr".to_string(), Source::User)
+    );
+    assert_eq!(
+        i18n::pick(None, None, Some("pt-BR")),
+        ("pt".to_string(), Source::Locale)
+    );
+    assert_eq!(
+        i18n::pick(None, None, Some("not a locale")),
+        ("en".to_string(), Source::Default)
+    );
+}
+
+#[tokio::test]
+async fn the_language_is_detected_until_set() {
+    let harness = Harness::start().await;
+
+    assert!(language(&harness, "", "pt-BR")
+        .await
+        .starts_with("The language of this chat is pt (from your Telegram app)"));
+
+    assert_eq!(
+        language(&harness, "ES", "pt-BR").await,
+        "The language of this chat is now es"
+    );
+    assert!(language(&harness, "", "pt-BR")
+        .await
+        .starts_with("The language of this chat is es (set for this chat)"));
+
+    assert_eq!(
+        language(&harness, "auto", "pt-BR").await,
+        "This chat now follows the language of each user"
+    );
+    assert!(language(&harness, "", "it")
+        .await
+        .starts_with("The language of this chat is it (from your Telegram app)"));
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    images::{self, ImagesConfig},
+};
+use image::{GenericImageView, ImageOutputFormat, Rgb, RgbImage};
+use reqwest::Url;
+use serde_json::json;
+use std::{error::Error, io::Cursor};
+use wiremock::{
+    matchers::{body_string_contains, method, path, path_regex},
+    Mock, ResponseTemplate,
+};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut png = Vec::new();
+    RgbImage::from_fn(width, height, |x, y| Rgb([x as u8, y as u8, 90]))
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .unwrap();
+    png
+}
+
+/// Mount a random dog at `/images/husky.jpg`, answered with the given response.
+async fn mount_dog(harness: &Harness, image: ResponseTemplate) -> String {
+    let url = format!("{}/images/husky.jpg", harness.dog_ceo.uri());
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": url, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/images/husky.jpg"))
+        .respond_with(image)
+        .mount(&harness.dog_ceo)
+        .await;
+    url
+}
+
+async fn doggo(harness: &Harness) -> Result<(), Box<dyn Error + Send + Sync>> {
+    answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        harness.state(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn images_telegram_can_not_fetch_are_uploaded() {
+    let harness = Harness::start().await;
+    let url = mount_dog(
+        &harness,
+        ResponseTemplate::new(200)
+            .insert_header("content-type", "image/jpeg")
+            .set_body_bytes("husky-bytes"),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"^/botTOKEN/(?i:sendPhoto)$"))
+        .and(body_string_contains(url.as_str()))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "ok": false,
+            "error_code": 400,
+            "description": "Bad Request: failed to get HTTP URL content"
+        })))
+        .with_priority(1)
+        .mount(&harness.telegram)
+        .await;
+
+    doggo(&harness).await.unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 2);
+    assert_eq!(photos[0]["photo"], url);
+    assert_eq!(photos[1]["photo"], "husky-bytes");
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
+
+#[tokio::test]
+async fn pages_are_not_uploaded_as_images() {
+    let mut harness = Harness::start().await;
+    harness.upload_images = true;
+    mount_dog(
+        &harness,
+        ResponseTemplate::new(200).set_body_raw("<html>Not found</html>", "text/html"),
+    )
+    .await;
+
+    assert!(doggo(&harness).await.is_err());
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    assert_eq!(
+        harness.sent("sendMessage").await[0]["text"],
+        "Sorry, something went wrong, please try again later."
+    );
+}
+
+#[tokio::test]
+async fn images_too_big_for_telegram_are_not_uploaded() {
+    let mut harness = Harness::start().await;
+    harness.upload_images = true;
+    mount_dog(
+        &harness,
+        ResponseTemplate::new(200)
+            .insert_header("content-type", "image/jpeg")
+            .set_body_bytes(vec![0; images::MAX_BYTES + 1]),
+    )
+    .await;
+
+    assert!(doggo(&harness).await.is_err());
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    assert_eq!(harness.sent("sendMessage").await.len(), 1);
+}
+
+#[test]
+fn files_are_named_after_the_image() {
+    let url = Url::parse("https://images.dog.ceo/breeds/husky/n02110185_1469.jpg").unwrap();
+    assert_eq!(images::file_name(&url, "dog.jpg"), "n02110185_1469.jpg");
+    let url = Url::parse("https://images.dog.ceo/").unwrap();
+    assert_eq!(images::file_name(&url, "dog.jpg"), "dog.jpg");
+}
+
+#[test]
+fn images_within_the_limits_are_left_alone() {
+    assert_eq!(images::shrink(&png(640, 480), 85).unwrap(), None);
+}
+
+#[test]
+fn images_too_wide_are_downscaled() {
+    let jpeg = images::shrink(&png(9990, 100), 85).unwrap().unwrap();
+
+    let image = image::load_from_memory(&jpeg).unwrap();
+    let (width, height) = image.dimensions();
+    assert!(width + height <= images::MAX_DIMENSIONS);
+    // The aspect ratio is kept
+    assert!((width as f64 / height as f64 - 99.9).abs() < 1.5);
+}
+
+#[tokio::test]
+async fn images_are_shrunk_before_the_upload_when_enabled() {
+    let mut harness = Harness::start().await;
+    harness.upload_images = true;
+    harness.images = ImagesConfig {
+        resize: true,
+        ..ImagesConfig::default()
+    };
+    mount_dog(
+        &harness,
+        ResponseTemplate::new(200)
+            .insert_header("content-type", "image/png")
+            .set_body_bytes(png(9990, 100)),
+    )
+    .await;
+
+    doggo(&harness).await.unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
+
+#[tokio::test]
+async fn breedhd_sends_the_original_image_as_a_document() {
+    let harness = Harness::start().await;
+    let url = format!("{}/breeds/husky/n02110185_1469.jpg", harness.dog_ceo.uri());
+    Mock::given(method("GET"))
+        .and(path("/breed/husky/images/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": url, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/husky/n02110185_1469.jpg"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "image/png")
+                .set_body_bytes(png(9990, 100)),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/breedhd husky"),
+        Command::BreedHd("husky".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert!(harness.sent("sendPhoto").await.is_empty());
+    assert_eq!(harness.sent("sendDocument").await.len(), 1);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::{Harness, USER_ID};
+use dog_bot::{
+    breed::BreedQuery,
+    inline::{answer_inline, InlineQueryKind},
+};
+use serde_json::json;
+use std::{str::FromStr, time::Duration};
+use teloxide::types::InlineQuery;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+fn inline_query(query: &str) -> InlineQuery {
+    serde_json::from_value(json!({
+        "id": "1",
+        "from": { "id": USER_ID, "is_bot": false, "first_name": "Marc" },
+        "query": query,
+        "offset": ""
+    }))
+    .unwrap()
+}
+
+#[test]
+fn queries_are_dogs_or_prices() {
+    assert_eq!(
+        InlineQueryKind::parse(""),
+        Some(InlineQueryKind::Dogs(None))
+    );
+    assert_eq!(
+        InlineQueryKind::parse("Golden Retriever"),
+        Some(InlineQueryKind::Dogs(Some(
+            BreedQuery::from_str("golden retriever").unwrap()
+        )))
+    );
+    assert_eq!(
+        InlineQueryKind::parse("$BTC, eth btc"),
+        Some(InlineQueryKind::Prices(vec![
+            "btc".to_string(),
+            "eth".to_string()
+        ]))
+    );
+    assert_eq!(InlineQueryKind::parse("$"), None);
+    assert_eq!(InlineQueryKind::parse("not a breed!"), None);
+}
+
+#[tokio::test]
+async fn identical_inline_queries_share_their_results() {
+    let harness = Harness::start().await;
+    let image = format!("{}/breeds/husky/1.jpg", harness.dog_ceo.uri());
+    Mock::given(method("GET"))
+        .and(path("/breed/husky/images/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": image, "status": "success" }))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let state = harness.state();
+    let (first, second) = tokio::join!(
+        answer_inline(harness.bot(), inline_query("husky"), state.clone()),
+        answer_inline(harness.bot(), inline_query("Husky "), state.clone()),
+    );
+    first.unwrap();
+    second.unwrap();
+    answer_inline(harness.bot(), inline_query("husky"), state)
+        .await
+        .unwrap();
+
+    // A single round of requests for the three queries
+    let requests = harness.dog_ceo.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 6);
+
+    let answers = harness.sent("answerInlineQuery").await;
+    assert_eq!(answers.len(), 3);
+    assert_eq!(answers[0]["cache_time"], 10);
+    assert_eq!(answers[0]["is_personal"], false);
+    // The same image every time, offered once
+    let results = answers[0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["type"], "photo");
+    assert_eq!(results[0]["photo_url"], image);
+}
+
+#[tokio::test]
+async fn failures_are_not_cached() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let state = harness.state();
+    answer_inline(harness.bot(), inline_query(""), state.clone())
+        .await
+        .unwrap();
+
+    let answers = harness.sent("answerInlineQuery").await;
+    assert_eq!(answers.len(), 1);
+    assert_eq!(answers[0]["cache_time"], 0);
+    assert_eq!(answers[0]["results"], json!([]));
+}
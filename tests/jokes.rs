@@ -0,0 +1,120 @@
+mod common;
+
+use common::{Harness, USER_ID};
+use dog_bot::commands::{answer, answer_callback, Command, PUNCHLINE};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+fn two_part_joke() -> serde_json::Value {
+    json!({
+        "error": false,
+        "category": "Programming",
+        "type": "twopart",
+        "setup": "Why do programmers prefer dark mode?",
+        "delivery": "Because light attracts bugs.",
+        "id": 42,
+        "safe": true,
+        "lang": "en"
+    })
+}
+
+#[tokio::test]
+async fn single_jokes_are_sent_at_once() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/joke/pun"))
+        .and(query_param("safe-mode", ""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "error": false,
+            "category": "Pun",
+            "type": "single",
+            "joke": "I'm reading a book about anti-gravity. It's impossible to put down.",
+            "id": 7,
+            "safe": true,
+            "lang": "en"
+        })))
+        .mount(&harness.jokeapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/joke pun"),
+        Command::Joke("pun".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "I'm reading a book about anti-gravity. It's impossible to put down."
+    );
+    assert!(messages[0].get("reply_markup").is_none());
+}
+
+#[tokio::test]
+async fn two_part_jokes_reveal_the_punchline_on_tap() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/joke/Any"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(two_part_joke()))
+        .mount(&harness.jokeapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/joke"),
+        Command::Joke(String::new()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "Why do programmers prefer dark mode?");
+    let data = format!("{}:{}:42", PUNCHLINE, USER_ID);
+    assert_eq!(
+        messages[0]["reply_markup"]["inline_keyboard"][0][0]["callback_data"],
+        data.as_str()
+    );
+
+    answer_callback(harness.bot(), common::callback(&data), harness.state())
+        .await
+        .unwrap();
+
+    let edits = harness.sent("editMessageText").await;
+    assert_eq!(
+        edits[0]["text"],
+        "Why do programmers prefer dark mode?\n\nBecause light attracts bugs."
+    );
+}
+
+#[tokio::test]
+async fn unknown_categories_are_rejected() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/joke knock-knock"),
+        Command::Joke("knock-knock".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Unknown category 'knock-knock', pick some of: programming, misc, pun, spooky, christmas, dark"
+    );
+    assert!(harness
+        .jokeapi
+        .received_requests()
+        .await
+        .unwrap()
+        .is_empty());
+}
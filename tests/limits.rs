@@ -0,0 +1,154 @@
+use dog_bot::{
+    commands::CommandsConfig,
+    limits::{CommandClass, Limiter},
+};
+use std::time::Duration;
+use teloxide::types::{ChatId, UserId};
+
+#[test]
+fn users_get_a_cooldown_once_over_their_rate() {
+    let limiter = Limiter::new(&CommandsConfig {
+        max_per_user: 2,
+        user_window_secs: 60,
+        ..CommandsConfig::default()
+    });
+
+    assert!(limiter.hit(UserId(1)).is_ok());
+    assert!(limiter.hit(UserId(1)).is_ok());
+    let cooldown = limiter.hit(UserId(1)).unwrap_err();
+    assert!(cooldown.first);
+    assert!(cooldown.remaining > Duration::from_secs(59));
+    assert!(cooldown.remaining <= Duration::from_secs(60));
+    // Told only once
+    assert!(!limiter.hit(UserId(1)).unwrap_err().first);
+
+    // Others aren't affected
+    assert!(limiter.hit(UserId(2)).is_ok());
+}
+
+#[test]
+fn the_window_slides() {
+    let limiter = Limiter::new(&CommandsConfig {
+        max_per_user: 1,
+        user_window_secs: 1,
+        ..CommandsConfig::default()
+    });
+
+    assert!(limiter.hit(UserId(1)).is_ok());
+    assert!(limiter.hit(UserId(1)).is_err());
+    std::thread::sleep(Duration::from_millis(1100));
+    assert!(limiter.hit(UserId(1)).is_ok());
+    // A new cooldown is told about again
+    assert!(limiter.hit(UserId(1)).unwrap_err().first);
+}
+
+#[test]
+fn no_limit_when_zero() {
+    let limiter = Limiter::new(&CommandsConfig {
+        max_per_user: 0,
+        ..CommandsConfig::default()
+    });
+    for _ in 0..100 {
+        assert!(limiter.hit(UserId(1)).is_ok());
+    }
+}
+
+#[test]
+fn each_class_has_its_own_cooldown_in_each_chat() {
+    let limiter = Limiter::new(&CommandsConfig::default());
+    let cooldown = Duration::from_secs(60);
+
+    assert!(limiter
+        .hit_command(
+            ChatId(1),
+            UserId(1),
+            Some((CommandClass::Expensive, cooldown))
+        )
+        .is_ok());
+    let turned_down = limiter
+        .hit_command(
+            ChatId(1),
+            UserId(1),
+            Some((CommandClass::Expensive, cooldown)),
+        )
+        .unwrap_err();
+    assert!(turned_down.first);
+    assert!(turned_down.remaining <= cooldown);
+    assert!(
+        !limiter
+            .hit_command(
+                ChatId(1),
+                UserId(1),
+                Some((CommandClass::Expensive, cooldown))
+            )
+            .unwrap_err()
+            .first
+    );
+
+    // Other classes, chats and users aren't affected
+    assert!(limiter
+        .hit_command(ChatId(1), UserId(1), Some((CommandClass::Cheap, cooldown)))
+        .is_ok());
+    assert!(limiter
+        .hit_command(
+            ChatId(2),
+            UserId(1),
+            Some((CommandClass::Expensive, cooldown))
+        )
+        .is_ok());
+    assert!(limiter
+        .hit_command(
+            ChatId(1),
+            UserId(2),
+            Some((CommandClass::Expensive, cooldown))
+        )
+        .is_ok());
+
+    // No cooldown when 0
+    for _ in 0..10 {
+        assert!(limiter
+            .hit_command(
+                ChatId(3),
+                UserId(1),
+                Some((CommandClass::Cheap, Duration::ZERO))
+            )
+            .is_ok());
+    }
+}
+
+#[test]
+fn commands_turned_down_by_their_class_keep_the_slots_of_the_user() {
+    let limiter = Limiter::new(&CommandsConfig {
+        max_per_user: 2,
+        user_window_secs: 60,
+        ..CommandsConfig::default()
+    });
+    let expensive = Some((CommandClass::Expensive, Duration::from_secs(60)));
+
+    assert!(limiter.hit_command(ChatId(1), UserId(1), expensive).is_ok());
+    for _ in 0..5 {
+        assert!(limiter
+            .hit_command(ChatId(1), UserId(1), expensive)
+            .is_err());
+    }
+    // Only the first one counted
+    assert!(limiter.hit_command(ChatId(1), UserId(1), None).is_ok());
+    assert!(limiter.hit_command(ChatId(1), UserId(1), None).is_err());
+}
+
+#[test]
+fn commands_turned_down_for_the_user_do_not_start_a_class_cooldown() {
+    let limiter = Limiter::new(&CommandsConfig {
+        max_per_user: 1,
+        user_window_secs: 1,
+        ..CommandsConfig::default()
+    });
+    let expensive = Some((CommandClass::Expensive, Duration::from_secs(60)));
+
+    assert!(limiter.hit(UserId(1)).is_ok());
+    assert!(limiter
+        .hit_command(ChatId(1), UserId(1), expensive)
+        .is_err());
+    std::thread::sleep(Duration::from_millis(1100));
+    assert!(limiter.hit_command(ChatId(1), UserId(1), expensive).is_ok());
+}
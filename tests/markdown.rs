@@ -0,0 +1,21 @@
+use dog_bot::markdown::EscapedText;
+use insta::assert_snapshot;
+
+#[test]
+fn user_text_is_escaped() {
+    assert_snapshot!(EscapedText::escape("a_b*c.d!"), @r"a\_b\*c\.d\!");
+    assert_snapshot!(EscapedText::escape("[golden](retriever) #1 - $2.5"), @r"\[golden\]\(retriever\) \#1 \- $2\.5");
+}
+
+#[test]
+fn markup_wraps_the_escaped_text() {
+    let quote = EscapedText::escape("Be kind.").italic()
+        + EscapedText::markup("\n— ")
+        + EscapedText::escape("Mr. Dog").bold();
+    assert_snapshot!(quote, @r###"
+    _Be kind\._
+    — *Mr\. Dog*
+    "###);
+
+    assert_snapshot!(EscapedText::escape("p4ss-w0rd!").spoiler(), @r"||p4ss\-w0rd\!||");
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    meme,
+};
+use image::{ImageOutputFormat, Rgb, RgbImage};
+use serde_json::json;
+use std::io::Cursor;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut png = Vec::new();
+    RgbImage::from_pixel(width, height, Rgb([120, 90, 60]))
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .unwrap();
+    png
+}
+
+#[test]
+fn captions_are_split_on_the_bar() {
+    assert_eq!(
+        meme::captions(" such dog |very wow "),
+        ("SUCH DOG".to_string(), "VERY WOW".to_string())
+    );
+    assert_eq!(
+        meme::captions("only the top"),
+        ("ONLY THE TOP".to_string(), String::new())
+    );
+    assert_eq!(
+        meme::captions("| only the bottom"),
+        (String::new(), "ONLY THE BOTTOM".to_string())
+    );
+}
+
+#[test]
+fn captions_are_drawn_in_white_over_the_image() {
+    let jpeg = meme::render(&png(400, 300), "SUCH DOG", "VERY WOW").unwrap();
+
+    let image = image::load_from_memory(&jpeg).unwrap().to_rgb8();
+    assert_eq!(image.dimensions(), (400, 300));
+    let white = |rows: std::ops::Range<u32>| {
+        rows.flat_map(|y| (0..400).map(move |x| (x, y)))
+            .filter(|(x, y)| image.get_pixel(*x, *y).0.iter().all(|c| *c > 200))
+            .count()
+    };
+    assert!(white(0..60) > 100);
+    assert!(white(240..300) > 100);
+    // The middle is left alone
+    assert_eq!(white(120..180), 0);
+}
+
+#[tokio::test]
+async fn memes_are_made_of_random_dogs() {
+    let harness = Harness::start().await;
+    let image_url = format!("{}/images/husky.png", harness.dog_ceo.uri());
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": image_url,
+            "status": "success"
+        })))
+        .mount(&harness.dog_ceo)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/images/husky.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(png(200, 150)))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/meme such dog | very wow"),
+        Command::Meme("such dog | very wow".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(harness.sent("sendPhoto").await.len(), 1);
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
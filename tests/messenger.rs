@@ -0,0 +1,95 @@
+mod common;
+
+use async_trait::async_trait;
+use common::Harness;
+use dog_bot::{
+    error::CommandError,
+    messenger::{self, Chat, Portable},
+};
+use reqwest::Url;
+use serde_json::json;
+use std::sync::Mutex;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+/// Keeps what the commands send, as another platform would.
+#[derive(Default)]
+struct FakeChat {
+    sent: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl Chat for FakeChat {
+    fn platform(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn send_text(&self, text: &str) -> Result<(), CommandError> {
+        self.sent.lock().unwrap().push(text.to_string());
+        Ok(())
+    }
+
+    async fn send_photo(&self, url: &Url, _caption: Option<&str>) -> Result<(), CommandError> {
+        self.sent.lock().unwrap().push(url.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn commands_are_parsed_without_their_prefix() {
+    assert_eq!(Portable::parse("DOGGO", ""), Some(Portable::Doggo));
+    assert_eq!(
+        Portable::parse("breed", " husky "),
+        Some(Portable::Breed("husky".to_string()))
+    );
+    assert_eq!(Portable::parse("forgetme", ""), None);
+}
+
+#[tokio::test]
+async fn dogs_are_sent_as_photos() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breed/husky/images/random"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg",
+            "status": "success"
+        })))
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let chat = FakeChat::default();
+    messenger::run(
+        &harness.state(),
+        &chat,
+        Portable::Breed("husky".to_string()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        *chat.sent.lock().unwrap(),
+        ["https://images.dog.ceo/breeds/husky/n02110185_1469.jpg"]
+    );
+    assert!(harness.sent("sendPhoto").await.is_empty());
+}
+
+#[tokio::test]
+async fn text_answers_are_the_same_as_on_telegram() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "tether-eurt": { "usd": 1.07 } })),
+        )
+        .mount(&harness.coingecko)
+        .await;
+
+    let chat = FakeChat::default();
+    messenger::run(&harness.state(), &chat, Portable::Euro)
+        .await
+        .unwrap();
+
+    assert_eq!(*chat.sent.lock().unwrap(), ["$1.07"]);
+}
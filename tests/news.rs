@@ -0,0 +1,190 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    api::news::{NewsApi, NewsError, Rss},
+    commands::{answer, Command},
+    http::{self, HttpConfig},
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+fn feed(titles: &[&str]) -> String {
+    let items = titles
+        .iter()
+        .enumerate()
+        .map(|(position, title)| {
+            format!(
+                "<item><title>{}</title><link>https://news.example/{}</link>\
+                 <description>Story {}</description></item>",
+                title, position, position
+            )
+        })
+        .collect::<String>();
+    format!(
+        "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>News</title>\
+         <link>https://news.example</link><description>News</description>{}</channel></rss>",
+        items
+    )
+}
+
+async fn mount_feed(harness: &Harness, route: &str, titles: &[&str]) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_string(feed(titles)))
+        .mount(&harness.news)
+        .await;
+}
+
+#[tokio::test]
+async fn the_top_headlines_are_listed() {
+    let harness = Harness::start().await;
+    mount_feed(&harness, "/rss.xml", &["A", "B", "C", "D", "E", "F"]).await;
+
+    answer(
+        harness.bot(),
+        common::message("/news"),
+        Command::News(String::new()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Top headlines:\n\n\
+         1. A\nhttps://news.example/0\n\n\
+         2. B\nhttps://news.example/1\n\n\
+         3. C\nhttps://news.example/2\n\n\
+         4. D\nhttps://news.example/3\n\n\
+         5. E\nhttps://news.example/4\n"
+    );
+}
+
+#[tokio::test]
+async fn headlines_are_filtered_by_topic() {
+    let harness = Harness::start().await;
+    mount_feed(
+        &harness,
+        "/rss.xml",
+        &["Dog wins prize", "Elections", "Dogs and cats"],
+    )
+    .await;
+
+    answer(
+        harness.bot(),
+        common::message("/news dog"),
+        Command::News("dog".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Top headlines about dog:\n\n\
+         1. Dog wins prize\nhttps://news.example/0\n\n\
+         2. Dogs and cats\nhttps://news.example/2\n"
+    );
+}
+
+#[tokio::test]
+async fn chats_can_use_their_own_feed() {
+    let harness = Harness::start().await;
+    mount_feed(&harness, "/dogs.xml", &["Puppy news"]).await;
+    Mock::given(method("GET"))
+        .and(path("/not-a-feed"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+        .mount(&harness.news)
+        .await;
+
+    let source = format!("{}/dogs.xml", harness.news.uri());
+    for args in [
+        format!("source {}/not-a-feed", harness.news.uri()),
+        format!("source {}", source),
+        String::new(),
+    ] {
+        answer(
+            harness.bot(),
+            common::message(&format!("/news {}", args)),
+            Command::News(args),
+            harness.state(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        format!(
+            "I couldn't read an RSS feed at {}/not-a-feed",
+            harness.news.uri()
+        )
+    );
+    assert_eq!(
+        messages[1]["text"],
+        format!("The news of this chat now come from {}", source)
+    );
+    assert_eq!(
+        messages[2]["text"],
+        "Top headlines:\n\n1. Puppy news\nhttps://news.example/0\n"
+    );
+    let settings = harness.storage.chat_settings(CHAT_ID).await.unwrap();
+    assert_eq!(settings.unwrap().news_source, Some(source));
+}
+
+#[tokio::test]
+async fn only_admins_change_the_news_source_of_a_group() {
+    let harness = Harness::start().await;
+    harness.member_status("member").await;
+    mount_feed(&harness, "/dogs.xml", &["Puppy news"]).await;
+
+    let args = format!("source {}/dogs.xml", harness.news.uri());
+    answer(
+        harness.bot(),
+        common::group_message(&format!("/news {}", args)),
+        Command::News(args),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Only the admins of the chat can change its news source"
+    );
+    let settings = harness.storage.chat_settings(CHAT_ID).await.unwrap();
+    assert!(settings.is_none());
+}
+
+#[tokio::test]
+async fn feeds_in_the_bot_network_are_not_read() {
+    let harness = Harness::start().await;
+    mount_feed(&harness, "/dogs.xml", &["Puppy news"]).await;
+    let rss = Rss::with_default_feed(
+        http::client(&HttpConfig::default(), harness.health.clone()),
+        format!("{}/rss.xml", harness.news.uri()),
+    );
+
+    for feed in [
+        format!("{}/dogs.xml", harness.news.uri()),
+        "http://localhost/dogs.xml".to_string(),
+        "http://10.0.0.1/dogs.xml".to_string(),
+        "http://169.254.169.254/latest/meta-data".to_string(),
+        "http://[::1]/dogs.xml".to_string(),
+    ] {
+        let headlines = rss.headlines(Some(&feed)).await;
+        assert!(
+            matches!(headlines, Err(NewsError::Private(_))),
+            "{} was read",
+            feed
+        );
+    }
+    assert!(harness.news.received_requests().await.unwrap().is_empty());
+}
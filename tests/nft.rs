@@ -0,0 +1,77 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    money,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[test]
+fn prices_are_formatted() {
+    assert_eq!(money::usd(35120.5), "$35,120.50");
+    assert_eq!(money::usd(1234567.891), "$1,234,567.89");
+    assert_eq!(money::usd(0.01234567), "$0.012346");
+    assert_eq!(money::amount(10.5, "ETH"), "10.5 ETH");
+    assert_eq!(money::change(-1.254), "-1.25%");
+}
+
+#[tokio::test]
+async fn floor_is_shown_in_eth_and_usd_with_the_change() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/nfts/pudgy-penguins"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "pudgy-penguins",
+            "name": "Pudgy Penguins",
+            "native_currency": "ethereum",
+            "native_currency_symbol": "eth",
+            "floor_price": { "native_currency": 10.52, "usd": 35120.5 },
+            "floor_price_in_usd_24h_percentage_change": -1.254
+        })))
+        .mount(&harness.coingecko)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/floor Pudgy Penguins"),
+        Command::Floor("Pudgy Penguins".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Pudgy Penguins floor: 10.52 ETH ($35,120.50)\n24h: -1.25%"
+    );
+}
+
+#[tokio::test]
+async fn unknown_collections_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/nfts/nope"))
+        .respond_with(
+            ResponseTemplate::new(404).set_body_json(json!({ "error": "coin not found" })),
+        )
+        .mount(&harness.coingecko)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/floor nope"),
+        Command::Floor("nope".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I don't know the collection 'nope'");
+}
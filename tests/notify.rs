@@ -0,0 +1,99 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    notify::NotifyRule,
+    server::{self, ServerConfig},
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+const TOKEN: &str = "ci-secret";
+
+/// Serve `/notify` for a token that may only message [`CHAT_ID`], returning the URL of the endpoint.
+async fn serve(harness: &Harness) -> String {
+    let config = ServerConfig {
+        notify: vec![NotifyRule {
+            token: TOKEN.to_string(),
+            chats: vec![CHAT_ID],
+        }],
+        ..ServerConfig::default()
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = server::router(harness.state(), &config);
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    format!("http://{}/notify", addr)
+}
+
+async fn post(url: &str, token: &str, body: Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn texts_go_to_the_default_chat_of_the_token() {
+    let harness = Harness::start().await;
+    let url = serve(&harness).await;
+
+    let res = post(
+        &url,
+        TOKEN,
+        json!({ "text": "*Build passed*", "parse_mode": "MarkdownV2" }),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.json::<Value>().await.unwrap(),
+        json!({ "chat_id": CHAT_ID, "message_id": 1 })
+    );
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["chat_id"], CHAT_ID);
+    assert_eq!(messages[0]["text"], "*Build passed*");
+    assert_eq!(messages[0]["parse_mode"], "MarkdownV2");
+}
+
+#[tokio::test]
+async fn photos_are_sent_with_the_text_as_caption() {
+    let harness = Harness::start().await;
+    let url = serve(&harness).await;
+
+    let res = post(
+        &url,
+        TOKEN,
+        json!({
+            "chat_id": CHAT_ID,
+            "text": "Someone is at the door",
+            "photo": "https://example.com/door.jpg"
+        }),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos[0]["photo"], "https://example.com/door.jpg");
+    assert_eq!(photos[0]["caption"], "Someone is at the door");
+}
+
+#[tokio::test]
+async fn tokens_only_message_their_chats() {
+    let harness = Harness::start().await;
+    let url = serve(&harness).await;
+
+    let res = post(&url, "wrong", json!({ "text": "hi" })).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = post(&url, TOKEN, json!({ "chat_id": 1, "text": "hi" })).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = post(&url, TOKEN, json!({ "parse_mode": "HTML" })).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = post(&url, TOKEN, json!({ "text": "hi", "parse_mode": "rtf" })).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
@@ -0,0 +1,67 @@
+use dog_bot::{
+    offsets::UpdateOffsets,
+    storage::{memory::MemoryStorage, Storage},
+};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn updates_are_handled_once() {
+    let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+    let offsets = UpdateOffsets::load(storage.clone(), "main").await;
+
+    assert!(offsets.claim(1));
+    // The chats are handled concurrently, their updates may be claimed out of order
+    assert!(offsets.claim(3));
+    assert!(offsets.claim(2));
+    assert!(!offsets.claim(2));
+    assert!(!offsets.claim(3));
+    // Saved every few seconds, not for every update
+    assert_eq!(storage.update_offset("main").await.unwrap(), None);
+
+    offsets.save().await;
+    assert_eq!(storage.update_offset("main").await.unwrap(), Some(3));
+}
+
+#[tokio::test]
+async fn the_offset_waits_for_the_updates_claimed_out_of_order() {
+    let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+    storage.save_update_offset("main", 5).await.unwrap();
+    let offsets = UpdateOffsets::load(storage.clone(), "main").await;
+
+    assert!(offsets.claim(7));
+    offsets.save().await;
+    assert_eq!(storage.update_offset("main").await.unwrap(), Some(5));
+
+    assert!(offsets.claim(6));
+    offsets.save().await;
+    assert_eq!(storage.update_offset("main").await.unwrap(), Some(7));
+}
+
+#[tokio::test]
+async fn gaps_in_the_ids_are_given_up_on_at_the_next_save() {
+    let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+    storage.save_update_offset("main", 5).await.unwrap();
+    let offsets = UpdateOffsets::load(storage.clone(), "main").await;
+
+    assert!(offsets.claim(900));
+    offsets.save().await;
+    assert_eq!(storage.update_offset("main").await.unwrap(), Some(5));
+    offsets.save().await;
+    assert_eq!(storage.update_offset("main").await.unwrap(), Some(900));
+    assert!(!offsets.claim(900));
+}
+
+#[tokio::test]
+async fn a_restart_skips_the_handled_updates() {
+    let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+    storage.save_update_offset("main", 5).await.unwrap();
+
+    let offsets = UpdateOffsets::load(storage.clone(), "main").await;
+    assert!(!offsets.claim(4));
+    assert!(!offsets.claim(5));
+    assert!(offsets.claim(6));
+
+    // Each bot has its own updates
+    let other = UpdateOffsets::load(storage, "other").await;
+    assert!(other.claim(5));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn pokemon_are_sent_with_their_types_and_stats() {
+    let harness = Harness::start().await;
+    let stat = |name: &str, value: u32| json!({ "base_stat": value, "effort": 0, "stat": { "name": name } });
+    Mock::given(method("GET"))
+        .and(path("/pokemon/growlithe"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": 58,
+            "name": "growlithe",
+            "sprites": {
+                "front_default": "https://img.test/sprites/58.png",
+                "other": {
+                    "official-artwork": { "front_default": "https://img.test/artwork/58.png" }
+                }
+            },
+            "types": [{ "slot": 1, "type": { "name": "fire" } }],
+            "stats": [
+                stat("hp", 55),
+                stat("attack", 70),
+                stat("defense", 45),
+                stat("special-attack", 70),
+                stat("special-defense", 50),
+                stat("speed", 60)
+            ]
+        })))
+        .mount(&harness.pokeapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/pokemon Growlithe"),
+        Command::Pokemon("Growlithe".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos[0]["photo"], "https://img.test/artwork/58.png");
+    assert_eq!(
+        photos[0]["caption"],
+        "#58 Growlithe\nType: Fire\n\nHP: 55\nAttack: 70\nDefense: 45\nSp. Atk: 70\nSp. Def: 50\nSpeed: 60\nTotal: 350"
+    );
+}
+
+#[tokio::test]
+async fn unknown_pokemon_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/pokemon/doggo"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+        .mount(&harness.pokeapi)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/pokemon doggo"),
+        Command::Pokemon("doggo".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I don't know the Pokémon 'doggo'");
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    prefetch,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn the_buffer_is_refilled_up_to_its_capacity() {
+    let mut harness = Harness::start().await;
+    harness.prefetched_dogs = 3;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message": "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg",
+            "status": "success"
+        })))
+        .expect(2)
+        .mount(&harness.dog_ceo)
+        .await;
+    let state = harness.state();
+    state
+        .dogs
+        .push("https://images.dog.ceo/breeds/pug/1.jpg".to_string());
+
+    prefetch::refill(state.clone()).await.unwrap();
+
+    assert_eq!(state.dogs.len(), 3);
+    assert_eq!(
+        state.dogs.pop().as_deref(),
+        Some("https://images.dog.ceo/breeds/pug/1.jpg")
+    );
+    // Already full, nothing is fetched
+    state
+        .dogs
+        .push("https://images.dog.ceo/breeds/pug/2.jpg".to_string());
+    prefetch::refill(state.clone()).await.unwrap();
+}
+
+#[tokio::test]
+async fn doggo_sends_a_prefetched_dog_first() {
+    let mut harness = Harness::start().await;
+    harness.prefetched_dogs = 1;
+    let state = harness.state();
+    state
+        .dogs
+        .push("https://images.dog.ceo/breeds/pug/1.jpg".to_string());
+
+    answer(
+        harness.bot(),
+        common::message("/doggo"),
+        Command::Doggo,
+        state.clone(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(
+        photos[0]["photo"],
+        "https://images.dog.ceo/breeds/pug/1.jpg"
+    );
+    assert!(state.dogs.is_empty());
+    assert!(harness
+        .dog_ceo
+        .received_requests()
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use dog_bot::{
+    api::price::{Binance, PriceApi},
+    http::{self, HttpConfig},
+};
+use serde_json::json;
+use std::{sync::Arc, time::Duration};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Knows the price of any symbol, its length, and fails for `err`.
+struct FixedPrices;
+
+#[async_trait]
+impl PriceApi for FixedPrices {
+    async fn usd_price(&self, symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error> {
+        match symbol {
+            "err" => Err(reqwest_middleware::Error::Middleware(anyhow::anyhow!(
+                "upstream down"
+            ))),
+            "unknown" => Ok(None),
+            _ => Ok(Some(symbol.len() as f64)),
+        }
+    }
+}
+
+fn symbols(symbols: &[&str]) -> Vec<String> {
+    symbols.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+#[tokio::test]
+async fn prices_are_fetched_concurrently() {
+    let binance = MockServer::start().await;
+    // Answers long after the test is over, unless the requests are all sent at once they never all arrive
+    Mock::given(method("GET"))
+        .and(path("/ticker/price"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "symbol": "BTCUSDT", "price": "1" }))
+                .set_delay(Duration::from_secs(60)),
+        )
+        .mount(&binance)
+        .await;
+    let api = Arc::new(Binance::with_base_url(
+        http::client(&HttpConfig::default(), Arc::default()),
+        binance.uri(),
+    ));
+
+    let prices = tokio::spawn({
+        let api = api.clone();
+        async move {
+            api.usd_prices(&symbols(&["btc", "eth", "doge", "sol"]))
+                .await
+        }
+    });
+    let requests = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let requests = binance.received_requests().await.unwrap();
+            if requests.len() == 4 {
+                return requests;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the prices were fetched one after the other");
+    prices.abort();
+
+    let mut pairs = requests
+        .iter()
+        .filter_map(|request| request.url.query())
+        .collect::<Vec<_>>();
+    pairs.sort_unstable();
+    assert_eq!(
+        pairs,
+        [
+            "symbol=BTCUSDT",
+            "symbol=DOGEUSDT",
+            "symbol=ETHUSDT",
+            "symbol=SOLUSDT"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn the_prices_of_several_symbols_are_collected() {
+    let prices = FixedPrices
+        .usd_prices(&symbols(&["btc", "eth", "doge", "unknown"]))
+        .await
+        .unwrap();
+
+    assert_eq!(prices.len(), 3);
+    assert_eq!(prices["doge"], 4.0);
+}
+
+#[tokio::test]
+async fn a_failing_price_leaves_the_others_alone() {
+    let prices = FixedPrices
+        .usd_prices(&symbols(&["BTC", "err"]))
+        .await
+        .unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices["btc"], 3.0);
+
+    assert!(FixedPrices.usd_prices(&symbols(&["err"])).await.is_err());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    privacy::{self, PURPOSES},
+    storage::schema::{self, Table},
+};
+
+#[test]
+fn the_schema_is_read_from_the_migrations() {
+    let tables = schema::parse([
+        "-- The first version\nCREATE TABLE IF NOT EXISTS users (\n    id INTEGER PRIMARY KEY,\n    name TEXT,\n    UNIQUE (name)\n);",
+        "ALTER TABLE users ADD COLUMN city TEXT;\nCREATE TABLE old (id INTEGER);\nDROP TABLE old;",
+        "ALTER TABLE users DROP COLUMN name",
+    ]);
+    assert_eq!(
+        tables,
+        [Table {
+            name: "users".to_string(),
+            columns: vec!["id".to_string(), "city".to_string()],
+        }]
+    );
+
+    let tables = schema::tables();
+    let preferences = tables
+        .iter()
+        .find(|table| table.name == "preferences")
+        .unwrap();
+    assert!(preferences.columns.contains(&"city".to_string()));
+}
+
+#[test]
+fn every_table_has_a_purpose() {
+    for table in schema::tables() {
+        assert!(
+            PURPOSES.iter().any(|(name, ..)| *name == table.name),
+            "tell /privacy why {} is stored",
+            table.name
+        );
+    }
+}
+
+#[test]
+fn tables_without_a_purpose_are_still_disclosed() {
+    let text = privacy::disclosure(&[
+        Table {
+            name: "favourites".to_string(),
+            columns: vec!["user_id".to_string(), "breed".to_string()],
+        },
+        Table {
+            name: "secrets".to_string(),
+            columns: vec!["user_id".to_string()],
+        },
+    ]);
+    assert!(text.contains("About you:\n• favourites (user_id, breed): your favourite breeds"));
+    assert!(text.contains("Other:\n• secrets (user_id): no description yet"));
+    assert!(
+        text.ends_with("Send /mydata to get what I store about you, or /forgetme to delete it.")
+    );
+}
+
+#[tokio::test]
+async fn privacy_lists_the_stored_data() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/privacy"),
+        Command::Privacy,
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    let text = messages[0]["text"].as_str().unwrap();
+    assert!(text.contains("• reminders (id, user_id, chat_id, at, text)"));
+    assert!(!text.contains("Other:"));
+}
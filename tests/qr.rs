@@ -0,0 +1,109 @@
+mod common;
+
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::{
+    commands::{answer, Command},
+    qr,
+};
+use serde_json::json;
+use teloxide::types::Message;
+use wiremock::{
+    matchers::{method, path, path_regex},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn texts_are_sent_as_qr_codes() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/qr https://dog.ceo"),
+        Command::Qr("https://dog.ceo".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert!(harness.sent("sendMessage").await.is_empty());
+}
+
+#[tokio::test]
+async fn qr_codes_in_replied_photos_are_read() {
+    let harness = Harness::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"^/botTOKEN/(?i:getFile)$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "result": {
+                "file_id": "photo",
+                "file_unique_id": "photo",
+                "file_size": 1,
+                "file_path": "photos/qr.png"
+            }
+        })))
+        .mount(&harness.telegram)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/file/botTOKEN/photos/qr.png"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_bytes(qr::encode("https://dog.ceo").unwrap()),
+        )
+        .mount(&harness.telegram)
+        .await;
+
+    let user = json!({ "id": USER_ID, "is_bot": false, "first_name": "Marc" });
+    let chat = json!({ "id": CHAT_ID, "type": "private", "first_name": "Marc" });
+    let message: Message = serde_json::from_value(json!({
+        "message_id": 2,
+        "date": 0,
+        "chat": chat,
+        "from": user,
+        "text": "/qr",
+        "reply_to_message": {
+            "message_id": 1,
+            "date": 0,
+            "chat": chat,
+            "from": user,
+            "photo": [{
+                "file_id": "photo",
+                "file_unique_id": "photo",
+                "width": 512,
+                "height": 512
+            }]
+        }
+    }))
+    .unwrap();
+
+    answer(
+        harness.bot(),
+        message,
+        Command::Qr(String::new()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "https://dog.ceo");
+}
+
+#[tokio::test]
+async fn texts_too_long_for_a_qr_code_are_refused() {
+    let harness = Harness::start().await;
+    let text = "woof ".repeat(1000);
+
+    answer(
+        harness.bot(),
+        common::message(&format!("/qr {}", text)),
+        Command::Qr(text),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "That's too long for a QR code");
+}
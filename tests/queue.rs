@@ -0,0 +1,61 @@
+use dog_bot::queue::{QueueConfig, SendQueue};
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+use teloxide::RequestError;
+
+#[tokio::test]
+async fn messages_are_paced() {
+    let queue = SendQueue::new(&QueueConfig {
+        messages_per_second: 10,
+        ..QueueConfig::default()
+    });
+
+    let start = Instant::now();
+    let sends = (0..5).map(|_| queue.send(|| async { Ok::<_, RequestError>(()) }));
+    futures::future::try_join_all(sends).await.unwrap();
+
+    // The first one goes right away, then one every 100ms
+    assert!(start.elapsed() >= Duration::from_millis(400));
+}
+
+#[tokio::test]
+async fn messages_are_sent_again_after_retry_after() {
+    let queue = SendQueue::new(&QueueConfig::default());
+    let attempts = AtomicU32::new(0);
+
+    let start = Instant::now();
+    let sent = queue
+        .send(|| async {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(RequestError::RetryAfter(Duration::from_secs(1)))
+            } else {
+                Ok("sent")
+            }
+        })
+        .await;
+
+    assert_eq!(sent.unwrap(), "sent");
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn the_queue_gives_up_after_max_retries() {
+    let queue = SendQueue::new(&QueueConfig {
+        messages_per_second: 0,
+        max_retries: 1,
+    });
+    let attempts = AtomicU32::new(0);
+
+    let sent: Result<(), _> = queue
+        .send(|| async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(RequestError::RetryAfter(Duration::ZERO))
+        })
+        .await;
+
+    assert!(matches!(sent, Err(RequestError::RetryAfter(_))));
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+}
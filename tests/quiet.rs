@@ -0,0 +1,43 @@
+use chrono::{NaiveTime, TimeZone, Utc};
+use chrono_tz::{Europe::Madrid, Tz};
+use dog_bot::quiet::QuietHours;
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+#[test]
+fn quiet_hours_can_span_midnight() {
+    let night: QuietHours = "23:00-08:00".parse().unwrap();
+    assert!(night.contains(time(23, 30)));
+    assert!(night.contains(time(3, 0)));
+    assert!(!night.contains(time(8, 0)));
+    assert!(!night.contains(time(12, 0)));
+
+    let nap: QuietHours = "14:00-15:30".parse().unwrap();
+    assert!(nap.contains(time(14, 45)));
+    assert!(!nap.contains(time(16, 0)));
+
+    assert!("23:00".parse::<QuietHours>().is_err());
+    assert!("10:00-10:00".parse::<QuietHours>().is_err());
+    assert_eq!(night.to_string(), "23:00-08:00");
+}
+
+#[test]
+fn quiet_hours_end_in_the_chat_timezone() {
+    let night: QuietHours = "23:00-08:00".parse().unwrap();
+
+    let midnight = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        night.ends_after(midnight, Tz::UTC),
+        Some(Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap())
+    );
+    // 08:00 in Madrid is 06:00 UTC in summer
+    assert_eq!(
+        night.ends_after(midnight, Madrid),
+        Some(Utc.with_ymd_and_hms(2024, 5, 1, 6, 0, 0).unwrap())
+    );
+
+    let noon = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    assert_eq!(night.ends_after(noon, Tz::UTC), None);
+}
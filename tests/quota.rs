@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use dog_bot::{
+    api::price::{CachedPriceApi, PriceApi},
+    cache::memory::MemoryCache,
+    circuit::CircuitOpen,
+    error::CommandError,
+    health::Health,
+    http::{self, HttpConfig},
+    quota::{Budget, QuotaExceeded, Quotas, Window},
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 1, h, m, s).unwrap()
+}
+
+fn quotas(per_minute: Option<u64>, per_day: Option<u64>) -> Quotas {
+    Quotas::new(HashMap::from([(
+        "dog.ceo".to_string(),
+        Budget {
+            per_minute,
+            per_day,
+        },
+    )]))
+}
+
+#[test]
+fn calls_are_counted_per_minute() {
+    let quotas = quotas(Some(2), None);
+
+    assert!(quotas.acquire("dog.ceo", at(9, 0, 10)).is_ok());
+    assert!(quotas.acquire("dog.ceo", at(9, 0, 20)).is_ok());
+    let exceeded = quotas.acquire("dog.ceo", at(9, 0, 45)).unwrap_err();
+    assert_eq!(exceeded.window, Window::Minute);
+    assert_eq!(exceeded.retry_after, Duration::from_secs(15));
+
+    // Other hosts have no budget
+    for _ in 0..10 {
+        assert!(quotas.acquire("api.coingecko.com", at(9, 0, 45)).is_ok());
+    }
+    assert!(quotas.acquire("dog.ceo", at(9, 1, 0)).is_ok());
+}
+
+#[test]
+fn calls_are_counted_per_day() {
+    let quotas = quotas(Some(10), Some(3));
+
+    for minute in 0..3 {
+        assert!(quotas.acquire("dog.ceo", at(9, minute, 0)).is_ok());
+    }
+    let exceeded = quotas.acquire("dog.ceo", at(23, 59, 0)).unwrap_err();
+    assert_eq!(exceeded.window, Window::Day);
+    assert_eq!(exceeded.retry_after, Duration::from_secs(60));
+
+    assert!(quotas
+        .acquire("dog.ceo", at(23, 59, 0) + chrono::Duration::minutes(1))
+        .is_ok());
+}
+
+#[tokio::test]
+async fn calls_over_budget_do_not_reach_the_upstream() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = HttpConfig {
+        quotas: HashMap::from([(
+            "127.0.0.1".to_string(),
+            Budget {
+                per_minute: None,
+                per_day: Some(1),
+            },
+        )]),
+        ..HttpConfig::default()
+    };
+    let client = http::client(&config, Arc::new(Health::default()));
+
+    assert!(client.get(server.uri()).send().await.is_ok());
+    let error = client.get(server.uri()).send().await.unwrap_err();
+    match &error {
+        reqwest_middleware::Error::Middleware(e) => assert!(e.is::<QuotaExceeded>()),
+        e => panic!("expected the quota to be exceeded, got {}", e),
+    }
+
+    let error = CommandError::Upstream {
+        upstream: "dog.ceo",
+        error,
+    };
+    assert_eq!(
+        error.user_message(),
+        Some("This service was asked too much lately, please try again later.")
+    );
+}
+
+#[tokio::test]
+async fn calls_turned_down_by_the_circuit_breaker_spend_no_budget() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let config = HttpConfig {
+        max_retries: 0,
+        circuit_breaker_threshold: 1,
+        circuit_breaker_cooldown_secs: 1,
+        quotas: HashMap::from([(
+            "127.0.0.1".to_string(),
+            Budget {
+                per_minute: None,
+                per_day: Some(2),
+            },
+        )]),
+        ..HttpConfig::default()
+    };
+    let client = http::client(&config, Arc::new(Health::default()));
+
+    assert_eq!(client.get(server.uri()).send().await.unwrap().status(), 503);
+    for _ in 0..3 {
+        match client.get(server.uri()).send().await.unwrap_err() {
+            reqwest_middleware::Error::Middleware(e) => assert!(e.is::<CircuitOpen>()),
+            e => panic!("expected an open circuit, got {}", e),
+        }
+    }
+
+    // The second unit of the budget is still there for the try after the cooldown
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(client.get(server.uri()).send().await.unwrap().status(), 503);
+}
+
+/// Answers once, then is over its quota.
+#[derive(Default)]
+struct RunsOut {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl PriceApi for RunsOut {
+    async fn usd_price(&self, _symbol: &str) -> Result<Option<f64>, reqwest_middleware::Error> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            Ok(Some(2.5))
+        } else {
+            Err(reqwest_middleware::Error::Middleware(
+                QuotaExceeded {
+                    upstream: "api.coingecko.com".to_string(),
+                    window: Window::Day,
+                    retry_after: Duration::from_secs(60),
+                }
+                .into(),
+            ))
+        }
+    }
+}
+
+#[tokio::test]
+async fn the_last_price_is_served_over_budget() {
+    let api = CachedPriceApi::new(
+        Arc::new(RunsOut::default()),
+        Arc::new(MemoryCache::default()),
+        Duration::ZERO,
+    );
+
+    assert_eq!(api.usd_price("btc").await.unwrap(), Some(2.5));
+    // Expired, but the upstream can't be asked again
+    assert_eq!(api.usd_price("BTC").await.unwrap(), Some(2.5));
+    assert_eq!(
+        api.usd_prices(&["btc".to_string()]).await.unwrap()["btc"],
+        2.5
+    );
+    // Nothing to serve for a new symbol
+    assert!(api.usd_price("eth").await.is_err());
+}
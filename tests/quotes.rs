@@ -0,0 +1,63 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn quotes_are_attributed_in_markdown() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/quotes/random"))
+        .and(query_param("author", "steve jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "_id": "1",
+            "content": "Stay hungry, stay foolish.",
+            "author": "Steve Jobs",
+            "authorSlug": "steve-jobs"
+        }])))
+        .mount(&harness.quotable)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/quote steve jobs"),
+        Command::Quote("steve jobs".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "_Stay hungry, stay foolish\\._\n— *Steve Jobs*"
+    );
+    assert_eq!(messages[0]["parse_mode"], "MarkdownV2");
+}
+
+#[tokio::test]
+async fn unknown_authors_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/quotes/random"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&harness.quotable)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/quote nobody"),
+        Command::Quote("nobody".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I don't know any quote of 'nobody'");
+}
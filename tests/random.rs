@@ -0,0 +1,148 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    random::{self, Charset, Dice, PasswordOptions},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn dice_are_parsed_in_the_usual_notation() {
+    assert_eq!(
+        "2d20".parse(),
+        Ok(Dice {
+            count: 2,
+            sides: 20
+        })
+    );
+    assert_eq!(" D6 ".parse(), Ok(Dice { count: 1, sides: 6 }));
+    assert_eq!(Dice { count: 3, sides: 8 }.to_string(), "3d8");
+
+    assert_eq!(
+        "20".parse::<Dice>(),
+        Err("'20' isn't a roll like 2d20".to_string())
+    );
+    assert!("2dx".parse::<Dice>().is_err());
+    assert!("-1d6".parse::<Dice>().is_err());
+    assert_eq!(
+        "0d6".parse::<Dice>(),
+        Err("I can roll between 1 and 100 dice".to_string())
+    );
+    assert_eq!(
+        "1d1".parse::<Dice>(),
+        Err("Dice have between 2 and 1000 sides".to_string())
+    );
+}
+
+#[test]
+fn rolls_stay_within_the_sides() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let rolls = Dice {
+        count: 100,
+        sides: 6,
+    }
+    .roll(&mut rng);
+
+    assert_eq!(rolls.len(), 100);
+    assert!(rolls.iter().all(|roll| (1..=6).contains(roll)));
+    // A hundred rolls show every side
+    assert!((1..=6).all(|side| rolls.contains(&side)));
+}
+
+#[test]
+fn options_are_separated_by_bars() {
+    assert_eq!(
+        random::options("pizza | sushi||tacos al pastor |"),
+        ["pizza", "sushi", "tacos al pastor"]
+    );
+    assert!(random::options("  ").is_empty());
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let options = random::options("pizza|sushi");
+    let picked = random::pick(&options, &mut rng).unwrap();
+    assert!(options.contains(&picked));
+    assert_eq!(random::pick(&[], &mut rng), None);
+    assert!(["Heads", "Tails"].contains(&random::flip(&mut rng)));
+}
+
+#[test]
+fn password_options_are_a_length_and_a_charset() {
+    assert_eq!("".parse(), Ok(PasswordOptions::default()));
+    assert_eq!(
+        "6 digits".parse(),
+        Ok(PasswordOptions {
+            length: 6,
+            charset: Charset::Digits
+        })
+    );
+    assert_eq!(
+        "ALNUM 32".parse(),
+        Ok(PasswordOptions {
+            length: 32,
+            charset: Charset::Alphanumeric
+        })
+    );
+    assert_eq!(
+        "1000".parse::<PasswordOptions>(),
+        Err("Passwords have between 4 and 128 characters".to_string())
+    );
+    assert!("emoji".parse::<PasswordOptions>().is_err());
+}
+
+#[test]
+fn passwords_have_every_kind_of_character() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..100 {
+        let password = random::password(&PasswordOptions::default(), &mut rng);
+        assert_eq!(password.len(), 20);
+        assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+        assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(password.chars().any(|c| c.is_ascii_digit()));
+        assert!(password.chars().any(|c| !c.is_ascii_alphanumeric()));
+    }
+
+    let pin = random::password(
+        &PasswordOptions {
+            length: 6,
+            charset: Charset::Digits,
+        },
+        &mut rng,
+    );
+    assert!(pin.len() == 6 && pin.chars().all(|c| c.is_ascii_digit()));
+}
+
+#[tokio::test]
+async fn secrets_are_sent_as_spoilers() {
+    let harness = Harness::start().await;
+
+    for (text, command) in [
+        (
+            "/password 12 alnum",
+            Command::Password("12 alnum".to_string()),
+        ),
+        ("/uuid v7", Command::Uuid("v7".to_string())),
+    ] {
+        answer(
+            harness.bot(),
+            common::message(text),
+            command,
+            harness.state(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["parse_mode"], "MarkdownV2");
+    let password = messages[0]["text"].as_str().unwrap();
+    assert!(password.starts_with("🔑 ||") && password.ends_with("||"));
+    assert_eq!(password.chars().count(), "🔑 ||||".chars().count() + 12);
+
+    assert_eq!(messages[1]["parse_mode"], "MarkdownV2");
+    let uuid = messages[1]["text"].as_str().unwrap();
+    let uuid = uuid.trim_matches('|').replace('\\', "");
+    assert_eq!(uuid.len(), 36);
+    // The version is the first digit of the third group
+    assert_eq!(uuid.split('-').nth(2).unwrap().chars().next(), Some('7'));
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use chrono_tz::{Europe::Madrid, Tz};
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::{
+    commands::{answer, answer_callback, Command},
+    reminders,
+    storage::{Reminder, Subscription},
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{body_partial_json, method, path_regex},
+    Mock, ResponseTemplate,
+};
+
+const SUPERGROUP_ID: i64 = -1001000;
+
+#[test]
+fn reminder_times_are_parsed() {
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(
+        reminders::parse("in 20m walk the dog", now, Tz::UTC),
+        Some((now + Duration::minutes(20), "walk the dog".to_string()))
+    );
+    assert_eq!(
+        reminders::parse("in 2 hours vet", now, Tz::UTC),
+        Some((now + Duration::hours(2), "vet".to_string()))
+    );
+    assert_eq!(
+        reminders::parse("tomorrow 9:00 call", now, Tz::UTC),
+        Some((
+            Utc.with_ymd_and_hms(2024, 5, 2, 9, 0, 0).unwrap(),
+            "call".to_string()
+        ))
+    );
+    assert_eq!(
+        reminders::parse("2025-01-01 12:00 party", now, Tz::UTC),
+        Some((
+            Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap(),
+            "party".to_string()
+        ))
+    );
+    assert_eq!(
+        reminders::parse("10:00 coffee", now, Tz::UTC),
+        Some((
+            Utc.with_ymd_and_hms(2024, 5, 2, 10, 0, 0).unwrap(),
+            "coffee".to_string()
+        ))
+    );
+
+    // 09:00 in Madrid is 07:00 UTC in summer
+    assert_eq!(
+        reminders::parse("tomorrow 9:00 call", now, Madrid),
+        Some((
+            Utc.with_ymd_and_hms(2024, 5, 2, 7, 0, 0).unwrap(),
+            "call".to_string()
+        ))
+    );
+
+    // Past, without text or nonsense
+    assert_eq!(
+        reminders::parse("2020-01-01 12:00 party", now, Tz::UTC),
+        None
+    );
+    assert_eq!(reminders::parse("in 20m", now, Tz::UTC), None);
+    assert_eq!(reminders::parse("in 20 fortnights run", now, Tz::UTC), None);
+    assert_eq!(reminders::parse("someday walk", now, Tz::UTC), None);
+}
+
+#[tokio::test]
+async fn reminders_are_stored_listed_and_cancelled() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+
+    answer(
+        harness.bot(),
+        common::message("/remind in 20m walk the dog"),
+        Command::Remind("in 20m walk the dog".to_string()),
+        state.clone(),
+    )
+    .await
+    .unwrap();
+
+    let stored = harness
+        .storage
+        .reminders(Some(USER_ID as i64))
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].text, "walk the dog");
+
+    answer(
+        harness.bot(),
+        common::message("/reminders"),
+        Command::Reminders,
+        state.clone(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    let button = &messages[1]["reply_markup"]["inline_keyboard"][0][0];
+    assert_eq!(button["text"], "Cancel 1");
+
+    answer_callback(
+        harness.bot(),
+        common::callback(button["callback_data"].as_str().unwrap()),
+        state.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert!(harness.storage.reminders(None).await.unwrap().is_empty());
+    assert!(state.scheduler.jobs().is_empty());
+}
+
+#[tokio::test]
+async fn reminders_due_while_down_are_sent_on_start() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+    harness
+        .storage
+        .add_reminder(&Reminder {
+            id: 0,
+            user_id: USER_ID as i64,
+            chat_id: CHAT_ID,
+            at: Utc::now() - Duration::minutes(5),
+            text: "feed the dog".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(reminders::load(&state).await.unwrap(), 1);
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "Reminder: feed the dog");
+    assert!(harness.storage.reminders(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reminders_follow_a_group_upgraded_to_a_supergroup() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+    Mock::given(method("POST"))
+        .and(path_regex(r"^/botTOKEN/(?i:sendMessage)$"))
+        .and(body_partial_json(json!({ "chat_id": CHAT_ID })))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "ok": false,
+            "error_code": 400,
+            "description": "Bad Request: group chat was upgraded to a supergroup chat",
+            "parameters": { "migrate_to_chat_id": SUPERGROUP_ID }
+        })))
+        .with_priority(1)
+        .mount(&harness.telegram)
+        .await;
+    harness
+        .storage
+        .add_reminder(&Reminder {
+            id: 0,
+            user_id: USER_ID as i64,
+            chat_id: CHAT_ID,
+            at: Utc::now() - Duration::minutes(5),
+            text: "feed the dog".to_string(),
+        })
+        .await
+        .unwrap();
+    harness
+        .storage
+        .save_subscription(&Subscription {
+            id: 0,
+            chat_id: CHAT_ID,
+            kind: "dailydog".to_string(),
+            schedule: "09:00".to_string(),
+            options: "{}".to_string(),
+        })
+        .await
+        .unwrap();
+
+    reminders::load(&state).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1]["chat_id"], SUPERGROUP_ID);
+    assert_eq!(messages[1]["text"], "Reminder: feed the dog");
+    assert!(harness.storage.reminders(None).await.unwrap().is_empty());
+    let subscriptions = harness.storage.subscriptions().await.unwrap();
+    assert_eq!(subscriptions[0].chat_id, SUPERGROUP_ID);
+}
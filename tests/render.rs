@@ -0,0 +1,116 @@
+use dog_bot::{
+    api::dog::BreedsList,
+    breed::BreedQuery,
+    messenger::Portable,
+    render::{self, MAX_MESSAGE},
+};
+use insta::assert_snapshot;
+use std::str::FromStr;
+use teloxide::types::UserId;
+
+fn breeds(breeds: &[(&str, &[&str])]) -> BreedsList {
+    breeds
+        .iter()
+        .map(|(breed, sub_breeds)| {
+            let sub_breeds = sub_breeds.iter().map(|s| s.to_string()).collect();
+            (breed.to_string(), sub_breeds)
+        })
+        .collect()
+}
+
+#[test]
+fn breeds_are_listed_alphabetically() {
+    let list = breeds(&[
+        ("retriever", &["golden", "chesapeake"]),
+        ("akita", &[]),
+        ("bulldog", &["boston", "french"]),
+    ]);
+
+    assert_snapshot!(render::breeds(&list), @r###"
+    -│ akita
+    -│ bulldog
+         |> boston
+         |> french
+    -│ retriever
+         |> golden
+         |> chesapeake
+    "###);
+}
+
+#[test]
+fn long_breed_lists_fit_in_a_message() {
+    let names = (0..1000).map(|i| format!("breed{}", i)).collect::<Vec<_>>();
+    let list = breeds(
+        &names
+            .iter()
+            .map(|name| (name.as_str(), &[][..]))
+            .collect::<Vec<_>>(),
+    );
+
+    let text = render::breeds(&list);
+    assert_eq!(text.chars().count(), MAX_MESSAGE);
+    assert!(text.ends_with('…'));
+}
+
+#[test]
+fn prices() {
+    assert_snapshot!(render::price("btc", Some(106000.0)), @"BTC: $106,000.00");
+    assert_snapshot!(render::price("doge", Some(0.123456)), @"DOGE: $0.123456");
+    assert_snapshot!(render::price("shib", None), @"I don't know the price of 'shib'");
+}
+
+#[test]
+fn rankings() {
+    let ranking = [("husky".to_string(), 12), ("pug".to_string(), 3)];
+    assert_snapshot!(render::ranking(&ranking), @r###"
+    1. husky (12)
+    2. pug (3)
+    "###);
+    assert_snapshot!(render::ranking(&[]), @"Nothing yet, try /breed husky");
+}
+
+#[test]
+fn help() {
+    assert_snapshot!(Portable::help("!"), @r###"
+    !doggo - Random dog
+    !breed husky - Random dog of the breed
+    !euro - Value of the euro in USD
+    !dominance - Crypto market cap and the dominance of BTC and ETH
+    !tvl lido - Value locked in a DeFi protocol, or the top chains with `tvl chains`
+    "###);
+}
+
+#[test]
+fn texts_are_cut_at_the_limit() {
+    assert_eq!(render::fit("woof".to_string(), 4), "woof");
+    assert_eq!(render::fit("woofs".to_string(), 4), "woo…");
+    // Characters, not bytes
+    assert_eq!(render::fit("🐶🐶🐶".to_string(), 3), "🐶🐶🐶");
+    assert_eq!(render::fit("🐶🐶🐶🐶".to_string(), 3), "🐶🐶…");
+    assert_eq!(render::fit_caption("a".repeat(2000)).chars().count(), 1024);
+}
+
+#[test]
+fn pages_wrap_around() {
+    let husky = BreedQuery::from_str("husky").unwrap();
+    let data = |index, len| {
+        render::page_buttons(UserId(2000), &husky, index, len)
+            .map(|buttons| buttons.map(|(_, data)| data))
+    };
+
+    assert_eq!(data(0, 1), None);
+    assert_eq!(
+        data(0, 3),
+        Some([
+            "breed-page:2000:2:husky".to_string(),
+            "breed-page:2000:1:husky".to_string()
+        ])
+    );
+    assert_eq!(
+        data(2, 3),
+        Some([
+            "breed-page:2000:1:husky".to_string(),
+            "breed-page:2000:0:husky".to_string()
+        ])
+    );
+}
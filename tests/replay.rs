@@ -0,0 +1,109 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    config::Config,
+    http::{self, HttpConfig, NotLocal},
+    replay::{self, Recorder},
+};
+use serde_json::json;
+use std::path::PathBuf;
+use teloxide::types::Update;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+const IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+
+fn update(id: i32, text: &str) -> Update {
+    let update = json!({
+        "update_id": id,
+        "message": serde_json::to_value(common::message(text)).unwrap()
+    });
+    serde_json::from_str(&update.to_string()).unwrap()
+}
+
+fn recording(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}.jsonl", name, std::process::id()));
+    std::fs::remove_file(&path).ok();
+    path
+}
+
+#[tokio::test]
+async fn recorded_updates_are_loaded_back() {
+    let path = recording("recorded");
+    let recorder = Recorder::open(&path).await.unwrap();
+    recorder.record(&update(1, "/doggo")).await;
+    recorder.record(&update(2, "/breed husky")).await;
+
+    let updates = replay::load(&path).unwrap();
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0], update(1, "/doggo"));
+    assert_eq!(updates[1].id, 2);
+
+    std::fs::write(&path, "{\"update_id\": 1}\nnot json\n").unwrap();
+    let error = replay::load(&path).unwrap_err();
+    assert!(error.to_string().contains("line 2"));
+}
+
+#[tokio::test]
+async fn replayed_updates_reach_the_handlers() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+
+    let failed = replay::replay(
+        vec![update(1, "/doggo"), update(2, "just chatting")],
+        harness.bot(),
+        replay::me(replay::DEFAULT_USERNAME),
+        harness.state(),
+    )
+    .await;
+
+    assert_eq!(failed, 0);
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(photos.len(), 1);
+    assert_eq!(photos[0]["chat_id"], CHAT_ID);
+    assert_eq!(photos[0]["photo"], IMAGE);
+}
+
+#[test]
+fn a_replay_keeps_away_from_the_data_of_the_bot() {
+    let mut config = Config::default();
+    config.storage.url = "postgres://bot@db/dogs".to_string();
+    config.cache.redis_url = Some("redis://cache:6379".to_string());
+    config.api.telegram_local = true;
+
+    let config = replay::isolate(config);
+    assert_eq!(config.storage.url, "memory://");
+    assert_eq!(config.cache.redis_url, None);
+    assert!(!config.api.telegram_local);
+}
+
+#[tokio::test]
+async fn a_replay_only_calls_the_upstreams_on_this_machine() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&harness.dog_ceo)
+        .await;
+    let client = http::local_client(&HttpConfig::default(), harness.health.clone());
+
+    let local = client.get(harness.dog_ceo.uri()).send().await;
+    assert_eq!(local.unwrap().status(), 200);
+    let remote = client
+        .get("https://dog.ceo/api/breeds/list/all")
+        .send()
+        .await;
+    assert!(matches!(
+        remote,
+        Err(reqwest_middleware::Error::Middleware(e)) if e.is::<NotLocal>()
+    ));
+}
@@ -0,0 +1,110 @@
+use chrono::{NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::{Europe::Madrid, Tz};
+use dog_bot::{
+    scheduler::{JobStatus, Schedule, Scheduler},
+    storage::{memory::MemoryStorage, Storage},
+};
+use std::{sync::Arc, time::Duration};
+
+#[test]
+fn daily_jobs_run_at_the_next_occurrence() {
+    let schedule = Schedule::Daily(NaiveTime::from_hms_opt(9, 0, 0).unwrap(), Tz::UTC);
+
+    let morning = Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_after(morning),
+        Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap()
+    );
+    let on_time = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_after(on_time),
+        Utc.with_ymd_and_hms(2024, 5, 2, 9, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn daily_jobs_follow_daylight_saving_time() {
+    let schedule = Schedule::Daily(NaiveTime::from_hms_opt(9, 0, 0).unwrap(), Madrid);
+
+    // 09:00 is 08:00 UTC in winter and 07:00 UTC in summer
+    let winter = Utc.with_ymd_and_hms(2024, 3, 30, 12, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_after(winter),
+        Utc.with_ymd_and_hms(2024, 3, 31, 7, 0, 0).unwrap()
+    );
+    let summer = Utc.with_ymd_and_hms(2024, 10, 26, 12, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_after(summer),
+        Utc.with_ymd_and_hms(2024, 10, 27, 8, 0, 0).unwrap()
+    );
+
+    // 02:30 doesn't exist the day the clocks go from 02:00 to 03:00
+    let skipped = Schedule::Daily(NaiveTime::from_hms_opt(2, 30, 0).unwrap(), Madrid);
+    assert_eq!(
+        skipped.next_after(winter),
+        Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap()
+    );
+}
+
+#[test]
+fn weekly_jobs_run_on_their_day() {
+    let schedule = Schedule::Weekly(
+        Weekday::Sun,
+        NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+        Tz::UTC,
+    );
+
+    // A Wednesday
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_after(now),
+        Utc.with_ymd_and_hms(2024, 5, 5, 18, 0, 0).unwrap()
+    );
+    let sunday_evening = Utc.with_ymd_and_hms(2024, 5, 5, 19, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_after(sunday_evening),
+        Utc.with_ymd_and_hms(2024, 5, 12, 18, 0, 0).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn failing_jobs_keep_running_and_are_tracked() {
+    let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+    let scheduler = Scheduler::new(storage.clone());
+
+    scheduler.add(
+        "fails",
+        Schedule::Every(Duration::from_millis(20)),
+        || async { Err("nope".into()) },
+    );
+    scheduler.add(
+        "panics",
+        Schedule::Every(Duration::from_millis(20)),
+        || async { panic!("boom") },
+    );
+    // Every panic may print a backtrace, which takes a while, so wait for the runs instead of a set time
+    let ran_twice = |jobs: &[JobStatus]| {
+        jobs.iter()
+            .all(|job| job.last.as_ref().is_some_and(|last| last.runs >= 2))
+    };
+    for _ in 0..100 {
+        if ran_twice(&scheduler.jobs()) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let jobs = scheduler.jobs();
+    assert_eq!(jobs.len(), 2);
+    for job in &jobs {
+        let last = job.last.as_ref().unwrap();
+        assert!(last.runs >= 2, "{} only ran {} times", job.name, last.runs);
+        assert_eq!(last.runs, last.failures);
+    }
+    let failed = storage.job_run("fails").await.unwrap().unwrap();
+    assert_eq!(failed.last_error.as_deref(), Some("nope"));
+
+    assert!(scheduler.remove("fails").await);
+    assert_eq!(storage.job_run("fails").await.unwrap(), None);
+    assert_eq!(scheduler.jobs().len(), 1);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    commands::{answer, Command, CommandsConfig},
+    limits::CommandClass,
+    settings,
+    storage::ChatSettings,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+const IMAGE: &str = "https://images.dog.ceo/breeds/husky/n02110185_1469.jpg";
+
+#[test]
+fn cooldowns_fall_back_to_the_config() {
+    let config = CommandsConfig {
+        cheap_cooldown_secs: 2,
+        expensive_cooldown_secs: 20,
+        ..CommandsConfig::default()
+    };
+    let mut chat = ChatSettings {
+        chat_id: CHAT_ID,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        settings::cooldown_secs(None, CommandClass::Cheap, &config),
+        2
+    );
+    settings::set(&mut chat, "expensive", "90").unwrap();
+    settings::set(&mut chat, "cheap", "off").unwrap();
+    assert_eq!(
+        settings::cooldown_secs(Some(&chat), CommandClass::Expensive, &config),
+        90
+    );
+    assert_eq!(
+        settings::cooldown_secs(Some(&chat), CommandClass::Cheap, &config),
+        0
+    );
+    assert!(settings::describe(Some(&chat), &config).contains("expensive (charts and albums): 90s"));
+
+    settings::set(&mut chat, "expensive", "default").unwrap();
+    assert_eq!(chat.expensive_cooldown_secs, None);
+    assert!(settings::set(&mut chat, "expensive", "-1").is_err());
+    assert!(settings::set(&mut chat, "expensive", "100000").is_err());
+    assert!(settings::set(&mut chat, "fast", "10").is_err());
+}
+
+#[tokio::test]
+async fn the_cooldown_of_the_chat_is_enforced() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/breeds/image/random"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": IMAGE, "status": "success" })),
+        )
+        .mount(&harness.dog_ceo)
+        .await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("/settings cheap 60"))
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        harness
+            .receive(&state, common::update("/doggo"))
+            .await
+            .unwrap();
+    }
+
+    let settings = state.storage.chat_settings(CHAT_ID).await.unwrap().unwrap();
+    assert_eq!(settings.cheap_cooldown_secs, Some(60));
+    assert_eq!(harness.sent("sendPhoto").await.len(), 1);
+}
+
+#[tokio::test]
+async fn only_admins_change_the_settings_of_a_group() {
+    let harness = Harness::start().await;
+    harness.member_status("member").await;
+    let state = harness.state();
+
+    for (text, command) in [
+        (
+            "/settings cheap 60",
+            Command::Settings("cheap 60".to_string()),
+        ),
+        ("/alttext on", Command::AltText("on".to_string())),
+        (
+            "/holidays country es",
+            Command::Holidays("country es".to_string()),
+        ),
+        (
+            "/settimezone Europe/Madrid",
+            Command::SetTimezone("Europe/Madrid".to_string()),
+        ),
+        ("/language es", Command::Language("es".to_string())),
+        (
+            "/quiethours 23:00-08:00",
+            Command::QuietHours("23:00-08:00".to_string()),
+        ),
+    ] {
+        answer(
+            harness.bot(),
+            common::group_message(text),
+            command,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let texts = harness
+        .sent("sendMessage")
+        .await
+        .into_iter()
+        .map(|message| message["text"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        texts,
+        [
+            "Only the admins of the chat can change its settings",
+            "Only the admins of the chat can change its captions",
+            "Only the admins of the chat can change its country",
+            "Only the admins of the chat can change its timezone",
+            "Only the admins of the chat can change its language",
+            "Only the admins of the chat can change its quiet hours",
+        ]
+    );
+    assert_eq!(harness.storage.chat_settings(CHAT_ID).await.unwrap(), None);
+}
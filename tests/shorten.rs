@@ -0,0 +1,94 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{body_string_contains, header, method, path},
+    Mock, ResponseTemplate,
+};
+
+fn short_url(total: u64, non_bots: u64) -> serde_json::Value {
+    json!({
+        "shortCode": "abc12",
+        "shortUrl": "https://s.test/abc12",
+        "longUrl": "https://dog.ceo/dog-api",
+        "dateCreated": "2024-06-01T10:00:00+00:00",
+        "visitsSummary": { "total": total, "nonBots": non_bots, "bots": total - non_bots }
+    })
+}
+
+#[tokio::test]
+async fn links_are_shortened() {
+    let harness = Harness::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v3/short-urls"))
+        .and(header("X-Api-Key", "KEY"))
+        .and(body_string_contains(
+            r#""longUrl":"https://dog.ceo/dog-api""#,
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(short_url(0, 0)))
+        .mount(&harness.shlink)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/shorten https://dog.ceo/dog-api"),
+        Command::Shorten("https://dog.ceo/dog-api".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "https://s.test/abc12\n\nSee how many times it was opened with /clicks abc12"
+    );
+}
+
+#[tokio::test]
+async fn only_web_links_are_shortened() {
+    let harness = Harness::start().await;
+
+    answer(
+        harness.bot(),
+        common::message("/shorten javascript:alert(1)"),
+        Command::Shorten("javascript:alert(1)".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Tell me a link, e.g. /shorten https://dog.ceo/dog-api"
+    );
+    assert!(harness.shlink.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn clicks_are_counted_by_short_link() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/v3/short-urls/abc12"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(short_url(7, 5)))
+        .mount(&harness.shlink)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/clicks https://s.test/abc12"),
+        Command::Clicks("https://s.test/abc12".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "https://s.test/abc12 was opened 7 times, 5 of them by people\n\nIt leads to https://dog.ceo/dog-api"
+    );
+}
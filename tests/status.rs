@@ -0,0 +1,59 @@
+mod common;
+
+use common::Harness;
+use dog_bot::status::Status;
+use insta::assert_snapshot;
+use std::time::Duration;
+
+#[test]
+fn the_status_is_summarized() {
+    let status = Status {
+        uptime: Duration::from_secs(2 * 86400 + 3 * 3600 + 59),
+        updates: 1234,
+        dog_ceo: Some(Duration::from_secs(12)),
+        coingecko: None,
+        breeds: Some(Duration::from_secs(5 * 60)),
+        buffered_dogs: 8,
+        inline_answers: 3,
+        jobs: 4,
+        failing_jobs: 1,
+    };
+
+    assert_snapshot!(status.text(), @r###"
+    ⏱ Up for 2d 3h
+    Updates handled: 1234
+
+    Last success
+    dog.ceo: 12s ago
+    CoinGecko: never
+
+    Cache
+    breeds fetched: 5m 0s ago
+    dogs ready: 8
+    inline answers: 3
+
+    Scheduled jobs: 4, 1 failing
+    "###);
+}
+
+#[tokio::test]
+async fn uptime_counts_the_updates() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("just chatting"))
+        .await
+        .unwrap();
+    harness
+        .receive(&state, common::update("/uptime"))
+        .await
+        .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    let text = messages[0]["text"].as_str().unwrap();
+    assert!(text.starts_with("⏱ Up for "), "{}", text);
+    assert!(text.contains("Updates handled: 2"), "{}", text);
+    assert_eq!(Status::of(&state).await.updates, 2);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn stock_prices_show_the_daily_change() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/AAPL"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "chart": {
+                "result": [{
+                    "meta": {
+                        "currency": "USD",
+                        "symbol": "AAPL",
+                        "regularMarketPrice": 202.0,
+                        "chartPreviousClose": 200.0
+                    }
+                }],
+                "error": null
+            }
+        })))
+        .mount(&harness.yahoo_finance)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/stock aapl"),
+        Command::Stock("aapl".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "AAPL: 202.00 USD (+1.00% today)");
+}
+
+#[tokio::test]
+async fn unknown_tickers_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/NOPE"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "chart": { "result": null, "error": { "code": "Not Found" } }
+        })))
+        .mount(&harness.yahoo_finance)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/stock nope"),
+        Command::Stock("nope".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I don't know the ticker 'NOPE'");
+}
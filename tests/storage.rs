@@ -0,0 +1,362 @@
+use chrono::Utc;
+use dog_bot::storage::{
+    memory::MemoryStorage, sqlite::SqliteStorage, Alert, ChatSettings, CommandRecord, CommandStats,
+    InactiveChat, Storage, Subscription,
+};
+
+/// Run every check against a backend.
+macro_rules! backend_tests {
+    ($backend:ident, $storage:expr) => {
+        mod $backend {
+            use super::*;
+
+            backend_tests!(@tests $storage;
+                users_keep_their_first_seen_date,
+                chat_settings_are_overwritten,
+                subscriptions_and_alerts_get_ids,
+                favourites_are_unique,
+                commands_are_counted,
+                command_stats_include_latency_and_failures,
+                breeds_are_ranked,
+                trivia_points_add_up,
+                chats_move_to_their_supergroup,
+                inactive_chats_come_back,
+                update_offsets_only_move_forward,
+            );
+        }
+    };
+    (@tests $storage:expr; $($check:ident,)*) => {
+        $(
+            #[tokio::test]
+            async fn $check() {
+                let storage: Box<dyn Storage> = Box::new($storage);
+                super::$check(storage.as_ref()).await;
+            }
+        )*
+    };
+}
+
+backend_tests!(memory, MemoryStorage::default());
+backend_tests!(
+    sqlite,
+    SqliteStorage::connect("sqlite::memory:").await.unwrap()
+);
+
+async fn users_keep_their_first_seen_date(storage: &dyn Storage) {
+    storage.see_user(1, Some("marc")).await.unwrap();
+    let first = storage.user(1).await.unwrap().unwrap();
+    storage.see_user(1, Some("marc2332")).await.unwrap();
+    let user = storage.user(1).await.unwrap().unwrap();
+
+    assert_eq!(user.username.as_deref(), Some("marc2332"));
+    assert_eq!(user.first_seen, first.first_seen);
+    assert!(storage.user(2).await.unwrap().is_none());
+}
+
+async fn chat_settings_are_overwritten(storage: &dyn Storage) {
+    let mut settings = ChatSettings {
+        chat_id: 10,
+        language: Some("en".to_string()),
+        timezone: None,
+        quiet_hours: None,
+        news_source: None,
+        nsfw_filter: false,
+        country: None,
+        alt_text: false,
+        cheap_cooldown_secs: None,
+        expensive_cooldown_secs: Some(30),
+    };
+
+    storage.save_chat_settings(&settings).await.unwrap();
+    settings.timezone = Some("Europe/Madrid".to_string());
+    settings.cheap_cooldown_secs = Some(5);
+    storage.save_chat_settings(&settings).await.unwrap();
+
+    assert_eq!(storage.chat_settings(10).await.unwrap(), Some(settings));
+}
+
+async fn subscriptions_and_alerts_get_ids(storage: &dyn Storage) {
+    let mut subscription = Subscription {
+        id: 0,
+        chat_id: 10,
+        kind: "dailydog".to_string(),
+        schedule: "09:00".to_string(),
+        options: "{}".to_string(),
+    };
+    let id = storage.save_subscription(&subscription).await.unwrap();
+    // Subscribing again only moves the schedule
+    subscription.schedule = "10:00".to_string();
+    assert_eq!(storage.save_subscription(&subscription).await.unwrap(), id);
+
+    let subscriptions = storage.chat_subscriptions(10).await.unwrap();
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].id, id);
+    assert_eq!(subscriptions[0].schedule, "10:00");
+    assert!(storage.chat_subscriptions(11).await.unwrap().is_empty());
+    storage.remove_subscription(id).await.unwrap();
+    assert!(storage.subscriptions().await.unwrap().is_empty());
+
+    let id = storage
+        .add_alert(&Alert {
+            id: 0,
+            user_id: 1,
+            chat_id: 10,
+            symbol: "btc".to_string(),
+            target: 100_000.0,
+            above: true,
+            move_percent: None,
+            window_secs: None,
+            created_at: None,
+            paused: false,
+        })
+        .await
+        .unwrap();
+    let alerts = storage.alerts(Some(1)).await.unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].id, id);
+    assert!(alerts[0].above);
+
+    let mut alert = alerts[0].clone();
+    alert.target = 90_000.0;
+    alert.paused = true;
+    storage.update_alert(&alert).await.unwrap();
+    assert_eq!(storage.alerts(Some(1)).await.unwrap(), [alert]);
+}
+
+async fn favourites_are_unique(storage: &dyn Storage) {
+    storage.add_favourite(1, "husky").await.unwrap();
+    storage.add_favourite(1, "husky").await.unwrap();
+    storage.add_favourite(1, "akita").await.unwrap();
+    assert_eq!(storage.favourites(1).await.unwrap(), ["akita", "husky"]);
+
+    storage.remove_favourite(1, "husky").await.unwrap();
+    assert_eq!(storage.favourites(1).await.unwrap(), ["akita"]);
+}
+
+async fn commands_are_counted(storage: &dyn Storage) {
+    let now = Utc::now();
+    let record = |command: &str, user_id: i64, days_ago: i64| CommandRecord {
+        command: command.to_string(),
+        user_id: Some(user_id),
+        chat_id: user_id * 10,
+        chat_type: "private".to_string(),
+        at: now - chrono::Duration::days(days_ago),
+        latency_ms: 100,
+        success: true,
+    };
+
+    for record in [
+        record("doggo", 1, 0),
+        record("euro", 2, 0),
+        record("doggo", 1, 0),
+        record("doggo", 3, 10),
+    ] {
+        storage.record_command(&record).await.unwrap();
+    }
+
+    let week_ago = now - chrono::Duration::days(7);
+    assert_eq!(
+        storage.command_counts(None, week_ago).await.unwrap(),
+        [("doggo".to_string(), 2), ("euro".to_string(), 1)]
+    );
+    assert_eq!(
+        storage.command_counts(Some(20), week_ago).await.unwrap(),
+        [("euro".to_string(), 1)]
+    );
+    assert_eq!(storage.active_users(week_ago).await.unwrap(), 2);
+}
+
+async fn command_stats_include_latency_and_failures(storage: &dyn Storage) {
+    let now = Utc::now();
+    let record = |command: &str, latency_ms: i64, success: bool, days_ago: i64| CommandRecord {
+        command: command.to_string(),
+        user_id: Some(1),
+        chat_id: 10,
+        chat_type: "private".to_string(),
+        at: now - chrono::Duration::days(days_ago),
+        latency_ms,
+        success,
+    };
+
+    for record in [
+        record("doggo", 100, true, 0),
+        record("doggo", 300, false, 0),
+        record("euro", 50, true, 0),
+        record("euro", 5000, false, 10),
+    ] {
+        storage.record_command(&record).await.unwrap();
+    }
+
+    assert_eq!(
+        storage
+            .command_stats(now - chrono::Duration::days(7))
+            .await
+            .unwrap(),
+        [
+            CommandStats {
+                command: "doggo".to_string(),
+                count: 2,
+                errors: 1,
+                avg_latency_ms: 200.0,
+            },
+            CommandStats {
+                command: "euro".to_string(),
+                count: 1,
+                errors: 0,
+                avg_latency_ms: 50.0,
+            },
+        ]
+    );
+}
+
+async fn breeds_are_ranked(storage: &dyn Storage) {
+    for (chat_id, breed) in [
+        (1, "husky"),
+        (1, "akita"),
+        (1, "husky"),
+        (2, "akita"),
+        (2, "akita"),
+    ] {
+        storage.record_breed(chat_id, breed).await.unwrap();
+    }
+
+    assert_eq!(
+        storage.popular_breeds(Some(1), None, 10).await.unwrap(),
+        [("husky".to_string(), 2), ("akita".to_string(), 1)]
+    );
+    assert_eq!(
+        storage.popular_breeds(None, None, 1).await.unwrap(),
+        [("akita".to_string(), 3)]
+    );
+    let later = Utc::now() + chrono::Duration::minutes(1);
+    assert!(storage
+        .popular_breeds(None, Some(later), 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+async fn trivia_points_add_up(storage: &dyn Storage) {
+    storage.add_trivia_points(1, 10, "Ana", 2).await.unwrap();
+    storage.add_trivia_points(1, 20, "Bo", 1).await.unwrap();
+    storage.add_trivia_points(1, 10, "Ana B.", 3).await.unwrap();
+    storage.add_trivia_points(2, 20, "Bo", 7).await.unwrap();
+
+    assert_eq!(
+        storage.trivia_scores(1, 10).await.unwrap(),
+        [("Ana B.".to_string(), 5), ("Bo".to_string(), 1)]
+    );
+
+    storage.forget_user(10).await.unwrap();
+    assert_eq!(
+        storage.trivia_scores(1, 10).await.unwrap(),
+        [("Bo".to_string(), 1)]
+    );
+}
+
+async fn chats_move_to_their_supergroup(storage: &dyn Storage) {
+    let subscription = |chat_id: i64, kind: &str| Subscription {
+        id: 0,
+        chat_id,
+        kind: kind.to_string(),
+        schedule: "09:00".to_string(),
+        options: "{}".to_string(),
+    };
+    storage
+        .save_chat_settings(&ChatSettings {
+            chat_id: 10,
+            language: Some("es".to_string()),
+            timezone: None,
+            quiet_hours: None,
+            news_source: None,
+            nsfw_filter: false,
+            country: None,
+            alt_text: false,
+            cheap_cooldown_secs: None,
+            expensive_cooldown_secs: None,
+        })
+        .await
+        .unwrap();
+    storage
+        .save_subscription(&subscription(10, "dailydog"))
+        .await
+        .unwrap();
+    storage
+        .save_subscription(&subscription(10, "weeklydigest"))
+        .await
+        .unwrap();
+    // The supergroup subscribed on its own already
+    storage
+        .save_subscription(&subscription(-100, "dailydog"))
+        .await
+        .unwrap();
+    storage.add_trivia_points(10, 1, "Ana", 2).await.unwrap();
+    storage.record_breed(10, "husky").await.unwrap();
+
+    storage.migrate_chat(10, -100).await.unwrap();
+
+    assert!(storage.chat_settings(10).await.unwrap().is_none());
+    let settings = storage.chat_settings(-100).await.unwrap().unwrap();
+    assert_eq!(settings.language.as_deref(), Some("es"));
+    assert!(storage.chat_subscriptions(10).await.unwrap().is_empty());
+    let mut kinds = storage
+        .chat_subscriptions(-100)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|subscription| subscription.kind)
+        .collect::<Vec<_>>();
+    kinds.sort();
+    assert_eq!(kinds, ["dailydog", "weeklydigest"]);
+    assert_eq!(
+        storage.trivia_scores(-100, 10).await.unwrap(),
+        [("Ana".to_string(), 2)]
+    );
+    assert_eq!(
+        storage.popular_breeds(Some(-100), None, 10).await.unwrap(),
+        [("husky".to_string(), 1)]
+    );
+}
+
+async fn inactive_chats_come_back(storage: &dyn Storage) {
+    let now = Utc::now();
+    let inactive = |chat_id: i64, reason: &str, minutes_ago: i64| InactiveChat {
+        chat_id,
+        reason: reason.to_string(),
+        since: now - chrono::Duration::minutes(minutes_ago),
+    };
+    storage
+        .deactivate_chat(&inactive(10, "blocked", 5))
+        .await
+        .unwrap();
+    storage
+        .deactivate_chat(&inactive(20, "not_found", 10))
+        .await
+        .unwrap();
+    storage
+        .deactivate_chat(&inactive(20, "kicked", 1))
+        .await
+        .unwrap();
+
+    let chats = storage.inactive_chats().await.unwrap();
+    assert_eq!(chats.len(), 2);
+    assert_eq!(chats[0].chat_id, 20);
+    assert_eq!(chats[0].reason, "kicked");
+
+    storage.reactivate_chat(20).await.unwrap();
+    storage.reactivate_chat(30).await.unwrap();
+    let chats = storage.inactive_chats().await.unwrap();
+    assert_eq!(chats.len(), 1);
+    assert_eq!(chats[0].chat_id, 10);
+}
+
+async fn update_offsets_only_move_forward(storage: &dyn Storage) {
+    assert_eq!(storage.update_offset("main").await.unwrap(), None);
+
+    storage.save_update_offset("main", 10).await.unwrap();
+    storage.save_update_offset("main", 7).await.unwrap();
+    storage.save_update_offset("other", 3).await.unwrap();
+
+    assert_eq!(storage.update_offset("main").await.unwrap(), Some(10));
+    assert_eq!(storage.update_offset("other").await.unwrap(), Some(3));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use chrono_tz::{America::Los_Angeles, Asia::Kolkata, Asia::Tokyo, Europe::Madrid, Tz};
+use common::Harness;
+use dog_bot::{
+    commands::{answer, Command},
+    timezones,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+#[test]
+fn times_are_compared_with_the_timezone_of_the_chat() {
+    let now = Utc.with_ymd_and_hms(2024, 6, 1, 20, 0, 0).unwrap();
+
+    assert_eq!(
+        timezones::compare(Tokyo, Madrid, now),
+        "05:00, Sunday 2 June\nAsia/Tokyo (UTC+09:00)\n7 hours ahead of you, already tomorrow there"
+    );
+    assert_eq!(
+        timezones::compare(Los_Angeles, Tokyo, now),
+        "13:00, Saturday 1 June\nAmerica/Los_Angeles (UTC-07:00)\n16 hours behind you, still yesterday there"
+    );
+    assert_eq!(
+        timezones::compare(Kolkata, Tz::UTC, now),
+        "01:30, Sunday 2 June\nAsia/Kolkata (UTC+05:30)\n5 hours 30 minutes ahead of you, already tomorrow there"
+    );
+    assert_eq!(
+        timezones::compare(Madrid, Madrid, now),
+        "22:00, Saturday 1 June\nEurope/Madrid (UTC+02:00)\nSame time as you"
+    );
+}
+
+#[tokio::test]
+async fn cities_are_located_with_their_timezone() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("name", "tokyo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [{
+                "id": 1850147,
+                "name": "Tokyo",
+                "country": "Japan",
+                "latitude": 35.6895,
+                "longitude": 139.69171,
+                "timezone": "Asia/Tokyo"
+            }]
+        })))
+        .mount(&harness.open_meteo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/time tokyo"),
+        Command::Time("tokyo".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    let text = messages[0]["text"].as_str().unwrap();
+    assert!(text.starts_with("Tokyo, Japan\n"), "{}", text);
+    assert!(
+        text.contains("Asia/Tokyo (UTC+09:00)\n9 hours ahead of you"),
+        "{}",
+        text
+    );
+}
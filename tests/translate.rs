@@ -0,0 +1,102 @@
+mod common;
+
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use teloxide::types::Message;
+use wiremock::{
+    matchers::{body_string_contains, method, path},
+    Mock, ResponseTemplate,
+};
+
+async fn mount_translation(harness: &Harness) {
+    Mock::given(method("POST"))
+        .and(path("/translate"))
+        .and(body_string_contains("q=good+boy"))
+        .and(body_string_contains("target=es"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "translatedText": "buen chico",
+            "detectedLanguage": { "confidence": 90.0, "language": "en" }
+        })))
+        .mount(&harness.libretranslate)
+        .await;
+}
+
+#[tokio::test]
+async fn text_is_translated() {
+    let harness = Harness::start().await;
+    mount_translation(&harness).await;
+
+    answer(
+        harness.bot(),
+        common::message("/translate es good boy"),
+        Command::Translate("es good boy".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "buen chico (en → es)");
+}
+
+#[tokio::test]
+async fn replied_messages_are_translated() {
+    let harness = Harness::start().await;
+    mount_translation(&harness).await;
+
+    let user = json!({ "id": USER_ID, "is_bot": false, "first_name": "Marc" });
+    let chat = json!({ "id": CHAT_ID, "type": "private", "first_name": "Marc" });
+    let message: Message = serde_json::from_value(json!({
+        "message_id": 2,
+        "date": 0,
+        "chat": chat,
+        "from": user,
+        "text": "/translate es",
+        "reply_to_message": {
+            "message_id": 1,
+            "date": 0,
+            "chat": chat,
+            "from": user,
+            "text": "good boy"
+        }
+    }))
+    .unwrap();
+
+    answer(
+        harness.bot(),
+        message,
+        Command::Translate("es".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "buen chico (en → es)");
+    assert_eq!(messages[0]["reply_to_message_id"], 2);
+}
+
+#[tokio::test]
+async fn unsupported_languages_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("POST"))
+        .and(path("/translate"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": "xx is not supported"
+        })))
+        .mount(&harness.libretranslate)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/translate xx good boy"),
+        Command::Translate("xx good boy".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I can't translate into 'xx'");
+}
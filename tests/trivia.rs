@@ -0,0 +1,153 @@
+mod common;
+
+use common::{Harness, CHAT_ID};
+use dog_bot::{
+    commands::{answer, answer_callback, Command},
+    trivia,
+};
+use serde_json::json;
+use teloxide::types::{CallbackQuery, ChatId};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+/// Press on an answer button by the given user.
+fn press(data: &str, user_id: u64, name: &str) -> CallbackQuery {
+    serde_json::from_value(json!({
+        "id": "1",
+        "from": { "id": user_id, "is_bot": false, "first_name": name },
+        "message": {
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": CHAT_ID, "type": "group", "title": "Dogs" },
+            "text": "sent"
+        },
+        "chat_instance": "1",
+        "data": data
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn right_answers_score_when_the_round_ends() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api_category.php"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "trivia_categories": [
+                { "id": 9, "name": "General Knowledge" },
+                { "id": 27, "name": "Animals" }
+            ]
+        })))
+        .mount(&harness.opentdb)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api.php"))
+        .and(query_param("category", "27"))
+        .and(query_param("difficulty", "medium"))
+        .and(query_param("encode", "url3986"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "response_code": 0,
+            "results": [{
+                "type": "multiple",
+                "difficulty": "medium",
+                "category": "Animals",
+                "question": "What%20is%20a%20baby%20dog%20called%3F",
+                "correct_answer": "Puppy",
+                "incorrect_answers": ["Kitten", "Cub", "Foal"]
+            }]
+        })))
+        .mount(&harness.opentdb)
+        .await;
+
+    // The round lives in the state, it's shared by the question and the answers
+    let state = harness.state();
+    answer(
+        harness.bot(),
+        common::message("/trivia animals medium"),
+        Command::Trivia("animals medium".to_string()),
+        state.clone(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "🧠 Animals (medium)\n\nWhat is a baby dog called?\n\n30 seconds to answer!"
+    );
+    let buttons = messages[0]["reply_markup"]["inline_keyboard"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row[0]["text"].as_str().unwrap().to_string(),
+                row[0]["callback_data"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(buttons.len(), 4);
+    let right = &buttons.iter().find(|(text, _)| text == "Puppy").unwrap().1;
+    let wrong = &buttons.iter().find(|(text, _)| text == "Cub").unwrap().1;
+
+    for (data, user_id, name) in [
+        (right, 1, "Ana"),
+        (wrong, 2, "Bo"),
+        // Only the first answer counts
+        (right, 2, "Bo"),
+    ] {
+        answer_callback(harness.bot(), press(data, user_id, name), state.clone())
+            .await
+            .unwrap();
+    }
+    trivia::close(state.clone(), ChatId(CHAT_ID)).await.unwrap();
+    assert!(!state.trivia.is_running(ChatId(CHAT_ID)));
+
+    let answers = harness.sent("answerCallbackQuery").await;
+    assert_eq!(
+        answers[0]["text"],
+        "You answered Puppy, wait for the time to be up"
+    );
+    assert_eq!(answers[2]["text"], "You already answered");
+    let edits = harness.sent("editMessageText").await;
+    assert_eq!(
+        edits[0]["text"],
+        "🧠 What is a baby dog called?\n\n✅ Puppy\n\nRight: Ana (+2)\n\nSee the scores with /trivia scores"
+    );
+    assert_eq!(
+        harness.storage.trivia_scores(CHAT_ID, 10).await.unwrap(),
+        [("Ana".to_string(), 2)]
+    );
+}
+
+#[tokio::test]
+async fn scores_are_ranked() {
+    let harness = Harness::start().await;
+    harness
+        .storage
+        .add_trivia_points(CHAT_ID, 1, "Ana", 2)
+        .await
+        .unwrap();
+    harness
+        .storage
+        .add_trivia_points(CHAT_ID, 2, "Bo", 5)
+        .await
+        .unwrap();
+
+    answer(
+        harness.bot(),
+        common::message("/trivia scores"),
+        Command::Trivia("scores".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Trivia scores:\n1. Bo (5)\n2. Ana (2)\n"
+    );
+}
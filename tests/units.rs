@@ -0,0 +1,56 @@
+use dog_bot::units::{self, Conversion};
+
+fn convert(conversion: &str) -> Result<String, String> {
+    conversion
+        .parse::<Conversion>()
+        .map(|conversion| conversion.to_string())
+}
+
+#[test]
+fn units_of_the_same_quantity_are_converted() {
+    assert_eq!(convert("10 km mi"), Ok("10 km = 6.21371 mi".to_string()));
+    assert_eq!(
+        convert("10km to miles"),
+        Ok("10 km = 6.21371 mi".to_string())
+    );
+    assert_eq!(convert("6 ft in cm"), Ok("6 ft = 182.88 cm".to_string()));
+    assert_eq!(convert("2.5 KG lb"), Ok("2.5 kg = 5.51156 lb".to_string()));
+    assert_eq!(convert("4 GiB MB"), Ok("4 GiB = 4294.97 MB".to_string()));
+    assert_eq!(convert("1 bit GB"), Ok("1 bit = 1.25e-10 GB".to_string()));
+}
+
+#[test]
+fn temperatures_are_offset() {
+    assert_eq!(convert("20 c f"), Ok("20 °C = 68 °F".to_string()));
+    assert_eq!(convert("-40 °F celsius"), Ok("-40 °F = -40 °C".to_string()));
+    assert_eq!(convert("0 K C"), Ok("0 K = -273.15 °C".to_string()));
+}
+
+#[test]
+fn mistakes_are_explained() {
+    assert_eq!(
+        convert("ten km mi"),
+        Err("'ten km mi' isn't a conversion like 10 km mi".to_string())
+    );
+    assert_eq!(
+        convert("10 km"),
+        Err("'10 km' isn't a conversion like 10 km mi".to_string())
+    );
+    assert_eq!(
+        convert("10 km parsecs"),
+        Err("I don't know the unit 'parsecs'".to_string())
+    );
+    assert_eq!(
+        convert("10 km kg"),
+        Err("I can't convert km into kg, km is a length and kg is a weight".to_string())
+    );
+}
+
+#[test]
+fn numbers_keep_six_significant_digits() {
+    assert_eq!(units::number(1000.0), "1000");
+    assert_eq!(units::number(0.5), "0.5");
+    assert_eq!(units::number(1.0 / 3.0), "0.333333");
+    assert_eq!(units::number(123456789.0), "123456789");
+    assert_eq!(units::number(0.0), "0");
+}
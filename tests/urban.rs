@@ -0,0 +1,134 @@
+mod common;
+
+use common::{Harness, CHAT_ID, USER_ID};
+use dog_bot::commands::{answer, answer_callback, Command, URBAN_PAGE};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+async fn mount_definitions(harness: &Harness) {
+    Mock::given(method("GET"))
+        .and(path("/define"))
+        .and(query_param("term", "doggo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "list": [
+                {
+                    "word": "doggo",
+                    "definition": "A [dog].",
+                    "example": "",
+                    "thumbs_up": 10,
+                    "thumbs_down": 2
+                },
+                {
+                    "word": "doggo",
+                    "definition": "A very good [boy].",
+                    "example": "Look at that [doggo]!",
+                    "thumbs_up": 120,
+                    "thumbs_down": 4
+                }
+            ]
+        })))
+        .mount(&harness.urban_dictionary)
+        .await;
+}
+
+#[tokio::test]
+async fn the_most_voted_definition_comes_first() {
+    let harness = Harness::start().await;
+    mount_definitions(&harness).await;
+
+    answer(
+        harness.bot(),
+        common::message("/urban Doggo"),
+        Command::Urban("Doggo".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "doggo\n\nA very good boy.\n\nLook at that doggo!\n\n👍 120 👎 4\n\n1/2"
+    );
+    assert_eq!(
+        messages[0]["reply_markup"]["inline_keyboard"][0][0]["callback_data"],
+        format!("{}:{}:1:doggo", URBAN_PAGE, USER_ID)
+    );
+}
+
+#[tokio::test]
+async fn the_buttons_page_through_the_definitions() {
+    let harness = Harness::start().await;
+    mount_definitions(&harness).await;
+
+    let data = format!("{}:{}:1:doggo", URBAN_PAGE, USER_ID);
+    answer_callback(harness.bot(), common::callback(&data), harness.state())
+        .await
+        .unwrap();
+
+    let edits = harness.sent("editMessageText").await;
+    assert_eq!(edits[0]["text"], "doggo\n\nA dog.\n\n👍 10 👎 2\n\n2/2");
+    assert_eq!(
+        edits[0]["reply_markup"]["inline_keyboard"][0][0]["callback_data"],
+        format!("{}:{}:0:doggo", URBAN_PAGE, USER_ID)
+    );
+}
+
+#[tokio::test]
+async fn filtered_chats_get_no_definitions() {
+    let harness = Harness::start().await;
+    mount_definitions(&harness).await;
+
+    for (text, command) in [
+        ("/nsfwfilter on", Command::NsfwFilter("on".to_string())),
+        ("/urban doggo", Command::Urban("doggo".to_string())),
+    ] {
+        answer(
+            harness.bot(),
+            common::message(text),
+            command,
+            harness.state(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "The NSFW filter is on");
+    assert_eq!(
+        messages[1]["text"],
+        "Urban Dictionary is off in this chat, see /nsfwfilter"
+    );
+    assert!(harness
+        .urban_dictionary
+        .received_requests()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn only_admins_turn_the_filter_of_a_group_on() {
+    let harness = Harness::start().await;
+    harness.member_status("member").await;
+
+    answer(
+        harness.bot(),
+        common::group_message("/nsfwfilter on"),
+        Command::NsfwFilter("on".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Only the admins of the chat can change its NSFW filter"
+    );
+    let settings = harness.storage.chat_settings(CHAT_ID).await.unwrap();
+    assert!(settings.is_none());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::Harness;
+use dog_bot::version::BuildInfo;
+use insta::assert_snapshot;
+
+#[test]
+fn the_build_is_described() {
+    let build = BuildInfo::current();
+    assert_eq!(build.version, env!("CARGO_PKG_VERSION"));
+    assert!(!build.git_sha.is_empty());
+    assert!(!build.built_at.is_empty());
+
+    let build = BuildInfo {
+        version: "1.2.3",
+        git_sha: "3f2c1ab",
+        built_at: "2024-05-01T09:00:00.000000000Z",
+        features: vec!["discord", "matrix"],
+    };
+    assert_snapshot!(build.text(), @r###"
+    dog-bot 1.2.3
+    commit: 3f2c1ab
+    built: 2024-05-01T09:00:00.000000000Z
+    features: discord, matrix
+    "###);
+    let build = BuildInfo {
+        features: Vec::new(),
+        ..build
+    };
+    assert!(build.text().ends_with("features: none"));
+}
+
+#[tokio::test]
+async fn version_answers_with_the_build() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+
+    harness
+        .receive(&state, common::update("/version"))
+        .await
+        .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["text"], BuildInfo::current().text());
+    assert_eq!(state.health.report().build, BuildInfo::current());
+}
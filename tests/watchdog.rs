@@ -0,0 +1,81 @@
+mod common;
+
+use common::Harness;
+use dog_bot::{
+    health::RequestCounts,
+    reporter::AdminConfig,
+    watchdog::{Change, Watchdog},
+};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+const ADMIN_CHAT_ID: i64 = 4000;
+
+fn counts(upstream: &str, total: u64, failed: u64) -> BTreeMap<String, RequestCounts> {
+    BTreeMap::from([(upstream.to_string(), RequestCounts { total, failed })])
+}
+
+fn config() -> AdminConfig {
+    AdminConfig {
+        chat_id: Some(ADMIN_CHAT_ID),
+        ..AdminConfig::default()
+    }
+}
+
+#[test]
+fn spikes_and_recoveries_are_reported_once() {
+    let watchdog = Watchdog::default();
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+
+    assert!(watchdog
+        .observe(at(0), counts("dog.ceo", 100, 0), &config())
+        .is_empty());
+    // 6 of the 10 requests since failed
+    assert_eq!(
+        watchdog.observe(at(30), counts("dog.ceo", 110, 6), &config()),
+        vec![Change::Failing {
+            upstream: "dog.ceo".to_string(),
+            failed: 6,
+            total: 10
+        }]
+    );
+    assert!(watchdog
+        .observe(at(60), counts("dog.ceo", 120, 12), &config())
+        .is_empty());
+    // Too few requests to tell
+    assert!(watchdog
+        .observe(at(400), counts("dog.ceo", 121, 12), &config())
+        .is_empty());
+    // The failures slid out of the window
+    assert_eq!(
+        watchdog.observe(at(700), counts("dog.ceo", 200, 12), &config()),
+        vec![Change::Recovered {
+            upstream: "dog.ceo".to_string()
+        }]
+    );
+}
+
+#[tokio::test]
+async fn the_admin_chat_is_alerted() {
+    let harness = Harness::start().await;
+    let state = harness.state();
+    state.reporter.reconfigure(config());
+    let watchdog = Watchdog::default();
+
+    watchdog.check(&state).await;
+    for _ in 0..10 {
+        state.health.upstream_request("api.coingecko.com", true);
+    }
+    watchdog.check(&state).await;
+
+    let sent = harness.sent("sendMessage").await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0]["chat_id"], ADMIN_CHAT_ID);
+    assert_eq!(
+        sent[0]["text"],
+        "📈 api.coingecko.com failed 10 of 10 requests over the last 5 minutes"
+    );
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+async fn mount_madrid(harness: &Harness) {
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("name", "madrid"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [{
+                "id": 3117735,
+                "name": "Madrid",
+                "country": "Spain",
+                "latitude": 40.4165,
+                "longitude": -3.70256
+            }]
+        })))
+        .mount(&harness.open_meteo)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/forecast"))
+        .and(query_param("latitude", "40.4165"))
+        .and(query_param("forecast_days", "3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "current": {
+                "time": "2024-06-01T12:00",
+                "temperature_2m": 27.4,
+                "apparent_temperature": 26.1,
+                "wind_speed_10m": 11.2,
+                "weather_code": 0
+            },
+            "daily": {
+                "time": ["2024-06-01", "2024-06-02", "2024-06-03"],
+                "weather_code": [0, 61, 95],
+                "temperature_2m_max": [29.0, 24.3, 22.0],
+                "temperature_2m_min": [16.2, 15.0, 14.4]
+            }
+        })))
+        .mount(&harness.open_meteo)
+        .await;
+}
+
+#[tokio::test]
+async fn weather_shows_the_conditions_and_forecast() {
+    let harness = Harness::start().await;
+    mount_madrid(&harness).await;
+
+    answer(
+        harness.bot(),
+        common::message("/weather madrid"),
+        Command::Weather("madrid".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(
+        messages[0]["text"],
+        "Weather in Madrid, Spain\n\
+         ☀️ Clear sky, 27°C (feels like 26°C), wind 11 km/h\n\n\
+         Sat 01 ☀️ 29°C / 16°C\n\
+         Sun 02 🌧 24°C / 15°C\n\
+         Mon 03 ⛈ 22°C / 14°C\n"
+    );
+}
+
+#[tokio::test]
+async fn weather_uses_the_default_city() {
+    let harness = Harness::start().await;
+    mount_madrid(&harness).await;
+
+    answer(
+        harness.bot(),
+        common::message("/prefs city Madrid"),
+        Command::Prefs("city Madrid".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+    answer(
+        harness.bot(),
+        common::message("/weather"),
+        Command::Weather(String::new()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert!(messages[1]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("Weather in Madrid, Spain"));
+}
+
+#[tokio::test]
+async fn unknown_cities_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&harness.open_meteo)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/weather atlantis"),
+        Command::Weather("atlantis".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "I couldn't find the city 'atlantis'");
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn summaries_are_in_the_language_of_the_user() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/es/page/summary/Golden_retriever"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "type": "standard",
+            "title": "Golden retriever",
+            "extract": "El golden retriever es una raza de perro.\nSegundo párrafo.",
+            "thumbnail": { "source": "https://upload.wikimedia.org/golden.jpg", "width": 320 },
+            "content_urls": {
+                "desktop": { "page": "https://es.wikipedia.org/wiki/Golden_retriever" },
+                "mobile": { "page": "https://es.m.wikipedia.org/wiki/Golden_retriever" }
+            }
+        })))
+        .mount(&harness.wikipedia)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/prefs language es"),
+        Command::Prefs("language es".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+    answer(
+        harness.bot(),
+        common::message("/wiki golden retriever"),
+        Command::Wiki("golden retriever".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(
+        photos[0]["photo"],
+        "https://upload.wikimedia.org/golden.jpg"
+    );
+    assert_eq!(
+        photos[0]["caption"],
+        "Golden retriever\n\nEl golden retriever es una raza de perro."
+    );
+    assert_eq!(
+        photos[0]["reply_markup"]["inline_keyboard"][0][0]["url"],
+        "https://es.wikipedia.org/wiki/Golden_retriever"
+    );
+}
+
+#[tokio::test]
+async fn missing_articles_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/en/page/summary/Asdfgh"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&harness.wikipedia)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/wiki Asdfgh"),
+        Command::Wiki("Asdfgh".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "There's no article about 'Asdfgh'");
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::Harness;
+use dog_bot::commands::{answer, Command};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn comics_are_sent_with_their_alt_text() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/327/info.0.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "num": 327,
+            "title": "Exploits of a Mom",
+            "safe_title": "Exploits of a Mom",
+            "img": "https://imgs.xkcd.com/comics/exploits_of_a_mom.png",
+            "alt": "Her daughter is named Help I'm trapped in a driver's license factory.",
+            "year": "2007"
+        })))
+        .mount(&harness.xkcd)
+        .await;
+
+    answer(
+        harness.bot(),
+        common::message("/xkcd 327"),
+        Command::Xkcd("327".to_string()),
+        harness.state(),
+    )
+    .await
+    .unwrap();
+
+    let photos = harness.sent("sendPhoto").await;
+    assert_eq!(
+        photos[0]["photo"],
+        "https://imgs.xkcd.com/comics/exploits_of_a_mom.png"
+    );
+    assert_eq!(
+        photos[0]["caption"],
+        "#327 Exploits of a Mom\n\nHer daughter is named Help I'm trapped in a driver's license factory."
+    );
+}
+
+#[tokio::test]
+async fn missing_comics_are_reported() {
+    let harness = Harness::start().await;
+    Mock::given(method("GET"))
+        .and(path("/99999/info.0.json"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&harness.xkcd)
+        .await;
+
+    for number in ["99999", "404"] {
+        answer(
+            harness.bot(),
+            common::message(&format!("/xkcd {}", number)),
+            Command::Xkcd(number.to_string()),
+            harness.state(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let messages = harness.sent("sendMessage").await;
+    assert_eq!(messages[0]["text"], "There's no xkcd number 99999 yet");
+    assert_eq!(messages[1]["text"], "#404 Not Found, there's no such comic");
+    assert!(harness.sent("sendPhoto").await.is_empty());
+}